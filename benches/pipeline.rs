@@ -0,0 +1,62 @@
+//! Criterion benchmarks for the analysis pipeline: `cargo bench`.
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use glim::app::{App, ZoomLevel};
+use glim::bench::{FixtureSize, fixture_repo, render_offscreen, rust_source, unified_diff};
+use glim::config::PathFilter;
+use glim::semantics::SemanticAnalyzer;
+use std::collections::HashMap;
+
+fn split_diff(c: &mut Criterion) {
+    let diff = unified_diff(FixtureSize::default());
+    c.bench_function("split_diff", |b| {
+        b.iter(|| App::split_diff(black_box(&diff)));
+    });
+}
+
+fn analyze(c: &mut Criterion) {
+    let old = rust_source(500, 0);
+    let new = rust_source(500, 1);
+    c.bench_function("analyze/cold", |b| {
+        b.iter(|| SemanticAnalyzer::new().analyze("f.rs", black_box(&new)));
+    });
+    c.bench_function("analyze/incremental", |b| {
+        let mut analyzer = SemanticAnalyzer::new();
+        b.iter(|| {
+            analyzer.analyze("f.rs", black_box(&old));
+            analyzer.analyze("f.rs", black_box(&new))
+        });
+    });
+}
+
+fn scan_and_render(c: &mut Criterion) {
+    let dir = std::env::temp_dir().join(format!("glim-criterion-{}", std::process::id()));
+    let repo = fixture_repo(&dir, FixtureSize::default()).expect("fixture repository");
+    let weights = HashMap::new();
+    c.bench_function("scan_local_repo", |b| {
+        b.iter(|| {
+            let mut analyzer = SemanticAnalyzer::new();
+            App::scan_local_repo(
+                &repo,
+                &dir,
+                &mut analyzer,
+                &weights,
+                &PathFilter::default(),
+                None,
+            )
+        });
+    });
+
+    let mut app = App::new(dir.clone());
+    app.zoom_level = ZoomLevel::Structure;
+    c.bench_function("render/structure", |b| b.iter(|| render_offscreen(&app)));
+    app.zoom_in();
+    c.bench_function("render/logic", |b| b.iter(|| render_offscreen(&app)));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = split_diff, analyze, scan_and_render
+}
+criterion_main!(benches);
@@ -0,0 +1,189 @@
+// Contextual quick actions for the selected Structure item
+use crate::app::StructureItem;
+use crate::multiplexer::PaneCommand;
+use crate::session::Session;
+use crate::suggestions::Suggestion;
+use git2::Oid;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickAction {
+    ToggleStage,
+    Discard,
+    OpenInEditor,
+    CopyPath,
+    ViewHistory,
+    Blame,
+    // Shallow and partial clones only
+    FetchHistory,
+    // PRs whose base branch moved on since their base commit
+    ViewBaseDrift,
+    // PRs reviewed from inside a clone of their repository
+    CheckoutPr,
+    // Local files, inside tmux / zellij or with `[pane]` commands configured
+    OpenInPane,
+    SendToEditorPane,
+    // Open PRs, whichever of the two changes their draft state
+    MarkReady,
+    ConvertToDraft,
+}
+
+impl QuickAction {
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::ToggleStage => "Stage / Unstage",
+            Self::Discard => "Discard changes",
+            Self::OpenInEditor => "Open in editor",
+            Self::CopyPath => "Copy path",
+            Self::ViewHistory => "View history",
+            Self::Blame => "Blame",
+            Self::FetchHistory => "Fetch more history",
+            Self::ViewBaseDrift => "View base drift",
+            Self::CheckoutPr => "Check out PR locally",
+            Self::OpenInPane => PaneCommand::Open.label(),
+            Self::SendToEditorPane => PaneCommand::Send.label(),
+            Self::MarkReady => "Mark ready for review",
+            Self::ConvertToDraft => "Convert to draft",
+        }
+    }
+
+    /// Actions that make sense for `item`; local-only actions are dropped for PR sources.
+    #[must_use]
+    pub fn for_item(item: &StructureItem, is_local: bool) -> Vec<Self> {
+        let mut actions = Vec::new();
+        if is_local && item.is_file {
+            actions.push(Self::ToggleStage);
+            actions.push(Self::Discard);
+        }
+        if is_local {
+            actions.push(Self::OpenInEditor);
+        }
+        actions.push(Self::CopyPath);
+        if is_local {
+            actions.push(Self::ViewHistory);
+        }
+        if is_local && item.is_file {
+            actions.push(Self::Blame);
+        }
+        actions
+    }
+}
+
+impl From<PaneCommand> for QuickAction {
+    fn from(command: PaneCommand) -> Self {
+        match command {
+            PaneCommand::Open => Self::OpenInPane,
+            PaneCommand::Send => Self::SendToEditorPane,
+        }
+    }
+}
+
+/// An open quick-action popup and its cursor.
+#[derive(Debug, Clone)]
+pub struct ActionMenu {
+    pub actions: Vec<QuickAction>,
+    pub selected: usize,
+}
+
+impl ActionMenu {
+    pub const fn next(&mut self) {
+        if self.selected + 1 < self.actions.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub const fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    #[must_use]
+    pub fn current(&self) -> Option<QuickAction> {
+        self.actions.get(self.selected).copied()
+    }
+}
+
+/// A destructive operation waiting for the user to confirm with `y`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PendingConfirm {
+    /// Restore a tracked file from the index, or delete it when new since HEAD (untracked or
+    /// only staged)
+    Discard { path: PathBuf, added: bool },
+    /// `Discard` for every file of a Structure visual selection, with each file's `added`
+    DiscardFiles(Vec<(PathBuf, bool)>),
+    /// Revert one unstaged hunk (the one covering working-tree `line`) to the index
+    DiscardHunk {
+        path: PathBuf,
+        line: usize,
+        header: String,
+    },
+    /// Deepen a shallow clone / fetch the missing objects of a partial one
+    FetchHistory { path: PathBuf, commands: String },
+    /// Apply a commit from the history view onto HEAD; `mainline` picks a merge's parent
+    CherryPick {
+        id: Oid,
+        summary: String,
+        mainline: u32,
+    },
+    /// Switch the clone at `root` to the PR's branch with `gh pr checkout`
+    CheckoutPr { root: PathBuf, number: u64 },
+    /// Write a reviewer's suggested change into the clone at `root`
+    ApplySuggestion {
+        root: PathBuf,
+        suggestion: Suggestion,
+    },
+    /// Go back to where a session that did not exit cleanly left off
+    RestoreSession(Box<Session>),
+    /// The index or HEAD moved under us; rescan before writing anything
+    Refresh,
+}
+
+impl PendingConfirm {
+    #[must_use]
+    pub fn prompt(&self) -> String {
+        match self {
+            Self::Discard { path, added: true } => {
+                format!("Delete new file {}?", path.display())
+            }
+            Self::Discard { path, .. } => {
+                format!("Discard working tree changes to {}?", path.display())
+            }
+            Self::DiscardFiles(files) => {
+                let added = files.iter().filter(|(_, added)| *added).count();
+                if added == 0 {
+                    format!("Discard working tree changes to {} files?", files.len())
+                } else {
+                    format!(
+                        "Discard working tree changes to {} files ({added} new will be deleted)?",
+                        files.len()
+                    )
+                }
+            }
+            Self::DiscardHunk { path, header, .. } => {
+                format!("Discard hunk {header} in {}?", path.display())
+            }
+            Self::FetchHistory { path, commands } => {
+                format!("Fetch more history for {} ({commands})?", path.display())
+            }
+            Self::CherryPick { id, summary, .. } => {
+                format!("Cherry-pick {id:.7} \"{summary}\" onto the current branch?")
+            }
+            Self::CheckoutPr { root, number } => format!(
+                "Check out PR #{number} in {} and review it locally?",
+                root.display()
+            ),
+            Self::ApplySuggestion { suggestion, .. } => format!(
+                "Apply the suggested change to {}:{}?",
+                suggestion.path,
+                suggestion.range()
+            ),
+            Self::RestoreSession(session) => format!(
+                "The last session here ended unexpectedly. Restore it ({})?",
+                session.describe()
+            ),
+            Self::Refresh => {
+                "Index or HEAD changed outside glim. Refresh before writing?".to_string()
+            }
+        }
+    }
+}
@@ -0,0 +1,476 @@
+// Public API growth: symbols a changeset newly makes public or exports, and Rust `pub` items
+// whose signature it changes or that it takes away
+use crate::diff::line_numbers;
+use std::collections::HashSet;
+use std::path::Path;
+
+// Words that may sit between `pub` and the kind of a Rust item
+const RUST_QUALIFIERS: &[&str] = &[
+    "async",
+    "unsafe",
+    "extern",
+    "\"C\"",
+    "\"system\"",
+    "default",
+];
+const RUST_KINDS: &[&str] = &[
+    "fn", "struct", "enum", "trait", "type", "const", "static", "mod", "union", "use",
+];
+// Words that may sit between `export` and the kind of a TypeScript / JavaScript declaration
+const TS_QUALIFIERS: &[&str] = &["default", "declare", "async", "abstract"];
+const TS_KINDS: &[&str] = &[
+    "function",
+    "function*",
+    "class",
+    "interface",
+    "type",
+    "enum",
+    "const",
+    "let",
+    "var",
+    "namespace",
+];
+
+/// A symbol that is public after the changeset but was not before.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiSymbol {
+    pub path: String,
+    // Line in the new file
+    pub line: usize,
+    // Declaration keyword as written, e.g. `fn`, `interface`, `func`
+    pub kind: String,
+    pub name: String,
+}
+
+// Lines a Rust signature may run over before its body
+const MAX_SIGNATURE_LINES: usize = 20;
+
+/// A public Rust item whose signature a changeset changes or that it removes, breaking its
+/// users.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiBreak {
+    pub path: String,
+    // Line in the new file, or in the old one for removed items
+    pub line: usize,
+    pub kind: String,
+    pub name: String,
+    // What changed, e.g. "adds `b: u8`, returns `u16` instead of `u8`", or "removed"
+    pub change: String,
+    pub removed: bool,
+}
+
+/// A `pub` declaration on one side of a diff: its line, kind, name and whitespace-normalised
+/// signature.
+struct Declaration {
+    line: usize,
+    kind: String,
+    name: String,
+    signature: String,
+}
+
+/// Kind and name of what `line` makes public, in the language of `path`.
+#[must_use]
+pub fn public_declaration(path: &str, line: &str) -> Option<(String, String)> {
+    let extension = Path::new(path).extension()?.to_str()?;
+    match extension {
+        "rs" => rust_declaration(line),
+        "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" | "mts" | "cts" => ts_declaration(line),
+        "go" => go_declaration(line),
+        _ => None,
+    }
+}
+
+/// Public declarations on the added lines of `diff` that no removed line of it declared.
+#[must_use]
+pub fn new_public_symbols(path: &str, diff: &[String]) -> Vec<ApiSymbol> {
+    let numbers = line_numbers(diff);
+    let removed: HashSet<(String, String)> = diff
+        .iter()
+        .zip(&numbers)
+        .filter(|(_, (old, new))| old.is_some() && new.is_none())
+        .filter_map(|(line, _)| public_declaration(path, &line[1..]))
+        .collect();
+    diff.iter()
+        .zip(&numbers)
+        .filter_map(|(line, &(old, new))| {
+            let line_no = new.filter(|_| old.is_none())?;
+            let declaration = public_declaration(path, &line[1..])?;
+            (!removed.contains(&declaration)).then(|| ApiSymbol {
+                path: path.to_string(),
+                line: line_no,
+                kind: declaration.0,
+                name: declaration.1,
+            })
+        })
+        .collect()
+}
+
+/// `pub` items of a Rust file's diff whose signature differs between its removed and added
+/// lines, or that only the removed lines declare. Signatures starting before a hunk go unseen.
+#[must_use]
+pub fn broken_rust_api(path: &str, diff: &[String]) -> Vec<ApiBreak> {
+    if Path::new(path).extension().is_none_or(|ext| ext != "rs") {
+        return Vec::new();
+    }
+    let numbers = line_numbers(diff);
+    // Each side of the diff: the context lines with the removed or the added ones
+    let side = |old_side: bool| -> Vec<(usize, &str)> {
+        diff.iter()
+            .zip(&numbers)
+            .filter_map(|(line, &(old, new))| {
+                Some((if old_side { old? } else { new? }, &line[1..]))
+            })
+            .collect()
+    };
+    let old = rust_declarations(&side(true));
+    let mut new = rust_declarations(&side(false));
+    let mut breaks = Vec::new();
+    let mut changed = Vec::new();
+    for declaration in old {
+        let same = |d: &Declaration| d.kind == declaration.kind && d.name == declaration.name;
+        if let Some(at) = new
+            .iter()
+            .position(|d| same(d) && d.signature == declaration.signature)
+        {
+            new.remove(at);
+        } else {
+            changed.push(declaration);
+        }
+    }
+    for declaration in changed {
+        let same = |d: &Declaration| d.kind == declaration.kind && d.name == declaration.name;
+        let (line, change, removed) = match new.iter().position(same) {
+            Some(at) => {
+                let now = new.remove(at);
+                let change = describe_change(&declaration, &now);
+                (now.line, change, false)
+            }
+            None => (declaration.line, "removed".to_string(), true),
+        };
+        breaks.push(ApiBreak {
+            path: path.to_string(),
+            line,
+            kind: declaration.kind,
+            name: declaration.name,
+            change,
+            removed,
+        });
+    }
+    breaks.sort_by_key(|b| b.line);
+    breaks
+}
+
+/// `pub` declarations among `lines` (numbered lines of one side of a diff), each signature
+/// read on to its body or `;` over lines that follow on without a gap.
+fn rust_declarations(lines: &[(usize, &str)]) -> Vec<Declaration> {
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &(line, text))| {
+            let (kind, name) = rust_declaration(text)?;
+            let mut signature = String::new();
+            for (n, &(number, text)) in lines[i..].iter().take(MAX_SIGNATURE_LINES).enumerate() {
+                if number != line + n {
+                    break;
+                }
+                // Fields and `use` end with their line
+                let end = text.find(['{', ';']).or_else(|| {
+                    (kind == "field" || n + 1 == MAX_SIGNATURE_LINES).then_some(text.len())
+                });
+                signature.push_str(&text[..end.unwrap_or(text.len())]);
+                signature.push(' ');
+                if end.is_some() {
+                    break;
+                }
+            }
+            Some(Declaration {
+                line,
+                kind,
+                name,
+                signature: normalize_signature(&signature),
+            })
+        })
+        .collect()
+}
+
+/// `signature` with whitespace only between words and no trailing commas, so reformatting
+/// a signature over several lines does not count as changing it.
+fn normalize_signature(signature: &str) -> String {
+    let words: Vec<&str> = signature.split_whitespace().collect();
+    let mut out = String::new();
+    for word in words {
+        let joins = out.ends_with(|c: char| c.is_alphanumeric() || c == '_')
+            && word.starts_with(|c: char| c.is_alphanumeric() || c == '_');
+        let arrow = word.starts_with("->") || out.ends_with("->") || out.ends_with(':');
+        if joins || arrow {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    out.trim_end_matches(',')
+        .replace(",)", ")")
+        .replace(",>", ">")
+        .replace(",}", "}")
+}
+
+/// How `now` differs from `was`: parameters added and removed and the return type for
+/// functions, otherwise just that the declaration changed.
+fn describe_change(was: &Declaration, now: &Declaration) -> String {
+    let (Some((was_params, was_returns)), Some((now_params, now_returns))) = (
+        split_fn_signature(&was.signature),
+        split_fn_signature(&now.signature),
+    ) else {
+        return "declaration changed".to_string();
+    };
+    let mut parts: Vec<String> = was_params
+        .iter()
+        .filter(|p| !now_params.contains(p))
+        .map(|p| format!("removes `{p}`"))
+        .collect();
+    parts.extend(
+        now_params
+            .iter()
+            .filter(|p| !was_params.contains(p))
+            .map(|p| format!("adds `{p}`")),
+    );
+    if was_returns != now_returns {
+        parts.push(format!(
+            "returns `{now_returns}` instead of `{was_returns}`"
+        ));
+    }
+    if parts.is_empty() {
+        "signature changed".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Parameters and return type (`()` when there is none) of a normalised `fn` signature.
+fn split_fn_signature(signature: &str) -> Option<(Vec<String>, String)> {
+    let start = signature.find(" fn ")? + 4;
+    // The parameter list opens at the first `(` outside generics
+    let mut angle = 0i32;
+    let open = signature[start..].char_indices().find_map(|(i, c)| {
+        match c {
+            '<' => angle += 1,
+            '>' if !signature[start..][..i].ends_with('-') => angle -= 1,
+            '(' if angle == 0 => return Some(start + i),
+            _ => {}
+        }
+        None
+    })?;
+    let mut depth = 0i32;
+    let mut params = Vec::new();
+    let mut current = String::new();
+    let mut close = None;
+    for (i, c) in signature[open..].char_indices() {
+        match c {
+            '(' | '<' | '[' => depth += 1,
+            ')' | '>' | ']' if !(c == '>' && current.ends_with('-')) => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 {
+            close = Some(open + i);
+            break;
+        }
+        if c == ',' && depth == 1 {
+            params.push(std::mem::take(&mut current).trim().to_string());
+        } else if i > 0 {
+            current.push(c);
+        }
+    }
+    let close = close?;
+    if !current.trim().is_empty() {
+        params.push(current.trim().to_string());
+    }
+    let rest = signature[close + 1..].trim();
+    let returns = rest
+        .strip_prefix("->")
+        .map_or("()", |r| r.split(" where ").next().unwrap_or(r).trim());
+    Some((params, returns.to_string()))
+}
+
+/// Leading identifier of `text`; TypeScript names may contain `$`.
+fn identifier(text: &str) -> Option<String> {
+    let name: String = text
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+        .collect();
+    (!name.is_empty()).then_some(name)
+}
+
+/// `pub` items; `pub(crate)` and friends stay inside the crate and do not count.
+fn rust_declaration(line: &str) -> Option<(String, String)> {
+    let rest = line.trim_start().strip_prefix("pub ")?;
+    let mut words = rest.split_whitespace().peekable();
+    while let Some(word) = words.next() {
+        // `pub const fn` is a function, `pub const X` a constant
+        let is_const_fn = word == "const"
+            && words
+                .peek()
+                .is_some_and(|next| *next == "fn" || RUST_QUALIFIERS.contains(next));
+        if RUST_QUALIFIERS.contains(&word) || is_const_fn {
+            continue;
+        }
+        if word == "use" {
+            let path = rest[rest.find("use")? + 3..].trim().trim_end_matches(';');
+            return Some(("use".to_string(), path.to_string()));
+        }
+        if RUST_KINDS.contains(&word) {
+            return Some((word.to_string(), identifier(words.next()?)?));
+        }
+        // `pub name: Type` in a struct body
+        return word
+            .strip_suffix(':')
+            .and_then(identifier)
+            .map(|name| ("field".to_string(), name));
+    }
+    None
+}
+
+/// `export` declarations and `export { a, b }` lists.
+fn ts_declaration(line: &str) -> Option<(String, String)> {
+    let rest = line.trim_start().strip_prefix("export ")?.trim_start();
+    if rest.starts_with('{') || rest.starts_with('*') {
+        let names = rest.split(" from ").next()?.trim().trim_end_matches(';');
+        return Some(("export".to_string(), names.to_string()));
+    }
+    let mut words = rest.split_whitespace();
+    while let Some(word) = words.next() {
+        if TS_QUALIFIERS.contains(&word) {
+            continue;
+        }
+        if TS_KINDS.contains(&word) {
+            let name = words
+                .next()
+                .and_then(|w| identifier(w.trim_start_matches('*')));
+            return Some((word.to_string(), name.unwrap_or_else(|| "default".into())));
+        }
+        // `export default someValue`
+        return rest
+            .starts_with("default")
+            .then(|| ("export".to_string(), "default".to_string()));
+    }
+    None
+}
+
+/// Top-level `func`, `type`, `var` and `const` declarations of capitalised names.
+fn go_declaration(line: &str) -> Option<(String, String)> {
+    // Only unindented lines are package level
+    let (kind, rest) = line.split_once(' ')?;
+    let (kind, rest) = match kind {
+        "func" => match rest.strip_prefix('(') {
+            // Methods: `func (r *Recv) Name(`
+            Some(receiver) => ("method", receiver.split_once(')')?.1.trim_start()),
+            None => ("func", rest),
+        },
+        "type" | "var" | "const" => (kind, rest),
+        _ => return None,
+    };
+    let name = identifier(rest)?;
+    name.starts_with(|c: char| c.is_uppercase())
+        .then(|| (kind.to_string(), name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{broken_rust_api, new_public_symbols, public_declaration};
+
+    #[test]
+    fn recognises_public_declarations() {
+        for (path, line, expected) in [
+            ("a.rs", "pub const fn new() -> Self {", Some(("fn", "new"))),
+            (
+                "a.rs",
+                "    pub const MAX: usize = 3;",
+                Some(("const", "MAX")),
+            ),
+            ("a.rs", "pub struct Config<T> {", Some(("struct", "Config"))),
+            ("a.rs", "    pub name: String,", Some(("field", "name"))),
+            ("a.rs", "pub use crate::x::Y;", Some(("use", "crate::x::Y"))),
+            ("a.rs", "pub(crate) fn hidden() {}", None),
+            (
+                "a.ts",
+                "export async function load() {",
+                Some(("function", "load")),
+            ),
+            (
+                "a.ts",
+                "export { a, b } from './x';",
+                Some(("export", "{ a, b }")),
+            ),
+            ("a.ts", "const local = 1;", None),
+            (
+                "a.go",
+                "func (s *Server) Start() error {",
+                Some(("method", "Start")),
+            ),
+            (
+                "a.go",
+                "type Option func(*Server)",
+                Some(("type", "Option")),
+            ),
+            ("a.go", "func helper() {}", None),
+        ] {
+            let expected = expected.map(|(kind, name)| (kind.to_string(), name.to_string()));
+            assert_eq!(public_declaration(path, line), expected, "{line}");
+        }
+    }
+
+    #[test]
+    fn skips_symbols_that_were_already_public() {
+        let diff: Vec<String> = [
+            " @@ -1,3 +1,4 @@",
+            "-pub fn old(a: u8) {}",
+            "+pub fn old(a: u16) {}",
+            "-fn grown() {}",
+            "+pub fn grown() {}",
+            "+pub struct Fresh;",
+            " ",
+        ]
+        .map(String::from)
+        .to_vec();
+        let found: Vec<(String, usize)> = new_public_symbols("src/lib.rs", &diff)
+            .into_iter()
+            .map(|s| (s.name, s.line))
+            .collect();
+        assert_eq!(found, [("grown".to_string(), 2), ("Fresh".to_string(), 3)]);
+    }
+
+    #[test]
+    fn flags_changed_and_removed_rust_signatures() {
+        let diff: Vec<String> = [
+            " @@ -1,12 +1,12 @@",
+            "-pub fn load(path: &Path) -> Config {",
+            "+pub fn load(path: &Path, strict: bool) -> Result<Config> {",
+            "     todo!()",
+            " }",
+            "-pub fn gone() {}",
+            "+fn gone() {}",
+            " pub fn wrapped(",
+            "-    a: u8,",
+            "+    a: u16,",
+            " ) {}",
+            "-pub fn reflowed(a: u8, b: u8) {}",
+            "+pub fn reflowed(",
+            "+    a: u8,",
+            "+    b: u8,",
+            "+) {}",
+        ]
+        .map(String::from)
+        .to_vec();
+        let found: Vec<String> = broken_rust_api("src/config.rs", &diff)
+            .into_iter()
+            .map(|b| format!("{}:{} {}", b.line, b.name, b.change))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                "1:load adds `strict: bool`, returns `Result<Config>` instead of `Config`",
+                "4:gone removed",
+                "5:wrapped removes `a: u8`, adds `a: u16`"
+            ]
+        );
+        assert_eq!(broken_rust_api("src/a.ts", &diff), []);
+    }
+}
@@ -1,12 +1,73 @@
-use crate::github::{GitHubClient, PrInfo};
-use crate::semantics::SemanticAnalyzer;
-use git2::{DiffOptions, Repository, StatusOptions};
-use std::collections::HashMap;
+use crate::actions::{ActionMenu, PendingConfirm, QuickAction};
+use crate::api_surface::{ApiBreak, ApiSymbol, broken_rust_api, new_public_symbols};
+use crate::branches::{BranchEntry, BranchPanel};
+use crate::cargo::CargoWorkspace;
+use crate::churn::Churn;
+use crate::clone::CloneState;
+use crate::commit::{CommitInfo, CommitRef, diff_files};
+use crate::config::{Config, PathFilter, heat_weight};
+use crate::conflict::{ConflictFile, Side};
+use crate::diff::{
+    anchor_line, describe_mode_change, hunk_span, is_context_line, is_meta_line, line_numbers,
+    mode_change, new_line_numbers, touches,
+};
+use crate::export::{FileReport, render_markdown, render_summary};
+use crate::github::{
+    BaseDrift, CheckAnnotation, CheckStatus, CiState, GhError, GitHubClient, MAX_DIFF_FILES,
+    PrFile, PrInfo, PrRef, PrReviewData, RateLimit, ReviewThread, diff_too_large, is_offline,
+    remote_slug, repo_url_slug,
+};
+use crate::handoff::Handoff;
+use crate::highlight::{self, Highlighter};
+use crate::history::{FileHistory, MergeView};
+use crate::jumps::{JumpList, Location};
+use crate::labels::LabelPicker;
+use crate::lfs::LfsChange;
+use crate::macros::MacroRecorder;
+use crate::markdown;
+use crate::mechanical::{self, Cluster};
+use crate::multiplexer::{self, PaneCommand};
+use crate::notes::{LineNote, Notes};
+use crate::onboarding::Onboarding;
+use crate::packages::Packages;
+use crate::panels::Panel;
+use crate::people::{PeoplePicker, PeopleRole};
+use crate::pr_cache::CachedPr;
+use crate::pr_picker::PrPicker;
+use crate::review::{CommentDraft, ReviewDraft};
+use crate::safe_mode::SafeMode;
+use crate::search::SearchQuery;
+use crate::semantics::{self, Complexity, SemanticAnalyzer, SymbolChange};
+use crate::session::Session;
+use crate::staging::{HunkStage, IndexSnapshot, StagingUndo, hunk_stages};
+use crate::stats::ChangeBreakdown;
+use crate::suggestions::Suggestion;
+use crate::symbol_index::{
+    self, Impact, ImpactPanel, IndexJob, IndexRequest, IndexedSymbol, SymbolIndex,
+};
+use crate::theme::DiffTheme;
+use crate::utils::{file_name, format_age, format_size, get_data_dir, open_url, path_from_bytes};
+use crate::verdicts::Verdicts;
+use git2::{BlameOptions, DiffOptions, MergeFileOptions, Oid, Repository, StatusOptions, Tree};
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Wrap};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use syntect::parsing::SyntaxReference;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// Symbols listed for a repo-wide `@` search
+const SYMBOL_HITS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
 pub enum ZoomLevel {
     Galaxy,
     Structure,
@@ -19,6 +80,44 @@ pub enum InputMode {
     Editing,
 }
 
+/// Which changes the local Logic view diffs, cycled with `t`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiffScope {
+    // HEAD (or the merge-base) to the working tree, through the index
+    All,
+    // Index to working tree: what `git add` would still pick up
+    Unstaged,
+    // HEAD (or the merge-base) to the index: exactly what the next commit contains
+    Staged,
+}
+
+impl DiffScope {
+    #[must_use]
+    pub const fn next(self) -> Self {
+        match self {
+            Self::All => Self::Unstaged,
+            Self::Unstaged => Self::Staged,
+            Self::Staged => Self::All,
+        }
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::All => "all changes",
+            Self::Unstaged => "unstaged only",
+            Self::Staged => "staged only",
+        }
+    }
+}
+
+/// The two lazygit-style halves of the local Structure view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StructureSection {
+    Staged,
+    Unstaged,
+}
+
 #[derive(Debug, Clone)]
 pub struct Module {
     pub name: String,
@@ -26,22 +125,153 @@ pub struct Module {
     pub description: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct StructureItem {
     pub text: String,
     pub path: String,
+    // Path as stored by git; may not be valid UTF-8, unlike the display `path`
+    pub fs_path: PathBuf,
     pub is_file: bool,
     pub status: String,
     pub line_no: Option<usize>,
     pub is_staged: bool,
+    // Working tree differs from the index (modified, deleted, or untracked)
+    pub is_unstaged: bool,
+    // Why symbol analysis was skipped for this file, shown as a badge
+    pub skip_reason: Option<&'static str>,
+    // Octal file modes when they changed, e.g. a script becoming executable
+    pub mode_change: Option<(u32, u32)>,
+    // Symbols only: the diff changes lines inside it, rather than it just being in the file
+    pub touched: bool,
+    // Code symbols only: lines, branches and nesting of the new version
+    pub complexity: Option<Complexity>,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct DashboardInfo {
     pub repo_name: String,
     pub branch_name: String,
+    // Caveats about what is shown: a shallow clone, an offline copy, base drift
+    pub warnings: Vec<String>,
     pub description: String,
     pub stats: String,
+    // Per-language and test/source/docs split of the changed lines
+    pub breakdown: String,
+}
+
+/// What the index and HEAD looked like at the last scan, to catch concurrent writers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoSnapshot {
+    index_mtime: Option<SystemTime>,
+    head: Option<Oid>,
+}
+
+impl RepoSnapshot {
+    #[must_use]
+    pub fn of(repo: &Repository) -> Self {
+        Self {
+            index_mtime: fs::metadata(repo.path().join("index"))
+                .and_then(|m| m.modified())
+                .ok(),
+            head: repo.head().ok().and_then(|h| h.target()),
+        }
+    }
+}
+
+impl StructureItem {
+    /// Name of a symbol row (its text after the kind); `None` for files and directories.
+    #[must_use]
+    pub fn symbol_name(&self) -> Option<&str> {
+        if self.is_file || self.status == "dir" {
+            return None;
+        }
+        let text = self.text.trim_start();
+        Some(
+            text.strip_prefix(self.status.as_str())
+                .unwrap_or(text)
+                .trim(),
+        )
+    }
+}
+
+/// How a confirmed cherry-pick ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CherryPickOutcome {
+    // Applied cleanly and committed as this commit
+    Committed(Oid),
+    // Stopped with these files conflicted; the index holds the conflict
+    Conflicted(Vec<String>),
+}
+
+/// What the Logic view shows instead of a patch when the selected file is binary.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BinarySummary {
+    // `None` when the side doesn't exist (added / deleted) or the size is unknown
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+    // Octal file modes, only set when they differ
+    pub mode_change: Option<(u32, u32)>,
+}
+
+impl BinarySummary {
+    /// Summary of the first binary delta in `diff`, loading blobs to let git sniff content.
+    #[must_use]
+    pub fn of(diff: &git2::Diff) -> Option<Self> {
+        (0..diff.deltas().len()).find_map(|i| {
+            let patch = git2::Patch::from_diff(diff, i).ok()??;
+            let delta = patch.delta();
+            if !delta.flags().is_binary() {
+                return None;
+            }
+            let size = |file: git2::DiffFile, exists: bool| exists.then(|| file.size());
+            let old_mode = u32::from(delta.old_file().mode());
+            let new_mode = u32::from(delta.new_file().mode());
+            Some(Self {
+                old_size: size(
+                    delta.old_file(),
+                    delta.status() != git2::Delta::Added
+                        && delta.status() != git2::Delta::Untracked,
+                ),
+                new_size: size(delta.new_file(), delta.status() != git2::Delta::Deleted),
+                mode_change: (old_mode != 0 && new_mode != 0 && old_mode != new_mode)
+                    .then_some((old_mode, new_mode)),
+            })
+        })
+    }
+
+    /// Whether a patch text (as `gh pr diff` prints it) only says the binary file differs.
+    #[must_use]
+    pub fn in_patch(lines: &[String]) -> bool {
+        lines.iter().any(|l| {
+            let l = l.trim_start();
+            l.starts_with("Binary files ") && l.ends_with(" differ") || l == "GIT binary patch"
+        })
+    }
+
+    /// Display lines for the summary panel.
+    #[must_use]
+    pub fn lines(&self) -> Vec<String> {
+        let size = |s: Option<u64>| s.map_or_else(|| "-".to_string(), format_size);
+        let mut out = vec![
+            "Binary file differs".to_string(),
+            String::new(),
+            format!("Size before: {}", size(self.old_size)),
+            format!("Size after:  {}", size(self.new_size)),
+        ];
+        if let (Some(old), Some(new)) = (self.old_size, self.new_size) {
+            let delta = new.cast_signed() - old.cast_signed();
+            let sign = if delta < 0 { "-" } else { "+" };
+            out.push(format!(
+                "Change:      {sign}{}",
+                format_size(delta.unsigned_abs())
+            ));
+        }
+        if let Some((old, new)) = self.mode_change {
+            out.push(format!("Mode:        {old:o} → {new:o}"));
+        }
+        out
+    }
 }
 
 pub enum DataSource {
@@ -53,6 +283,14 @@ pub enum DataSource {
         pr_info: Box<PrInfo>,
         raw_diff: String,
         file_diffs: HashMap<String, Vec<String>>,
+        review: Box<PrReviewData>,
+        // Set when the base branch moved on since the PR's base commit
+        drift: Option<Box<BaseDrift>>,
+    },
+    // One commit, local or on GitHub, diffed against its first parent
+    Commit {
+        commit: Box<CommitInfo>,
+        file_diffs: HashMap<String, Vec<String>>,
     },
 }
 
@@ -68,10 +306,25 @@ impl fmt::Debug for DataSource {
                 .debug_struct("GitHub")
                 .field("pr_info", pr_info)
                 .finish_non_exhaustive(),
+            Self::Commit { commit, .. } => f
+                .debug_struct("Commit")
+                .field("commit", commit)
+                .finish_non_exhaustive(),
         }
     }
 }
 
+/// The Logic view for one Structure item, with the panel state that goes with its lines.
+#[derive(Default)]
+struct LogicContent {
+    lines: Vec<String>,
+    conflict: Option<ConflictFile>,
+    binary: Option<BinarySummary>,
+    lfs: Option<LfsChange>,
+    hunk_stages: Vec<Option<HunkStage>>,
+}
+
+#[allow(clippy::struct_excessive_bools)]
 pub struct App {
     pub zoom_level: ZoomLevel,
     pub modules: Vec<Module>,
@@ -80,15 +333,144 @@ pub struct App {
     // Indices of structures that match the search query
     pub filtered_structure_indices: Vec<usize>,
     pub selected_index: usize,
+    // Visual mode in the Structure view: the position `v` was pressed on; the range ends at
+    // `selected_index`
+    pub structure_anchor: Option<usize>,
+    // Cursor line in the Logic view; `selected_index` keeps the Structure selection
+    pub logic_cursor: usize,
+    // Visual mode in the Logic view: the line `V` was pressed on; the range ends at the cursor
+    pub logic_anchor: Option<usize>,
     pub analyzer: SemanticAnalyzer,
     pub source: Option<DataSource>,
+    // Index/HEAD state the current scan was built from (local sources only)
+    pub repo_snapshot: Option<RepoSnapshot>,
     pub error_msg: Option<String>,
+    // Structured `gh` failure behind `error_msg`, for the remediation screen
+    pub gh_error: Option<GhError>,
     pub repo_root: PathBuf,
     pub dashboard_info: DashboardInfo,
     pub context_lines: u32,
+    pub diff_scope: DiffScope,
+    // Blame overlay for context lines in the Logic view (aligned with `logic_view_content`)
+    pub show_blame: bool,
+    pub blame_lines: Vec<Option<String>>,
+    // Merge-base of HEAD and `config.base_branch` while diffing the whole branch
+    pub merge_base: Option<Oid>,
+    // Diff colours from the user's gitconfig (`color.diff.*`)
+    pub diff_theme: DiffTheme,
+    // Logic view long-line handling: soft wrap, or horizontal scroll by `h_scroll` columns
+    pub wrap_lines: bool,
+    pub h_scroll: usize,
+    // Wrapped row of the cursor line the cursor sits on, as (line, row); any other line
+    // starts at its first row
+    pub logic_wrap: (usize, usize),
+    // Inner size of the Logic view at the last draw, so movement can follow wrapped rows
+    pub logic_viewport: Cell<(u16, u16)>,
+    // Syntax colours of the Logic view diff, filled in by a background worker
+    pub highlighter: Highlighter,
+    // Changes-only mode: the Logic view hides context lines, keeping hunk and file headers
+    pub changes_only: bool,
+    // Three-way view replacing the diff when the selected file has merge conflicts
+    pub conflict: Option<ConflictFile>,
+    // Size/mode summary replacing the diff when the selected file is binary
+    pub binary: Option<BinarySummary>,
+    // Object summary replacing the pointer-text diff of a Git LFS file
+    pub lfs: Option<LfsChange>,
+    // How much of each Logic view hunk is staged, per line; only for local diffs of HEAD to
+    // the working tree, where both halves show at once
+    pub hunk_stages: Vec<Option<HunkStage>>,
+    // File history listing (and the commit opened from it) replacing the diff
+    pub history: Option<FileHistory>,
+    // The PR's base drift replacing the diff, until the Logic view is left
+    pub showing_drift: bool,
+    // Focused Structure section (local sources only) and the other section's selection
+    pub structure_section: StructureSection,
+    pub other_section_index: usize,
     // Search State
     pub input_mode: InputMode,
     pub search_query: String,
+    // Symbols of the whole repository (local sources only), shared with the worker filling it
+    pub symbol_index: Arc<Mutex<SymbolIndex>>,
+    // The worker's current run, the paths waiting for the next, and whether a full run finished
+    index_job: Option<IndexJob>,
+    index_queue: Option<IndexRequest>,
+    // `git fetch` of more history running in the background, for the file it was asked for
+    history_fetch: Option<(PathBuf, Receiver<anyhow::Result<()>>)>,
+    // File history walk running in the background, for the listing marked `loading`
+    history_load: Option<Receiver<Result<FileHistory, git2::Error>>>,
+    pub indexed: bool,
+    // References to each changed symbol, keyed by its path and line
+    pub impact: Impact,
+    // References of one symbol listed with `I`, while open
+    pub impact_panel: Option<ImpactPanel>,
+    // Matches of the `@` search and the highlighted one
+    pub symbol_hits: Vec<IndexedSymbol>,
+    pub symbol_hit: usize,
+    // Quick actions popup for the selected Structure item
+    pub action_menu: Option<ActionMenu>,
+    // Highlighted row of the CI checks popup (`C`), while it is open
+    pub checks_cursor: Option<usize>,
+    // Highlighted row of the profile picker (`P`): 0 is the base settings, then each profile
+    pub profile_cursor: Option<usize>,
+    // Top row of the PR description popup (`D`), while it is open
+    pub description_scroll: Option<u16>,
+    // Reviewer / assignee picker (`A`), while it is open
+    pub people_picker: Option<PeoplePicker>,
+    // Label picker (`L`), while it is open
+    pub label_picker: Option<LabelPicker>,
+    // Review threads (path, line) whose suggested change the local clone already has
+    pub applied_suggestions: HashSet<(String, usize)>,
+    // Per-file verdicts given with `F` on this repository or PR
+    pub verdicts: Verdicts,
+    // Private line notes left with `n` on this repository or PR
+    pub notes: Notes,
+    // Files shown in the Logic view this session, for the review progress
+    pub opened: HashSet<String>,
+    // Files making the same edit, folded under their first file unless expanded with `g`
+    pub clusters: Vec<Cluster>,
+    // Keys of the clusters listed in full
+    pub expanded_clusters: HashSet<String>,
+    // Symbols the changeset newly makes public, for the Galaxy view
+    pub api_changes: Vec<ApiSymbol>,
+    // Public Rust items whose signature the changeset changes or that it removes
+    pub api_breaks: Vec<ApiBreak>,
+    // Open PRs to choose from when started without one
+    pub pr_picker: Option<PrPicker>,
+    // The review queue to go back to from the PR picked off it
+    pub review_queue: Option<PrPicker>,
+    // Config-defined Galaxy panels, run once per load
+    pub panels: Vec<Panel>,
+    // Review being composed for the loaded PR
+    pub review_draft: Option<ReviewDraft>,
+    // Comment being written on a line of the PR's diff
+    pub comment_draft: Option<CommentDraft>,
+    // Private note being written on a line of the diff
+    pub note_draft: Option<CommentDraft>,
+    // Branch list popup (local sources only)
+    pub branch_panel: Option<BranchPanel>,
+    // File (and line) the main loop should open in $EDITOR after suspending the TUI
+    pub pending_editor: Option<(PathBuf, Option<usize>)>,
+    // Locations left by zooms and searches, walked with Ctrl-o / Ctrl-i
+    pub jumps: JumpList,
+    // Recorded key sequences, replayed with `@<reg>`
+    pub macros: MacroRecorder,
+    // One-shot feedback shown in the footer until the next key press
+    pub notice: Option<String>,
+    // Destructive action awaiting a y/n answer
+    pub pending_confirm: Option<PendingConfirm>,
+    // Working tree stashed for the session; restored on toggle or exit
+    pub safe_mode: Option<SafeMode>,
+    // Crates of a Cargo workspace at the repository root; the Galaxy view groups by crate
+    pub cargo: Option<CargoWorkspace>,
+    // JavaScript workspace packages and Go modules, grouped like crates
+    pub packages: Packages,
+    // Recent commits per path, raising the heat of files that keep changing
+    pub churn: Churn,
+    // Index entries replaced by recent stage / unstage actions, reverted with `U`
+    pub staging_undo: StagingUndo,
+    pub config: Config,
+    // First-run setup screen, shown over everything until saved or skipped
+    pub onboarding: Option<Onboarding>,
 }
 
 impl fmt::Debug for App {
@@ -112,65 +494,468 @@ impl Default for App {
     }
 }
 
+// The last chance to hand back a safe-mode stash, e.g. when a panic unwinds past the main loop
+impl Drop for App {
+    fn drop(&mut self) {
+        if self.safe_mode.is_none() {
+            return;
+        }
+        match self.restore_safe_mode() {
+            Ok(()) => eprintln!("glim: restored the working tree stashed by safe mode"),
+            Err(e) => eprintln!(
+                "glim: could not restore the safe mode stash ({e:#}); recover it with `git stash pop`"
+            ),
+        }
+    }
+}
+
 impl App {
     #[must_use]
     pub fn new(path: PathBuf) -> Self {
-        let mut app = Self {
+        Self::with_config(path, Config::default())
+    }
+
+    #[must_use]
+    pub fn with_config(path: PathBuf, config: Config) -> Self {
+        let path_str = path.to_string_lossy().to_string();
+        // A repository URL without a PR number asks which PR to review
+        if let Some(repo) = repo_url_slug(&path_str) {
+            return Self::with_pr_picker(Some(repo), config);
+        }
+        let mut app = Self::unloaded(path.clone(), config);
+        app.load_symbol_queries();
+
+        // Determine mode: existing paths first (a directory may well be called `42`), then
+        // commits and PR refs, then branch names; what can only be a path must exist
+        let exists = path.exists();
+        let commit_ref = CommitRef::parse(&path_str).filter(|_| !exists);
+        let pr_ref = PrRef::parse(&path_str)
+            .or_else(|| (!is_path_like(&path)).then(|| PrRef::Branch(path_str.clone())))
+            .filter(|_| !exists);
+        if !exists && is_path_like(&path) {
+            app.error_msg = Some(format!("Local Error: {} does not exist", path.display()));
+        } else if let Some(commit_ref) = commit_ref {
+            if let Err(e) = app.load_commit(&commit_ref) {
+                app.gh_error = e.downcast_ref::<GhError>().cloned();
+                app.error_msg = Some(format!("Commit Error: {e}"));
+            }
+        } else if let Some(pr_ref) = pr_ref {
+            match app.load_github(&pr_ref) {
+                Ok(()) => {}
+                Err(e) => {
+                    app.gh_error = e.downcast_ref::<GhError>().cloned();
+                    app.error_msg = Some(format!("GitHub Error: {e}"));
+                }
+            }
+        } else {
+            match app.load_local(path) {
+                Ok(()) => {}
+                Err(e) => app.error_msg = Some(format!("Local Error: {e}")),
+            }
+        }
+
+        // Match the diff colours of the user's other git tooling
+        let git_config = match &app.source {
+            Some(DataSource::Local { repo, .. }) => repo.config().ok(),
+            _ => git2::Config::open_default().ok(),
+        };
+        if let Some(git_config) = git_config {
+            app.diff_theme = DiffTheme::from_git_config(&git_config);
+        }
+
+        app.run_panels();
+        if let Some(key) = app.session_key() {
+            app.verdicts = Verdicts::load(&key);
+            app.notes = Notes::load(&key);
+        }
+        app.survey_changes();
+        app.find_applied_suggestions();
+
+        // Start on whichever section has something in it
+        if !app.structures.iter().any(|i| i.is_unstaged)
+            && app.structures.iter().any(|i| i.is_staged)
+        {
+            app.structure_section = StructureSection::Staged;
+        }
+
+        if !app.structures.is_empty() {
+            app.update_search(); // Initialize filtered list
+            app.load_diff();
+        }
+
+        app.apply_start_view();
+        app
+    }
+
+    /// State before anything is loaded.
+    fn unloaded(path: PathBuf, config: Config) -> Self {
+        Self {
             zoom_level: ZoomLevel::Galaxy,
             modules: vec![],
             structures: vec![],
             logic_view_content: vec![],
             filtered_structure_indices: vec![],
             selected_index: 0,
+            structure_anchor: None,
+            logic_cursor: 0,
+            logic_anchor: None,
             analyzer: SemanticAnalyzer::new(),
             source: None,
+            repo_snapshot: None,
             error_msg: None,
-            repo_root: path.clone(),
+            gh_error: None,
+            repo_root: path,
             dashboard_info: DashboardInfo::default(),
             context_lines: 3,
+            diff_scope: DiffScope::All,
+            show_blame: false,
+            blame_lines: vec![],
+            merge_base: None,
+            diff_theme: DiffTheme::default(),
+            wrap_lines: true,
+            h_scroll: 0,
+            logic_wrap: (0, 0),
+            logic_viewport: Cell::new((0, 0)),
+            highlighter: Highlighter::default(),
+            changes_only: false,
+            conflict: None,
+            binary: None,
+            lfs: None,
+            hunk_stages: Vec::new(),
+            history: None,
+            showing_drift: false,
+            structure_section: StructureSection::Unstaged,
+            other_section_index: 0,
             input_mode: InputMode::Normal,
             search_query: String::new(),
-        };
+            action_menu: None,
+            review_draft: None,
+            comment_draft: None,
+            note_draft: None,
+            pr_picker: None,
+            review_queue: None,
+            checks_cursor: None,
+            profile_cursor: None,
+            description_scroll: None,
+            people_picker: None,
+            label_picker: None,
+            applied_suggestions: HashSet::new(),
+            opened: HashSet::new(),
+            verdicts: Verdicts::default(),
+            notes: Notes::default(),
+            clusters: Vec::new(),
+            expanded_clusters: HashSet::new(),
+            api_changes: Vec::new(),
+            api_breaks: Vec::new(),
+            panels: Vec::new(),
+            branch_panel: None,
+            pending_editor: None,
+            symbol_index: Arc::default(),
+            index_job: None,
+            index_queue: None,
+            history_fetch: None,
+            history_load: None,
+            indexed: false,
+            impact: Impact::new(),
+            impact_panel: None,
+            symbol_hits: Vec::new(),
+            symbol_hit: 0,
+            jumps: JumpList::default(),
+            macros: MacroRecorder::default(),
+            notice: None,
+            pending_confirm: None,
+            safe_mode: None,
+            cargo: None,
+            packages: Packages::default(),
+            churn: Churn::default(),
+            staging_undo: StagingUndo::default(),
+            config,
+            onboarding: None,
+        }
+    }
 
-        // Determine mode
-        let path_str = path.to_string_lossy();
-        if path_str.starts_with("http")
-            || path_str.contains("github.com")
-            || path_str.contains("/pull/")
-        {
-            match app.load_github(&path_str) {
-                Ok(()) => {}
-                Err(e) => app.error_msg = Some(format!("GitHub Error: {e}")),
+    /// Lists the open PRs of `repo` (or of the current directory's repository) to pick one
+    /// to review.
+    #[must_use]
+    pub fn with_pr_picker(repo: Option<String>, config: Config) -> Self {
+        Self::picking(
+            PrPicker {
+                repo,
+                ..PrPicker::default()
+            },
+            config,
+        )
+    }
+
+    /// Lists the open PRs awaiting the user's review, in `queue_repos` or anywhere, to work
+    /// through one by one.
+    #[must_use]
+    pub fn with_review_queue(config: Config) -> Self {
+        Self::picking(PrPicker::review_queue(), config)
+    }
+
+    /// Fills `picker` with its PRs (the repository's open ones, or the review queue).
+    fn picking(mut picker: PrPicker, config: Config) -> Self {
+        let mut app = Self::unloaded(PathBuf::from("."), config);
+        let listed = GitHubClient::check_auth().and_then(|()| {
+            if picker.queue.is_some() {
+                GitHubClient::review_requests(&app.config.queue_repos)
+            } else {
+                GitHubClient::list_prs(picker.repo.as_deref())
+            }
+        });
+        match listed {
+            Ok(entries) => {
+                if entries.is_empty() {
+                    app.error_msg = Some(if picker.queue.is_some() {
+                        "No reviews requested".to_string()
+                    } else {
+                        "No open pull requests".to_string()
+                    });
+                }
+                picker.entries = entries;
             }
+            Err(e) => {
+                app.gh_error = e.downcast_ref::<GhError>().cloned();
+                app.error_msg = Some(format!("GitHub Error: {e}"));
+            }
+        }
+        picker.selected = 0;
+        // Kept when empty too, so a retry lists again
+        app.pr_picker = Some(picker);
+        app
+    }
+
+    /// Loads the PR highlighted in the picker, as if it had been given on the command line.
+    pub fn open_picked_pr(&mut self) {
+        let Some(url) = self
+            .pr_picker
+            .as_ref()
+            .and_then(PrPicker::current)
+            .map(|pr| pr.url.clone())
+        else {
+            return;
+        };
+        let queue = self.pr_picker.take().filter(|p| p.queue.is_some());
+        *self = Self::with_config(PathBuf::from(url), self.config.clone());
+        self.review_queue = queue;
+        self.offer_session_restore();
+    }
+
+    /// Leaves the PR picked off the review queue and shows the queue again.
+    pub fn return_to_queue(&mut self) {
+        let Some(queue) = self.review_queue.take() else {
+            return;
+        };
+        *self = Self::unloaded(PathBuf::from("."), self.config.clone());
+        self.pr_picker = Some(queue);
+    }
+    /// Runs the configured Galaxy panel commands in the repository (or the current directory
+    /// for a PR).
+    fn run_panels(&mut self) {
+        let Some(source) = self.session_key() else {
+            return;
+        };
+        let dir = self
+            .local_root()
+            .map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+        // Side by side, so slow commands only cost the slowest one's time
+        self.panels = std::thread::scope(|scope| {
+            // Collected so every command starts before the first join
+            #[allow(clippy::needless_collect)]
+            let runs: Vec<_> = self
+                .config
+                .panels
+                .iter()
+                .map(|panel| scope.spawn(|| Panel::run(panel, &dir, &source)))
+                .collect();
+            runs.into_iter().filter_map(|run| run.join().ok()).collect()
+        });
+    }
+
+    /// Closes the first-run screen and writes the config file, so it does not come back:
+    /// the config chosen on it when `apply` is set, the one in effect when it was skipped.
+    pub fn finish_onboarding(&mut self, apply: bool) {
+        let Some(onboarding) = self.onboarding.take() else {
+            return;
+        };
+        let config = if apply {
+            &onboarding.config
         } else {
-            match app.load_local(path) {
-                Ok(()) => {}
-                Err(e) => app.error_msg = Some(format!("Local Error: {e}")),
+            &self.config
+        };
+        self.notice = Some(match config.save() {
+            Ok(()) => format!("Config written to {}", Config::path().display()),
+            Err(e) => format!("{e:#}"),
+        });
+        if apply {
+            self.config = onboarding.config;
+            self.apply_start_view();
+        }
+    }
+
+    /// Reloads everything from scratch, e.g. after fixing a `gh` login problem.
+    pub fn retry(&mut self) {
+        if let Some(picker) = self.pr_picker.take() {
+            *self = Self::picking(picker, self.config.clone());
+            return;
+        }
+        *self = Self::with_config(self.repo_root.clone(), self.config.clone());
+    }
+
+    /// Zooms from Galaxy to the configured start view, skipping Galaxy for small changesets.
+    fn apply_start_view(&mut self) {
+        let mut target = self.config.start_view;
+        let changed_files = self.changed_file_count();
+        let trivial_galaxy = self.config.auto_skip && self.modules.len() == 1;
+        if target == ZoomLevel::Galaxy
+            && changed_files > 0
+            && (changed_files <= self.config.skip_galaxy_max_files || trivial_galaxy)
+        {
+            target = ZoomLevel::Structure;
+        }
+
+        while self.zoom_level != target {
+            let before = self.zoom_level;
+            self.zoom_in();
+            if self.zoom_level == before {
+                break; // Nothing to zoom into (e.g. empty changeset)
             }
         }
+    }
 
-        if !app.structures.is_empty() {
-            app.update_search(); // Initialize filtered list
-            app.load_diff();
+    #[must_use]
+    pub fn changed_file_count(&self) -> usize {
+        self.structures.iter().filter(|i| i.is_file).count()
+    }
+
+    /// Moves the Structure selection onto the first file (skipping directory headers).
+    fn select_first_file(&mut self) {
+        if let Some(pos) = self
+            .filtered_structure_indices
+            .iter()
+            .position(|&i| self.structures[i].is_file)
+        {
+            self.selected_index = pos;
+            self.load_diff();
         }
+    }
 
-        app
+    /// Zoom levels from Galaxy down to the current one, with the selected file for Logic.
+    #[must_use]
+    pub fn breadcrumbs(&self) -> Vec<String> {
+        let mut crumbs = vec!["Galaxy".to_string()];
+        if matches!(self.zoom_level, ZoomLevel::Structure | ZoomLevel::Logic) {
+            crumbs.push("Structure".to_string());
+        }
+        if matches!(self.zoom_level, ZoomLevel::Logic) {
+            crumbs.push(
+                self.selected_item()
+                    .map_or_else(|| "Logic".to_string(), |i| i.path.clone()),
+            );
+        }
+        crumbs
+    }
+
+    /// The Structure item currently selected (through the search filter).
+    #[must_use]
+    pub fn selected_item(&self) -> Option<&StructureItem> {
+        self.filtered_structure_indices
+            .get(self.selected_index)
+            .and_then(|&i| self.structures.get(i))
+    }
+
+    /// CI annotations for each Logic view line (aligned with `logic_view_content`).
+    #[must_use]
+    pub fn line_annotations(&self) -> Vec<Vec<&CheckAnnotation>> {
+        let numbers = new_line_numbers(&self.logic_view_content);
+        let (Some(review), Some(item)) = (self.pr_review(), self.selected_item()) else {
+            return vec![vec![]; numbers.len()];
+        };
+        numbers
+            .into_iter()
+            .map(|line| {
+                review
+                    .annotations
+                    .iter()
+                    .filter(|a| a.path == item.path && Some(a.line) == line)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// PR review threads anchored to each Logic view line (aligned with `logic_view_content`).
+    #[must_use]
+    pub fn line_threads(&self) -> Vec<Vec<&ReviewThread>> {
+        let numbers = line_numbers(&self.logic_view_content);
+        // The base drift diff is not the PR's, so its line numbers do not match
+        let (Some(review), Some(item), false, true) = (
+            self.pr_review(),
+            self.selected_item(),
+            self.showing_drift,
+            self.config.show_comments,
+        ) else {
+            return vec![vec![]; numbers.len()];
+        };
+        numbers
+            .into_iter()
+            .map(|(old, new)| {
+                review
+                    .threads
+                    .iter()
+                    .filter(|t| {
+                        t.path == item.path && Some(t.line) == if t.left { old } else { new }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The private notes on each line of the Logic view; none on the base drift or on a
+    /// commit from the file's history, whose line numbers are not the changeset's.
+    #[must_use]
+    pub fn line_notes(&self) -> Vec<Vec<&LineNote>> {
+        let numbers = line_numbers(&self.logic_view_content);
+        let (Some(item), false, None) = (self.selected_item(), self.showing_drift, &self.history)
+        else {
+            return vec![vec![]; numbers.len()];
+        };
+        let notes = self.notes.on(&item.path);
+        numbers
+            .into_iter()
+            .map(|(old, new)| {
+                notes
+                    .iter()
+                    .filter(|n| Some(n.line) == if n.left { old } else { new })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Review threads, viewed state, and CI annotations when reviewing a PR.
+    #[must_use]
+    pub const fn pr_review(&self) -> Option<&PrReviewData> {
+        match &self.source {
+            Some(DataSource::GitHub { review, .. }) => Some(review),
+            _ => None,
+        }
     }
 
     // --- Search Logic ---
     pub fn update_search(&mut self) {
-        if self.search_query.is_empty() {
-            self.filtered_structure_indices = (0..self.structures.len()).collect();
-        } else {
-            let query = self.search_query.to_lowercase();
-            self.filtered_structure_indices = self
-                .structures
-                .iter()
-                .enumerate()
-                .filter(|(_, item)| item.text.to_lowercase().contains(&query))
-                .map(|(i, _)| i)
-                .collect();
+        if let Some(name) = self.search_query.strip_prefix('@') {
+            let name = name.trim().to_string();
+            self.symbol_hits = match self.symbol_index.lock() {
+                Ok(index) if !name.is_empty() => index.search(&name, SYMBOL_HITS),
+                _ => Vec::new(),
+            };
+            self.symbol_hit = 0;
+            return;
         }
+        self.symbol_hits.clear();
+        self.filtered_structure_indices = self.section_indices(self.structure_section);
+        // Positions in the old list mean nothing in the new one
+        self.structure_anchor = None;
         // Reset selection if out of bounds or empty
         if self.filtered_structure_indices.is_empty()
             || self.selected_index >= self.filtered_structure_indices.len()
@@ -181,13 +966,103 @@ impl App {
         self.load_diff();
     }
 
+    /// Whether the Structure view is split into Staged / Unstaged sections.
+    #[must_use]
+    pub const fn has_sections(&self) -> bool {
+        matches!(self.source, Some(DataSource::Local { .. })) && self.merge_base.is_none()
+    }
+
+    /// Structure indices in `section` that match the search query.
+    #[must_use]
+    pub fn section_indices(&self, section: StructureSection) -> Vec<usize> {
+        let query = SearchQuery::parse(&self.search_query);
+        let sectioned = self.has_sections();
+        let in_section = |item: &StructureItem| {
+            !sectioned
+                || match section {
+                    StructureSection::Staged => item.is_staged,
+                    StructureSection::Unstaged => item.is_unstaged,
+                }
+        };
+        // Symbol scopes keep the files of matching symbols as headings
+        let symbol_files: HashSet<&str> = if query.is_symbol_scoped() {
+            self.structures
+                .iter()
+                .filter(|item| in_section(item) && query.matches(item))
+                .map(|item| item.path.as_str())
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        // Folded clusters show only the first of their files listed in this section
+        let folded: HashMap<&str, &str> = self
+            .clusters
+            .iter()
+            .filter(|c| query.is_empty() && !self.expanded_clusters.contains(&c.key))
+            .flat_map(|c| c.paths.iter().map(|p| (p.as_str(), c.key.as_str())))
+            .collect();
+        let mut shown: HashMap<&str, &str> = HashMap::new();
+        self.structures
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| in_section(item))
+            .filter(|(_, item)| {
+                folded
+                    .get(item.path.as_str())
+                    .is_none_or(|key| *shown.entry(key).or_insert(item.path.as_str()) == item.path)
+            })
+            .filter(|(_, item)| {
+                query.is_empty()
+                    || query.matches(item)
+                    || (item.is_file && symbol_files.contains(item.path.as_str()))
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Moves focus to the other Structure section, keeping each section's selection.
+    pub fn switch_section(&mut self) {
+        if !self.has_sections() || !matches!(self.zoom_level, ZoomLevel::Structure) {
+            return;
+        }
+        self.structure_section = match self.structure_section {
+            StructureSection::Staged => StructureSection::Unstaged,
+            StructureSection::Unstaged => StructureSection::Staged,
+        };
+        std::mem::swap(&mut self.selected_index, &mut self.other_section_index);
+        self.update_search();
+    }
+
     pub fn enter_search(&mut self) {
+        self.record_jump();
         self.input_mode = InputMode::Editing;
         self.search_query.clear();
         self.update_search();
     }
 
-    pub const fn exit_search(&mut self) {
+    /// Whether the search box holds a repo-wide `@` symbol search.
+    #[must_use]
+    pub fn is_symbol_search(&self) -> bool {
+        self.input_mode == InputMode::Editing && self.search_query.starts_with('@')
+    }
+
+    pub fn move_symbol_hit(&mut self, down: bool) {
+        self.symbol_hit = if down {
+            (self.symbol_hit + 1).min(self.symbol_hits.len().saturating_sub(1))
+        } else {
+            self.symbol_hit.saturating_sub(1)
+        };
+    }
+
+    pub fn exit_search(&mut self) {
+        if self.search_query.starts_with('@') {
+            let hit = self.symbol_hits.get(self.symbol_hit).cloned();
+            self.cancel_search();
+            if let Some(hit) = hit {
+                self.go_to_symbol(&hit);
+            }
+            return;
+        }
         self.input_mode = InputMode::Normal;
         // Keep the filter? No, standard behavior is usually reset or keep.
         // Let's reset for now if Esc is pressed, but if Enter was used we might keep it.
@@ -210,93 +1085,193 @@ impl App {
             |n| n.to_string_lossy().to_string(),
         );
 
+        if self.config.merge_base {
+            self.merge_base = Some(Self::resolve_merge_base(&repo, &self.config.base_branch)?);
+        }
+        self.cargo = CargoWorkspace::load(&path);
+        self.packages = Packages::detect(&path);
+        self.churn = Churn::compute(&repo, self.config.heat_half_life_days);
+        let base_tree = Self::base_tree(&repo, self.merge_base);
+        let (_, structures) = Self::scan_local_repo(
+            &repo,
+            &path,
+            &mut self.analyzer,
+            &self.config.heat_weights,
+            &self.config.scan,
+            base_tree.as_ref(),
+        );
+        let modules = self.local_modules(&repo, base_tree.as_ref(), &structures);
+
+        self.dashboard_info = self.local_dashboard(&repo, repo_name, &structures);
+        drop(base_tree);
+        self.repo_snapshot = Some(RepoSnapshot::of(&repo));
+        self.modules = modules;
+        self.structures = structures;
+        self.source = Some(DataSource::Local { repo, root: path });
+        self.staging_undo.clear();
+        self.reset_index();
+        self.request_index(IndexRequest::full(self.changed_files()));
+        Ok(())
+    }
+
+    fn local_dashboard(
+        &self,
+        repo: &Repository,
+        repo_name: String,
+        structures: &[StructureItem],
+    ) -> DashboardInfo {
+        let base_tree = Self::base_tree(repo, self.merge_base);
         let branch_name = repo.head().map_or_else(
             |_| "Empty Repo".to_string(),
             |head| head.shorthand().unwrap_or("DETACHED HEAD").to_string(),
         );
-
-        let (modules, structures) = Self::scan_local_repo(&repo, &path, &mut self.analyzer);
-
-        self.dashboard_info = DashboardInfo {
+        let warnings = CloneState::of(repo)
+            .label()
+            .map(|label| format!("{label}: history is incomplete"))
+            .into_iter()
+            .collect();
+        DashboardInfo {
             repo_name,
             branch_name,
-            description: "Local Working Tree Changes".to_string(),
+            warnings,
+            description: self.merge_base.map_or_else(
+                || "Local Working Tree Changes".to_string(),
+                |oid| {
+                    format!(
+                        "Changes since merge-base with {} ({:.7})",
+                        self.config.base_branch, oid
+                    )
+                },
+            ),
             stats: format!(
                 "{} files changed",
                 structures.iter().filter(|i| i.is_file).count()
             ),
-        };
+            breakdown: Self::local_breakdown(repo, base_tree.as_ref()).summary(),
+        }
+    }
 
-        self.modules = modules;
-        self.structures = structures;
-        self.source = Some(DataSource::Local { repo, root: path });
-        Ok(())
+    /// The PR from GitHub, with its diff from the on-disk cache while its head commit is
+    /// unchanged; plus the base drift, which is always fetched fresh. Review data (threads,
+    /// checks, viewed files) moves without new commits, so it is fetched every time. Without
+    /// GitHub the last cached copy stands in, flagged by the returned `true`; `refresh` skips
+    /// the cache entirely.
+    fn fetch_github(
+        pr_ref: &PrRef,
+        refresh: bool,
+    ) -> anyhow::Result<(CachedPr, Option<BaseDrift>, bool)> {
+        let (info, review) =
+            match GitHubClient::check_auth().and_then(|()| GitHubClient::fetch_pr(pr_ref)) {
+                Ok(fetched) => fetched,
+                // Only a missing connection makes the cached copy the best there is
+                Err(e) if refresh || !is_offline(&e) => return Err(e),
+                Err(e) => {
+                    let cached = pr_ref
+                        .offline_url(Self::origin_slug().as_deref())
+                        .and_then(|url| CachedPr::load_latest(&url));
+                    return cached.map(|cached| (cached, None, true)).ok_or(e);
+                }
+            };
+        // Drift is best-effort; a failed fetch should not block the review
+        if let Some(cached) = CachedPr::load(&info.url, &info.head_ref_oid).filter(|_| !refresh) {
+            let drift = GitHubClient::fetch_base_drift(&info).ok().flatten();
+            let pr = CachedPr {
+                info,
+                review,
+                ..cached
+            };
+            // Kept for reviewing offline
+            let _ = pr.save();
+            return Ok((pr, drift, false));
+        }
+        // The diff and the base comparison are independent REST calls; fetch them side by side
+        let (diff, drift) = std::thread::scope(|scope| {
+            let drift = scope.spawn(|| GitHubClient::fetch_base_drift(&info));
+            // GitHub will not render diffs of huge PRs; an empty diff makes them load per file
+            let diff = if info.files.len() > MAX_DIFF_FILES {
+                Ok(String::new())
+            } else {
+                match GitHubClient::fetch_pr_diff(&info) {
+                    Err(e) if diff_too_large(&e.to_string()) => Ok(String::new()),
+                    diff => diff,
+                }
+            };
+            (diff, drift.join())
+        });
+        let pr = CachedPr::new(info, review, diff?);
+        // A read-only data directory only means the next load fetches again
+        let _ = pr.save();
+        Ok((pr, drift.ok().and_then(Result::ok).flatten(), false))
     }
 
-    fn load_github(&mut self, pr_ref: &str) -> anyhow::Result<()> {
-        GitHubClient::check_auth()?;
-        let info = GitHubClient::fetch_pr_info(pr_ref)?;
-        let raw_diff = GitHubClient::fetch_pr_diff(pr_ref)?;
+    /// `owner/repo` of the `origin` remote of the repository glim runs in, for PRs given by
+    /// number.
+    fn origin_slug() -> Option<String> {
+        let repo = Repository::discover(std::env::current_dir().ok()?).ok()?;
+        let remote = repo.find_remote("origin").ok()?;
+        remote.url().and_then(remote_slug)
+    }
 
-        let file_diffs = Self::split_diff(&raw_diff);
+    fn load_github(&mut self, pr_ref: &PrRef) -> anyhow::Result<()> {
+        let (
+            CachedPr {
+                info,
+                review,
+                diff: raw_diff,
+                patches,
+                fetched_at,
+            },
+            drift,
+            offline,
+        ) = Self::fetch_github(pr_ref, self.config.refresh)?;
+        let drift = drift.map(Box::new);
 
-        let mut dir_counts: HashMap<String, usize> = HashMap::new();
-        let mut structures = Vec::new();
+        let mut file_diffs = Self::split_diff(&raw_diff);
+        file_diffs.extend(patches);
 
-        for file in &info.files {
-            let path = Path::new(&file.path);
-            if let Some(parent) = path.parent() {
-                let parent_str = parent.to_string_lossy().to_string();
-                *dir_counts
-                    .entry(if parent_str.is_empty() {
-                        "root".to_string()
-                    } else {
-                        parent_str
-                    })
-                    .or_insert(0) += 1;
-            }
+        let structures = Self::file_tree(&info.files, &file_diffs);
 
-            structures.push(StructureItem {
-                text: file.path.clone(),
-                path: file.path.clone(),
-                is_file: true,
-                status: format!("+{} -{}", file.additions, file.deletions),
-                line_no: None,
-                is_staged: false,
-            });
-        }
-
-        let mut modules: Vec<Module> = dir_counts
-            .into_iter()
-            .map(|(name, count)| {
-                let heat = (count * 10).min(100) as u8;
-                Module {
-                    name,
-                    heat,
-                    description: format!("{count} changed files"),
-                }
-            })
-            .collect();
-        modules.sort_by(|a, b| b.heat.cmp(&a.heat));
+        let modules = Self::build_modules(
+            info.files.iter().map(|f| f.path.as_str()),
+            &self.config.heat_weights,
+        );
 
         // Parse repo name from URL (e.g., https://github.com/owner/repo/pull/123)
-        let repo_name = info
-            .url
-            .trim_start_matches("https://github.com/")
-            .split("/pull/")
-            .next()
-            .unwrap_or("Unknown Repo")
-            .to_string();
+        let repo_name = info.repo_slug().unwrap_or("Unknown Repo").to_string();
+
+        let mut stats = format!(
+            "+{} -{} ({} files)",
+            info.additions, info.deletions, info.changed_files
+        );
+        // GitHub lists at most 3000 files of a PR
+        if (info.files.len() as u64) < info.changed_files {
+            stats = format!("{stats}, only {} listed", info.files.len());
+        }
+        if raw_diff.is_empty() && !info.files.is_empty() {
+            stats = format!("{stats}, diffs load per file");
+        }
+
+        let mut warnings = Vec::new();
+        if offline {
+            warnings.push(format!("offline copy from {} ago", format_age(fetched_at)));
+        }
+        if let Some(drift) = &drift {
+            warnings.push(drift.warning(&info));
+        }
 
         // Populate Dashboard
         self.dashboard_info = DashboardInfo {
             repo_name,
             branch_name: format!("#{}", info.number),
+            warnings,
             description: info.title.clone(),
-            stats: format!(
-                "+{} -{} ({} files)",
-                info.additions, info.deletions, info.changed_files
-            ),
+            stats,
+            breakdown: ChangeBreakdown::from_files(
+                info.files
+                    .iter()
+                    .map(|f| (f.path.as_str(), f.additions, f.deletions)),
+            )
+            .summary(),
         };
 
         self.modules = modules;
@@ -305,12 +1280,120 @@ impl App {
             pr_info: Box::new(info),
             raw_diff,
             file_diffs,
+            review: Box::new(review),
+            drift,
         });
 
         Ok(())
     }
 
-    fn split_diff(raw: &str) -> HashMap<String, Vec<String>> {
+    /// Structure items for a changeset fetched as a whole: files grouped under their
+    /// directories, so large PRs and commits read as a tree.
+    fn file_tree(
+        files: &[PrFile],
+        file_diffs: &HashMap<String, Vec<String>>,
+    ) -> Vec<StructureItem> {
+        let mut dirs: BTreeMap<String, Vec<&PrFile>> = BTreeMap::new();
+        for file in files {
+            dirs.entry(Self::module_name(&file.path))
+                .or_default()
+                .push(file);
+        }
+
+        let mut structures = Vec::new();
+        for (dir, files) in dirs {
+            let additions: u64 = files.iter().map(|f| f.additions).sum();
+            let deletions: u64 = files.iter().map(|f| f.deletions).sum();
+            structures.push(StructureItem {
+                text: format!("{dir}/ (+{additions} -{deletions})"),
+                path: String::new(),
+                fs_path: PathBuf::new(),
+                is_file: false,
+                status: "dir".to_string(),
+                line_no: None,
+                is_staged: false,
+                is_unstaged: false,
+                skip_reason: None,
+                mode_change: None,
+                touched: false,
+                complexity: None,
+            });
+
+            for file in files {
+                let name = Path::new(&file.path)
+                    .file_name()
+                    .map_or_else(|| file.path.clone(), |n| n.to_string_lossy().to_string());
+                structures.push(StructureItem {
+                    text: format!("  {name}"),
+                    path: file.path.clone(),
+                    fs_path: PathBuf::from(&file.path),
+                    is_file: true,
+                    status: format!("+{} -{}", file.additions, file.deletions),
+                    line_no: None,
+                    is_staged: false,
+                    is_unstaged: false,
+                    skip_reason: None,
+                    mode_change: file_diffs.get(&file.path).and_then(|l| mode_change(l)),
+                    touched: false,
+                    complexity: None,
+                });
+            }
+        }
+        structures
+    }
+
+    /// Loads a single commit's or a comparison's files and diff into the normal views.
+    fn load_commit(&mut self, commit_ref: &CommitRef) -> anyhow::Result<()> {
+        let (commit, file_diffs) = match commit_ref {
+            CommitRef::GitHub { repo, sha } => {
+                GitHubClient::check_auth()?;
+                let (commit, diff) = GitHubClient::fetch_commit(repo, sha)?;
+                (commit, Self::split_diff(&diff))
+            }
+            CommitRef::Compare { repo, range } => {
+                GitHubClient::check_auth()?;
+                let (commit, diff) = GitHubClient::fetch_compare(repo, range)?;
+                (commit, Self::split_diff(&diff))
+            }
+            CommitRef::Local(rev) => {
+                let repo = Repository::discover(".")?;
+                let (commit, diff) = CommitInfo::load_local(&repo, rev, self.context_lines)?;
+                (commit, Self::patch_lines(&diff).into_iter().collect())
+            }
+        };
+        let files = diff_files(&file_diffs);
+        let (additions, deletions) = files
+            .iter()
+            .fold((0, 0), |(a, d), f| (a + f.additions, d + f.deletions));
+        self.dashboard_info = DashboardInfo {
+            repo_name: commit.repo.clone(),
+            branch_name: commit.label(),
+            warnings: Vec::new(),
+            description: format!("{} ({})", commit.summary, commit.author),
+            stats: format!("+{additions} -{deletions} ({} files)", files.len()),
+            breakdown: ChangeBreakdown::from_files(
+                files
+                    .iter()
+                    .map(|f| (f.path.as_str(), f.additions, f.deletions)),
+            )
+            .summary(),
+        };
+        self.modules = Self::build_modules(
+            files.iter().map(|f| f.path.as_str()),
+            &self.config.heat_weights,
+        );
+        self.structures = Self::file_tree(&files, &file_diffs);
+        self.source = Some(DataSource::Commit {
+            commit: Box::new(commit),
+            file_diffs,
+        });
+        Ok(())
+    }
+
+    /// Splits a unified diff into per-file line lists, keyed by new path.
+    #[doc(hidden)]
+    #[must_use]
+    pub fn split_diff(raw: &str) -> HashMap<String, Vec<String>> {
         let mut map = HashMap::new();
         let mut current_file = String::new();
         let mut current_lines = Vec::new();
@@ -334,71 +1417,296 @@ impl App {
         map
     }
 
-    fn scan_local_repo(
+    /// Builds Galaxy modules and Structure items from the repository status.
+    #[doc(hidden)]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn scan_local_repo(
         repo: &Repository,
         root: &Path,
         analyzer: &mut SemanticAnalyzer,
+        weights: &HashMap<String, f32>,
+        filter: &PathFilter,
+        base: Option<&Tree>,
     ) -> (Vec<Module>, Vec<StructureItem>) {
         let mut status_opts = StatusOptions::new();
-        status_opts.include_untracked(true);
+        status_opts.include_untracked(true).include_unreadable(true);
+        if Self::ignores_case(repo) {
+            status_opts.sort_case_insensitively(true);
+        }
 
         let statuses = repo
             .statuses(Some(&mut status_opts))
             .unwrap_or_else(|_| repo.statuses(None).expect("Failed to get statuses"));
 
-        let mut dir_counts: HashMap<String, usize> = HashMap::new();
+        let worktree: HashMap<Vec<u8>, git2::Status> = statuses
+            .iter()
+            .map(|e| (e.path_bytes().to_vec(), e.status()))
+            .collect();
+        // In merge-base mode the file list comes from the branch diff instead
+        let changes = base.map_or_else(
+            || {
+                statuses
+                    .iter()
+                    .map(|e| (e.path_bytes().to_vec(), e.status()))
+                    .collect()
+            },
+            |tree| Self::branch_changes(repo, tree),
+        );
+
+        let modes = Self::mode_changes(repo, base);
+        let hunks = Self::hunk_spans(repo, base, None);
         let mut structures = Vec::new();
 
-        for entry in statuses.iter() {
-            let path_str = entry.path().unwrap_or("unknown").to_string();
-            let status_char = format!("{:?}", entry.status());
+        for (path_bytes, status) in changes {
+            // Non-UTF-8 paths keep their raw bytes for git operations
+            let fs_path = path_from_bytes(&path_bytes);
+            let path_str = String::from_utf8_lossy(&path_bytes).to_string();
+            // Excluded paths are dropped before anything is read from disk
+            if !filter.allows(&path_str) {
+                continue;
+            }
+            let status_char = format!("{status:?}");
+            let local = worktree
+                .get(&path_bytes)
+                .copied()
+                .unwrap_or(git2::Status::CURRENT);
+
+            let is_staged = local.intersects(
+                git2::Status::INDEX_NEW
+                    | git2::Status::INDEX_MODIFIED
+                    | git2::Status::INDEX_DELETED
+                    | git2::Status::INDEX_RENAMED
+                    | git2::Status::INDEX_TYPECHANGE,
+            );
+            let is_unstaged = local.intersects(
+                git2::Status::WT_NEW
+                    | git2::Status::WT_MODIFIED
+                    | git2::Status::WT_DELETED
+                    | git2::Status::WT_RENAMED
+                    | git2::Status::WT_TYPECHANGE
+                    | git2::Status::CONFLICTED,
+            );
 
-            let is_staged = entry.status().contains(git2::Status::INDEX_NEW)
-                || entry.status().contains(git2::Status::INDEX_MODIFIED)
-                || entry.status().contains(git2::Status::INDEX_DELETED);
+            // Only read files the analyzer understands, and never huge or binary blobs
+            let full_path = root.join(&fs_path);
+            let analysis = if analyzer.supports(&path_str) && full_path.exists() {
+                Some(semantics::read_analyzable(&full_path))
+            } else {
+                None
+            };
 
             structures.push(StructureItem {
                 text: path_str.clone(),
                 path: path_str.clone(),
+                fs_path: fs_path.clone(),
                 is_file: true,
                 status: status_char,
                 line_no: None,
                 is_staged,
+                is_unstaged,
+                skip_reason: analysis.as_ref().and_then(|r| r.as_ref().err().copied()),
+                mode_change: modes.get(&path_bytes).copied(),
+                touched: false,
+                complexity: None,
             });
 
-            if let Some(parent) = Path::new(&path_str).parent() {
-                let parent_str = parent.to_string_lossy().to_string();
-                *dir_counts
-                    .entry(if parent_str.is_empty() {
-                        "root".to_string()
-                    } else {
-                        parent_str
-                    })
-                    .or_insert(0) += 1;
+            if let Some(Ok(content)) = analysis {
+                let file = &structures[structures.len() - 1];
+                let spans = hunks.get(&path_bytes).map_or(&[][..], Vec::as_slice);
+                let symbols: Vec<StructureItem> = analyzer
+                    .analyze(&path_str, &content)
+                    .into_iter()
+                    .map(|sym| Self::symbol_item(file, sym, spans))
+                    .collect();
+                structures.extend(symbols);
             }
+        }
 
-            let full_path = root.join(&path_str);
-            if full_path.exists()
-                && let Ok(content) = fs::read_to_string(&full_path)
-            {
-                let symbols = analyzer.analyze(&path_str, &content);
-                for sym in symbols {
-                    structures.push(StructureItem {
-                        text: format!("  {} {}", sym.kind, sym.name),
-                        path: path_str.clone(),
-                        is_file: false,
-                        status: sym.kind,
-                        line_no: Some(sym.start_line),
-                        is_staged: false,
-                    });
+        // Trees of files no longer changed would only take up memory
+        let listed: HashSet<&str> = structures
+            .iter()
+            .filter(|i| i.is_file)
+            .map(|i| i.path.as_str())
+            .collect();
+        analyzer.retain(|path| listed.contains(path));
+
+        let modules = Self::build_modules(
+            structures
+                .iter()
+                .filter(|i| i.is_file)
+                .map(|i| i.path.as_str()),
+            weights,
+        );
+
+        (modules, structures)
+    }
+
+    /// New-file lines each file's hunks change between `base` (or HEAD) and the working tree,
+    /// as [`hunk_span`]s; only `path` when given.
+    fn hunk_spans(
+        repo: &Repository,
+        base: Option<&Tree>,
+        path: Option<&Path>,
+    ) -> HashMap<Vec<u8>, Vec<(usize, usize)>> {
+        let head = repo.head().and_then(|h| h.peel_to_tree()).ok();
+        let mut opts = DiffOptions::new();
+        opts.context_lines(0)
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        if let Some(path) = path {
+            opts.pathspec(path).disable_pathspec_match(true);
+        }
+        let mut spans: HashMap<Vec<u8>, Vec<(usize, usize)>> = HashMap::new();
+        if let Ok(diff) =
+            repo.diff_tree_to_workdir_with_index(base.or(head.as_ref()), Some(&mut opts))
+        {
+            let _ = diff.foreach(
+                &mut |_, _| true,
+                None,
+                Some(&mut |delta, hunk| {
+                    if let Some(path) = delta.new_file().path_bytes() {
+                        spans.entry(path.to_vec()).or_default().push(hunk_span(
+                            hunk.new_start() as usize,
+                            hunk.new_lines() as usize,
+                        ));
+                    }
+                    true
+                }),
+                None,
+            );
+        }
+        spans
+    }
+
+    /// Files whose mode differs between `base` (or HEAD) and the working tree.
+    fn mode_changes(repo: &Repository, base: Option<&Tree>) -> HashMap<Vec<u8>, (u32, u32)> {
+        let head = repo.head().and_then(|h| h.peel_to_tree()).ok();
+        let Ok(diff) = repo.diff_tree_to_workdir_with_index(base.or(head.as_ref()), None) else {
+            return HashMap::new();
+        };
+        diff.deltas()
+            .filter_map(|delta| {
+                let old = u32::from(delta.old_file().mode());
+                let new = u32::from(delta.new_file().mode());
+                let path = delta.new_file().path_bytes()?.to_vec();
+                (old != 0 && new != 0 && old != new).then_some((path, (old, new)))
+            })
+            .collect()
+    }
+
+    /// Files that differ between `base` and the working tree, as index-style statuses.
+    fn branch_changes(repo: &Repository, base: &Tree) -> Vec<(Vec<u8>, git2::Status)> {
+        let mut opts = DiffOptions::new();
+        opts.include_untracked(true).recurse_untracked_dirs(true);
+        let Ok(diff) = repo.diff_tree_to_workdir_with_index(Some(base), Some(&mut opts)) else {
+            return Vec::new();
+        };
+        diff.deltas()
+            .filter_map(|delta| {
+                let status = match delta.status() {
+                    git2::Delta::Added => git2::Status::INDEX_NEW,
+                    git2::Delta::Deleted => git2::Status::INDEX_DELETED,
+                    git2::Delta::Renamed => git2::Status::INDEX_RENAMED,
+                    git2::Delta::Typechange => git2::Status::INDEX_TYPECHANGE,
+                    git2::Delta::Untracked => git2::Status::WT_NEW,
+                    git2::Delta::Conflicted => git2::Status::CONFLICTED,
+                    _ => git2::Status::INDEX_MODIFIED,
+                };
+                let file = if delta.status() == git2::Delta::Deleted {
+                    delta.old_file()
+                } else {
+                    delta.new_file()
+                };
+                Some((file.path_bytes()?.to_vec(), status))
+            })
+            .collect()
+    }
+
+    /// Merge-base of HEAD and `branch`, falling back to `origin/<branch>`.
+    fn resolve_merge_base(repo: &Repository, branch: &str) -> anyhow::Result<Oid> {
+        let head = repo.head()?.peel_to_commit()?.id();
+        let target = repo
+            .revparse_single(branch)
+            .or_else(|_| repo.revparse_single(&format!("origin/{branch}")))
+            .map_err(|_| anyhow::anyhow!("Base branch {branch} not found"))?
+            .peel_to_commit()?
+            .id();
+        Ok(repo.merge_base(head, target)?)
+    }
+
+    fn base_tree(repo: &Repository, merge_base: Option<Oid>) -> Option<Tree<'_>> {
+        repo.find_commit(merge_base?).and_then(|c| c.tree()).ok()
+    }
+
+    /// Switches between diffing uncommitted changes and the whole branch since its merge-base.
+    pub fn toggle_merge_base(&mut self) {
+        let Some(DataSource::Local { repo, .. }) = &self.source else {
+            self.notice =
+                Some("Merge-base mode is only available for local repositories".to_string());
+            return;
+        };
+        if self.merge_base.take().is_some() {
+            self.notice = Some("Showing uncommitted changes".to_string());
+        } else {
+            match Self::resolve_merge_base(repo, &self.config.base_branch) {
+                Ok(oid) => {
+                    self.merge_base = Some(oid);
+                    self.notice = Some(format!(
+                        "Showing everything since merge-base with {}",
+                        self.config.base_branch
+                    ));
+                }
+                Err(e) => {
+                    self.notice = Some(format!("Merge-base unavailable: {e}"));
+                    return;
                 }
             }
         }
+        self.refresh_local();
+        self.selected_index = 0;
+        self.logic_view_content.clear();
+        self.select_first_file();
+    }
+
+    /// Directory a changed file is grouped under in the Galaxy view.
+    fn module_name(path: &str) -> String {
+        let parent = Path::new(path)
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if parent.is_empty() {
+            "root".to_string()
+        } else {
+            parent
+        }
+    }
+
+    /// Builds heat-ranked modules, scaling each file by its configured heat weight.
+    fn build_modules<'a>(
+        paths: impl Iterator<Item = &'a str>,
+        weights: &HashMap<String, f32>,
+    ) -> Vec<Module> {
+        Self::build_modules_by(paths, |path| heat_weight(path, weights), Self::module_name)
+    }
+
+    /// `build_modules`, with `heat` weighing each path and `group` naming its module.
+    fn build_modules_by<'a>(
+        paths: impl Iterator<Item = &'a str>,
+        heat: impl Fn(&str) -> f32,
+        group: impl Fn(&str) -> String,
+    ) -> Vec<Module> {
+        let mut dirs: HashMap<String, (usize, f32)> = HashMap::new();
+        for path in paths {
+            let entry = dirs.entry(group(path)).or_insert((0, 0.0));
+            entry.0 += 1;
+            entry.1 += heat(path);
+        }
 
-        let mut modules: Vec<Module> = dir_counts
+        let mut modules: Vec<Module> = dirs
             .into_iter()
-            .map(|(name, count)| {
-                let heat = (count * 10).min(100) as u8;
+            .map(|(name, (count, weight))| {
+                let heat = (weight * 10.0).clamp(0.0, 100.0).round() as u8;
                 Module {
                     name,
                     heat,
@@ -406,9 +1714,377 @@ impl App {
                 }
             })
             .collect();
-        modules.sort_by(|a, b| b.heat.cmp(&a.heat));
+        modules.sort_by_key(|m| std::cmp::Reverse(m.heat));
+        modules
+    }
 
-        (modules, structures)
+    /// Heat of a changed local file: its configured weight, raised by each recent commit to it
+    /// and, with diminishing returns, by each place referencing its changed symbols.
+    fn file_heat(&self, path: &str) -> f32 {
+        let references: usize = self
+            .impact
+            .iter()
+            .filter(|((p, _), _)| p == path)
+            .map(|(_, refs)| refs.len())
+            .sum();
+        heat_weight(path, &self.config.heat_weights)
+            * (1.0 + self.churn.score(path))
+            * (references as f32).ln_1p().mul_add(0.5, 1.0)
+    }
+
+    /// Galaxy modules of a local source: per crate / package with their line counts in a
+    /// monorepo, else per directory. Either way, modules with changes to a crate whose version
+    /// was not bumped since `base` (or HEAD) are flagged.
+    fn local_modules(
+        &self,
+        repo: &Repository,
+        base: Option<&Tree>,
+        structures: &[StructureItem],
+    ) -> Vec<Module> {
+        let crates = self.cargo.as_ref().map_or(&[][..], |w| &w.crates[..]);
+        // A single package would fold every directory into one module
+        let monorepo = crates.len() + self.packages.packages.len() >= 2;
+        let label = |path: &str| {
+            let krate = self.cargo.as_ref().and_then(|w| w.crate_of(path));
+            match (monorepo, krate, self.packages.package_of(path)) {
+                (false, ..) | (true, None, None) => Self::module_name(path),
+                (true, Some(c), Some(p)) if p.dir.len() > c.dir.len() => p.label(),
+                (true, Some(c), _) => c.label(),
+                (true, None, Some(p)) => p.label(),
+            }
+        };
+        let changed = || {
+            structures
+                .iter()
+                .filter(|i| i.is_file)
+                .map(|i| i.path.as_str())
+        };
+        let mut modules = Self::build_modules_by(changed(), |path| self.file_heat(path), label);
+        let mut lines: HashMap<String, (u64, u64)> = HashMap::new();
+        if monorepo {
+            for (path, additions, deletions) in Self::local_line_counts(repo, base) {
+                let entry = lines.entry(label(&path)).or_default();
+                entry.0 += additions;
+                entry.1 += deletions;
+            }
+        }
+        let head = repo.head().and_then(|h| h.peel_to_tree()).ok();
+        let old = base.or(head.as_ref());
+        // Each crate's manifest is read once, however many of its files changed
+        let mut unbumped_crates: HashMap<String, bool> = HashMap::new();
+        let unbumped: HashSet<String> = changed()
+            .filter(|path| {
+                let krate = self.cargo.as_ref().and_then(|w| w.crate_of(path));
+                let Some((krate, old)) = krate.zip(old) else {
+                    return false;
+                };
+                *unbumped_crates
+                    .entry(krate.label())
+                    .or_insert_with(|| krate.version_bumped(repo, old) == Some(false))
+            })
+            .map(label)
+            .collect();
+        for module in &mut modules {
+            if let Some((additions, deletions)) = lines.get(&module.name) {
+                module.description = format!("{}, +{additions} -{deletions}", module.description);
+            }
+            if unbumped.contains(&module.name) {
+                module.description = format!("{}, version not bumped", module.description);
+            }
+        }
+        modules
+    }
+
+    /// Language and file-class breakdown of the local changes.
+    fn local_breakdown(repo: &Repository, base: Option<&Tree>) -> ChangeBreakdown {
+        let files = Self::local_line_counts(repo, base);
+        ChangeBreakdown::from_files(files.iter().map(|(p, a, d)| (p.as_str(), *a, *d)))
+    }
+
+    /// Line counts per changed file (HEAD vs. working tree, untracked included).
+    fn local_line_counts(repo: &Repository, base: Option<&Tree>) -> Vec<(String, u64, u64)> {
+        let mut opts = DiffOptions::new();
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        let head = repo.head().and_then(|h| h.peel_to_tree()).ok();
+        let tree = base.or(head.as_ref());
+        let Ok(diff) = repo.diff_tree_to_workdir_with_index(tree, Some(&mut opts)) else {
+            return Vec::new();
+        };
+
+        let mut files = Vec::new();
+        for idx in 0..diff.deltas().len() {
+            if let Ok(Some(patch)) = git2::Patch::from_diff(&diff, idx)
+                && let Ok((_, additions, deletions)) = patch.line_stats()
+                && let Some(path) = patch.delta().new_file().path()
+            {
+                files.push((
+                    path.to_string_lossy().to_string(),
+                    additions as u64,
+                    deletions as u64,
+                ));
+            }
+        }
+        files
+    }
+
+    /// Whether the repository is configured with `core.ignorecase` (macOS/Windows defaults).
+    fn ignores_case(repo: &Repository) -> bool {
+        repo.config()
+            .and_then(|c| c.get_bool("core.ignorecase"))
+            .unwrap_or(false)
+    }
+
+    /// Structure row for `sym` in `file`, touched when one of the file's hunk `spans` is in it.
+    fn symbol_item(
+        file: &StructureItem,
+        sym: SymbolChange,
+        spans: &[(usize, usize)],
+    ) -> StructureItem {
+        StructureItem {
+            text: format!("  {} {}", sym.kind, sym.name),
+            path: file.path.clone(),
+            fs_path: file.fs_path.clone(),
+            is_file: false,
+            status: sym.kind,
+            line_no: Some(sym.start_line),
+            // Symbols follow their file into the Staged/Unstaged sections
+            is_staged: file.is_staged,
+            is_unstaged: file.is_unstaged,
+            skip_reason: None,
+            mode_change: None,
+            touched: touches(spans, sym.start_line, sym.end_line),
+            complexity: sym.complexity,
+        }
+    }
+
+    /// Re-analyzes one edited file incrementally, replacing only its symbol rows.
+    ///
+    /// Used by live watching so a save does not trigger a full repository scan.
+    pub fn refresh_file(&mut self, path: &str) {
+        let Some(DataSource::Local { repo, root }) = &self.source else {
+            return;
+        };
+        let Some(file_pos) = self
+            .structures
+            .iter()
+            .position(|i| i.is_file && i.path == path)
+        else {
+            return;
+        };
+
+        let full_path = root.join(&self.structures[file_pos].fs_path);
+        let analysis = if self.analyzer.supports(path) && full_path.exists() {
+            Some(semantics::read_analyzable(&full_path))
+        } else {
+            None
+        };
+        let symbols = if let Some(Ok(content)) = &analysis {
+            self.analyzer.analyze(path, content)
+        } else {
+            self.analyzer.forget(path);
+            vec![]
+        };
+
+        let fs_path = &self.structures[file_pos].fs_path;
+        let base_tree = Self::base_tree(repo, self.merge_base);
+        let spans = Self::hunk_spans(repo, base_tree.as_ref(), Some(fs_path))
+            .into_values()
+            .flatten()
+            .collect::<Vec<_>>();
+        drop(base_tree);
+
+        let file = &mut self.structures[file_pos];
+        file.skip_reason = analysis.and_then(Result::err);
+        let file = file.clone();
+        let end = self.structures[file_pos + 1..]
+            .iter()
+            .position(|i| i.is_file || i.path != path)
+            .map_or(self.structures.len(), |n| file_pos + 1 + n);
+        self.structures.splice(
+            file_pos + 1..end,
+            symbols
+                .into_iter()
+                .map(|sym| Self::symbol_item(&file, sym, &spans)),
+        );
+        self.update_search();
+    }
+
+    /// Paths of the changed files listed in the Structure view.
+    fn changed_files(&self) -> Vec<String> {
+        self.structures
+            .iter()
+            .filter(|item| item.is_file)
+            .map(|item| item.path.clone())
+            .collect()
+    }
+
+    /// Drops the symbol index and any run filling it, e.g. for another repository or query
+    /// set; the next full request builds it again.
+    fn reset_index(&mut self) {
+        self.symbol_index = Arc::default();
+        self.index_job = None;
+        self.index_queue = None;
+        self.indexed = false;
+        self.impact.clear();
+    }
+
+    /// Queues `request` for the index worker and starts it unless it is busy.
+    fn request_index(&mut self, request: IndexRequest) {
+        self.index_queue
+            .get_or_insert_with(IndexRequest::default)
+            .merge(request);
+        self.start_indexing();
+    }
+
+    /// Hands the queued paths to a new worker run, finding references to the touched symbols.
+    fn start_indexing(&mut self) {
+        let Some(DataSource::Local { root, .. }) = &self.source else {
+            return;
+        };
+        if self.index_job.is_some() {
+            return;
+        }
+        let Some(request) = self.index_queue.take() else {
+            return;
+        };
+        let targets = self
+            .structures
+            .iter()
+            .filter(|item| !item.is_file && item.touched)
+            .filter_map(|item| Some((item.path.clone(), item.line_no?)))
+            .collect();
+        self.index_job = Some(IndexJob::spawn(
+            Arc::clone(&self.symbol_index),
+            root.clone(),
+            self.config.symbol_queries.clone(),
+            request,
+            targets,
+        ));
+    }
+
+    /// Picks up the index worker's references once it is done: the Galaxy heat and an open
+    /// `@` search catch up, and paths queued meanwhile start the next run.
+    pub fn poll_index(&mut self) {
+        let Some(impact) = self.index_job.as_ref().and_then(IndexJob::finished) else {
+            return;
+        };
+        let job = self.index_job.take();
+        self.indexed |= job.is_some_and(|job| job.is_full());
+        self.impact = impact;
+        if let Some(DataSource::Local { repo, .. }) = &self.source {
+            let base_tree = Self::base_tree(repo, self.merge_base);
+            self.modules = self.local_modules(repo, base_tree.as_ref(), &self.structures);
+        }
+        if self.is_symbol_search() {
+            let hit = self.symbol_hit;
+            self.update_search();
+            self.symbol_hit = hit.min(self.symbol_hits.len().saturating_sub(1));
+        }
+        self.start_indexing();
+    }
+
+    /// Lists every reference to the selected symbol, changed or not; local sources only.
+    pub fn open_impact(&mut self) {
+        let Some(DataSource::Local { root, .. }) = &self.source else {
+            self.notice = Some("References can only be found in a local repository".to_string());
+            return;
+        };
+        if !self.indexed {
+            self.notice = Some("Still indexing the repository; try again shortly".to_string());
+            return;
+        }
+        let Some(item) = self.selected_item() else {
+            return;
+        };
+        let Some((symbol, candidates)) = self.symbol_index.lock().ok().and_then(|index| {
+            let symbol = index.symbol_at(&item.path, item.line_no.filter(|_| !item.is_file)?)?;
+            Some((symbol.clone(), index.mentioning(&[symbol])))
+        }) else {
+            self.notice = Some("Select a symbol to list its references".to_string());
+            return;
+        };
+        // Touched symbols were looked up by the index worker already
+        let references = self
+            .impact
+            .get(&(item.path.clone(), symbol.start_line))
+            .cloned()
+            .unwrap_or_else(|| {
+                let mut analyzer = SemanticAnalyzer::with_queries(&self.config.symbol_queries)
+                    .unwrap_or_else(|_| SemanticAnalyzer::new());
+                symbol_index::find_references(
+                    root,
+                    &mut analyzer,
+                    &candidates,
+                    &[(item.path.as_str(), &symbol)],
+                )
+                .pop()
+                .unwrap_or_default()
+            });
+        if references.is_empty() {
+            self.notice = Some(format!("Nothing references {}", symbol.name));
+            return;
+        }
+        self.impact_panel = Some(ImpactPanel::new(symbol.name, references, root));
+    }
+
+    /// Opens the highlighted reference in $EDITOR.
+    pub fn open_reference(&mut self) {
+        let Some(panel) = self.impact_panel.take() else {
+            return;
+        };
+        if let (Some((reference, _)), Some(DataSource::Local { root, .. })) =
+            (panel.current(), &self.source)
+        {
+            self.pending_editor = Some((root.join(&reference.path), Some(reference.line)));
+        }
+    }
+
+    /// Shows `hit` in the Logic view when the changeset lists it, or opens it in $EDITOR.
+    fn go_to_symbol(&mut self, hit: &IndexedSymbol) {
+        let listed = self.structures.iter().position(|item| {
+            !item.is_file && item.path == hit.path && item.line_no == Some(hit.line)
+        });
+        if let Some(i) = listed {
+            let item = &self.structures[i];
+            let section = if self.has_sections() && !item.is_unstaged {
+                StructureSection::Staged
+            } else if self.has_sections() && !item.is_staged {
+                StructureSection::Unstaged
+            } else {
+                self.structure_section
+            };
+            let location = Location {
+                zoom: ZoomLevel::Logic,
+                section,
+                item: Some((item.path.clone(), item.text.clone())),
+                index: 0,
+                line: 0,
+            };
+            // The jump back was recorded when the search opened
+            self.go_to(&location);
+        } else if let Some(DataSource::Local { root, .. }) = &self.source {
+            self.pending_editor = Some((root.join(&hit.path), Some(hit.line)));
+        }
+    }
+
+    /// Syntax the Logic view is highlighted with: the selected file's.
+    #[must_use]
+    pub fn syntax(&self) -> &'static SyntaxReference {
+        self.selected_item().map_or_else(
+            || highlight::SYNTAXES.find_syntax_plain_text(),
+            |item| highlight::syntax_for(&item.path),
+        )
+    }
+
+    /// Hands the Logic view's new lines to the background highlighter.
+    fn highlight_content(&self) {
+        self.highlighter.load(
+            &self.logic_view_content,
+            self.syntax(),
+            &self.config.syntax_theme,
+        );
     }
 
     fn load_diff(&mut self) {
@@ -418,6 +2094,14 @@ impl App {
 
         // Logic View content clearing logic
         self.logic_view_content.clear();
+        self.logic_anchor = None;
+        self.h_scroll = 0;
+        self.conflict = None;
+        self.binary = None;
+        self.lfs = None;
+        self.hunk_stages.clear();
+        self.history = None;
+        self.showing_drift = false;
 
         // Get the REAL index from the filtered list
         if self.selected_index >= self.filtered_structure_indices.len() {
@@ -429,48 +2113,114 @@ impl App {
         }
 
         let real_index = self.filtered_structure_indices[self.selected_index];
-        let item = &self.structures[real_index];
-
-        let path = &item.path;
-        if path.is_empty() {
+        if self.structures[real_index].path.is_empty() {
             return;
         }
+        let path = self.structures[real_index].path.clone();
+        if self.zoom_level == ZoomLevel::Logic && self.structures[real_index].is_file {
+            self.opened.insert(path.clone());
+        }
+        if let Some(e) = self.fetch_missing_patch(&path) {
+            self.notice = Some(format!("Cannot fetch the diff of {path}: {e}"));
+        }
+        let content = self.logic_content(&self.structures[real_index]);
+        self.logic_view_content = content.lines;
+        self.conflict = content.conflict;
+        self.binary = content.binary;
+        self.lfs = content.lfs;
+        self.hunk_stages = content.hunk_stages;
 
-        match self.source.as_ref().expect("Source must be loaded") {
-            DataSource::Local { repo, .. } => {
-                let mut diff_opts = DiffOptions::new();
-                diff_opts.pathspec(path);
-                diff_opts.context_lines(self.context_lines);
+        self.logic_cursor = self
+            .logic_cursor
+            .min(self.logic_view_content.len().saturating_sub(1));
+        self.highlight_content();
+        self.load_blame();
+    }
+
+    /// PRs too large for one diff fetch a file's patch on first selection, along with the
+    /// rest of its page of the file list; returns the error when that fails.
+    fn fetch_missing_patch(&mut self, path: &str) -> Option<anyhow::Error> {
+        let Some(DataSource::GitHub {
+            pr_info,
+            raw_diff,
+            file_diffs,
+            ..
+        }) = &mut self.source
+        else {
+            return None;
+        };
+        if !raw_diff.is_empty() || file_diffs.contains_key(path) {
+            return None;
+        }
+        // Pages usually follow the file order; look through the others if it was not there
+        let guess = GitHubClient::file_page(pr_info, path);
+        let pages = std::iter::once(guess)
+            .chain((1..=GitHubClient::file_pages(pr_info)).filter(|&p| p != guess));
+        for page in pages {
+            match GitHubClient::fetch_file_patches(pr_info, page) {
+                Ok(patches) => {
+                    // Keep the page for offline reviews; a failed write only costs a refetch
+                    let _ = CachedPr::add_patches(&pr_info.url, &pr_info.head_ref_oid, &patches);
+                    file_diffs.extend(patches);
+                }
+                Err(e) => return Some(e),
+            }
+            if file_diffs.contains_key(path) {
+                return None;
+            }
+        }
+        None
+    }
 
-                let diff = if let Ok(tree) = repo.head().and_then(|h| h.peel_to_tree()) {
-                    repo.diff_tree_to_workdir_with_index(Some(&tree), Some(&mut diff_opts))
-                        .ok()
+    /// What the Logic view shows for `item`, without touching the view itself.
+    fn logic_content(&self, item: &StructureItem) -> LogicContent {
+        let mut content = LogicContent::default();
+        let fs_path = &item.fs_path;
+        match self.source.as_ref().expect("Source must be loaded") {
+            DataSource::Local { repo, root } if item.status.contains("CONFLICTED") => {
+                content.conflict = Self::load_conflict(repo, root, fs_path);
+                if let Some(conflict) = &content.conflict {
+                    content.lines = conflict.lines().into_iter().map(|l| l.text).collect();
                 } else {
-                    repo.diff_tree_to_workdir_with_index(None, Some(&mut diff_opts))
-                        .ok()
-                };
+                    content
+                        .lines
+                        .push(" Conflicted, but no conflict markers left in the file.".to_string());
+                }
+            }
+            DataSource::Local { repo, .. } => {
+                let tree = Self::base_tree(repo, self.merge_base)
+                    .or_else(|| repo.head().and_then(|h| h.peel_to_tree()).ok());
+                let diff = self.local_file_diff(repo, tree.as_ref(), fs_path);
 
-                if let Some(diff) = diff {
-                    let _ = diff.print(git2::DiffFormat::Patch, |_, _, line| {
-                        let content = String::from_utf8_lossy(line.content())
-                            .trim_end()
-                            .to_string();
-                        let prefix = match line.origin() {
-                            '+' => "+",
-                            '-' => "-",
-                            _ => " ",
-                        };
-                        self.logic_view_content.push(format!("{prefix}{content}"));
-                        true
-                    });
+                content.binary = diff.as_ref().and_then(BinarySummary::of);
+                if let Some(summary) = &content.binary {
+                    content.lines = summary.lines();
+                } else if let Some(diff) = diff {
+                    content.lines = Self::patch_lines(&diff)
+                        .into_iter()
+                        .flat_map(|(_, lines)| lines)
+                        .collect();
+                    if self.diff_scope == DiffScope::All
+                        && let Some((removed, added)) =
+                            Self::index_changes(repo, tree.as_ref(), fs_path)
+                    {
+                        content.hunk_stages = hunk_stages(&content.lines, &removed, &added);
+                    }
                 }
             }
-            DataSource::GitHub { file_diffs, .. } => {
-                if let Some(lines) = file_diffs.get(path) {
-                    self.logic_view_content = lines.clone();
+            DataSource::GitHub { file_diffs, .. } | DataSource::Commit { file_diffs, .. } => {
+                if let Some(lines) = file_diffs.get(&item.path) {
+                    if BinarySummary::in_patch(lines) {
+                        let summary = BinarySummary::default();
+                        content.lines = summary.lines();
+                        content.binary = Some(summary);
+                    } else {
+                        content.lines.clone_from(lines);
+                    }
                 } else {
-                    self.logic_view_content
-                        .push("No diff available for this file.".to_string());
+                    content
+                        .lines
+                        .push(" No diff available for this file.".to_string());
                 }
             }
         }
@@ -478,74 +2228,2448 @@ impl App {
         if !item.is_file
             && let Some(line) = item.line_no
         {
-            self.logic_view_content
+            content
+                .lines
                 .push(format!("--- Focused on Line {line} ---"));
         }
-    }
 
-    pub fn increase_context(&mut self) {
-        if matches!(self.zoom_level, ZoomLevel::Logic)
-            && matches!(self.source, Some(DataSource::Local { .. }))
-        {
-            self.context_lines = self.context_lines.saturating_add(3);
-            self.load_diff();
+        // Pointer text is not the change under review; the object behind it is
+        let mode = mode_change(&content.lines).filter(|_| content.binary.is_none());
+        if let Some(change) = LfsChange::from_patch(&content.lines) {
+            let change = match self.source.as_ref() {
+                Some(DataSource::Local { repo, .. }) => change.with_store(repo.path()),
+                _ => change,
+            };
+            content.lines = change.lines();
+            content.lfs = Some(change);
+        }
+        // Mode-only changes have no hunks, so say it up front rather than in the header noise
+        if let Some(change) = mode {
+            content
+                .lines
+                .insert(0, format!(" {}", describe_mode_change(change)));
         }
+        content
     }
 
-    pub fn decrease_context(&mut self) {
-        if matches!(self.zoom_level, ZoomLevel::Logic)
-            && matches!(self.source, Some(DataSource::Local { .. }))
-        {
-            self.context_lines = self.context_lines.saturating_sub(3).max(1);
-            self.load_diff();
+    /// Diff of one file of the working tree in the current scope, against `tree`.
+    fn local_file_diff<'r>(
+        &self,
+        repo: &'r Repository,
+        tree: Option<&git2::Tree>,
+        fs_path: &Path,
+    ) -> Option<git2::Diff<'r>> {
+        let mut diff_opts = DiffOptions::new();
+        diff_opts.pathspec(fs_path);
+        diff_opts.context_lines(self.context_lines);
+        diff_opts.ignore_case(Self::ignores_case(repo));
+        // Untracked files have no diff of their own; show their content as all-added
+        diff_opts
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+
+        match self.diff_scope {
+            DiffScope::All => repo.diff_tree_to_workdir_with_index(tree, Some(&mut diff_opts)),
+            DiffScope::Unstaged => repo.diff_index_to_workdir(None, Some(&mut diff_opts)),
+            DiffScope::Staged => repo.diff_tree_to_index(tree, None, Some(&mut diff_opts)),
         }
+        .ok()
     }
 
-    #[allow(clippy::missing_panics_doc)]
-    pub fn toggle_stage(&mut self) {
-        if let Some(DataSource::Local { repo, root: path }) = &self.source
-            && !self.filtered_structure_indices.is_empty()
-        {
-            // Use real index
-            let real_index = self.filtered_structure_indices[self.selected_index];
-            let item = &self.structures[real_index];
+    /// For the hunk markers of `path`: the lines of `tree` the index has dropped, and the
+    /// working tree lines the index does not have yet.
+    fn index_changes(
+        repo: &Repository,
+        tree: Option<&git2::Tree>,
+        path: &Path,
+    ) -> Option<(HashSet<usize>, HashSet<usize>)> {
+        let options = || {
+            let mut options = DiffOptions::new();
+            options
+                .pathspec(path)
+                .context_lines(0)
+                .include_untracked(true)
+                .show_untracked_content(true);
+            options
+        };
+        let lines = |diff: &git2::Diff, origin: char| {
+            let mut numbers = HashSet::new();
+            let _ = diff.foreach(
+                &mut |_, _| true,
+                None,
+                None,
+                Some(&mut |_, _, line| {
+                    let number = if origin == '-' {
+                        line.old_lineno()
+                    } else {
+                        line.new_lineno()
+                    };
+                    if line.origin() == origin
+                        && let Some(n) = number
+                    {
+                        numbers.insert(n as usize);
+                    }
+                    true
+                }),
+            );
+            numbers
+        };
+        let staged = repo
+            .diff_tree_to_index(tree, None, Some(&mut options()))
+            .ok()?;
+        let unstaged = repo
+            .diff_index_to_workdir(None, Some(&mut options()))
+            .ok()?;
+        Some((lines(&staged, '-'), lines(&unstaged, '+')))
+    }
 
-            if !item.is_file {
-                return;
+    /// Prints `diff` as prefixed patch lines, grouped per file in diff order.
+    pub(crate) fn patch_lines(diff: &git2::Diff) -> Vec<(String, Vec<String>)> {
+        let mut files: Vec<(String, Vec<String>)> = Vec::new();
+        let _ = diff.print(git2::DiffFormat::Patch, |delta, _, line| {
+            let path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if files.last().is_none_or(|(last, _)| *last != path) {
+                files.push((path, Vec::new()));
             }
-
-            let file_path = Path::new(&item.path);
-            let mut index = repo.index().expect("Failed to get index");
-
-            if item.is_staged {
-                if let Ok(head) = repo.head() {
-                    let obj = head
-                        .peel(git2::ObjectType::Any)
-                        .expect("Failed to peel HEAD");
-                    repo.reset_default(Some(&obj), vec![file_path])
-                        .expect("Failed to unstage");
+            let content = String::from_utf8_lossy(line.content());
+            let prefix = match line.origin() {
+                '+' => "+",
+                '-' => "-",
+                _ => " ",
+            };
+            // File headers arrive as one multi-line chunk; a blank line of code is still a line
+            if let Some((_, lines)) = files.last_mut() {
+                if matches!(line.origin(), '+' | '-' | ' ') {
+                    lines.push(format!("{prefix}{}", content.trim_end()));
                 } else {
-                    index
-                        .remove_path(file_path)
-                        .expect("Failed to remove from index");
+                    lines.extend(
+                        content
+                            .trim_end()
+                            .lines()
+                            .map(|l| format!("{prefix}{}", l.trim_end())),
+                    );
+                }
+            }
+            true
+        });
+        files
+    }
+
+    /// Parses the conflict markers in the working file, recovering base sections from the index.
+    fn load_conflict(repo: &Repository, root: &Path, fs_path: &Path) -> Option<ConflictFile> {
+        let content = fs::read_to_string(root.join(fs_path)).ok()?;
+        let mut conflict = ConflictFile::parse(&content);
+        if !conflict.has_conflicts() {
+            return None;
+        }
+        if conflict.regions().any(|r| r.base.is_none())
+            && let Some(diff3) = Self::diff3_merge(repo, fs_path)
+        {
+            conflict.fill_bases(&ConflictFile::parse(&diff3));
+        }
+        Some(conflict)
+    }
+
+    /// Re-runs the merge for `fs_path` from its index stages with diff3-style markers.
+    fn diff3_merge(repo: &Repository, fs_path: &Path) -> Option<String> {
+        let index = repo.index().ok()?;
+        let wanted = fs_path.as_os_str().as_encoded_bytes();
+        let entry = index.conflicts().ok()?.flatten().find(|c| {
+            [&c.our, &c.their]
+                .into_iter()
+                .flatten()
+                .any(|e| e.path == wanted)
+        })?;
+        let (ancestor, ours, theirs) = (entry.ancestor?, entry.our?, entry.their?);
+        let mut opts = MergeFileOptions::new();
+        opts.style_diff3(true);
+        let merged = repo
+            .merge_file_from_index(&ancestor, &ours, &theirs, Some(&mut opts))
+            .ok()?;
+        String::from_utf8(merged.content().to_vec()).ok()
+    }
+
+    /// Takes `side` for the conflict region under the Logic cursor (again to undo).
+    pub fn resolve_conflict(&mut self, side: Side) {
+        let Some(conflict) = self.conflict.as_mut() else {
+            return;
+        };
+        let Some(region) = conflict
+            .lines()
+            .get(self.logic_cursor)
+            .and_then(|l| l.region)
+        else {
+            self.notice = Some("Move the cursor into a conflict region first".to_string());
+            return;
+        };
+        conflict.resolve(region, side);
+        let lines = conflict.lines();
+        // Keep the cursor on the region it just changed
+        self.logic_cursor = lines
+            .iter()
+            .position(|l| l.region == Some(region))
+            .unwrap_or(0);
+        self.logic_view_content = lines.into_iter().map(|l| l.text).collect();
+        self.highlight_content();
+    }
+
+    /// Writes the chosen sides to disk and stages the file once nothing is left unresolved.
+    pub fn write_conflict(&mut self) {
+        if !self.ensure_fresh() {
+            return;
+        }
+        let (Some(conflict), Some(DataSource::Local { repo, root }), Some(item)) =
+            (&self.conflict, &self.source, self.selected_item())
+        else {
+            return;
+        };
+        let fs_path = item.fs_path.clone();
+        let unresolved = conflict.unresolved();
+        if let Err(e) = fs::write(root.join(&fs_path), conflict.render()) {
+            self.notice = Some(format!("Write failed: {e}"));
+            return;
+        }
+        self.notice = Some(if unresolved > 0 {
+            format!("Written; {unresolved} conflict(s) still unresolved")
+        } else {
+            match repo.index().and_then(|mut index| {
+                index.add_path(&fs_path)?;
+                index.write()
+            }) {
+                Ok(()) => "Conflict resolved and staged".to_string(),
+                Err(e) => format!("Written, but staging failed: {e}"),
+            }
+        });
+        self.refresh_local();
+    }
+
+    pub fn toggle_blame(&mut self) {
+        if !matches!(self.source, Some(DataSource::Local { .. })) {
+            self.notice = Some("Blame is only available for local repositories".to_string());
+            return;
+        }
+        self.show_blame = !self.show_blame;
+        self.load_blame();
+        if self.show_blame
+            && let Some(DataSource::Local { repo, .. }) = &self.source
+        {
+            self.notice = CloneState::of(repo).warning("Blame");
+        }
+    }
+
+    /// Opens the selected file in the Logic view with the blame column on.
+    fn show_file_blame(&mut self) {
+        self.zoom_in();
+        if self.zoom_level == ZoomLevel::Logic && !self.show_blame {
+            self.toggle_blame();
+        }
+    }
+
+    /// Fills `blame_lines` with `hash author age` for each context line of the current diff.
+    fn load_blame(&mut self) {
+        self.blame_lines.clear();
+        if !self.show_blame {
+            return;
+        }
+        let (Some(DataSource::Local { repo, .. }), Some(item)) =
+            (&self.source, self.selected_item())
+        else {
+            return;
+        };
+        // Blames the old side of the diff, so only lines it still has are annotated: the
+        // committed file (the merge-base in merge-base mode, where blame stops), or the index
+        // when only unstaged changes are shown
+        let mut opts = BlameOptions::new();
+        if let Some(oid) = self.merge_base {
+            opts.newest_commit(oid);
+        }
+        let Ok(committed) = repo.blame_file(&item.fs_path, Some(&mut opts)) else {
+            return;
+        };
+        let blame = if self.diff_scope == DiffScope::Unstaged {
+            let staged = repo.index().ok().and_then(|index| {
+                let entry = index.get_path(&item.fs_path, 0)?;
+                repo.find_blob(entry.id).ok()
+            });
+            let Some(Ok(blame)) = staged.map(|blob| committed.blame_buffer(blob.content())) else {
+                return;
+            };
+            blame
+        } else {
+            committed
+        };
+
+        self.blame_lines = line_numbers(&self.logic_view_content)
+            .into_iter()
+            .map(|(old, new)| {
+                let hunk = blame.get_line(old?)?;
+                new?; // Context lines only; removed lines are what the diff is about
+                if hunk.final_commit_id().is_zero() {
+                    return Some("staged, not committed".to_string());
+                }
+                let sig = hunk.final_signature();
+                let id = hunk.final_commit_id().to_string();
+                // `^` like `git blame`: the line predates the history we have, e.g. a shallow clone
+                let boundary = if hunk.is_boundary() { "^" } else { "" };
+                Some(format!(
+                    "{boundary}{} {:<10.10} {:>4}",
+                    &id[..7],
+                    sig.name().unwrap_or("unknown"),
+                    format_age(sig.when().seconds())
+                ))
+            })
+            .collect();
+    }
+
+    /// Cycles the local diff between all, unstaged-only and staged-only changes.
+    pub fn cycle_diff_scope(&mut self) {
+        if !matches!(self.source, Some(DataSource::Local { .. })) {
+            return;
+        }
+        self.diff_scope = self.diff_scope.next();
+        self.notice = Some(format!("Diff: {}", self.diff_scope.label()));
+        self.load_diff();
+    }
+
+    /// Switches the Logic view between soft wrapping and horizontal scrolling.
+    pub const fn toggle_wrap(&mut self) {
+        self.wrap_lines = !self.wrap_lines;
+        self.h_scroll = 0;
+    }
+
+    /// `x`: shows only added and removed lines, whatever the context setting.
+    pub fn toggle_changes_only(&mut self) {
+        self.changes_only = !self.changes_only;
+        // Keep the cursor on a line that is still shown
+        if !self.logic_line_visible(self.logic_cursor) {
+            self.logic_cursor = (self.logic_cursor..self.logic_view_content.len())
+                .chain((0..self.logic_cursor).rev())
+                .find(|&i| self.logic_line_visible(i))
+                .unwrap_or(0);
+        }
+        self.notice = Some(
+            if self.changes_only {
+                "Showing changed lines only"
+            } else {
+                "Showing context lines"
+            }
+            .to_string(),
+        );
+    }
+
+    /// Whether Logic view line `i` is shown; changes-only mode hides context lines of diffs.
+    #[must_use]
+    pub fn logic_line_visible(&self, i: usize) -> bool {
+        // History listings, drift summaries and the conflict, binary and LFS panels are not
+        // diffs; their indented lines would pass for context
+        let diff = self.history.as_ref().is_none_or(|h| h.open.is_some())
+            && !self.showing_drift
+            && self.conflict.is_none()
+            && self.binary.is_none()
+            && self.lfs.is_none();
+        !(self.changes_only
+            && diff
+            && self
+                .logic_view_content
+                .get(i)
+                .is_some_and(|line| is_context_line(line)))
+    }
+
+    /// Screen rows Logic view line `i` takes at the last drawn width: more than one when a
+    /// long line is soft-wrapped.
+    #[must_use]
+    pub fn logic_line_rows(&self, i: usize) -> usize {
+        let width = self.logic_viewport.get().0;
+        let Some(text) = self.logic_view_content.get(i) else {
+            return 1;
+        };
+        if !self.wrap_lines || width == 0 {
+            return 1;
+        }
+        // Same text as the rendered line (blame column, gutter, prefix), so it wraps alike
+        let line = if self.show_blame {
+            let blame = self.blame_lines.get(i).cloned().flatten();
+            format!("{:<28}│ {text}", blame.unwrap_or_default())
+        } else {
+            format!(" {text}")
+        };
+        wrapped_rows(&Line::raw(line), width)
+    }
+
+    /// Wrapped row of the cursor line the cursor is on, counted from 0.
+    #[must_use]
+    pub fn logic_wrap_row(&self) -> usize {
+        if self.logic_wrap.0 == self.logic_cursor {
+            self.logic_wrap
+                .1
+                .min(self.logic_line_rows(self.logic_cursor) - 1)
+        } else {
+            0
+        }
+    }
+
+    /// `PageDown` / `PageUp` in the Logic view: moves a screenful of wrapped rows, keeping a
+    /// couple of rows of overlap.
+    pub fn logic_page(&mut self, down: bool) {
+        let rows = usize::from(self.logic_viewport.get().1)
+            .saturating_sub(2)
+            .max(1);
+        for _ in 0..rows {
+            if down {
+                self.next();
+            } else {
+                self.previous();
+            }
+        }
+    }
+
+    /// Pans the unwrapped Logic view by `delta` columns, stopping at the longest line.
+    pub fn pan(&mut self, delta: isize) {
+        if self.wrap_lines {
+            return;
+        }
+        let longest = self
+            .logic_view_content
+            .iter()
+            .map(|l| l.chars().count())
+            .max()
+            .unwrap_or(0);
+        self.h_scroll = self.h_scroll.saturating_add_signed(delta).min(longest);
+    }
+
+    pub fn increase_context(&mut self) {
+        if matches!(self.zoom_level, ZoomLevel::Logic)
+            && matches!(self.source, Some(DataSource::Local { .. }))
+        {
+            self.context_lines = self.context_lines.saturating_add(3);
+            self.reload_logic();
+        }
+    }
+
+    pub fn decrease_context(&mut self) {
+        if matches!(self.zoom_level, ZoomLevel::Logic)
+            && matches!(self.source, Some(DataSource::Local { .. }))
+        {
+            self.context_lines = self.context_lines.saturating_sub(3).max(1);
+            self.reload_logic();
+        }
+    }
+
+    /// Re-renders what the Logic view shows: an opened history commit, or the file's diff.
+    fn reload_logic(&mut self) {
+        if self.history.as_ref().is_some_and(|h| h.open.is_some()) {
+            self.render_history_entry();
+        } else {
+            self.load_diff();
+        }
+    }
+
+    /// False (and asks to refresh) when another process touched the index or HEAD since
+    /// the last scan, so a write would clobber its changes.
+    fn ensure_fresh(&mut self) -> bool {
+        let Some(DataSource::Local { repo, .. }) = &self.source else {
+            return true;
+        };
+        if self.repo_snapshot.as_ref() == Some(&RepoSnapshot::of(repo)) {
+            return true;
+        }
+        self.pending_confirm = Some(PendingConfirm::Refresh);
+        false
+    }
+
+    pub fn toggle_stage(&mut self) {
+        if self.structure_anchor.is_some() {
+            // Like `a` / `u` on the selection, in the direction Space moves the focused section
+            let stage = if self.has_sections() {
+                self.structure_section == StructureSection::Unstaged
+            } else {
+                self.selected_files().iter().any(|item| !item.is_staged)
+            };
+            self.set_listed_staged(stage);
+            return;
+        }
+        if !self.ensure_fresh() {
+            return;
+        }
+        let Some(DataSource::Local { repo, root }) = &self.source else {
+            return;
+        };
+        let Some(item) = self.selected_item().filter(|item| item.is_file) else {
+            return;
+        };
+        // Space moves the item to the other section; without sections it toggles
+        let stage = if self.has_sections() {
+            self.structure_section == StructureSection::Unstaged
+        } else {
+            !item.is_staged
+        };
+        let action = if stage { "staging" } else { "unstaging" };
+        let paths = [item.fs_path.clone()];
+        let result = IndexSnapshot::capture(repo, &paths, format!("{action} {}", item.path))
+            .and_then(|snapshot| {
+                Self::write_staging(repo, root, &paths, stage)?;
+                self.staging_undo.push(snapshot);
+                Ok(())
+            });
+        if let Err(e) = result {
+            self.notice = Some(format!("Action failed: {e}"));
+        }
+        self.refresh_local();
+    }
+
+    /// `a`: stages every listed file that has unstaged changes, in one index write.
+    pub fn stage_all(&mut self) {
+        self.set_listed_staged(true);
+    }
+
+    /// `u`: unstages every listed file that has staged changes.
+    pub fn unstage_all(&mut self) {
+        self.set_listed_staged(false);
+    }
+
+    fn set_listed_staged(&mut self, stage: bool) {
+        if !matches!(self.zoom_level, ZoomLevel::Structure) || !self.ensure_fresh() {
+            return;
+        }
+        // A visual selection narrows "listed" to the selected rows; with sections, "listed"
+        // otherwise means the section the files would move out of
+        let indices = if let Some((start, end)) = self.structure_selection() {
+            self.filtered_structure_indices[start..=end].to_vec()
+        } else if self.has_sections() {
+            self.section_indices(if stage {
+                StructureSection::Unstaged
+            } else {
+                StructureSection::Staged
+            })
+        } else {
+            self.filtered_structure_indices.clone()
+        };
+        let paths: Vec<PathBuf> = indices
+            .into_iter()
+            .map(|i| &self.structures[i])
+            .filter(|item| {
+                item.is_file
+                    && if stage {
+                        item.is_unstaged
+                    } else {
+                        item.is_staged
+                    }
+            })
+            .map(|item| item.fs_path.clone())
+            .collect();
+        if paths.is_empty() {
+            self.notice = Some(format!(
+                "Nothing to {}",
+                if stage { "stage" } else { "unstage" }
+            ));
+            return;
+        }
+        let action = if stage { "staging" } else { "unstaging" };
+        let result = match &self.source {
+            Some(DataSource::Local { repo, root }) => {
+                IndexSnapshot::capture(repo, &paths, format!("{action} {} file(s)", paths.len()))
+                    .and_then(|snapshot| {
+                        Self::write_staging(repo, root, &paths, stage)?;
+                        self.staging_undo.push(snapshot);
+                        Ok(())
+                    })
+            }
+            _ => return,
+        };
+        self.notice = Some(match result {
+            Ok(()) if stage => format!("Staged {} file(s)", paths.len()),
+            Ok(()) => format!("Unstaged {} file(s)", paths.len()),
+            Err(e) => format!("Action failed: {e}"),
+        });
+        self.refresh_local();
+    }
+
+    // --- Structure View Selection ---
+
+    /// `v`: starts a selection at the highlighted row, or drops the current one.
+    pub const fn toggle_structure_visual(&mut self) {
+        self.structure_anchor =
+            if self.structure_anchor.is_none() && !self.filtered_structure_indices.is_empty() {
+                Some(self.selected_index)
+            } else {
+                None
+            };
+    }
+
+    /// Inclusive range of selected positions in `filtered_structure_indices`.
+    #[must_use]
+    pub fn structure_selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.structure_anchor?;
+        let last = self.filtered_structure_indices.len().checked_sub(1)?;
+        Some((
+            anchor.min(self.selected_index).min(last),
+            anchor.max(self.selected_index).min(last),
+        ))
+    }
+
+    /// File rows inside the visual selection; symbol rows only ride along with their file.
+    #[must_use]
+    pub fn selected_files(&self) -> Vec<&StructureItem> {
+        self.structure_selection()
+            .map(|(start, end)| {
+                self.filtered_structure_indices[start..=end]
+                    .iter()
+                    .map(|&i| &self.structures[i])
+                    .filter(|item| item.is_file)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// `U`: reverts the most recent stage / unstage, leaving other index changes alone.
+    pub fn undo_staging(&mut self) {
+        if !self.ensure_fresh() {
+            return;
+        }
+        let Some(DataSource::Local { repo, .. }) = &self.source else {
+            return;
+        };
+        let Some(snapshot) = self.staging_undo.pop() else {
+            self.notice = Some("Nothing to undo".to_string());
+            return;
+        };
+        let description = snapshot.description.clone();
+        self.notice = Some(match snapshot.restore(repo) {
+            Ok(()) => format!("Undid {description}"),
+            Err(e) => format!("Undo failed: {e}"),
+        });
+        self.refresh_local();
+    }
+
+    /// Stages (or unstages) `paths` with a single read and write of the index.
+    fn write_staging(
+        repo: &Repository,
+        root: &Path,
+        paths: &[PathBuf],
+        stage: bool,
+    ) -> Result<(), git2::Error> {
+        if !stage && let Ok(head) = repo.head() {
+            // Resets the index entries to HEAD, which also writes the index
+            let head = head.peel(git2::ObjectType::Commit)?;
+            return repo.reset_default(Some(&head), paths);
+        }
+        let mut index = repo.index()?;
+        index.read(false)?;
+        for path in paths {
+            if stage && root.join(path).exists() {
+                index.add_path(path)?;
+            } else {
+                // Staging a deletion, or unstaging in a repository without commits
+                index.remove_path(path)?;
+            }
+        }
+        index.write()
+    }
+
+    /// Re-scans the working tree after it changed underneath us.
+    pub fn refresh_local(&mut self) {
+        // Files that stopped being changed may have been reverted or deleted
+        let previous = self.changed_files();
+        if let Some(DataSource::Local { repo, root }) = &self.source {
+            if !self.churn.is_current(repo) {
+                self.churn = Churn::compute(repo, self.config.heat_half_life_days);
+            }
+            let base_tree = Self::base_tree(repo, self.merge_base);
+            let (_, structures) = Self::scan_local_repo(
+                repo,
+                root,
+                &mut self.analyzer,
+                &self.config.heat_weights,
+                &self.config.scan,
+                base_tree.as_ref(),
+            );
+            let modules = self.local_modules(repo, base_tree.as_ref(), &structures);
+            let repo_name = std::mem::take(&mut self.dashboard_info.repo_name);
+            self.dashboard_info = self.local_dashboard(repo, repo_name, &structures);
+            drop(base_tree);
+            self.repo_snapshot = Some(RepoSnapshot::of(repo));
+            self.modules = modules;
+            self.structures = structures;
+            self.survey_changes();
+            self.update_search(); // Re-apply filter to update indices
+            let changed = self.changed_files();
+            self.request_index(IndexRequest::paths(previous.into_iter().chain(changed)));
+        }
+    }
+
+    /// Terminal window / tab title: the repository and branch, PR or commit under review.
+    #[must_use]
+    pub fn terminal_title(&self) -> String {
+        let info = &self.dashboard_info;
+        if self.source.is_none() {
+            return "glim".to_string();
+        }
+        format!("glim: {} {}", info.repo_name, info.branch_name)
+    }
+
+    /// Working directory of a local source, e.g. for the filesystem watcher.
+    #[must_use]
+    pub fn local_root(&self) -> Option<&Path> {
+        match &self.source {
+            Some(DataSource::Local { repo, .. }) => repo.workdir(),
+            _ => None,
+        }
+    }
+
+    /// Rescans after files changed on disk; the watcher leaves ignored paths out.
+    pub fn on_files_changed(&mut self, paths: &[PathBuf]) {
+        if !matches!(self.source, Some(DataSource::Local { .. })) {
+            return;
+        }
+        // Picked up by the index run the rescan starts
+        let touched = paths.iter().filter_map(|p| p.to_str().map(str::to_string));
+        self.index_queue
+            .get_or_insert_with(IndexRequest::default)
+            .merge(IndexRequest::paths(touched));
+        self.refresh_and_reload();
+    }
+
+    /// Rescans and, in the Logic view, reloads the diff with the cursor kept in place.
+    fn refresh_and_reload(&mut self) {
+        let cursor = self.logic_cursor;
+        self.refresh_local();
+        if self.zoom_level == ZoomLevel::Logic && self.conflict.is_none() {
+            self.load_diff();
+            self.logic_cursor = cursor.min(self.logic_view_content.len().saturating_sub(1));
+        }
+    }
+
+    // --- Safe Mode ---
+
+    /// Stashes the working tree, or restores it when safe mode is already on.
+    pub fn toggle_safe_mode(&mut self) {
+        let Some(DataSource::Local { repo, .. }) = &mut self.source else {
+            return;
+        };
+        self.notice = Some(if let Some(safe) = self.safe_mode.take() {
+            match safe.restore(repo) {
+                Ok(()) => "Safe mode off: working tree restored".to_string(),
+                Err(e) => {
+                    let msg = format!("Restore failed: {e}");
+                    self.safe_mode = Some(safe);
+                    msg
+                }
+            }
+        } else {
+            match SafeMode::begin(repo) {
+                Ok(safe) => {
+                    self.safe_mode = Some(safe);
+                    "Safe mode on: changes stashed until you press S again or quit".to_string()
+                }
+                Err(e) => format!("Safe mode unavailable: {e}"),
+            }
+        });
+        self.refresh_local();
+    }
+
+    /// Restores any safe-mode stash; called on exit so no work is left behind.
+    ///
+    /// # Errors
+    /// Returns an error if the stash cannot be re-applied.
+    pub fn restore_safe_mode(&mut self) -> anyhow::Result<()> {
+        if let (Some(safe), Some(DataSource::Local { repo, .. })) =
+            (self.safe_mode.take(), &mut self.source)
+        {
+            safe.restore(repo)?;
+        }
+        Ok(())
+    }
+
+    // --- Branches ---
+
+    pub fn open_branch_panel(&mut self) {
+        let Some(DataSource::Local { repo, .. }) = &self.source else {
+            return;
+        };
+        match BranchEntry::list(repo) {
+            Ok(entries) if entries.is_empty() => {
+                self.notice = Some("No branches yet".to_string());
+            }
+            Ok(entries) => {
+                let selected = entries.iter().position(|b| b.is_head).unwrap_or(0);
+                self.branch_panel = Some(BranchPanel { entries, selected });
+            }
+            Err(e) => self.notice = Some(format!("Cannot list branches: {e}")),
+        }
+    }
+
+    /// Checks out the highlighted branch and rescans the working tree.
+    pub fn checkout_selected_branch(&mut self) {
+        let Some(entry) = self.branch_panel.take().and_then(|p| p.current().cloned()) else {
+            return;
+        };
+        let Some(DataSource::Local { repo, .. }) = &self.source else {
+            return;
+        };
+        if let Err(e) = entry.checkout(repo) {
+            self.notice = Some(format!("Checkout failed: {e:#}"));
+            return;
+        }
+        self.notice = Some(format!("Switched to {}", entry.local_name()));
+        self.refresh_local();
+        self.selected_index = 0;
+        self.logic_view_content.clear();
+        self.select_first_file();
+    }
+
+    // --- Export ---
+
+    /// Diff lines for every changed file, keyed by path.
+    fn all_file_diffs(&self) -> HashMap<String, Vec<String>> {
+        match &self.source {
+            Some(DataSource::Local { repo, .. }) => {
+                let mut opts = DiffOptions::new();
+                opts.context_lines(self.context_lines)
+                    .include_untracked(true)
+                    .recurse_untracked_dirs(true)
+                    .show_untracked_content(true);
+                let tree = Self::base_tree(repo, self.merge_base)
+                    .or_else(|| repo.head().and_then(|h| h.peel_to_tree()).ok());
+                repo.diff_tree_to_workdir_with_index(tree.as_ref(), Some(&mut opts))
+                    .map(|diff| Self::patch_lines(&diff).into_iter().collect())
+                    .unwrap_or_default()
+            }
+            Some(DataSource::GitHub { file_diffs, .. } | DataSource::Commit { file_diffs, .. }) => {
+                file_diffs.clone()
+            }
+            None => HashMap::new(),
+        }
+    }
+
+    /// Writes an annotated Markdown review of the whole changeset to the data directory.
+    pub fn export_review(&mut self) {
+        if self.source.is_none() {
+            return;
+        }
+        let diffs = self.all_file_diffs();
+        let reports = self.file_reports(&diffs);
+        let markdown = render_markdown(&self.dashboard_info, &reports);
+        let name = file_name(&format!(
+            "{}-{}.md",
+            self.dashboard_info.repo_name, self.dashboard_info.branch_name
+        ));
+        let dir = get_data_dir().join("reviews");
+        let path = dir.join(name);
+        self.notice = Some(
+            match fs::create_dir_all(&dir).and_then(|()| fs::write(&path, markdown)) {
+                Ok(()) => format!("Review exported to {}", path.display()),
+                Err(e) => format!("Export failed: {e}"),
+            },
+        );
+    }
+
+    /// `H`: writes this reviewer's verdicts, notes, bookmarks, seen files and place in the
+    /// changeset to a handoff bundle a colleague can pick up with `--import`.
+    pub fn export_handoff(&mut self) {
+        if self.source.is_none() {
+            return;
+        }
+        let handoff = Handoff::new(
+            &self.verdicts,
+            &self.notes,
+            &self.opened,
+            Some(self.current_location()),
+        );
+        let path = handoff.path(&format!(
+            "{}-{}",
+            self.dashboard_info.repo_name, self.dashboard_info.branch_name
+        ));
+        self.notice = Some(match handoff.save(&path) {
+            Ok(()) => format!("Handoff bundle written to {}", path.display()),
+            Err(e) => format!("Handoff export failed: {e:#}"),
+        });
+    }
+
+    /// Takes over a colleague's verdicts from the handoff bundle at `path`, keeping any this
+    /// reviewer gave already, and goes to where they stopped; Ctrl-o comes back.
+    pub fn import_handoff(&mut self, path: &Path) {
+        let Some(key) = self.session_key() else {
+            return;
+        };
+        let handoff = match Handoff::load(path, &key) {
+            Ok(handoff) => handoff,
+            Err(e) => {
+                self.notice = Some(format!("Handoff not imported: {e:#}"));
+                return;
+            }
+        };
+        let merge = handoff.merge_into(&mut self.verdicts, &mut self.notes, &mut self.opened);
+        let plural = if merge.added == 1 { "" } else { "s" };
+        let mut notice = format!(
+            "Imported {} verdict{plural} from {}",
+            merge.added, handoff.reviewer
+        );
+        if merge.kept > 0 {
+            notice = format!("{notice} ({} of yours kept)", merge.kept);
+        }
+        notice = format!(
+            "{notice}, {} note(s) and bookmark(s), {} seen file(s)",
+            merge.notes, merge.viewed
+        );
+        if let Err(e) = self.verdicts.save().and_then(|()| self.notes.save()) {
+            notice = format!("{notice}; not saved: {e}");
+        }
+        if let Some(location) = &handoff.location {
+            self.record_jump();
+            self.go_to(location);
+        }
+        self.notice = Some(notice);
+    }
+
+    /// Copies a short Markdown summary of the changeset (stats, top modules, flagged issues)
+    /// to the clipboard.
+    pub fn copy_summary(&mut self) {
+        if self.source.is_none() {
+            return;
+        }
+        let diffs = self.all_file_diffs();
+        let reports = self.file_reports(&diffs);
+        let failing: Vec<&str> = self
+            .pr_checks()
+            .iter()
+            .filter(|c| c.state == CiState::Failing)
+            .map(|c| c.name.as_str())
+            .collect();
+        let summary = render_summary(&self.dashboard_info, &self.modules, &reports, &failing);
+        self.notice = Some(
+            match arboard::Clipboard::new().and_then(|mut c| c.set_text(summary)) {
+                Ok(()) => "Copied changeset summary".to_string(),
+                Err(e) => format!("Clipboard unavailable: {e}"),
+            },
+        );
+    }
+
+    /// What the exports say about each changed file, with its symbols and review state.
+    fn file_reports<'a>(&'a self, diffs: &'a HashMap<String, Vec<String>>) -> Vec<FileReport<'a>> {
+        let review = self.pr_review();
+        let mut reports: Vec<FileReport> = Vec::new();
+        for item in &self.structures {
+            if item.is_file {
+                if item.status == "dir" {
+                    continue;
+                }
+                reports.push(FileReport {
+                    path: &item.path,
+                    status: &item.status,
+                    diff: diffs.get(&item.path).map_or(&[], Vec::as_slice),
+                    annotations: review
+                        .map(|r| {
+                            r.annotations
+                                .iter()
+                                .filter(|a| a.path == item.path)
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                    unresolved_threads: review
+                        .and_then(|r| r.unresolved_threads.get(&item.path).copied())
+                        .unwrap_or(0),
+                    viewed: review.is_some_and(|r| r.viewed.contains(&item.path)),
+                    verdict: self.verdicts.get(&item.path),
+                    notes: self.notes.on(&item.path),
+                    ..FileReport::default()
+                });
+            } else if let Some(file) = reports.last_mut()
+                && file.path == item.path
+            {
+                file.symbols.push(item.text.trim());
+                if let Some(complexity) =
+                    item.complexity.filter(|c| item.touched && c.severity() > 0)
+                {
+                    file.complex.push((item.text.trim(), complexity));
+                }
+            }
+        }
+        reports
+    }
+
+    // --- Verdicts ---
+
+    /// Paths of the changed files, each once even when staged and unstaged.
+    #[must_use]
+    pub fn changed_paths(&self) -> Vec<&str> {
+        let mut paths: Vec<&str> = self
+            .structures
+            .iter()
+            .filter(|i| i.is_file && i.status != "dir")
+            .map(|i| i.path.as_str())
+            .collect();
+        paths.sort_unstable();
+        paths.dedup();
+        paths
+    }
+
+    /// Counts the selected file as looked at, for [`Self::review_progress`].
+    fn note_opened(&mut self) {
+        if let Some(item) = self.selected_item().filter(|item| item.is_file) {
+            let path = item.path.clone();
+            self.opened.insert(path);
+        }
+    }
+
+    /// Changed files opened in the Logic view this session, out of all of them, for the
+    /// header; `None` before anything is loaded.
+    #[must_use]
+    pub fn review_progress(&self) -> Option<(usize, usize)> {
+        let paths = self.changed_paths();
+        let opened = paths.iter().filter(|p| self.opened.contains(**p)).count();
+        (!paths.is_empty()).then_some((opened, paths.len()))
+    }
+
+    /// Dashboard roll-up of the verdicts, e.g. `3 approve, 1 blocked, 4 open`.
+    #[must_use]
+    pub fn verdict_summary(&self) -> Option<String> {
+        self.verdicts.summary(&self.changed_paths())
+    }
+
+    /// Moves the selected file (or the file of the selected symbol) to its next verdict.
+    pub fn cycle_verdict(&mut self) {
+        let Some(path) = self
+            .selected_item()
+            .filter(|i| i.status != "dir")
+            .map(|i| i.path.clone())
+        else {
+            return;
+        };
+        let verdict = self.verdicts.cycle(&path);
+        // A folded cluster is judged as a whole
+        let label = if let Some(cluster) = self
+            .cluster_of(&path)
+            .filter(|c| !self.expanded_clusters.contains(&c.key))
+            .cloned()
+        {
+            for member in &cluster.paths {
+                self.verdicts.set(member, verdict);
+            }
+            format!("{} files like {path}", cluster.paths.len())
+        } else {
+            path
+        };
+        self.notice = Some(match self.verdicts.save() {
+            Err(e) => format!("Verdict not saved: {e}"),
+            Ok(()) => verdict.map_or_else(
+                || format!("{label}: no verdict"),
+                |v| format!("{label}: {}", v.label()),
+            ),
+        });
+    }
+
+    // --- Mechanical Changes ---
+
+    /// Reads the whole diff for what spans files: the files making the same edit (expanded
+    /// clusters that still exist stay expanded), the newly public symbols and the broken ones.
+    fn survey_changes(&mut self) {
+        let diffs = self.all_file_diffs();
+        let mut api_changes: Vec<ApiSymbol> = diffs
+            .iter()
+            .flat_map(|(path, diff)| new_public_symbols(path, diff))
+            .collect();
+        api_changes.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+        self.api_changes = api_changes;
+        let mut api_breaks: Vec<ApiBreak> = diffs
+            .iter()
+            .flat_map(|(path, diff)| broken_rust_api(path, diff))
+            .collect();
+        api_breaks.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+        self.api_breaks = api_breaks;
+        self.clusters = if self.config.mechanical_min_files == 0 {
+            Vec::new()
+        } else {
+            mechanical::clusters(&diffs, self.config.mechanical_min_files)
+        };
+        let keys: HashSet<&str> = self.clusters.iter().map(|c| c.key.as_str()).collect();
+        self.expanded_clusters.retain(|k| keys.contains(k.as_str()));
+    }
+
+    /// "2 changed, 1 removed" for the public Rust items the changeset breaks; `None` when it
+    /// breaks none.
+    #[must_use]
+    pub fn api_break_summary(&self) -> Option<String> {
+        if self.api_breaks.is_empty() {
+            return None;
+        }
+        let removed = self.api_breaks.iter().filter(|b| b.removed).count();
+        let changed = self.api_breaks.len() - removed;
+        let mut parts = Vec::new();
+        if changed > 0 {
+            parts.push(format!("{changed} changed"));
+        }
+        if removed > 0 {
+            parts.push(format!("{removed} removed"));
+        }
+        Some(format!(
+            "⚠ {} public item(s): a breaking change for dependents",
+            parts.join(", ")
+        ))
+    }
+
+    /// The cluster of files making the same edit as `path`.
+    #[must_use]
+    pub fn cluster_of(&self, path: &str) -> Option<&Cluster> {
+        self.clusters
+            .iter()
+            .find(|c| c.paths.iter().any(|p| p == path))
+    }
+
+    /// Folds or lists in full the cluster of the selected file, keeping it selected.
+    pub fn toggle_cluster(&mut self) {
+        let Some(item) = self.selected_item() else {
+            return;
+        };
+        let (path, text) = (item.path.clone(), item.text.clone());
+        let Some(key) = self.cluster_of(&path).map(|c| c.key.clone()) else {
+            self.notice = Some(format!("{path} has no mechanical look-alikes"));
+            return;
+        };
+        if !self.expanded_clusters.remove(&key) {
+            self.expanded_clusters.insert(key);
+        }
+        self.update_search();
+        // Folding hides the selection unless it is the representative
+        let path = self
+            .cluster_of(&path)
+            .filter(|c| !self.expanded_clusters.contains(&c.key))
+            .map_or(path, |c| c.representative().to_string());
+        if let Some(pos) = self.filtered_structure_indices.iter().position(|&i| {
+            let item = &self.structures[i];
+            item.path == path && (item.text == text || item.is_file)
+        }) {
+            self.selected_index = pos;
+            self.load_diff();
+        }
+    }
+
+    // --- Quick Actions ---
+
+    pub fn open_action_menu(&mut self) {
+        if !matches!(self.zoom_level, ZoomLevel::Structure) {
+            return;
+        }
+        let is_local = matches!(self.source, Some(DataSource::Local { .. }));
+        if let Some(item) = self.selected_item() {
+            let mut actions = QuickAction::for_item(item, is_local);
+            if let Some(DataSource::Local { repo, .. }) = &self.source
+                && !CloneState::of(repo).is_complete()
+            {
+                actions.push(QuickAction::FetchHistory);
+            }
+            if let Some(DataSource::GitHub { drift: Some(_), .. }) = &self.source
+                && item.is_file
+            {
+                actions.push(QuickAction::ViewBaseDrift);
+            }
+            if self.pr_clone().is_some() {
+                actions.push(QuickAction::CheckoutPr);
+            }
+            if let Some(DataSource::GitHub { pr_info, .. }) = &self.source
+                && pr_info.state == "OPEN"
+            {
+                actions.push(if pr_info.is_draft {
+                    QuickAction::MarkReady
+                } else {
+                    QuickAction::ConvertToDraft
+                });
+            }
+            if is_local {
+                for command in [PaneCommand::Open, PaneCommand::Send] {
+                    if multiplexer::template(&self.config.pane, command).is_some() {
+                        actions.push(QuickAction::from(command));
+                    }
+                }
+            }
+            self.action_menu = Some(ActionMenu {
+                actions,
+                selected: 0,
+            });
+        }
+    }
+
+    pub fn run_selected_action(&mut self) {
+        let Some(action) = self.action_menu.take().and_then(|m| m.current()) else {
+            return;
+        };
+        match action {
+            QuickAction::ToggleStage => self.toggle_stage(),
+            QuickAction::Discard => self.request_discard(),
+            QuickAction::OpenInEditor => self.open_in_editor(),
+            QuickAction::CopyPath => self.copy_selected_path(),
+            QuickAction::ViewHistory => self.show_history(),
+            QuickAction::Blame => self.show_file_blame(),
+            QuickAction::FetchHistory => self.request_fetch_history(),
+            QuickAction::ViewBaseDrift => self.show_base_drift(),
+            QuickAction::CheckoutPr => self.request_checkout_pr(),
+            QuickAction::OpenInPane => self.run_pane_command(PaneCommand::Open),
+            QuickAction::SendToEditorPane => self.run_pane_command(PaneCommand::Send),
+            QuickAction::MarkReady => self.set_pr_draft(false),
+            QuickAction::ConvertToDraft => self.set_pr_draft(true),
+        }
+    }
+
+    /// Whether the PR under review is a draft.
+    #[must_use]
+    pub fn pr_is_draft(&self) -> bool {
+        matches!(&self.source, Some(DataSource::GitHub { pr_info, .. }) if pr_info.is_draft)
+    }
+
+    /// Converts the PR to a draft or marks it ready, then reads the state back from GitHub.
+    pub fn set_pr_draft(&mut self, draft: bool) {
+        let Some(DataSource::GitHub { pr_info, .. }) = &mut self.source else {
+            return;
+        };
+        if let Err(e) = GitHubClient::set_draft(pr_info, draft) {
+            self.notice = Some(format!("Changing the draft state failed: {e}"));
+            return;
+        }
+        // Someone may have changed it at the same time; fall back on what we asked for
+        pr_info.is_draft = GitHubClient::fetch_head(&PrRef::Url(pr_info.url.clone()))
+            .map_or(draft, |head| head.is_draft);
+        self.notice = Some(if pr_info.is_draft {
+            format!("PR #{} is a draft", pr_info.number)
+        } else {
+            format!("PR #{} is ready for review", pr_info.number)
+        });
+    }
+
+    /// Working directory of the clone of the PR's repository glim runs in, if it is one.
+    fn pr_clone(&self) -> Option<PathBuf> {
+        let Some(DataSource::GitHub { pr_info, .. }) = &self.source else {
+            return None;
+        };
+        let slug = pr_info.repo_slug()?;
+        let repo = Repository::discover(std::env::current_dir().ok()?).ok()?;
+        let remotes = repo.remotes().ok()?;
+        let is_clone = remotes.iter().flatten().any(|name| {
+            repo.find_remote(name)
+                .ok()
+                .and_then(|remote| remote.url().and_then(remote_slug))
+                .is_some_and(|remote| remote.eq_ignore_ascii_case(slug))
+        });
+        is_clone.then(|| repo.workdir().map(Path::to_path_buf))?
+    }
+
+    /// Asks before switching the local clone to the PR's branch.
+    pub fn request_checkout_pr(&mut self) {
+        let Some(DataSource::GitHub { pr_info, .. }) = &self.source else {
+            return;
+        };
+        let number = pr_info.number;
+        match self.pr_clone() {
+            Some(root) => self.pending_confirm = Some(PendingConfirm::CheckoutPr { root, number }),
+            None => {
+                self.notice = Some(
+                    "Run glim inside a clone of this repository to check out the PR".to_string(),
+                );
+            }
+        }
+    }
+
+    /// Checks the PR out in the clone at `root` and reloads it as a local repository, diffed
+    /// against the merge-base with the PR's base branch so the same changes show.
+    fn checkout_pr(&mut self, root: PathBuf) {
+        let Some(DataSource::GitHub { pr_info, .. }) = &self.source else {
+            return;
+        };
+        if let Err(e) = GitHubClient::checkout_pr(pr_info, &root) {
+            self.notice = Some(format!("Checkout failed: {e:#}"));
+            return;
+        }
+        let number = pr_info.number;
+        let mut config = self.config.clone();
+        if !pr_info.base_ref_name.is_empty() {
+            config.base_branch.clone_from(&pr_info.base_ref_name);
+            config.merge_base = true;
+        }
+        *self = Self::with_config(root, config);
+        if self.error_msg.is_none() {
+            self.notice = Some(format!("Checked out PR #{number}"));
+        }
+    }
+
+    // --- Suggested Changes ---
+
+    /// The suggested change in a review thread on the Logic view cursor line.
+    fn suggestion_at_cursor(&self) -> Option<Suggestion> {
+        self.line_threads()
+            .get(self.logic_cursor)?
+            .iter()
+            .find_map(|thread| Suggestion::from_thread(thread))
+    }
+
+    /// The lines of the PR's version of `path` the suggestion replaces, from its diff.
+    fn suggested_over(&self, suggestion: &Suggestion) -> Option<Vec<String>> {
+        let Some(DataSource::GitHub { file_diffs, .. }) = &self.source else {
+            return None;
+        };
+        let diff = file_diffs.get(&suggestion.path)?;
+        let lines: Vec<String> = diff
+            .iter()
+            .zip(line_numbers(diff))
+            .filter(|(_, (_, new))| {
+                new.is_some_and(|n| (suggestion.start..=suggestion.end).contains(&n))
+            })
+            .map(|(line, _)| line.get(1..).unwrap_or_default().to_string())
+            .collect();
+        (lines.len() == suggestion.end + 1 - suggestion.start).then_some(lines)
+    }
+
+    /// `a` in the Logic view: asks before writing the suggested change on the cursor line
+    /// into the local clone, once it is clear the clone has the lines it was made against.
+    pub fn request_apply_suggestion(&mut self) {
+        let Some(suggestion) = self.suggestion_at_cursor() else {
+            self.notice = Some("No suggested change on this line".to_string());
+            return;
+        };
+        let Some(root) = self.pr_clone() else {
+            self.notice =
+                Some("Run glim inside a clone of this repository to apply suggestions".to_string());
+            return;
+        };
+        let text = fs::read_to_string(root.join(&suggestion.path)).unwrap_or_default();
+        if suggestion.is_applied(&text) {
+            self.applied_suggestions
+                .insert((suggestion.path.clone(), suggestion.end));
+            self.notice = Some("The suggested change is already applied".to_string());
+            return;
+        }
+        let applies = self
+            .suggested_over(&suggestion)
+            .and_then(|expected| suggestion.apply(&text, &expected));
+        if applies.is_none() {
+            self.notice = Some(format!(
+                "{}:{} differs from the PR; check out the PR branch first",
+                suggestion.path,
+                suggestion.range()
+            ));
+            return;
+        }
+        self.pending_confirm = Some(PendingConfirm::ApplySuggestion { root, suggestion });
+    }
+
+    fn apply_suggestion(&mut self, root: &Path, suggestion: &Suggestion) {
+        let path = root.join(&suggestion.path);
+        let result = fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| {
+                let expected = self
+                    .suggested_over(suggestion)
+                    .ok_or_else(|| anyhow::anyhow!("the PR diff does not cover it"))?;
+                let applied = suggestion
+                    .apply(&text, &expected)
+                    .ok_or_else(|| anyhow::anyhow!("the file changed since it was checked"))?;
+                Ok(fs::write(&path, applied)?)
+            });
+        self.notice = Some(match result {
+            Ok(()) => {
+                self.applied_suggestions
+                    .insert((suggestion.path.clone(), suggestion.end));
+                format!(
+                    "Applied the suggestion to {}:{}",
+                    suggestion.path,
+                    suggestion.range()
+                )
+            }
+            Err(e) => format!("Suggestion not applied: {e:#}"),
+        });
+    }
+
+    /// Notes which suggested changes of the PR the local clone already has.
+    fn find_applied_suggestions(&mut self) {
+        self.applied_suggestions.clear();
+        let (Some(review), Some(root)) = (self.pr_review(), self.pr_clone()) else {
+            return;
+        };
+        let mut files: HashMap<String, String> = HashMap::new();
+        let mut applied = HashSet::new();
+        for suggestion in review.threads.iter().filter_map(Suggestion::from_thread) {
+            let text = files.entry(suggestion.path.clone()).or_insert_with(|| {
+                fs::read_to_string(root.join(&suggestion.path)).unwrap_or_default()
+            });
+            if suggestion.is_applied(text) {
+                applied.insert((suggestion.path, suggestion.end));
+            }
+        }
+        self.applied_suggestions = applied;
+    }
+
+    /// Asks before deepening a shallow clone or fetching the selected file's missing objects.
+    pub fn request_fetch_history(&mut self) {
+        let (Some(DataSource::Local { repo, .. }), Some(item)) =
+            (&self.source, self.selected_item())
+        else {
+            return;
+        };
+        let clone = CloneState::of(repo);
+        if clone.is_complete() {
+            self.notice = Some("This clone already has the full history".to_string());
+            return;
+        }
+        if self.history_fetch.is_some() {
+            self.notice = Some("Already fetching more history".to_string());
+            return;
+        }
+        self.pending_confirm = Some(PendingConfirm::FetchHistory {
+            path: item.fs_path.clone(),
+            commands: clone.fetch_description(),
+        });
+    }
+
+    /// Asks for confirmation before discarding the selected file's changes.
+    pub fn request_discard(&mut self) {
+        if !self.ensure_fresh() {
+            return;
+        }
+        if self.structure_anchor.is_some() {
+            self.request_discard_selection();
+            return;
+        }
+        let (Some(DataSource::Local { repo, .. }), Some(item)) =
+            (&self.source, self.selected_item())
+        else {
+            return;
+        };
+        if !item.is_file {
+            return;
+        }
+        self.pending_confirm = Some(PendingConfirm::Discard {
+            path: item.fs_path.clone(),
+            added: is_added(repo, &item.fs_path),
+        });
+    }
+
+    fn request_discard_selection(&mut self) {
+        let Some(DataSource::Local { repo, .. }) = &self.source else {
+            return;
+        };
+        let files: Vec<(PathBuf, bool)> = self
+            .selected_files()
+            .into_iter()
+            .map(|item| (item.fs_path.clone(), is_added(repo, &item.fs_path)))
+            .collect();
+        if files.is_empty() {
+            self.notice = Some("No files in the selection".to_string());
+        } else {
+            self.pending_confirm = Some(PendingConfirm::DiscardFiles(files));
+        }
+    }
+
+    /// `d` in the Logic view: asks to revert the unstaged hunk under the cursor, like
+    /// `checkout -p`.
+    pub fn request_discard_hunk(&mut self) {
+        if self.conflict.is_some()
+            || self.binary.is_some()
+            || self.lfs.is_some()
+            || self.history.is_some()
+        {
+            return;
+        }
+        if self.diff_scope == DiffScope::Staged {
+            self.notice = Some("Only unstaged hunks can be discarded (press t)".to_string());
+            return;
+        }
+        if !self.ensure_fresh() {
+            return;
+        }
+        let (Some(DataSource::Local { repo, .. }), Some(item)) =
+            (&self.source, self.selected_item())
+        else {
+            return;
+        };
+        let path = item.fs_path.clone();
+        let hunk = anchor_line(&self.logic_view_content, self.logic_cursor)
+            .and_then(|line| Some((line, Self::unstaged_hunk_at(repo, &path, line)?)));
+        match hunk {
+            Some((line, header)) => {
+                self.pending_confirm = Some(PendingConfirm::DiscardHunk { path, line, header });
+            }
+            None => self.notice = Some("No unstaged change under the cursor".to_string()),
+        }
+    }
+
+    /// Index-to-worktree diff of `path`; `reverse` makes the working tree the old side.
+    fn unstaged_diff<'r>(
+        repo: &'r Repository,
+        path: &Path,
+        reverse: bool,
+    ) -> Option<git2::Diff<'r>> {
+        let mut opts = DiffOptions::new();
+        opts.pathspec(path).reverse(reverse);
+        repo.diff_index_to_workdir(None, Some(&mut opts)).ok()
+    }
+
+    /// Header of the unstaged hunk covering working-tree `line` of `path`.
+    fn unstaged_hunk_at(repo: &Repository, path: &Path, line: usize) -> Option<String> {
+        let diff = Self::unstaged_diff(repo, path, false)?;
+        let file_patch = git2::Patch::from_diff(&diff, 0).ok()??;
+        (0..file_patch.num_hunks()).find_map(|i| {
+            let (hunk, _) = file_patch.hunk(i).ok()?;
+            let start = hunk.new_start() as usize;
+            (start..start + (hunk.new_lines() as usize).max(1))
+                .contains(&line)
+                .then(|| String::from_utf8_lossy(hunk.header()).trim().to_string())
+        })
+    }
+
+    /// Applies the reverse of the unstaged hunk covering `line` to the working tree.
+    fn discard_hunk(&self, path: &Path, line: usize) -> anyhow::Result<()> {
+        let Some(DataSource::Local { repo, .. }) = &self.source else {
+            return Ok(());
+        };
+        let diff = Self::unstaged_diff(repo, path, true)
+            .ok_or_else(|| anyhow::anyhow!("Failed to diff {}", path.display()))?;
+        let mut opts = git2::ApplyOptions::new();
+        // Reversed, the working tree is the old side, so the hunk is found by its old range
+        opts.hunk_callback(|hunk| {
+            hunk.is_some_and(|h| {
+                let start = h.old_start() as usize;
+                (start..start + (h.old_lines() as usize).max(1)).contains(&line)
+            })
+        });
+        repo.apply(&diff, git2::ApplyLocation::WorkDir, Some(&mut opts))?;
+        Ok(())
+    }
+
+    pub fn confirm_pending(&mut self) {
+        let Some(pending) = self.pending_confirm.take() else {
+            return;
+        };
+        // Actions that report their own outcome return early; the rest change files on disk
+        let (result, done) = match pending {
+            PendingConfirm::CherryPick { id, mainline, .. } => {
+                return self.finish_cherry_pick(id, mainline);
+            }
+            PendingConfirm::CheckoutPr { root, .. } => return self.checkout_pr(root),
+            PendingConfirm::ApplySuggestion { root, suggestion } => {
+                return self.apply_suggestion(&root, &suggestion);
+            }
+            PendingConfirm::RestoreSession(session) => {
+                self.restore_session(&session);
+                self.notice = Some(format!("Restored {}", session.describe()));
+                return;
+            }
+            PendingConfirm::Discard { path, added } => (
+                self.discard(&path, added),
+                format!("Discarded {}", path.display()),
+            ),
+            PendingConfirm::DiscardFiles(files) => (
+                files
+                    .iter()
+                    .try_for_each(|(path, added)| self.discard(path, *added)),
+                format!("Discarded {} files", files.len()),
+            ),
+            PendingConfirm::DiscardHunk { path, line, header } => (
+                self.discard_hunk(&path, line),
+                format!("Discarded hunk {header} in {}", path.display()),
+            ),
+            PendingConfirm::FetchHistory { path, .. } => (
+                self.fetch_history(&path),
+                format!("Fetching more history for {}…", path.display()),
+            ),
+            PendingConfirm::Refresh => (Ok(()), "Refreshed".to_string()),
+        };
+        self.notice = Some(result.map_or_else(|e| format!("Action failed: {e}"), |()| done));
+        self.refresh_and_reload();
+    }
+
+    /// Starts the git fetch in the background; network round trips would freeze the UI.
+    fn fetch_history(&mut self, path: &Path) -> anyhow::Result<()> {
+        let Some(DataSource::Local { repo, root }) = &self.source else {
+            return Ok(());
+        };
+        let (clone, root, file) = (CloneState::of(repo), root.clone(), path.to_path_buf());
+        let (tx, rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("glim-fetch-history".to_string())
+            .spawn(move || {
+                let _ = tx.send(clone.fetch(&root, &file));
+            })?;
+        self.history_fetch = Some((path.to_path_buf(), rx));
+        Ok(())
+    }
+
+    /// Picks up the background fetch of more history once git is done, and reloads so blame
+    /// and file history see it.
+    pub fn poll_history_fetch(&mut self) {
+        let Some((path, rx)) = &self.history_fetch else {
+            return;
+        };
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Err(anyhow::anyhow!("the fetch stopped")),
+        };
+        let path = path.clone();
+        self.history_fetch = None;
+        let result = result.and_then(|()| {
+            if let Some(DataSource::Local { repo, .. }) = &mut self.source {
+                // libgit2 reads the shallow boundary once per handle
+                *repo = Repository::open(repo.path())?;
+            }
+            Ok(())
+        });
+        self.notice = Some(match result {
+            Ok(()) => format!("Fetched more history for {}", path.display()),
+            Err(e) => format!("Fetching more history failed: {e:#}"),
+        });
+        self.refresh_and_reload();
+    }
+
+    /// Restores `path` in the working tree from the index, like `git restore`; a file new
+    /// since HEAD is taken out of the index and deleted instead.
+    fn discard(&self, path: &Path, added: bool) -> anyhow::Result<()> {
+        let Some(DataSource::Local { repo, root }) = &self.source else {
+            return Ok(());
+        };
+        if added {
+            let mut index = repo.index()?;
+            if index.get_path(path, 0).is_some() {
+                index.remove_path(path)?;
+                index.write()?;
+            }
+            match fs::remove_file(root.join(path)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        } else {
+            let mut opts = git2::build::CheckoutBuilder::new();
+            opts.path(path).force();
+            repo.checkout_index(None, Some(&mut opts))?;
+        }
+        Ok(())
+    }
+
+    pub fn open_in_editor(&mut self) {
+        if let (Some(DataSource::Local { root, .. }), Some(item)) =
+            (&self.source, self.selected_item())
+        {
+            self.pending_editor = Some((root.join(&item.fs_path), item.line_no));
+        }
+    }
+
+    /// Opens the selected file in a tmux / zellij pane, or sends it to the editor pane, at
+    /// the Logic cursor's line (the symbol's line from the Structure view).
+    pub fn run_pane_command(&mut self, command: PaneCommand) {
+        let Some(template) = multiplexer::template(&self.config.pane, command) else {
+            self.notice = Some(format!(
+                "Not inside tmux or zellij; set [pane] commands in {}",
+                Config::path().display()
+            ));
+            return;
+        };
+        let (Some(DataSource::Local { root, .. }), Some(item)) =
+            (&self.source, self.selected_item())
+        else {
+            self.notice = Some("Select a file of a local repository first".to_string());
+            return;
+        };
+        let cursor_line = (self.zoom_level == ZoomLevel::Logic)
+            .then(|| anchor_line(&self.logic_view_content, self.logic_cursor))
+            .flatten();
+        let line = cursor_line.or(item.line_no).unwrap_or(1);
+        let expanded = multiplexer::expand(&template, &root.join(&item.fs_path), line, root);
+        self.notice = Some(match multiplexer::run(&expanded, root) {
+            Ok(()) => format!("{}: {}:{line}", command.label(), item.path),
+            Err(e) => format!("{} failed: {e}", command.label()),
+        });
+    }
+
+    pub fn copy_selected_path(&mut self) {
+        let Some(path) = self.selected_item().map(|i| i.path.clone()) else {
+            return;
+        };
+        self.notice = Some(
+            match arboard::Clipboard::new().and_then(|mut c| c.set_text(path.clone())) {
+                Ok(()) => format!("Copied {path}"),
+                Err(e) => format!("Clipboard unavailable: {e}"),
+            },
+        );
+    }
+
+    // --- Logic View Selection ---
+
+    /// `V`: starts a line selection at the cursor, or drops the current one.
+    pub const fn toggle_logic_visual(&mut self) {
+        self.logic_anchor = if self.logic_anchor.is_none() && !self.logic_view_content.is_empty() {
+            Some(self.logic_cursor)
+        } else {
+            None
+        };
+    }
+
+    /// Inclusive line range of the visual selection, in `logic_view_content` order.
+    #[must_use]
+    pub fn logic_selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.logic_anchor?;
+        let last = self.logic_view_content.len().checked_sub(1)?;
+        Some((
+            anchor.min(self.logic_cursor).min(last),
+            anchor.max(self.logic_cursor).min(last),
+        ))
+    }
+
+    /// `y`: copies the selected lines (or the cursor line) without the diff markers.
+    pub fn copy_logic_selection(&mut self) {
+        let (start, end) = self
+            .logic_selection()
+            .unwrap_or((self.logic_cursor, self.logic_cursor));
+        let Some(lines) = self.logic_view_content.get(start..=end) else {
+            return;
+        };
+        // Hunk and file headers are diff plumbing, not part of the code region
+        let text: Vec<&str> = lines
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| self.logic_line_visible(start + i))
+            .map(|(_, l)| l)
+            .filter(|l| !l.trim_start().starts_with("@@") && !is_meta_line(l))
+            .map(|l| l.get(1..).unwrap_or_default())
+            .collect();
+        self.notice = Some(
+            match arboard::Clipboard::new().and_then(|mut c| c.set_text(text.join("\n"))) {
+                Ok(()) => format!("Copied {} line(s)", text.len()),
+                Err(e) => format!("Clipboard unavailable: {e}"),
+            },
+        );
+        self.logic_anchor = None;
+    }
+
+    /// Shows the commits touching the selected file in the Logic view.
+    pub fn show_history(&mut self) {
+        let (Some(DataSource::Local { repo, .. }), Some(item)) =
+            (&self.source, self.selected_item())
+        else {
+            return;
+        };
+        // Walking thousands of commits takes a while; the walk gets a handle of its own
+        let (git_dir, path) = (repo.path().to_path_buf(), item.fs_path.clone());
+        let (tx, rx) = mpsc::channel();
+        let walk = {
+            let path = path.clone();
+            std::thread::Builder::new()
+                .name("glim-history".to_string())
+                .spawn(move || {
+                    let history =
+                        Repository::open(git_dir).and_then(|repo| FileHistory::load(&repo, &path));
+                    let _ = tx.send(history);
+                })
+        };
+        if let Err(e) = walk {
+            return self.set_history(Err(git2::Error::from_str(&e.to_string())));
+        }
+        let history = FileHistory::reading(&path);
+        self.logic_view_content = history.listing(&item.path);
+        self.history = Some(history);
+        self.history_load = Some(rx);
+        self.highlight_content();
+        self.logic_cursor = 0;
+        self.zoom_level = ZoomLevel::Logic;
+    }
+
+    /// Shows the finished history walk, unless the listing it was for has been left.
+    pub fn poll_history(&mut self) {
+        let Some(rx) = &self.history_load else {
+            return;
+        };
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Err(git2::Error::from_str("the walk stopped")),
+        };
+        self.history_load = None;
+        if self.history.as_ref().is_some_and(|h| h.loading) {
+            self.set_history(result);
+        }
+    }
+
+    /// Replaces the Logic view with the file history listing, or why it could not be read.
+    fn set_history(&mut self, result: Result<FileHistory, git2::Error>) {
+        let (Some(DataSource::Local { repo, .. }), Some(item)) =
+            (&self.source, self.selected_item())
+        else {
+            return;
+        };
+        let warning = CloneState::of(repo).warning("File history");
+        match result {
+            Ok(mut history) => {
+                history.note = warning;
+                self.logic_view_content = history.listing(&item.path);
+                self.history = Some(history);
+            }
+            Err(e) => {
+                self.logic_view_content = vec![
+                    format!(" History of {}", item.path),
+                    String::new(),
+                    format!(" Failed to read history: {e}"),
+                ];
+                self.logic_view_content
+                    .extend(warning.map(|warning| format!(" {warning}")));
+                self.history = None;
+            }
+        }
+        self.highlight_content();
+        self.logic_cursor = 0;
+        self.zoom_level = ZoomLevel::Logic;
+    }
+
+    /// GitHub API quota left, for the header; empty for local sources.
+    #[must_use]
+    pub fn rate_limits(&self) -> Vec<RateLimit> {
+        if self.pr_review().is_some() {
+            GitHubClient::rate_limits()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// CI checks of the PR's head commit; empty for local sources.
+    #[must_use]
+    pub fn pr_checks(&self) -> &[CheckStatus] {
+        match self.pr_review() {
+            Some(review) if self.config.show_checks => &review.checks,
+            _ => &[],
+        }
+    }
+
+    /// Opens the CI checks popup on the first (most urgent) check.
+    pub fn open_checks(&mut self) {
+        if self.pr_checks().is_empty() {
+            self.notice = Some("No CI checks reported for this PR".to_string());
+        } else {
+            self.checks_cursor = Some(0);
+        }
+    }
+
+    pub fn move_checks_cursor(&mut self, delta: isize) {
+        let last = self.pr_checks().len().saturating_sub(1);
+        if let Some(cursor) = self.checks_cursor.as_mut() {
+            *cursor = cursor.saturating_add_signed(delta).min(last);
+        }
+    }
+
+    /// Opens the highlighted check's details page in the browser.
+    pub fn open_check_details(&mut self) {
+        let Some(check) = self.checks_cursor.and_then(|i| self.pr_checks().get(i)) else {
+            return;
+        };
+        self.notice = Some(check.url.as_ref().map_or_else(
+            || format!("{} has no details page", check.name),
+            |url| match open_url(url) {
+                Ok(()) => format!("Opened {url}"),
+                Err(e) => format!("Cannot open {url}: {e}"),
+            },
+        ));
+    }
+
+    /// The PR's description; `None` for local sources and PRs left without one.
+    #[must_use]
+    pub fn pr_description(&self) -> Option<&str> {
+        match &self.source {
+            Some(DataSource::GitHub { pr_info, .. }) if !pr_info.body.trim().is_empty() => {
+                Some(&pr_info.body)
+            }
+            _ => None,
+        }
+    }
+
+    /// Opens the whole PR description in a scrollable popup.
+    pub fn open_description(&mut self) {
+        if self.pr_description().is_none() {
+            self.notice = Some("This PR has no description".to_string());
+        } else {
+            self.description_scroll = Some(0);
+        }
+    }
+
+    pub fn scroll_description(&mut self, delta: i16) {
+        let rows = self
+            .pr_description()
+            .map_or(0, |body| markdown::render(body).len());
+        let last = u16::try_from(rows.saturating_sub(1)).unwrap_or(u16::MAX);
+        if let Some(scroll) = self.description_scroll.as_mut() {
+            *scroll = scroll.saturating_add_signed(delta).min(last);
+        }
+    }
+
+    // --- Profiles ---
+
+    /// Rows of the profile picker: the base settings, then the profiles in `config.toml`.
+    #[must_use]
+    pub fn profile_names(&self) -> Vec<&str> {
+        std::iter::once("(no profile)")
+            .chain(self.config.base().profiles.keys().map(String::as_str))
+            .collect()
+    }
+
+    /// Opens the profile picker on the active profile.
+    pub fn open_profiles(&mut self) {
+        if self.config.base().profiles.is_empty() {
+            self.notice = Some(format!("No [profiles] in {}", Config::path().display()));
+            return;
+        }
+        let active = self.config.profile();
+        self.profile_cursor = Some(
+            self.profile_names()
+                .iter()
+                .position(|&name| Some(name) == active)
+                .unwrap_or(0),
+        );
+    }
+
+    pub fn move_profile_cursor(&mut self, delta: isize) {
+        let last = self.profile_names().len() - 1;
+        if let Some(cursor) = self.profile_cursor.as_mut() {
+            *cursor = cursor.saturating_add_signed(delta).min(last);
+        }
+    }
+
+    /// Switches to the highlighted profile, re-running panels and rescanning a local
+    /// repository so its filters and heat weights apply.
+    pub fn switch_profile(&mut self) {
+        let Some(cursor) = self.profile_cursor.take() else {
+            return;
+        };
+        let config = match cursor {
+            0 => self.config.without_profile(),
+            i => self.config.with_profile(self.profile_names()[i]),
+        };
+        match config {
+            Ok(config) => {
+                self.config = config;
+                self.run_panels();
+                self.notice = Some(format!(
+                    "Profile: {}",
+                    self.config.profile().unwrap_or("none")
+                ));
+                self.load_symbol_queries();
+                self.refresh_local();
+            }
+            Err(e) => self.notice = Some(format!("{e:#}")),
+        }
+    }
+
+    /// Rebuilds the analyzer with the config's `symbol_queries`, keeping the built-in queries
+    /// when they are broken.
+    fn load_symbol_queries(&mut self) {
+        match SemanticAnalyzer::with_queries(&self.config.symbol_queries) {
+            Ok(analyzer) => self.analyzer = analyzer,
+            Err(e) => {
+                self.analyzer = SemanticAnalyzer::new();
+                self.notice = Some(format!("{e:#}; using the built-in symbol queries"));
+            }
+        }
+        // Symbols found with the old queries are stale
+        self.reset_index();
+        self.index_queue = Some(IndexRequest::full(Vec::new()));
+    }
+
+    /// Opens the review composer; PR sources only.
+    pub fn open_review_draft(&mut self) {
+        if matches!(self.source, Some(DataSource::GitHub { .. })) {
+            self.review_draft = Some(ReviewDraft::default());
+        } else {
+            self.notice = Some("Reviews can only be submitted for pull requests".to_string());
+        }
+    }
+
+    /// Sends the composed review; the draft stays open when GitHub refuses it.
+    pub fn submit_review(&mut self) {
+        let (Some(draft), Some(DataSource::GitHub { pr_info, .. })) =
+            (&self.review_draft, &self.source)
+        else {
+            return;
+        };
+        let mut body = draft.body();
+        if self.config.verdicts_in_review
+            && let Some(verdicts) = self.verdicts.comment(&self.changed_paths())
+        {
+            body = if body.is_empty() {
+                verdicts
+            } else {
+                format!("{body}\n\n{verdicts}")
+            };
+        }
+        if body.is_empty() && draft.event.needs_body() {
+            self.notice = Some(format!("{} needs a message", draft.event.label()));
+            return;
+        }
+        match GitHubClient::submit_review(pr_info, draft.event, &body) {
+            Ok(()) => {
+                let mut notice = format!(
+                    "Submitted review ({}) on PR #{}",
+                    draft.event.label(),
+                    pr_info.number
+                );
+                self.review_draft = None;
+                if let Some(queue) = self.review_queue.as_mut() {
+                    queue.mark_reviewed(&pr_info.url);
+                    if let Some((reviewed, total)) = queue.progress() {
+                        notice = format!("{notice}; {reviewed} of {total} reviewed");
+                    }
+                    self.return_to_queue();
                 }
+                self.notice = Some(notice);
+            }
+            Err(e) => self.notice = Some(format!("Review not submitted: {e}")),
+        }
+    }
+
+    /// `c` in the Logic view: opens a comment on the cursor line of the PR's diff.
+    pub fn open_line_comment(&mut self) {
+        if self.pr_review().is_none() || self.showing_drift {
+            self.notice =
+                Some("Line comments can only be left on a pull request's diff".to_string());
+            return;
+        }
+        let Some(path) = self.selected_item().map(|item| item.path.clone()) else {
+            return;
+        };
+        let numbers = line_numbers(&self.logic_view_content);
+        let draft = match numbers.get(self.logic_cursor) {
+            Some(&(Some(old), None)) => CommentDraft::new(path, old, true),
+            Some(&(_, Some(new))) => CommentDraft::new(path, new, false),
+            _ => {
+                self.notice = Some("Move the cursor onto a line of the diff".to_string());
+                return;
+            }
+        };
+        self.comment_draft = Some(draft);
+    }
+
+    /// `n` in the Logic view: opens the private note on the cursor line, to write or edit.
+    pub fn open_line_note(&mut self) {
+        if self.showing_drift || self.history.is_some() {
+            self.notice = Some("Notes can only be left on the changeset's own diff".to_string());
+            return;
+        }
+        let Some(path) = self.selected_item().map(|item| item.path.clone()) else {
+            return;
+        };
+        let numbers = line_numbers(&self.logic_view_content);
+        let (line, left) = match numbers.get(self.logic_cursor) {
+            Some(&(Some(old), None)) => (old, true),
+            Some(&(_, Some(new))) => (new, false),
+            _ => {
+                self.notice = Some("Move the cursor onto a line of the diff".to_string());
+                return;
+            }
+        };
+        let text = self
+            .notes
+            .get(&path, line, left)
+            .unwrap_or_default()
+            .to_string();
+        self.note_draft = Some(CommentDraft::note(path, line, left, &text));
+    }
+
+    /// Keeps the note being written; an empty one removes the note on its line.
+    pub fn save_line_note(&mut self) {
+        let Some(draft) = self.note_draft.take() else {
+            return;
+        };
+        let body = draft.body();
+        self.notes.set(&draft.path, draft.line, draft.left, &body);
+        let done = if body.is_empty() { "removed" } else { "saved" };
+        self.notice = Some(match self.notes.save() {
+            Ok(()) => format!("Note {done} on {}:{}", draft.path, draft.line),
+            Err(e) => format!("Note {done} for this session only: {e}"),
+        });
+    }
+
+    /// Posts the line comment; the draft stays open when GitHub refuses it. The comment shows
+    /// under its line at once, as a thread of its own.
+    pub fn submit_line_comment(&mut self) {
+        let (
+            Some(draft),
+            Some(DataSource::GitHub {
+                pr_info, review, ..
+            }),
+        ) = (&self.comment_draft, &mut self.source)
+        else {
+            return;
+        };
+        let body = draft.body();
+        if body.is_empty() {
+            self.notice = Some("A comment needs a message".to_string());
+            return;
+        }
+        match GitHubClient::post_line_comment(pr_info, &draft.path, draft.line, draft.left, &body) {
+            Ok(comment) => {
+                self.notice = Some(format!("Commented on {}:{}", draft.path, draft.line));
+                *review
+                    .unresolved_threads
+                    .entry(draft.path.clone())
+                    .or_insert(0) += 1;
+                review.threads.push(ReviewThread {
+                    path: draft.path.clone(),
+                    line: draft.line,
+                    start_line: None,
+                    left: draft.left,
+                    resolved: false,
+                    comments: vec![comment],
+                });
+                self.comment_draft = None;
+            }
+            Err(e) => self.notice = Some(format!("Comment not posted: {e}")),
+        }
+    }
+
+    /// `v` in the Logic view: ticks the selected file's "Viewed" box on the PR, or clears it,
+    /// as the ✔ in the Structure list shows.
+    pub fn toggle_viewed(&mut self) {
+        let Some(path) = self.selected_item().map(|item| item.path.clone()) else {
+            return;
+        };
+        let Some(DataSource::GitHub {
+            pr_info, review, ..
+        }) = &mut self.source
+        else {
+            return;
+        };
+        let viewed = !review.viewed.contains(&path);
+        self.notice = Some(match GitHubClient::set_viewed(pr_info, &path, viewed) {
+            Ok(()) if viewed => {
+                review.viewed.insert(path.clone());
+                format!("Marked {path} as viewed")
+            }
+            Ok(()) => {
+                review.viewed.remove(&path);
+                format!("Marked {path} as not viewed")
+            }
+            Err(e) => format!("Viewed state not updated: {e:#}"),
+        });
+    }
+
+    /// Requested reviewers and assignees for the dashboard, e.g.
+    /// `reviewers: ana, o/core · assignees: none`; `None` for local sources.
+    #[must_use]
+    pub fn pr_people(&self) -> Option<String> {
+        let review = self.pr_review()?;
+        let names = |people: &[String]| {
+            if people.is_empty() {
+                "none".to_string()
             } else {
-                index.add_path(file_path).expect("Failed to add path");
+                people.join(", ")
             }
-            index.write().expect("Failed to write index");
+        };
+        Some(format!(
+            "reviewers: {} · assignees: {}",
+            names(&review.reviewers),
+            names(&review.assignees)
+        ))
+    }
 
-            let (modules, structures) = Self::scan_local_repo(repo, path, &mut self.analyzer);
-            self.modules = modules;
-            self.structures = structures;
-            self.update_search(); // Re-apply filter to update indices
+    /// Lists the people who can review or be assigned the PR; its author is left out.
+    pub fn open_people_picker(&mut self) {
+        let Some(DataSource::GitHub { pr_info, .. }) = &self.source else {
+            self.notice = Some("Reviewers can only be requested for pull requests".to_string());
+            return;
+        };
+        match GitHubClient::fetch_people(pr_info) {
+            Ok(mut people) => {
+                people.retain(|p| *p != pr_info.author.login);
+                self.people_picker = Some(PeoplePicker::new(people));
+            }
+            Err(e) => self.notice = Some(format!("Cannot list people: {e}")),
+        }
+    }
+
+    /// Requests a review from, or assigns, the highlighted person; the picker stays open
+    /// when GitHub refuses.
+    pub fn add_person(&mut self) {
+        let (
+            Some(picker),
+            Some(DataSource::GitHub {
+                pr_info, review, ..
+            }),
+        ) = (&self.people_picker, &mut self.source)
+        else {
+            return;
+        };
+        let Some(login) = picker.current().map(String::from) else {
+            return;
+        };
+        let role = picker.role;
+        match GitHubClient::add_person(pr_info, role, &login) {
+            Ok(()) => {
+                let people = match role {
+                    PeopleRole::Reviewer => &mut review.reviewers,
+                    PeopleRole::Assignee => &mut review.assignees,
+                };
+                if !people.contains(&login) {
+                    people.push(login.clone());
+                }
+                self.notice = Some(format!(
+                    "{}: {login} on PR #{}",
+                    role.label(),
+                    pr_info.number
+                ));
+                self.people_picker = None;
+            }
+            Err(e) => self.notice = Some(format!("{} failed: {e}", role.label())),
+        }
+    }
+
+    /// Lists the repository's labels to put on the PR or take off it.
+    pub fn open_label_picker(&mut self) {
+        let Some(DataSource::GitHub { pr_info, .. }) = &self.source else {
+            self.notice = Some("Labels can only be edited on pull requests".to_string());
+            return;
+        };
+        match GitHubClient::fetch_labels(pr_info) {
+            Ok(labels) => self.label_picker = Some(LabelPicker::new(labels)),
+            Err(e) => self.notice = Some(format!("Cannot list labels: {e}")),
+        }
+    }
+
+    /// Takes the highlighted label off the PR when it has it, puts it on otherwise; the
+    /// picker stays open for the next one.
+    pub fn toggle_label(&mut self) {
+        let (
+            Some(picker),
+            Some(DataSource::GitHub {
+                pr_info, review, ..
+            }),
+        ) = (&self.label_picker, &mut self.source)
+        else {
+            return;
+        };
+        let Some(label) = picker.current().cloned() else {
+            return;
+        };
+        let on = !review.labels.iter().any(|l| l.name == label.name);
+        if let Err(e) = GitHubClient::set_label(pr_info, &label.name, on) {
+            self.notice = Some(format!("Label not changed: {e}"));
+            return;
+        }
+        self.notice = Some(if on {
+            format!("Labelled PR #{} {}", pr_info.number, label.name)
+        } else {
+            format!("Removed {} from PR #{}", label.name, pr_info.number)
+        });
+        if on {
+            review.labels.push(label);
+        } else {
+            review.labels.retain(|l| l.name != label.name);
+        }
+    }
+
+    /// Shows how far the PR's base branch moved on, and what changed in the selected file there.
+    pub fn show_base_drift(&mut self) {
+        let (
+            Some(DataSource::GitHub {
+                pr_info,
+                drift: Some(drift),
+                ..
+            }),
+            Some(item),
+        ) = (&self.source, self.selected_item())
+        else {
+            return;
+        };
+        self.logic_view_content = drift.lines(pr_info, &item.path);
+        self.highlight_content();
+        self.showing_drift = true;
+        self.logic_cursor = 0;
+        self.zoom_level = ZoomLevel::Logic;
+    }
+
+    /// Enter on a history entry: shows that commit's change to the file.
+    fn open_history_entry(&mut self) {
+        let Some(history) = self.history.as_mut() else {
+            return;
+        };
+        let Some(index) = history.entry_at(self.logic_cursor) else {
+            return;
+        };
+        history.open = Some((index, self.logic_cursor));
+        history.merge_view = MergeView::Parent(0);
+        self.logic_cursor = 0;
+        self.render_history_entry();
+    }
+
+    /// Back from a commit to the history listing; false when no commit was open.
+    fn close_history_entry(&mut self) -> bool {
+        let Some((_, cursor)) = self.history.as_mut().and_then(|h| h.open.take()) else {
+            return false;
+        };
+        if let (Some(history), Some(item)) = (&self.history, self.selected_item()) {
+            self.logic_view_content = history.listing(&item.path);
+        }
+        self.highlight_content();
+        self.logic_cursor = cursor;
+        true
+    }
+
+    /// For an opened merge commit: compares with the next parent, then shows the combined diff.
+    pub fn cycle_merge_view(&mut self) {
+        let Some(history) = self.history.as_mut() else {
+            return;
+        };
+        let Some((index, _)) = history.open else {
+            return;
+        };
+        let parents = history.entries[index].parents;
+        if parents < 2 {
+            self.notice = Some("Not a merge commit".to_string());
+            return;
+        }
+        history.merge_view = history.merge_view.cycle(parents);
+        self.logic_cursor = 0;
+        self.render_history_entry();
+    }
+
+    /// `c` in the history view: asks before applying the commit under the cursor (or the
+    /// opened one) onto HEAD. Merges are picked against the parent shown with `m`.
+    pub fn request_cherry_pick(&mut self) {
+        let Some(history) = &self.history else {
+            return;
+        };
+        let index = match history.open {
+            Some((index, _)) => Some(index),
+            None => history.entry_at(self.logic_cursor),
+        };
+        let Some(entry) = index.map(|i| &history.entries[i]) else {
+            return;
+        };
+        if entry.on_head {
+            self.notice = Some("That commit is already on the current branch".to_string());
+            return;
+        }
+        let (id, parents) = (entry.id, entry.parents);
+        let mainline = match (parents, history.open, history.merge_view) {
+            (0 | 1, _, _) => 0,
+            (_, Some(_), MergeView::Parent(n)) => n as u32 + 1,
+            _ => {
+                self.notice =
+                    Some("Open the merge and choose a parent with m to cherry-pick it".to_string());
+                return;
+            }
+        };
+        if !self.ensure_fresh() {
+            return;
+        }
+        let Some(DataSource::Local { repo, .. }) = &self.source else {
+            return;
+        };
+        let summary = repo
+            .find_commit(id)
+            .ok()
+            .and_then(|c| c.summary().map(str::to_string))
+            .unwrap_or_default();
+        self.pending_confirm = Some(PendingConfirm::CherryPick {
+            id,
+            summary,
+            mainline,
+        });
+    }
+
+    /// Runs a confirmed cherry-pick; on conflicts, opens the first conflicted file.
+    fn finish_cherry_pick(&mut self, id: Oid, mainline: u32) {
+        let result = match &self.source {
+            Some(DataSource::Local { repo, .. }) => Self::cherry_pick(repo, id, mainline),
+            _ => return,
+        };
+        self.refresh_and_reload();
+        match result {
+            Ok(CherryPickOutcome::Committed(new)) => {
+                self.notice = Some(format!("Cherry-picked {id:.7} as {new:.7}"));
+            }
+            Ok(CherryPickOutcome::Conflicted(paths)) => {
+                if let Some(path) = paths.first() {
+                    self.reveal(path, None);
+                }
+                self.notice = Some(format!(
+                    "Cherry-pick stopped on conflicts in {} file(s); resolve them, then `git cherry-pick --continue`",
+                    paths.len()
+                ));
+            }
+            Err(e) => self.notice = Some(format!("Cherry-pick failed: {e}")),
+        }
+    }
+
+    /// Applies `id` onto HEAD like `git cherry-pick`, committing when it applies cleanly.
+    fn cherry_pick(repo: &Repository, id: Oid, mainline: u32) -> anyhow::Result<CherryPickOutcome> {
+        if repo.state() != git2::RepositoryState::Clean {
+            anyhow::bail!("another merge, rebase or cherry-pick is in progress");
+        }
+        let head = repo.head()?.peel_to_commit()?;
+        // The pick is committed from the index, which must not carry unrelated staged work
+        let staged = repo.diff_tree_to_index(Some(&head.tree()?), None, None)?;
+        if staged.deltas().len() > 0 {
+            anyhow::bail!("commit or unstage your staged changes first");
+        }
+
+        let commit = repo.find_commit(id)?;
+        let mut opts = git2::CherrypickOptions::new();
+        opts.mainline(mainline);
+        repo.cherrypick(&commit, Some(&mut opts))?;
+
+        let mut index = repo.index()?;
+        if index.has_conflicts() {
+            let paths = index
+                .conflicts()?
+                .filter_map(Result::ok)
+                .filter_map(|c| c.our.or(c.their).or(c.ancestor))
+                .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+                .collect();
+            return Ok(CherryPickOutcome::Conflicted(paths));
         }
+        let tree = repo.find_tree(index.write_tree()?)?;
+        let new = repo.commit(
+            Some("HEAD"),
+            &commit.author(),
+            &repo.signature()?,
+            commit.message().unwrap_or_default(),
+            &tree,
+            &[&head],
+        )?;
+        repo.cleanup_state()?;
+        Ok(CherryPickOutcome::Committed(new))
+    }
+
+    fn render_history_entry(&mut self) {
+        let (Some(DataSource::Local { repo, .. }), Some(history)) = (&self.source, &self.history)
+        else {
+            return;
+        };
+        let Some((index, _)) = history.open else {
+            return;
+        };
+        self.logic_view_content = history
+            .commit_diff(repo, index, self.context_lines)
+            .unwrap_or_else(|e| vec![format!(" Failed to read commit: {e}")]);
+        self.highlight_content();
+        self.logic_anchor = None;
+        self.h_scroll = 0;
     }
 
     pub fn next(&mut self) {
+        if matches!(self.zoom_level, ZoomLevel::Logic) {
+            let row = self.logic_wrap_row();
+            if row + 1 < self.logic_line_rows(self.logic_cursor) {
+                self.logic_wrap = (self.logic_cursor, row + 1);
+            } else if let Some(next) = (self.logic_cursor + 1..self.logic_view_content.len())
+                .find(|&i| self.logic_line_visible(i))
+            {
+                self.logic_cursor = next;
+                self.logic_wrap = (next, 0);
+            }
+            return;
+        }
+
         let max = match self.zoom_level {
             ZoomLevel::Galaxy => self.modules.len(),
             ZoomLevel::Structure => self.filtered_structure_indices.len(), // Use filtered len
-            ZoomLevel::Logic => self.logic_view_content.len().max(1),
+            ZoomLevel::Logic => unreachable!(),
         };
 
         if max > 0 && self.selected_index < max - 1 {
@@ -557,6 +4681,21 @@ impl App {
     }
 
     pub fn previous(&mut self) {
+        if matches!(self.zoom_level, ZoomLevel::Logic) {
+            let row = self.logic_wrap_row();
+            if row > 0 {
+                self.logic_wrap = (self.logic_cursor, row - 1);
+            } else if let Some(previous) = (0..self.logic_cursor)
+                .rev()
+                .find(|&i| self.logic_line_visible(i))
+            {
+                // Moving up enters a wrapped line at its last row
+                self.logic_cursor = previous;
+                self.logic_wrap = (previous, self.logic_line_rows(previous) - 1);
+            }
+            return;
+        }
+
         if self.selected_index > 0 {
             self.selected_index -= 1;
             if matches!(self.zoom_level, ZoomLevel::Structure) {
@@ -568,32 +4707,295 @@ impl App {
     pub fn zoom_in(&mut self) {
         match self.zoom_level {
             ZoomLevel::Galaxy => {
+                self.record_jump();
                 self.zoom_level = ZoomLevel::Structure;
                 self.selected_index = 0;
                 self.load_diff();
+
+                // A single changed file makes the Structure list a formality
+                if self.config.auto_skip && self.changed_file_count() == 1 {
+                    self.select_first_file();
+                    self.zoom_level = ZoomLevel::Logic;
+                    self.logic_cursor = 0;
+                    self.note_opened();
+                }
             }
             ZoomLevel::Structure => {
                 // Prevent zooming if list is empty
                 if !self.filtered_structure_indices.is_empty() {
+                    self.record_jump();
                     self.zoom_level = ZoomLevel::Logic;
-                    self.selected_index = 0;
+                    self.logic_cursor = 0;
+                    self.structure_anchor = None;
+                    self.note_opened();
                 }
             }
-            ZoomLevel::Logic => {}
+            ZoomLevel::Logic => self.open_history_entry(),
         }
     }
 
-    pub const fn zoom_out(&mut self) {
+    pub fn zoom_out(&mut self) {
         match self.zoom_level {
-            ZoomLevel::Galaxy => {}
+            ZoomLevel::Galaxy => self.return_to_queue(),
             ZoomLevel::Structure => {
+                self.record_jump();
                 self.zoom_level = ZoomLevel::Galaxy;
                 self.selected_index = 0;
+                self.structure_anchor = None;
             }
             ZoomLevel::Logic => {
+                self.logic_anchor = None;
+                if self.close_history_entry() {
+                    return;
+                }
+                // Return to the file that was being read
+                self.record_jump();
                 self.zoom_level = ZoomLevel::Structure;
-                self.selected_index = 0;
+                if self.history.is_some() || self.showing_drift {
+                    self.load_diff();
+                }
+            }
+        }
+    }
+
+    // --- Jump List ---
+
+    #[must_use]
+    pub fn current_location(&self) -> Location {
+        let item = if self.zoom_level == ZoomLevel::Galaxy {
+            None
+        } else {
+            self.selected_item()
+                .map(|i| (i.path.clone(), i.text.clone()))
+        };
+        Location {
+            zoom: self.zoom_level,
+            section: self.structure_section,
+            item,
+            index: self.selected_index,
+            line: self.logic_cursor,
+        }
+    }
+
+    // --- Session Recovery ---
+
+    /// Identifies the reviewed repository or PR across runs.
+    #[must_use]
+    pub fn session_key(&self) -> Option<String> {
+        match self.source.as_ref()? {
+            DataSource::Local { root, .. } => Some(
+                root.canonicalize()
+                    .unwrap_or_else(|_| root.clone())
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+            DataSource::GitHub { pr_info, .. } => Some(pr_info.url.clone()),
+            DataSource::Commit { commit, .. } => {
+                Some(commit.url.clone().unwrap_or_else(|| commit.sha.clone()))
             }
         }
     }
+
+    /// Offers to restore the session an earlier run of this repository or PR left behind.
+    pub fn offer_session_restore(&mut self) {
+        if let Some(session) = self.session_key().and_then(|key| Session::load(&key)) {
+            self.pending_confirm = Some(PendingConfirm::RestoreSession(Box::new(session)));
+        }
+    }
+
+    fn restore_session(&mut self, session: &Session) {
+        self.context_lines = session.context_lines;
+        self.wrap_lines = session.wrap_lines;
+        self.changes_only = session.changes_only;
+        self.show_blame = session.show_blame;
+        if matches!(self.source, Some(DataSource::Local { .. })) {
+            self.diff_scope = session.diff_scope;
+        }
+        self.search_query.clone_from(&session.search_query);
+        self.opened.extend(session.opened.iter().cloned());
+        if self.notes.merge(&session.notes()) > 0 {
+            let _ = self.notes.save();
+        }
+        self.go_to(&session.location);
+    }
+
+    /// `K`: bookmarks the current place (view, item and Logic line), or removes its bookmark.
+    pub fn toggle_bookmark(&mut self) {
+        if self.source.is_none() {
+            return;
+        }
+        let set = self.notes.toggle_bookmark(self.current_location());
+        let done = if set {
+            "Bookmarked"
+        } else {
+            "Removed bookmark on"
+        };
+        let place = self
+            .current_location()
+            .item
+            .map_or_else(|| "the Galaxy view".to_string(), |(path, _)| path);
+        self.notice = Some(match self.notes.save() {
+            Ok(()) => format!("{done} {place}"),
+            Err(e) => format!("{done} {place} for this session only: {e}"),
+        });
+    }
+
+    /// `'`: goes to the next bookmark, as a jump, wrapping around after the last.
+    pub fn next_bookmark(&mut self) {
+        let current = self.current_location();
+        let Some((n, location)) = self
+            .notes
+            .next_bookmark(&current)
+            .map(|(n, location)| (n, location.clone()))
+        else {
+            self.notice = Some("No bookmarks yet; K sets one".to_string());
+            return;
+        };
+        self.record_jump();
+        self.go_to(&location);
+        self.notice = Some(format!("Bookmark {n}/{}", self.notes.bookmarks.len()));
+    }
+
+    fn record_jump(&mut self) {
+        let location = self.current_location();
+        self.jumps.push(location);
+    }
+
+    /// Ctrl-o: returns to the location before the last zoom or search.
+    pub fn jump_back(&mut self) {
+        let current = self.current_location();
+        if let Some(location) = self.jumps.back(current) {
+            self.go_to(&location);
+        }
+    }
+
+    /// Ctrl-i: undoes a [`Self::jump_back`].
+    pub fn jump_forward(&mut self) {
+        if let Some(location) = self.jumps.forward() {
+            self.go_to(&location);
+        }
+    }
+
+    /// Shows `path` in the Logic view with the cursor on new-file `line`, as a jump.
+    ///
+    /// Returns `false` when the file is not part of the changeset.
+    pub fn reveal(&mut self, path: &str, line: Option<usize>) -> bool {
+        let Some(section) = self.section_of(path) else {
+            return false;
+        };
+        self.record_jump();
+        self.go_to(&Location {
+            zoom: ZoomLevel::Logic,
+            section,
+            item: Some((path.to_string(), path.to_string())),
+            index: 0,
+            line: 0,
+        });
+        if let Some(line) = line
+            && let Some(pos) = new_line_numbers(&self.logic_view_content)
+                .iter()
+                .position(|n| n.is_some_and(|n| n >= line))
+        {
+            self.logic_cursor = pos;
+        }
+        true
+    }
+
+    /// Logic view lines for `path`, built aside so the view on screen stays as it is.
+    pub fn diff_of(&mut self, path: &str) -> Option<Vec<String>> {
+        let index = self
+            .structures
+            .iter()
+            .position(|item| item.is_file && item.path == path)?;
+        if let Some(e) = self.fetch_missing_patch(path) {
+            self.notice = Some(format!("Cannot fetch the diff of {path}: {e}"));
+        }
+        Some(self.logic_content(&self.structures[index]).lines)
+    }
+
+    /// Structure section listing the file row for `path`, preferring the focused one.
+    fn section_of(&self, path: &str) -> Option<StructureSection> {
+        let other = match self.structure_section {
+            StructureSection::Staged => StructureSection::Unstaged,
+            StructureSection::Unstaged => StructureSection::Staged,
+        };
+        let sectioned = self.has_sections();
+        [self.structure_section, other]
+            .into_iter()
+            .find(|&section| {
+                self.structures.iter().any(|item| {
+                    item.is_file
+                        && item.path == path
+                        && (!sectioned
+                            || match section {
+                                StructureSection::Staged => item.is_staged,
+                                StructureSection::Unstaged => item.is_unstaged,
+                            })
+                })
+            })
+    }
+
+    fn go_to(&mut self, location: &Location) {
+        if self.has_sections() && self.structure_section != location.section {
+            self.structure_section = location.section;
+            self.other_section_index = self.selected_index;
+        }
+        // A leftover filter could hide the target, so the jump drops it
+        if location.item.is_some() && !self.search_query.is_empty() {
+            self.search_query.clear();
+        }
+        // ...and so could a folded cluster
+        if let Some((path, _)) = &location.item
+            && let Some(cluster) = self.cluster_of(path)
+            && cluster.representative() != path
+        {
+            self.expanded_clusters.insert(cluster.key.clone());
+        }
+        self.filtered_structure_indices = self.section_indices(self.structure_section);
+
+        self.zoom_level = location.zoom;
+        self.selected_index = location
+            .item
+            .as_ref()
+            .and_then(|(path, text)| {
+                self.filtered_structure_indices.iter().position(|&i| {
+                    self.structures[i].path == *path && self.structures[i].text == *text
+                })
+            })
+            .unwrap_or(location.index);
+        let max = match self.zoom_level {
+            ZoomLevel::Galaxy => self.modules.len(),
+            _ => self.filtered_structure_indices.len(),
+        };
+        if self.selected_index >= max {
+            self.selected_index = 0;
+        }
+        if self.zoom_level != ZoomLevel::Galaxy {
+            self.load_diff();
+        }
+        self.logic_cursor = location
+            .line
+            .min(self.logic_view_content.len().saturating_sub(1));
+    }
+}
+
+/// Screen rows `line` takes when soft-wrapped to `width` columns, as the Logic view wraps it.
+#[must_use]
+pub fn wrapped_rows(line: &Line, width: u16) -> usize {
+    Paragraph::new(line.clone())
+        .wrap(Wrap { trim: false })
+        .line_count(width)
+        .max(1)
+}
+
+/// Whether `path` is spelt as a path (absolute, or starting from `.` or `~`) rather than as
+/// a branch name, which may contain slashes too.
+fn is_path_like(path: &Path) -> bool {
+    path.is_absolute() || path.starts_with(".") || path.starts_with("..") || path.starts_with("~")
+}
+
+/// Whether `path` is new since HEAD: untracked, or added to the index only.
+fn is_added(repo: &Repository, path: &Path) -> bool {
+    repo.status_file(path)
+        .is_ok_and(|s| s.intersects(git2::Status::WT_NEW | git2::Status::INDEX_NEW))
 }
@@ -0,0 +1,252 @@
+// Synthetic fixtures and timings for the analysis pipeline, shared by `benches/` and `glim bench`
+use crate::app::{App, ZoomLevel};
+use crate::config::PathFilter;
+use crate::semantics::SemanticAnalyzer;
+use anyhow::{Context, Result};
+use git2::{IndexAddOption, Repository, Signature};
+use ratatui::{Terminal, backend::TestBackend};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Size of the generated fixtures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixtureSize {
+    pub files: usize,
+    // Functions per file; each one is a handful of lines
+    pub functions: usize,
+}
+
+impl FixtureSize {
+    /// `files` files of roughly `lines` lines each, a quarter of them changed.
+    #[must_use]
+    pub fn with_lines(files: usize, lines: usize) -> Self {
+        Self {
+            files,
+            // Each function takes four lines, plus a struct every tenth
+            functions: (lines * 10 / 46).max(1),
+        }
+    }
+}
+
+impl Default for FixtureSize {
+    fn default() -> Self {
+        Self {
+            files: 200,
+            functions: 50,
+        }
+    }
+}
+
+/// Rust source with `functions` functions; `revision` changes every other body.
+#[must_use]
+pub fn rust_source(functions: usize, revision: usize) -> String {
+    let mut out = String::new();
+    for i in 0..functions {
+        let value = if i % 2 == 0 { i + revision } else { i };
+        let _ = writeln!(out, "pub fn f{i}(x: u32) -> u32 {{\n    x + {value}\n}}\n");
+        if i % 10 == 0 {
+            let _ = writeln!(out, "pub struct S{i} {{\n    pub field: u32,\n}}\n");
+        }
+    }
+    out
+}
+
+/// A `gh pr diff`-style unified diff touching every fixture file.
+#[must_use]
+pub fn unified_diff(size: FixtureSize) -> String {
+    let mut out = String::new();
+    for file in 0..size.files {
+        let path = format!("src/m{}/f{file}.rs", file % 10);
+        let _ = writeln!(out, "diff --git a/{path} b/{path}");
+        let _ = writeln!(out, "--- a/{path}\n+++ b/{path}");
+        for i in (0..size.functions).step_by(2) {
+            let _ = writeln!(out, "@@ -{0},3 +{0},3 @@", i * 4 + 1);
+            let _ = writeln!(out, " pub fn f{i}(x: u32) -> u32 {{");
+            let _ = writeln!(out, "-    x + {i}\n+    x + {}", i + 1);
+            let _ = writeln!(out, " }}");
+        }
+    }
+    out
+}
+
+/// Creates a repository under `dir` with one commit and every file modified in the working tree.
+///
+/// # Errors
+/// Returns an error if the directory, files, or commit cannot be written.
+pub fn fixture_repo(dir: &Path, size: FixtureSize) -> Result<Repository> {
+    if dir.exists() {
+        fs::remove_dir_all(dir).with_context(|| format!("Failed to clear {}", dir.display()))?;
+    }
+    let repo = Repository::init(dir).context("Failed to init fixture repository")?;
+    write_files(dir, size, 0)?;
+
+    let mut index = repo.index()?;
+    index.add_all(["*"], IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let sig = Signature::now("glim bench", "bench@example.com")?;
+    repo.commit(Some("HEAD"), &sig, &sig, "fixture", &tree, &[])?;
+    drop(tree);
+
+    write_files(dir, size, 1)?;
+    Ok(repo)
+}
+
+fn write_files(dir: &Path, size: FixtureSize, revision: usize) -> Result<()> {
+    let source = rust_source(size.functions, revision);
+    for file in 0..size.files {
+        let module = dir.join(format!("src/m{}", file % 10));
+        fs::create_dir_all(&module)?;
+        fs::write(module.join(format!("f{file}.rs")), &source)?;
+    }
+    Ok(())
+}
+
+/// Default scratch directory for fixture repositories.
+#[must_use]
+pub fn scratch_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("glim-bench-{}", std::process::id()))
+}
+
+/// The repository `glim stress` opens; removed from disk when dropped.
+#[derive(Debug)]
+pub struct StressRepo {
+    dir: PathBuf,
+}
+
+impl StressRepo {
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl Drop for StressRepo {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Generates the repository `glim stress` opens.
+///
+/// # Errors
+/// Returns an error if the fixture repository cannot be created.
+pub fn stress_repo(size: FixtureSize) -> Result<StressRepo> {
+    let dir = std::env::temp_dir().join(format!("glim-stress-{}", std::process::id()));
+    let repo = StressRepo { dir };
+    fixture_repo(repo.path(), size)?;
+    Ok(repo)
+}
+
+/// Renders the current view of `app` into an off-screen buffer.
+///
+/// # Errors
+/// Returns an error if the test backend fails to draw.
+pub fn render_offscreen(app: &App) -> Result<()> {
+    let mut terminal = Terminal::new(TestBackend::new(200, 60))?;
+    terminal.draw(|frame| crate::ui::render(app, frame))?;
+    Ok(())
+}
+
+/// Runs `f` `iterations` times and returns the median duration.
+fn median(iterations: usize, mut f: impl FnMut()) -> Duration {
+    let mut samples: Vec<Duration> = (0..iterations.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .collect();
+    samples.sort();
+    samples[samples.len() / 2]
+}
+
+/// Times each pipeline stage on generated fixtures and returns a printable report.
+///
+/// # Errors
+/// Returns an error if the fixture repository cannot be created.
+pub fn run(size: FixtureSize, iterations: usize) -> Result<String> {
+    let dir = scratch_dir();
+    let repo = fixture_repo(&dir, size)?;
+    let diff = unified_diff(size);
+    let source = rust_source(size.functions, 1);
+    let weights = HashMap::new();
+
+    let mut results = Vec::new();
+    results.push((
+        "split_diff",
+        median(iterations, || {
+            std::hint::black_box(App::split_diff(&diff));
+        }),
+    ));
+    results.push((
+        "analyze (cold)",
+        median(iterations, || {
+            std::hint::black_box(SemanticAnalyzer::new().analyze("f.rs", &source));
+        }),
+    ));
+    let mut analyzer = SemanticAnalyzer::new();
+    let revisions = [rust_source(size.functions, 0), source.clone()];
+    let mut revision = 0;
+    results.push((
+        "analyze (incremental)",
+        median(iterations, || {
+            revision = 1 - revision;
+            std::hint::black_box(analyzer.analyze("f.rs", &revisions[revision]));
+        }),
+    ));
+    results.push((
+        "analyze (unchanged)",
+        median(iterations, || {
+            std::hint::black_box(analyzer.analyze("f.rs", &source));
+        }),
+    ));
+    results.push((
+        "scan_local_repo",
+        median(iterations, || {
+            let mut analyzer = SemanticAnalyzer::new();
+            std::hint::black_box(App::scan_local_repo(
+                &repo,
+                &dir,
+                &mut analyzer,
+                &weights,
+                &PathFilter::default(),
+                None,
+            ));
+        }),
+    ));
+
+    let mut app = App::new(dir.clone());
+    app.zoom_level = ZoomLevel::Structure;
+    results.push((
+        "render (structure)",
+        median(iterations, || {
+            let _ = render_offscreen(&app);
+        }),
+    ));
+    app.zoom_in();
+    results.push((
+        "render (logic)",
+        median(iterations, || {
+            let _ = render_offscreen(&app);
+        }),
+    ));
+    drop(repo);
+    let _ = fs::remove_dir_all(&dir);
+
+    let mut report = format!(
+        "{} files x {} functions, median of {iterations} runs\n",
+        size.files, size.functions
+    );
+    for (name, time) in results {
+        let _ = writeln!(
+            report,
+            "{name:<24} {:>10.3} ms",
+            time.as_secs_f64() * 1000.0
+        );
+    }
+    Ok(report)
+}
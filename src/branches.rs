@@ -0,0 +1,117 @@
+// Local and remote branch listing with ahead/behind counts and checkout
+use anyhow::{Context, Result, bail};
+use git2::{BranchType, Oid, Repository, build::CheckoutBuilder};
+
+/// One row of the branch panel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchEntry {
+    pub name: String,
+    pub is_remote: bool,
+    pub is_head: bool,
+    // Local branches compare against their upstream, remote branches against HEAD
+    pub ahead_behind: Option<(usize, usize)>,
+}
+
+impl BranchEntry {
+    /// Local branch name this entry checks out to (`origin/feat` becomes `feat`).
+    #[must_use]
+    pub fn local_name(&self) -> &str {
+        if self.is_remote {
+            self.name
+                .split_once('/')
+                .map_or(&self.name, |(_, rest)| rest)
+        } else {
+            &self.name
+        }
+    }
+
+    /// Lists local branches first, then remote-tracking branches, each sorted by name.
+    ///
+    /// # Errors
+    /// Returns an error if the branch references cannot be read.
+    pub fn list(repo: &Repository) -> Result<Vec<Self>> {
+        let head_oid = repo.head().ok().and_then(|h| h.target());
+        let mut entries = Vec::new();
+
+        for branch in repo.branches(None)? {
+            let (branch, kind) = branch?;
+            let Some(name) = branch.name()?.map(ToString::to_string) else {
+                continue;
+            };
+            // `origin/HEAD` is an alias, not a branch anyone checks out
+            if kind == BranchType::Remote && name.ends_with("/HEAD") {
+                continue;
+            }
+            let tip = branch.get().target();
+            let base = match kind {
+                BranchType::Local => branch.upstream().ok().and_then(|u| u.get().target()),
+                BranchType::Remote => head_oid,
+            };
+            let ahead_behind = tip
+                .zip(base)
+                .and_then(|(tip, base)| repo.graph_ahead_behind(tip, base).ok());
+            entries.push(Self {
+                name,
+                is_remote: kind == BranchType::Remote,
+                is_head: branch.is_head(),
+                ahead_behind,
+            });
+        }
+
+        entries.sort_by(|a, b| (a.is_remote, &a.name).cmp(&(b.is_remote, &b.name)));
+        Ok(entries)
+    }
+
+    /// Checks the branch out, creating a tracking branch for remotes that have no local one.
+    ///
+    /// Uses a safe checkout, so local changes that would be overwritten abort the switch.
+    ///
+    /// # Errors
+    /// Returns an error if the branch is missing or the working tree conflicts with it.
+    pub fn checkout(&self, repo: &Repository) -> Result<()> {
+        let local = self.local_name();
+        if repo.find_branch(local, BranchType::Local).is_err() {
+            if !self.is_remote {
+                bail!("Branch {local} no longer exists");
+            }
+            let remote = repo.find_branch(&self.name, BranchType::Remote)?;
+            let commit = remote.get().peel_to_commit()?;
+            let mut created = repo.branch(local, &commit, false)?;
+            created.set_upstream(Some(&self.name))?;
+        }
+
+        let refname = format!("refs/heads/{local}");
+        let target: Oid = repo
+            .refname_to_id(&refname)
+            .with_context(|| format!("Cannot resolve {refname}"))?;
+        let tree = repo.find_commit(target)?.into_object();
+        repo.checkout_tree(&tree, Some(CheckoutBuilder::new().safe()))
+            .context("Local changes would be overwritten by checkout")?;
+        repo.set_head(&refname)?;
+        Ok(())
+    }
+}
+
+/// The open branch panel and its cursor.
+#[derive(Debug, Clone)]
+pub struct BranchPanel {
+    pub entries: Vec<BranchEntry>,
+    pub selected: usize,
+}
+
+impl BranchPanel {
+    pub const fn next(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub const fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    #[must_use]
+    pub fn current(&self) -> Option<&BranchEntry> {
+        self.entries.get(self.selected)
+    }
+}
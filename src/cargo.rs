@@ -0,0 +1,137 @@
+// Cargo workspaces: Galaxy modules per crate (from `cargo metadata`) instead of per directory
+use git2::Tree;
+use serde_json::Value;
+use std::path::Path;
+use std::process::Command;
+
+/// One workspace member.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crate {
+    pub name: String,
+    pub version: String,
+    // Repository-relative directory of the manifest; empty for a crate at the root
+    pub dir: String,
+}
+
+impl Crate {
+    /// Galaxy module name, e.g. `glim v0.3.1`.
+    #[must_use]
+    pub fn label(&self) -> String {
+        format!("{} v{}", self.name, self.version)
+    }
+
+    /// Repository-relative path of the crate's `Cargo.toml`.
+    #[must_use]
+    pub fn manifest(&self) -> String {
+        if self.dir.is_empty() {
+            "Cargo.toml".to_string()
+        } else {
+            format!("{}/Cargo.toml", self.dir)
+        }
+    }
+
+    /// Whether `path` belongs to this crate's directory.
+    fn contains(&self, path: &str) -> bool {
+        self.dir.is_empty()
+            || path
+                .strip_prefix(&self.dir)
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+
+    /// False when the manifest in `old` has the same version; `None` when that cannot be told
+    /// (a new crate, or a version inherited from the workspace).
+    #[must_use]
+    pub fn version_bumped(&self, repo: &git2::Repository, old: &Tree) -> Option<bool> {
+        let entry = old.get_path(Path::new(&self.manifest())).ok()?;
+        let blob = repo.find_blob(entry.id()).ok()?;
+        let manifest: toml::Value =
+            toml::from_str(std::str::from_utf8(blob.content()).ok()?).ok()?;
+        let version = manifest.get("package")?.get("version")?.as_str()?;
+        Some(version != self.version)
+    }
+}
+
+/// The crates of the Cargo workspace at a repository root.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CargoWorkspace {
+    pub crates: Vec<Crate>,
+}
+
+impl CargoWorkspace {
+    /// Runs `cargo metadata` in `root`; `None` without a root `Cargo.toml` or a working cargo.
+    #[must_use]
+    pub fn load(root: &Path) -> Option<Self> {
+        if !root.join("Cargo.toml").is_file() {
+            return None;
+        }
+        let output = Command::new("cargo")
+            .args([
+                "metadata",
+                "--format-version",
+                "1",
+                "--no-deps",
+                "--offline",
+            ])
+            .current_dir(root)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let json: Value = serde_json::from_slice(&output.stdout).ok()?;
+        let root = root.canonicalize().ok()?;
+        Self::parse(&json, &root)
+    }
+
+    fn parse(json: &Value, root: &Path) -> Option<Self> {
+        let crates: Vec<Crate> = json["packages"]
+            .as_array()?
+            .iter()
+            .filter_map(|package| {
+                let manifest = Path::new(package["manifest_path"].as_str()?);
+                let dir = manifest.parent()?.strip_prefix(root).ok()?;
+                Some(Crate {
+                    name: package["name"].as_str()?.to_string(),
+                    version: package["version"].as_str()?.to_string(),
+                    dir: dir.to_string_lossy().replace('\\', "/"),
+                })
+            })
+            .collect();
+        (!crates.is_empty()).then_some(Self { crates })
+    }
+
+    /// The innermost crate containing `path`, so nested crates win over the root one.
+    #[must_use]
+    pub fn crate_of(&self, path: &str) -> Option<&Crate> {
+        self.crates
+            .iter()
+            .filter(|c| c.contains(path))
+            .max_by_key(|c| c.dir.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CargoWorkspace, Crate};
+    use serde_json::json;
+    use std::path::Path;
+
+    #[test]
+    fn maps_paths_to_innermost_crate() {
+        let metadata = json!({ "packages": [
+            { "name": "app", "version": "0.3.1", "manifest_path": "/ws/Cargo.toml" },
+            { "name": "core", "version": "1.0.0", "manifest_path": "/ws/crates/core/Cargo.toml" },
+        ]});
+        let ws = CargoWorkspace::parse(&metadata, Path::new("/ws")).expect("workspace");
+        let name = |path| ws.crate_of(path).map(Crate::label);
+        assert_eq!(
+            name("crates/core/src/lib.rs"),
+            Some("core v1.0.0".to_string())
+        );
+        assert_eq!(
+            name("crates/core-extra/x.rs"),
+            Some("app v0.3.1".to_string())
+        );
+        assert_eq!(ws.crates[1].manifest(), "crates/core/Cargo.toml");
+    }
+}
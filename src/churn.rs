@@ -0,0 +1,109 @@
+// Recency-weighted churn: files changed often and lately run hotter in the Galaxy view
+use git2::{DiffOptions, Oid, Repository, Sort};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Commits walked back from HEAD at most, so huge histories stay cheap to score
+const MAX_COMMITS: usize = 300;
+// Commits weighing less than this no longer move the heat map; the walk stops there
+const MIN_WEIGHT: f32 = 0.01;
+
+/// Decayed commit counts per path, as of one HEAD.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Churn {
+    // HEAD the scores were computed at; they are stale once it moves
+    pub head: Option<Oid>,
+    scores: HashMap<String, f32>,
+}
+
+impl Churn {
+    /// Scores every path touched by recent commits reachable from HEAD. Each commit adds
+    /// `0.5^(age / half_life)`; a non-positive half-life leaves history out entirely.
+    #[must_use]
+    pub fn compute(repo: &Repository, half_life_days: f32) -> Self {
+        let head = repo.head().ok().and_then(|h| h.target());
+        let mut churn = Self {
+            head,
+            scores: HashMap::new(),
+        };
+        if half_life_days > 0.0
+            && let Some(head) = head
+        {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs().cast_signed());
+            // Best effort: a broken history just means no history-based heat
+            let _ = churn.walk(repo, head, now, half_life_days);
+        }
+        churn
+    }
+
+    fn walk(
+        &mut self,
+        repo: &Repository,
+        head: Oid,
+        now: i64,
+        half_life_days: f32,
+    ) -> Result<(), git2::Error> {
+        let mut walk = repo.revwalk()?;
+        walk.set_sorting(Sort::TIME)?;
+        walk.push(head)?;
+        for oid in walk.take(MAX_COMMITS) {
+            let commit = repo.find_commit(oid?)?;
+            let weight = decay(now - commit.time().seconds(), half_life_days);
+            if weight < MIN_WEIGHT {
+                break;
+            }
+            // Merges repeat their branches' changes
+            if commit.parent_count() > 1 {
+                continue;
+            }
+            let parent = commit.parents().next().map(|p| p.tree()).transpose()?;
+            let diff = repo.diff_tree_to_tree(
+                parent.as_ref(),
+                Some(&commit.tree()?),
+                Some(DiffOptions::new().skip_binary_check(true)),
+            )?;
+            for delta in diff.deltas() {
+                if let Some(path) = delta.new_file().path().and_then(|p| p.to_str()) {
+                    *self.scores.entry(path.replace('\\', "/")).or_insert(0.0) += weight;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether HEAD still points where the scores were computed.
+    #[must_use]
+    pub fn is_current(&self, repo: &Repository) -> bool {
+        self.head == repo.head().ok().and_then(|h| h.target())
+    }
+
+    /// Decayed number of recent commits to `path`.
+    #[must_use]
+    pub fn score(&self, path: &str) -> f32 {
+        self.scores.get(path).copied().unwrap_or(0.0)
+    }
+}
+
+/// Weight of a commit `age_secs` old: 1 now, halving every `half_life_days`.
+#[must_use]
+pub fn decay(age_secs: i64, half_life_days: f32) -> f32 {
+    let age_days = age_secs.max(0) as f32 / 86_400.0;
+    0.5_f32.powf(age_days / half_life_days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decay;
+
+    #[test]
+    fn halves_every_half_life() {
+        let day = 86_400;
+        assert!((decay(0, 30.0) - 1.0).abs() < 1e-6);
+        assert!((decay(30 * day, 30.0) - 0.5).abs() < 1e-6);
+        assert!((decay(90 * day, 30.0) - 0.125).abs() < 1e-6);
+        // Clock skew: commits from the future count as new
+        assert!((decay(-day, 30.0) - 1.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,121 @@
+// Shallow and partial clones: which history-based views are incomplete, and filling them in
+use anyhow::{Context, Result};
+use git2::Repository;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+// Commits fetched per "fetch more history" on a shallow clone
+const DEEPEN_BY: u32 = 100;
+
+/// How much of the history a local clone actually has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CloneState {
+    // `git clone --depth`: commits past the boundary are missing entirely
+    pub shallow: bool,
+    // `git clone --filter`: objects are fetched lazily, which libgit2 cannot do
+    pub partial: bool,
+}
+
+impl CloneState {
+    #[must_use]
+    pub fn of(repo: &Repository) -> Self {
+        let partial = repo.config().is_ok_and(|config| {
+            config.get_string("extensions.partialclone").is_ok()
+                || config
+                    .entries(Some(r"remote\..*\.promisor"))
+                    .is_ok_and(|mut entries| {
+                        let mut promisor = false;
+                        while let Some(Ok(entry)) = entries.next() {
+                            promisor |= entry.value().is_some_and(|v| v == "true");
+                        }
+                        promisor
+                    })
+        });
+        Self {
+            shallow: repo.is_shallow(),
+            partial,
+        }
+    }
+
+    #[must_use]
+    pub const fn is_complete(self) -> bool {
+        !self.shallow && !self.partial
+    }
+
+    #[must_use]
+    pub const fn label(self) -> Option<&'static str> {
+        match (self.shallow, self.partial) {
+            (true, true) => Some("shallow, partial clone"),
+            (true, false) => Some("shallow clone"),
+            (false, true) => Some("partial clone"),
+            (false, false) => None,
+        }
+    }
+
+    /// Warning for a view built from history, e.g. `Blame`; `None` for a full clone.
+    #[must_use]
+    pub fn warning(self, feature: &str) -> Option<String> {
+        self.label().map(|label| {
+            format!("{feature} may be incomplete in this {label}; fetch more history from the actions menu (.)")
+        })
+    }
+
+    /// What `fetch` will run, for the confirmation prompt.
+    #[must_use]
+    pub fn fetch_description(self) -> String {
+        let mut steps = Vec::new();
+        if self.shallow {
+            steps.push(format!("git fetch --deepen={DEEPEN_BY}"));
+        }
+        if self.partial {
+            steps.push("git log -p (downloads the file's missing versions)".to_string());
+        }
+        steps.join(", then ")
+    }
+
+    /// Deepens a shallow clone and, for a partial clone, lets git fetch every version of `path`.
+    ///
+    /// Runs the git CLI in `root`, since libgit2 can neither deepen nor fetch promised objects.
+    ///
+    /// # Errors
+    /// Returns an error if git cannot be run or a fetch fails.
+    pub fn fetch(self, root: &Path, path: &Path) -> Result<()> {
+        if self.shallow {
+            git(root, &[&format!("--deepen={DEEPEN_BY}")], "fetch")?;
+        }
+        if self.partial {
+            // Walking the patches makes git download each missing blob of the file
+            let path = path.to_string_lossy();
+            git(root, &["--format=", "--patch", "--", &path], "log")?;
+        }
+        Ok(())
+    }
+}
+
+fn git(root: &Path, args: &[&str], subcommand: &str) -> Result<()> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .arg(subcommand)
+        .args(args)
+        // A credential prompt would hang behind the TUI, or draw over it from another thread
+        .stdin(Stdio::null())
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GCM_INTERACTIVE", "never")
+        .env(
+            "GIT_SSH_COMMAND",
+            std::env::var("GIT_SSH_COMMAND").map_or_else(
+                |_| "ssh -o BatchMode=yes".to_string(),
+                |ssh| format!("{ssh} -o BatchMode=yes"),
+            ),
+        )
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {subcommand} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
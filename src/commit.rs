@@ -0,0 +1,296 @@
+// Reviewing a single commit (a GitHub commit URL or a local SHA) against its first parent, or
+// a GitHub comparison of two refs
+use crate::github::{PrFile, repo_url_slug};
+use anyhow::Result;
+use git2::{Diff, DiffOptions, Repository};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+// Authors named on the dashboard for a comparison before the rest are counted
+const MAX_AUTHORS: usize = 3;
+
+/// A commit as typed on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommitRef {
+    /// Commit page on GitHub, e.g. `https://github.com/owner/repo/commit/<sha>`
+    GitHub { repo: String, sha: String },
+    /// Comparison on GitHub, e.g. `https://github.com/owner/repo/compare/v1.2...release/1.3`
+    Compare { repo: String, range: String },
+    /// SHA or SHA prefix in the repository of the current directory
+    Local(String),
+}
+
+impl CommitRef {
+    /// Parses a GitHub commit or compare URL, or a bare SHA. Bare SHAs need at least one
+    /// letter, so PR numbers keep their meaning.
+    #[must_use]
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if let Some((repo, rest)) = input.split_once("/compare/") {
+            let repo = repo_url_slug(repo)?;
+            // Refs may contain slashes, so only a query or fragment ends the range
+            let range = rest.split(['#', '?']).next()?.trim_end_matches('/');
+            return range.contains("..").then(|| Self::Compare {
+                repo,
+                range: range.to_string(),
+            });
+        }
+        if let Some((repo, rest)) = input.split_once("/commit/") {
+            let repo = repo_url_slug(repo)?;
+            let sha = rest.split(['/', '#', '?']).next()?;
+            return is_sha(sha).then(|| Self::GitHub {
+                repo,
+                sha: sha.to_string(),
+            });
+        }
+        (is_sha(input) && !input.bytes().all(|b| b.is_ascii_digit()))
+            .then(|| Self::Local(input.to_string()))
+    }
+}
+
+fn is_sha(text: &str) -> bool {
+    (7..=40).contains(&text.len()) && text.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// The commit or comparison under review.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitInfo {
+    // Full SHA of a commit, `base...head` of a comparison
+    pub sha: String,
+    // First line of the message, or the commit count of a comparison
+    pub summary: String,
+    // Commit author, or the authors in a comparison
+    pub author: String,
+    // `owner/repo` on GitHub, the directory name of a local repository
+    pub repo: String,
+    // Commit or compare page on GitHub; `None` for local commits
+    pub url: Option<String>,
+}
+
+impl CommitInfo {
+    #[must_use]
+    pub fn short_sha(&self) -> &str {
+        &self.sha[..self.sha.len().min(7)]
+    }
+
+    /// Shown where a PR shows its number, e.g. `commit 3b7b783` or `compare v1.2...v1.3`.
+    #[must_use]
+    pub fn label(&self) -> String {
+        if is_sha(&self.sha) {
+            format!("commit {}", self.short_sha())
+        } else {
+            format!("compare {}", self.sha)
+        }
+    }
+
+    /// Reads the REST API's comparison (`repos/{repo}/compare/{range}`).
+    #[must_use]
+    pub fn from_github_compare(json: &Value, repo: &str, range: &str) -> Self {
+        let mut authors: Vec<&str> = Vec::new();
+        for commit in json["commits"].as_array().into_iter().flatten() {
+            let author = commit["author"]["login"]
+                .as_str()
+                .or_else(|| commit["commit"]["author"]["name"].as_str());
+            if let Some(author) = author
+                && !authors.contains(&author)
+            {
+                authors.push(author);
+            }
+        }
+        let mut author = authors
+            .iter()
+            .take(MAX_AUTHORS)
+            .copied()
+            .collect::<Vec<_>>()
+            .join(", ");
+        if authors.len() > MAX_AUTHORS {
+            author = format!("{author} +{} more", authors.len() - MAX_AUTHORS);
+        }
+        let commits = json["total_commits"].as_u64().unwrap_or_default();
+        Self {
+            sha: range.to_string(),
+            summary: format!("{commits} commit(s)"),
+            author,
+            repo: repo.to_string(),
+            url: json["html_url"].as_str().map(String::from),
+        }
+    }
+
+    /// Reads the REST API's commit object (`repos/{repo}/commits/{sha}`).
+    #[must_use]
+    pub fn from_github(json: &Value, repo: &str) -> Self {
+        let commit = &json["commit"];
+        // Commits by addresses not linked to an account have no `author` login
+        let author = json["author"]["login"]
+            .as_str()
+            .or_else(|| commit["author"]["name"].as_str())
+            .unwrap_or("unknown");
+        Self {
+            sha: json["sha"].as_str().unwrap_or_default().to_string(),
+            summary: commit["message"]
+                .as_str()
+                .and_then(|m| m.lines().next())
+                .unwrap_or_default()
+                .to_string(),
+            author: author.to_string(),
+            repo: repo.to_string(),
+            url: json["html_url"].as_str().map(String::from),
+        }
+    }
+
+    /// Resolves `rev` in `repo` and diffs it against its first parent; root commits are
+    /// diffed against the empty tree.
+    ///
+    /// # Errors
+    /// Returns error if `rev` does not name a commit or the diff fails.
+    pub fn load_local<'r>(
+        repo: &'r Repository,
+        rev: &str,
+        context_lines: u32,
+    ) -> Result<(Self, Diff<'r>)> {
+        let commit = repo
+            .revparse_single(rev)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|_| anyhow::anyhow!("No commit {rev} in this repository"))?;
+        let parent = commit.parents().next().map(|p| p.tree()).transpose()?;
+        let mut opts = DiffOptions::new();
+        opts.context_lines(context_lines);
+        let diff =
+            repo.diff_tree_to_tree(parent.as_ref(), Some(&commit.tree()?), Some(&mut opts))?;
+        let repo_name = repo
+            .workdir()
+            .unwrap_or_else(|| repo.path())
+            .file_name()
+            .map_or_else(
+                || "Unknown Repo".to_string(),
+                |n| n.to_string_lossy().to_string(),
+            );
+        let info = Self {
+            sha: commit.id().to_string(),
+            summary: commit.summary().unwrap_or_default().to_string(),
+            author: commit.author().name().unwrap_or("unknown").to_string(),
+            repo: repo_name,
+            url: None,
+        };
+        Ok((info, diff))
+    }
+}
+
+/// Changed files with their line counts, sorted by path, from per-file diff lines.
+#[must_use]
+pub fn diff_files<S: BuildHasher>(file_diffs: &HashMap<String, Vec<String>, S>) -> Vec<PrFile> {
+    let mut files: Vec<PrFile> = file_diffs
+        .iter()
+        .map(|(path, lines)| {
+            // Only hunk bodies count; `+++`/`---` file headers come before the first hunk
+            let body = lines
+                .iter()
+                .skip_while(|l| !l.trim_start().starts_with("@@"))
+                .skip(1);
+            let (mut additions, mut deletions) = (0, 0);
+            for line in body {
+                match line.as_bytes().first() {
+                    Some(b'+') => additions += 1,
+                    Some(b'-') => deletions += 1,
+                    _ => {}
+                }
+            }
+            PrFile {
+                path: path.clone(),
+                additions,
+                deletions,
+            }
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommitInfo, CommitRef, diff_files};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn parses_commit_refs() {
+        assert_eq!(
+            CommitRef::parse("https://github.com/owner/repo/commit/0a1e973f#diff-1"),
+            Some(CommitRef::GitHub {
+                repo: "owner/repo".into(),
+                sha: "0a1e973f".into(),
+            })
+        );
+        assert_eq!(
+            CommitRef::parse("7452a9f"),
+            Some(CommitRef::Local("7452a9f".into()))
+        );
+        assert_eq!(CommitRef::parse("1234567"), None);
+        assert_eq!(CommitRef::parse("feature/login"), None);
+        assert_eq!(
+            CommitRef::parse("github.com/owner/repo/compare/v1.2...release/1.3?expand=1"),
+            Some(CommitRef::Compare {
+                repo: "owner/repo".into(),
+                range: "v1.2...release/1.3".into(),
+            })
+        );
+        assert_eq!(
+            CommitRef::parse("https://github.com/owner/repo/pull/12"),
+            None
+        );
+    }
+
+    #[test]
+    fn reads_commit_and_counts_lines() {
+        let info = CommitInfo::from_github(
+            &json!({
+                "sha": "0a1e973fdeadbeef",
+                "html_url": "https://github.com/o/r/commit/0a1e973fdeadbeef",
+                "author": null,
+                "commit": { "message": "Add queue\n\nDetails", "author": { "name": "Ada" } },
+            }),
+            "o/r",
+        );
+        assert_eq!(
+            (info.label(), info.summary.as_str(), info.author.as_str()),
+            ("commit 0a1e973".to_string(), "Add queue", "Ada")
+        );
+        let commit = |login: &str| json!({ "author": { "login": login } });
+        let compare = CommitInfo::from_github_compare(
+            &json!({
+                "total_commits": 5,
+                "commits": [commit("a"), commit("b"), commit("a"), commit("c"), commit("d")],
+            }),
+            "o/r",
+            "v1...v2",
+        );
+        assert_eq!(
+            (
+                compare.label(),
+                compare.summary.as_str(),
+                compare.author.as_str()
+            ),
+            (
+                "compare v1...v2".to_string(),
+                "5 commit(s)",
+                "a, b, c +1 more"
+            )
+        );
+
+        let diffs = HashMap::from([(
+            "src/a.rs".to_string(),
+            [
+                "diff --git a/src/a.rs b/src/a.rs",
+                "--- a/src/a.rs",
+                "+++ b/src/a.rs",
+            ]
+            .into_iter()
+            .chain(["@@ -1,2 +1,2 @@", "-old", "+new", "+more", " same"])
+            .map(String::from)
+            .collect(),
+        )]);
+        let files = diff_files(&diffs);
+        assert_eq!((files[0].additions, files[0].deletions), (2, 1));
+    }
+}
@@ -0,0 +1,509 @@
+// User configuration loaded from `<config dir>/config.toml`
+use crate::app::ZoomLevel;
+use crate::theme::ColorMode;
+use crate::utils::{get_config_dir, glob_match};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::hash::BuildHasher;
+use std::path::PathBuf;
+
+// Bundled syntect theme used when `syntax_theme` is unset or unknown
+pub const DEFAULT_SYNTAX_THEME: &str = "base16-ocean.dark";
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Config {
+    /// View to open on start
+    pub start_view: ZoomLevel,
+    /// Skip the Galaxy view when at most this many files changed (0 disables)
+    pub skip_galaxy_max_files: usize,
+    /// Zoom past views with a single module or a single changed file
+    pub auto_skip: bool,
+    /// Fold this many or more files making the same edit into one Structure entry (0 never folds)
+    pub mechanical_min_files: usize,
+    /// Heat multipliers by glob (`.md`, `generated/**`); 0 removes a file from the heat map
+    pub heat_weights: HashMap<String, f32>,
+    /// Days after which a past commit adds half as much heat to the files it touched
+    /// (0 leaves history out of the heat map)
+    pub heat_half_life_days: f32,
+    /// Branch whose merge-base with HEAD the merge-base mode diffs against
+    pub base_branch: String,
+    /// Start in merge-base mode (everything on the branch, not just uncommitted changes)
+    pub merge_base: bool,
+    /// 24-bit colours; off maps them onto the 256-colour palette
+    pub truecolor: bool,
+    /// `normal`, `high-contrast` (black and white) or `monochrome` (symbols and emphasis only)
+    pub color_mode: ColorMode,
+    /// Nerd Font glyphs for symbol icons
+    pub nerd_fonts: bool,
+    /// Rescan automatically when files in the repository change
+    pub watch: bool,
+    /// Set the terminal title and report the directory and command boundaries (OSC 7 / 133)
+    pub terminal_integration: bool,
+    /// Changed paths to scan; excluded files are neither listed nor read
+    pub scan: PathFilter,
+    /// Syntect theme for the Logic view (e.g. `InspiredGitHub` on a light terminal)
+    pub syntax_theme: String,
+    /// Logic view line backgrounds; unset slots are derived from the syntax theme
+    pub diff_colors: DiffColors,
+    /// Underline likely typos in added comments, doc comments and Markdown
+    pub spell_check: bool,
+    /// Extra Galaxy panels showing the output of a command
+    pub panels: Vec<PanelConfig>,
+    /// Repositories (`owner/repo`) `glim queue` looks for review requests in; empty searches all
+    pub queue_repos: Vec<String>,
+    /// Galaxy view lists the CI checks of a PR
+    pub show_checks: bool,
+    /// Logic view shows existing review comments under the lines they were left on
+    pub show_comments: bool,
+    /// Submitted PR reviews end with the per-file verdicts
+    pub verdicts_in_review: bool,
+    /// Commands that open the selected file in a tmux / zellij pane or send it to an editor
+    pub pane: PaneConfig,
+    /// Extra tree-sitter symbol patterns by language (`rust`, `typescript`, `tsx`, `go`)
+    pub symbol_queries: BTreeMap<String, SymbolQuery>,
+    /// Named sets of the settings above, laid over them with `--profile` or `P`
+    pub profiles: BTreeMap<String, toml::Table>,
+    /// Active profile, laid over the settings in `base`
+    #[serde(skip)]
+    pub active_profile: Option<String>,
+    /// Settings from the config file, kept once a profile or command-line override is laid
+    /// over them
+    #[serde(skip)]
+    base: Option<Box<Self>>,
+    /// Command-line settings, which win over the config file and any profile
+    #[serde(skip)]
+    pub overrides: Overrides,
+    /// Fetch PRs from GitHub even when the cached copy is current (`--refresh`)
+    #[serde(skip)]
+    pub refresh: bool,
+}
+
+/// Settings given on the command line, laid over the config file and any profile.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Overrides {
+    pub start_view: Option<ZoomLevel>,
+    pub color_mode: Option<ColorMode>,
+    // Diff against merge-base(HEAD, branch)
+    pub base_branch: Option<String>,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl Overrides {
+    fn apply(&self, config: &mut Config) {
+        if let Some(view) = self.start_view {
+            config.start_view = view;
+        }
+        if let Some(mode) = self.color_mode {
+            config.color_mode = mode;
+        }
+        if let Some(branch) = &self.base_branch {
+            config.base_branch.clone_from(branch);
+            config.merge_base = true;
+        }
+        config.scan.include.extend(self.include.iter().cloned());
+        config.scan.exclude.extend(self.exclude.iter().cloned());
+    }
+}
+
+/// A `[[panels]]` entry: a shell command whose output the Galaxy view shows under `title`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PanelConfig {
+    pub title: String,
+    pub command: String,
+    /// Output rows shown at most
+    #[serde(default = "default_panel_height")]
+    pub height: u16,
+}
+
+const fn default_panel_height() -> u16 {
+    6
+}
+
+/// `[pane]` command templates with `{path}`, `{line}` and `{root}` placeholders; empty ones
+/// fall back to the built-in commands for tmux and zellij.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PaneConfig {
+    /// Opens the file in an editor in a new pane
+    pub open: String,
+    /// Sends the file to the editor in an existing pane
+    pub send: String,
+}
+
+/// A `[symbol_queries.<language>]` table: tree-sitter patterns capturing each symbol under
+/// its kind (e.g. `@test`) and its name as `@name`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SymbolQuery {
+    pub query: String,
+    /// Use instead of the built-in patterns rather than alongside them
+    pub replace: bool,
+}
+
+/// Include / exclude globs (as in `heat_weights`) over repository-relative paths.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PathFilter {
+    /// When non-empty, only paths matching one of these are kept
+    pub include: Vec<String>,
+    /// Paths matching any of these are dropped, even when included
+    pub exclude: Vec<String>,
+}
+
+impl PathFilter {
+    /// Whether `path` passes; a pattern matching a parent directory covers everything below it.
+    #[must_use]
+    pub fn allows(&self, path: &str) -> bool {
+        // Untracked directories are reported as `dir/`
+        let path = path.trim_end_matches('/');
+        let hit = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|pattern| ancestors(path).any(|p| glob_match(pattern, p)))
+        };
+        (self.include.is_empty() || hit(&self.include)) && !hit(&self.exclude)
+    }
+}
+
+/// `a/b/c`, `a/b`, `a`.
+fn ancestors(path: &str) -> impl Iterator<Item = &str> {
+    std::iter::successors(Some(path), |p| p.rsplit_once('/').map(|(parent, _)| parent))
+}
+
+/// Background colours for diff lines, as git colour values (`#203820`, `22`, `green`).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DiffColors {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub added: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub removed: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// How far derived backgrounds move from the theme background toward green / red (0-1)
+    pub tint: f32,
+    /// Minimum contrast ratio (WCAG) between theme text and a derived background
+    pub min_contrast: f32,
+}
+
+impl Default for DiffColors {
+    fn default() -> Self {
+        Self {
+            added: None,
+            removed: None,
+            context: None,
+            tint: 0.25,
+            min_contrast: 4.5,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            start_view: ZoomLevel::Galaxy,
+            skip_galaxy_max_files: 0,
+            auto_skip: false,
+            mechanical_min_files: 0,
+            heat_weights: HashMap::new(),
+            heat_half_life_days: 0.0,
+            base_branch: "main".to_string(),
+            merge_base: false,
+            truecolor: true,
+            color_mode: ColorMode::Normal,
+            nerd_fonts: false,
+            watch: true,
+            terminal_integration: true,
+            scan: PathFilter::default(),
+            syntax_theme: DEFAULT_SYNTAX_THEME.to_string(),
+            diff_colors: DiffColors::default(),
+            spell_check: true,
+            panels: Vec::new(),
+            queue_repos: Vec::new(),
+            show_checks: true,
+            show_comments: true,
+            verdicts_in_review: false,
+            pane: PaneConfig::default(),
+            symbol_queries: BTreeMap::new(),
+            profiles: BTreeMap::new(),
+            active_profile: None,
+            base: None,
+            overrides: Overrides::default(),
+            refresh: false,
+        }
+    }
+}
+
+impl Config {
+    #[must_use]
+    pub fn path() -> PathBuf {
+        get_config_dir().join("config.toml")
+    }
+
+    /// Loads the config file, falling back to defaults when it does not exist.
+    ///
+    /// # Errors
+    /// Returns an error if the file exists but cannot be read or parsed.
+    pub fn load() -> Result<Self> {
+        let path = Self::path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&raw).with_context(|| format!("Invalid config in {}", path.display()))
+    }
+
+    /// Writes the config file, creating the config directory if needed. Edits made to these
+    /// settings go to the file; the active profile and command-line overrides do not.
+    ///
+    /// # Errors
+    /// Returns an error if the directory or file cannot be written.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let raw =
+            toml::to_string_pretty(&self.file_settings()?).context("Failed to serialize config")?;
+        fs::write(&path, raw).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// The config file's settings, with whatever differs from what its layers would give
+    /// written over them.
+    fn file_settings(&self) -> Result<toml::Table> {
+        let serialize =
+            |config: &Self| toml::Table::try_from(config).context("Failed to serialize config");
+        let mut file = serialize(self.base())?;
+        if self.base.is_some() {
+            let layered = self.base().layered(self.profile(), &self.overrides)?;
+            keep_edits(&mut file, &serialize(&layered)?, &serialize(self)?);
+        }
+        Ok(file)
+    }
+
+    /// Settings before any profile or command-line override was laid over them.
+    #[must_use]
+    pub fn base(&self) -> &Self {
+        self.base.as_deref().unwrap_or(self)
+    }
+
+    /// Name of the active profile.
+    #[must_use]
+    pub fn profile(&self) -> Option<&str> {
+        self.active_profile.as_deref()
+    }
+
+    /// The base settings with profile `name` laid over them, then the command-line overrides;
+    /// tables such as `scan` merge key by key, lists such as `panels` are replaced.
+    ///
+    /// # Errors
+    /// Returns an error if there is no such profile or its settings do not parse.
+    pub fn with_profile(&self, name: &str) -> Result<Self> {
+        self.relayered(Some(name), &self.overrides)
+    }
+
+    /// The base settings with only the command-line overrides laid over them.
+    ///
+    /// # Errors
+    /// Returns an error if the settings cannot be copied.
+    pub fn without_profile(&self) -> Result<Self> {
+        self.relayered(None, &self.overrides)
+    }
+
+    /// These settings with `overrides` in place of the command-line overrides.
+    ///
+    /// # Errors
+    /// Returns an error if the active profile no longer parses.
+    pub fn with_overrides(&self, overrides: &Overrides) -> Result<Self> {
+        self.relayered(self.profile(), overrides)
+    }
+
+    fn relayered(&self, profile: Option<&str>, overrides: &Overrides) -> Result<Self> {
+        let mut config = self.base().layered(profile, overrides)?;
+        config.refresh = self.refresh;
+        Ok(config)
+    }
+
+    /// These (base) settings with `profile` and then `overrides` laid over them.
+    fn layered(&self, profile: Option<&str>, overrides: &Overrides) -> Result<Self> {
+        let mut config: Self = match profile {
+            Some(name) => {
+                let profile = self.profiles.get(name).with_context(|| {
+                    format!("No profile named {name} in {}", Self::path().display())
+                })?;
+                let mut settings =
+                    toml::Table::try_from(self).context("Failed to serialize config")?;
+                overlay(&mut settings, profile);
+                settings
+                    .try_into()
+                    .with_context(|| format!("Invalid settings in profile {name}"))?
+            }
+            None => self.clone(),
+        };
+        overrides.apply(&mut config);
+        config.active_profile = profile.map(str::to_string);
+        config.base = Some(Box::new(self.clone()));
+        config.overrides = overrides.clone();
+        Ok(config)
+    }
+}
+
+/// Writes into `file` what differs between `layered` and `edited`, descending into tables
+/// present in all three, so an edit to one `scan` key leaves the others as the file had them.
+fn keep_edits(file: &mut toml::Table, layered: &toml::Table, edited: &toml::Table) {
+    for (key, value) in edited {
+        match (file.get_mut(key), layered.get(key), value) {
+            (
+                Some(toml::Value::Table(file)),
+                Some(toml::Value::Table(layered)),
+                toml::Value::Table(edited),
+            ) => keep_edits(file, layered, edited),
+            (_, layered, value) if layered != Some(value) => {
+                file.insert(key.clone(), value.clone());
+            }
+            _ => {}
+        }
+    }
+    for key in layered.keys().filter(|key| !edited.contains_key(*key)) {
+        file.remove(key);
+    }
+}
+
+/// Copies `top` over `base`, descending into tables present in both.
+fn overlay(base: &mut toml::Table, top: &toml::Table) {
+    for (key, value) in top {
+        if let (Some(toml::Value::Table(base)), toml::Value::Table(top)) =
+            (base.get_mut(key), value)
+        {
+            overlay(base, top);
+        } else {
+            base.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+/// Heat multiplier for `path`; when several patterns match, the lowest weight wins.
+#[must_use]
+pub fn heat_weight<S: BuildHasher>(path: &str, weights: &HashMap<String, f32, S>) -> f32 {
+    weights
+        .iter()
+        .filter(|(pattern, _)| glob_match(pattern, path))
+        .map(|(_, &weight)| weight.max(0.0))
+        .reduce(f32::min)
+        .unwrap_or(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, Overrides, PathFilter};
+
+    #[test]
+    fn filters_paths_and_their_directories() {
+        let filter = PathFilter {
+            include: vec![],
+            exclude: vec!["node_modules".to_string(), "target/**".to_string()],
+        };
+        assert!(filter.allows("src/main.rs"));
+        assert!(!filter.allows("web/node_modules/"));
+        assert!(!filter.allows("web/node_modules/left-pad/index.js"));
+        assert!(!filter.allows("target/debug/glim"));
+
+        let only_rust = PathFilter {
+            include: vec![".rs".to_string()],
+            exclude: vec!["generated/**".to_string()],
+        };
+        assert!(only_rust.allows("src/app.rs"));
+        assert!(!only_rust.allows("README.md"));
+        assert!(!only_rust.allows("generated/schema.rs"));
+    }
+
+    #[test]
+    fn lays_profiles_over_base_settings() {
+        let base: Config = toml::from_str(
+            r#"
+            spell_check = false
+            scan = { exclude = ["vendor"] }
+            [[panels]]
+            title = "Coverage"
+            command = "cat coverage.txt"
+            [profiles.quick-triage]
+            panels = []
+            show_comments = false
+            scan = { include = [".rs"] }
+            "#,
+        )
+        .expect("config");
+        let triage = base.with_profile("quick-triage").expect("profile");
+        assert!(triage.panels.is_empty() && !triage.show_comments && !triage.spell_check);
+        assert_eq!(triage.scan.include, [".rs"]);
+        assert_eq!(triage.scan.exclude, ["vendor"]);
+        assert_eq!(triage.profile(), Some("quick-triage"));
+        assert_eq!(triage.base().panels.len(), 1);
+        assert!(triage.with_profile("deep-review").is_err());
+    }
+
+    #[test]
+    fn keeps_command_line_overrides_across_profiles() {
+        let base: Config = toml::from_str(
+            r#"
+            base_branch = "main"
+            scan = { exclude = ["vendor"] }
+            [profiles.quick-triage]
+            base_branch = "develop"
+            show_comments = false
+            "#,
+        )
+        .expect("config");
+        let overrides = Overrides {
+            base_branch: Some("release".to_string()),
+            exclude: vec!["dist".to_string()],
+            ..Overrides::default()
+        };
+        let config = base.with_overrides(&overrides).expect("overrides");
+        let triage = config.with_profile("quick-triage").expect("profile");
+        assert_eq!(triage.base_branch, "release");
+        assert!(triage.merge_base && !triage.show_comments);
+        assert_eq!(triage.scan.exclude, ["vendor", "dist"]);
+        let plain = triage.without_profile().expect("no profile");
+        assert_eq!(plain.base_branch, "release");
+        assert_eq!(plain.scan.exclude, ["vendor", "dist"]);
+        assert!(plain.show_comments);
+    }
+
+    #[test]
+    fn saves_edits_but_not_profiles_or_overrides() {
+        let base: Config = toml::from_str(
+            r#"
+            scan = { exclude = ["vendor"] }
+            [profiles.quick-triage]
+            show_comments = false
+            "#,
+        )
+        .expect("config");
+        let overrides = Overrides {
+            exclude: vec!["dist".to_string()],
+            ..Overrides::default()
+        };
+        let mut config = base
+            .with_overrides(&overrides)
+            .and_then(|config| config.with_profile("quick-triage"))
+            .expect("layered");
+        config.nerd_fonts = !base.nerd_fonts;
+        config.scan.include.push(".rs".to_string());
+        let saved: Config = config
+            .file_settings()
+            .and_then(|table| Ok(table.try_into()?))
+            .expect("saved");
+        assert_eq!(saved.nerd_fonts, !base.nerd_fonts);
+        assert!(saved.show_comments);
+        assert_eq!(saved.scan.include, [".rs"]);
+        assert_eq!(saved.scan.exclude, ["vendor"]);
+    }
+}
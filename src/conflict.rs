@@ -0,0 +1,303 @@
+// Three-way view of files with merge conflict markers, with per-region resolution
+use std::fmt::Write;
+
+/// Which side of a conflict region to keep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Ours,
+    Theirs,
+}
+
+/// One `<<<<<<<` ... `>>>>>>>` block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictRegion {
+    pub ours_label: String,
+    pub ours: Vec<String>,
+    // Only present with `merge.conflictStyle = diff3` markers or when recovered from the index
+    pub base: Option<Vec<String>>,
+    pub base_label: String,
+    pub theirs: Vec<String>,
+    pub theirs_label: String,
+    pub resolution: Option<Side>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Common(Vec<String>),
+    Conflict(ConflictRegion),
+}
+
+/// Role of a rendered line, used for colouring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Common,
+    Marker,
+    Ours,
+    Base,
+    Theirs,
+    Resolved,
+}
+
+/// A display line of the conflict view and the region it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictLine {
+    pub kind: LineKind,
+    pub text: String,
+    pub region: Option<usize>,
+}
+
+/// A conflicted file split into common text and conflict regions.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ConflictFile {
+    segments: Vec<Segment>,
+    trailing_newline: bool,
+}
+
+impl ConflictFile {
+    /// Splits `content` on conflict markers; unterminated blocks are kept as plain text.
+    #[must_use]
+    pub fn parse(content: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut common = Vec::new();
+        // Split on '\n' only so CRLF files keep their '\r'
+        let body = content.strip_suffix('\n').unwrap_or(content);
+        let mut lines = body.split('\n').filter(|_| !content.is_empty());
+
+        while let Some(line) = lines.next() {
+            let Some(ours_label) = marker_label(line, "<<<<<<<") else {
+                common.push(line.to_string());
+                continue;
+            };
+
+            let mut raw = vec![line.to_string()];
+            let mut region = ConflictRegion {
+                ours_label,
+                ours: Vec::new(),
+                base: None,
+                base_label: String::new(),
+                theirs: Vec::new(),
+                theirs_label: String::new(),
+                resolution: None,
+            };
+            // 0 = ours, 1 = base, 2 = theirs
+            let mut part = 0;
+            let mut closed = false;
+            for line in lines.by_ref() {
+                raw.push(line.to_string());
+                if part == 0
+                    && let Some(label) = marker_label(line, "|||||||")
+                {
+                    region.base = Some(Vec::new());
+                    region.base_label = label;
+                    part = 1;
+                } else if part < 2 && line.trim_end() == "=======" {
+                    part = 2;
+                } else if part == 2
+                    && let Some(label) = marker_label(line, ">>>>>>>")
+                {
+                    region.theirs_label = label;
+                    closed = true;
+                    break;
+                } else {
+                    match part {
+                        0 => region.ours.push(line.to_string()),
+                        1 => region.base.get_or_insert_default().push(line.to_string()),
+                        _ => region.theirs.push(line.to_string()),
+                    }
+                }
+            }
+
+            if closed {
+                if !common.is_empty() {
+                    segments.push(Segment::Common(std::mem::take(&mut common)));
+                }
+                segments.push(Segment::Conflict(region));
+            } else {
+                common.extend(raw);
+            }
+        }
+        if !common.is_empty() {
+            segments.push(Segment::Common(common));
+        }
+
+        Self {
+            segments,
+            trailing_newline: content.ends_with('\n'),
+        }
+    }
+
+    pub fn regions(&self) -> impl Iterator<Item = &ConflictRegion> {
+        self.segments.iter().filter_map(|s| match s {
+            Segment::Conflict(region) => Some(region),
+            Segment::Common(_) => None,
+        })
+    }
+
+    fn regions_mut(&mut self) -> impl Iterator<Item = &mut ConflictRegion> {
+        self.segments.iter_mut().filter_map(|s| match s {
+            Segment::Conflict(region) => Some(region),
+            Segment::Common(_) => None,
+        })
+    }
+
+    #[must_use]
+    pub fn has_conflicts(&self) -> bool {
+        self.regions().next().is_some()
+    }
+
+    #[must_use]
+    pub fn unresolved(&self) -> usize {
+        self.regions().filter(|r| r.resolution.is_none()).count()
+    }
+
+    /// Picks a side for region `index`; choosing the same side again undoes it.
+    pub fn resolve(&mut self, index: usize, side: Side) {
+        if let Some(region) = self.regions_mut().nth(index) {
+            region.resolution = (region.resolution != Some(side)).then_some(side);
+        }
+    }
+
+    /// Copies base sections from `other` (the same merge in diff3 style) when regions line up.
+    pub fn fill_bases(&mut self, other: &Self) {
+        let theirs: Vec<&ConflictRegion> = other.regions().collect();
+        if theirs.len() != self.regions().count() {
+            return;
+        }
+        for (region, source) in self.regions_mut().zip(theirs) {
+            if region.base.is_none() && region.ours == source.ours && region.theirs == source.theirs
+            {
+                region.base.clone_from(&source.base);
+                region.base_label.clone_from(&source.base_label);
+            }
+        }
+    }
+
+    /// Display lines: unresolved regions with all three sides, resolved ones with the pick.
+    #[must_use]
+    pub fn lines(&self) -> Vec<ConflictLine> {
+        let mut out = Vec::new();
+        let mut region_index = 0;
+        let line = |kind, text: &str, region| ConflictLine {
+            kind,
+            text: text.to_string(),
+            region,
+        };
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Common(lines) => {
+                    out.extend(lines.iter().map(|l| line(LineKind::Common, l, None)));
+                }
+                Segment::Conflict(region) => {
+                    let r = Some(region_index);
+                    region_index += 1;
+                    if let Some(side) = region.resolution {
+                        let (label, chosen) = match side {
+                            Side::Ours => ("ours", &region.ours),
+                            Side::Theirs => ("theirs", &region.theirs),
+                        };
+                        out.push(line(LineKind::Marker, &format!("✔ took {label}"), r));
+                        out.extend(chosen.iter().map(|l| line(LineKind::Resolved, l, r)));
+                        continue;
+                    }
+
+                    out.push(line(
+                        LineKind::Marker,
+                        &format!("<<<<<<< ours {}", region.ours_label),
+                        r,
+                    ));
+                    out.extend(region.ours.iter().map(|l| line(LineKind::Ours, l, r)));
+                    if let Some(base) = &region.base {
+                        out.push(line(LineKind::Marker, "||||||| base", r));
+                        out.extend(base.iter().map(|l| line(LineKind::Base, l, r)));
+                    }
+                    out.push(line(LineKind::Marker, "=======", r));
+                    out.extend(region.theirs.iter().map(|l| line(LineKind::Theirs, l, r)));
+                    out.push(line(
+                        LineKind::Marker,
+                        &format!(">>>>>>> theirs {}", region.theirs_label),
+                        r,
+                    ));
+                }
+            }
+        }
+        out
+    }
+
+    /// File content with resolved regions applied and unresolved ones left as markers.
+    #[must_use]
+    pub fn render(&self) -> String {
+        fn push(out: &mut String, lines: &[String]) {
+            for line in lines {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Common(lines) => push(&mut out, lines),
+                Segment::Conflict(region) => match region.resolution {
+                    Some(Side::Ours) => push(&mut out, &region.ours),
+                    Some(Side::Theirs) => push(&mut out, &region.theirs),
+                    None => {
+                        let _ = writeln!(out, "{}", marker("<<<<<<<", &region.ours_label));
+                        push(&mut out, &region.ours);
+                        if let Some(base) = &region.base {
+                            let _ = writeln!(out, "{}", marker("|||||||", &region.base_label));
+                            push(&mut out, base);
+                        }
+                        out.push_str("=======\n");
+                        push(&mut out, &region.theirs);
+                        let _ = writeln!(out, "{}", marker(">>>>>>>", &region.theirs_label));
+                    }
+                },
+            }
+        }
+        if !self.trailing_newline && out.ends_with('\n') {
+            out.pop();
+        }
+        out
+    }
+}
+
+fn marker(marker: &str, label: &str) -> String {
+    if label.is_empty() {
+        marker.to_string()
+    } else {
+        format!("{marker} {label}")
+    }
+}
+
+/// Label after a 7-character conflict marker, or `None` if `line` isn't that marker.
+fn marker_label(line: &str, marker: &str) -> Option<String> {
+    let rest = line.strip_prefix(marker)?;
+    if rest.trim().is_empty() || rest.starts_with(' ') {
+        Some(rest.trim().to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConflictFile, Side};
+
+    const MERGED: &str =
+        "a\n<<<<<<< HEAD\nours\n||||||| base\norig\n=======\ntheirs\n>>>>>>> topic\nz\n";
+
+    #[test]
+    fn parses_and_resolves_regions() {
+        let mut file = ConflictFile::parse(MERGED);
+        assert_eq!(file.unresolved(), 1);
+        let region = file.regions().next().cloned().expect("one region");
+        assert_eq!(region.base, Some(vec!["orig".to_string()]));
+        assert_eq!(region.theirs_label, "topic");
+        assert_eq!(file.render(), MERGED);
+
+        file.resolve(0, Side::Theirs);
+        assert_eq!(file.unresolved(), 0);
+        assert_eq!(file.render(), "a\ntheirs\nz\n");
+    }
+}
@@ -0,0 +1,230 @@
+// Pure helpers for working with unified diff text as shown in the Logic view
+
+/// Maps each diff line to its `(old, new)` file line numbers.
+///
+/// Hunk headers (`@@ -a,b +c,d @@`, optionally indented by the local diff printer) reset the
+/// counters; added lines have no old number, removed lines no new one, headers neither.
+#[must_use]
+pub fn line_numbers(lines: &[String]) -> Vec<(Option<usize>, Option<usize>)> {
+    let mut next: Option<(usize, usize)> = None;
+    lines
+        .iter()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("@@") {
+                next = parse_hunk_starts(trimmed);
+                return (None, None);
+            }
+            let Some((old, new)) = next else {
+                return (None, None);
+            };
+            if line.starts_with('-') {
+                next = Some((old + 1, new));
+                (Some(old), None)
+            } else if line.starts_with('+') {
+                next = Some((old, new + 1));
+                (None, Some(new))
+            } else {
+                next = Some((old + 1, new + 1));
+                (Some(old), Some(new))
+            }
+        })
+        .collect()
+}
+
+/// Maps each diff line to its line number in the new file.
+#[must_use]
+pub fn new_line_numbers(lines: &[String]) -> Vec<Option<usize>> {
+    line_numbers(lines)
+        .into_iter()
+        .map(|(_, new)| new)
+        .collect()
+}
+
+/// New-file line that the diff line at `cursor` belongs to, for locating its hunk.
+///
+/// Removed lines have no number of their own, so they borrow the nearest numbered line of
+/// the same hunk, or the hunk's start when it only removes. Headers yield `None`.
+#[must_use]
+pub fn anchor_line(lines: &[String], cursor: usize) -> Option<usize> {
+    let numbers = new_line_numbers(lines);
+    if let Some(n) = numbers.get(cursor).copied().flatten() {
+        return Some(n);
+    }
+    let is_header = |i: usize| lines[i].trim_start().starts_with("@@");
+    if cursor >= lines.len() || is_header(cursor) {
+        return None;
+    }
+    let before = (0..cursor)
+        .rev()
+        .take_while(|&i| !is_header(i))
+        .find_map(|i| numbers[i]);
+    before
+        .or_else(|| {
+            (cursor + 1..lines.len())
+                .take_while(|&i| !is_header(i))
+                .find_map(|i| numbers[i])
+        })
+        .or_else(|| {
+            let header = (0..cursor).rev().find(|&i| is_header(i))?;
+            parse_hunk_starts(lines[header].trim_start()).map(|(_, new)| new.max(1))
+        })
+}
+
+/// New-file lines a hunk starting at `new_start` with `new_lines` lines changed, inclusive.
+///
+/// A hunk that only removes lines reports the line before the gap; its span is the empty
+/// `(k + 1, k)` between lines `k` and `k + 1`, which [`touches`] counts only when a symbol
+/// holds both.
+#[must_use]
+pub const fn hunk_span(new_start: usize, new_lines: usize) -> (usize, usize) {
+    if new_lines == 0 {
+        (new_start + 1, new_start)
+    } else {
+        (new_start, new_start + new_lines - 1)
+    }
+}
+
+/// Whether any of the hunk `spans` falls within lines `start..=end`.
+#[must_use]
+pub fn touches(spans: &[(usize, usize)], start: usize, end: usize) -> bool {
+    spans.iter().any(|&(from, to)| from <= end && to >= start)
+}
+
+/// Extracts `(a, c)` from a hunk header `@@ -a,b +c,d @@`.
+fn parse_hunk_starts(header: &str) -> Option<(usize, usize)> {
+    let start = |sign: char| {
+        header
+            .split_whitespace()
+            .find(|p| p.starts_with(sign))?
+            .trim_start_matches(sign)
+            .split(',')
+            .next()?
+            .parse()
+            .ok()
+    };
+    Some((start('-')?, start('+')?))
+}
+
+/// Whether `line` is part of a file header (`diff --git`, `index`, `---`/`+++`, modes, renames).
+///
+/// Leading whitespace from the local diff printer is ignored.
+#[must_use]
+pub fn is_meta_line(line: &str) -> bool {
+    const HEADERS: &[&str] = &[
+        "diff --git ",
+        "index ",
+        "--- a/",
+        "--- /dev/null",
+        "+++ b/",
+        "+++ /dev/null",
+        "new file mode ",
+        "deleted file mode ",
+        "old mode ",
+        "new mode ",
+        "similarity index ",
+        "rename from ",
+        "rename to ",
+        "Binary files ",
+    ];
+    let line = line.trim_start();
+    HEADERS.iter().any(|h| line.starts_with(h))
+}
+
+/// Whether `line` is an unchanged context line, as opposed to a change or a header.
+#[must_use]
+pub fn is_context_line(line: &str) -> bool {
+    line.starts_with(' ') && !line.trim_start().starts_with("@@") && !is_meta_line(line)
+}
+
+/// `(old, new)` octal file modes from a patch's `old mode` / `new mode` header lines.
+#[must_use]
+pub fn mode_change(lines: &[String]) -> Option<(u32, u32)> {
+    let mode = |header: &str| {
+        lines.iter().find_map(|l| {
+            let digits = l.trim_start().strip_prefix(header)?;
+            u32::from_str_radix(digits.trim(), 8).ok()
+        })
+    };
+    Some((mode("old mode ")?, mode("new mode ")?))
+}
+
+/// Short Structure badge for a mode change: `+x` / `-x` for the executable bit, else both modes.
+#[must_use]
+pub fn mode_badge((old, new): (u32, u32)) -> String {
+    match (old, new) {
+        (0o100_644, 0o100_755) => "+x".to_string(),
+        (0o100_755, 0o100_644) => "-x".to_string(),
+        _ => format!("{old:o}→{new:o}"),
+    }
+}
+
+/// Annotation line for the top of the Logic view, e.g. `Mode: 100644 → 100755 (now executable)`.
+#[must_use]
+pub fn describe_mode_change((old, new): (u32, u32)) -> String {
+    let kind = |mode: u32| match mode {
+        0o100_755 => "executable",
+        0o100_644 => "regular file",
+        0o120_000 => "symlink",
+        0o160_000 => "submodule",
+        _ => "other",
+    };
+    format!("Mode: {old:o} → {new:o} ({} → {})", kind(old), kind(new))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        anchor_line, hunk_span, line_numbers, mode_badge, mode_change, new_line_numbers, touches,
+    };
+
+    #[test]
+    fn numbers_follow_hunk_headers() {
+        let lines: Vec<String> = [
+            "diff --git a/x b/x",
+            "@@ -1,2 +10,3 @@",
+            " a",
+            "-b",
+            "+c",
+            "+d",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        assert_eq!(
+            new_line_numbers(&lines),
+            vec![None, None, Some(10), None, Some(11), Some(12)]
+        );
+        assert_eq!(line_numbers(&lines)[3], (Some(2), None));
+        assert_eq!(anchor_line(&lines, 3), Some(10));
+        assert_eq!(anchor_line(&lines, 1), None);
+    }
+
+    #[test]
+    fn reads_mode_headers() {
+        let lines: Vec<String> = [
+            " diff --git a/run.sh b/run.sh",
+            " old mode 100644",
+            " new mode 100755",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        assert_eq!(mode_change(&lines), Some((0o100_644, 0o100_755)));
+        assert_eq!(mode_badge((0o100_644, 0o100_755)), "+x");
+        assert_eq!(mode_change(&lines[..2]), None);
+    }
+
+    #[test]
+    fn hunks_touch_the_symbols_they_change() {
+        // Lines 5-6 rewritten, and line 20 removed from between 19 and 20
+        let spans = [hunk_span(5, 2), hunk_span(19, 0)];
+        assert_eq!(spans, [(5, 6), (20, 19)]);
+        assert!(touches(&spans, 1, 5));
+        assert!(!touches(&spans, 7, 18));
+        assert!(touches(&spans, 15, 25));
+        // A removal just after a symbol's last line leaves it alone
+        assert!(!touches(&spans, 10, 19));
+        assert!(!touches(&spans, 20, 30));
+    }
+}
@@ -1,8 +1,13 @@
 // Event handling utilities (Placeholder for future expansion)
 use crossterm::event::KeyEvent;
+use std::path::PathBuf;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum AppEvent {
     Input(KeyEvent),
     Tick,
+    // Repository-relative paths that changed on disk, batched by the watcher
+    FilesChanged(Vec<PathBuf>),
+    // The watcher could not watch part of the tree, with why
+    WatchFailed(String),
 }
@@ -1,13 +1,169 @@
-use anyhow::{Context, Result};
-use serde::Deserialize;
-use std::process::Command;
+use crate::commit::CommitInfo;
+use crate::people::PeopleRole;
+use crate::review::ReviewEvent;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-#[derive(Debug, Deserialize, Clone)]
+// Metadata, files, review threads, viewed state, and CI checks for one PR in a single round
+// trip; the longer lists are paged in after it
+const PR_QUERY: &str = r"
+query($owner: String!, $name: String!, $number: Int!) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      id number title body state isDraft url changedFiles additions deletions baseRefName
+      baseRefOid headRefOid
+      author { login }
+      reviewRequests(first: 50) { nodes { requestedReviewer {
+        ... on User { login }
+        ... on Team { combinedSlug }
+      } } }
+      assignees(first: 20) { nodes { login } }
+      labels(first: 30) { nodes { name color } }
+      files(first: 100) {
+        nodes { path additions deletions viewerViewedState }
+        pageInfo { hasNextPage endCursor }
+      }
+      reviewThreads(first: 100) {
+        nodes { id isResolved path line startLine diffSide
+          comments(first: 50) {
+            nodes { author { login } body createdAt }
+            pageInfo { hasNextPage endCursor }
+          }
+        }
+        pageInfo { hasNextPage endCursor }
+      }
+      commits(last: 1) { nodes { commit {
+        statusCheckRollup { contexts(first: 100) {
+          nodes {
+            ... on CheckRun { name status conclusion detailsUrl }
+            ... on StatusContext { context state targetUrl }
+          }
+          pageInfo { hasNextPage endCursor }
+        } }
+        checkSuites(first: 20) { nodes {
+        checkRuns(first: 20) { nodes { name annotations(first: 50) { nodes {
+          path message title annotationLevel location { start { line } }
+        } } } }
+      } } } } }
+    }
+  }
+}
+";
+
+// Ticks a file's "Viewed" box on the PR, or clears it
+const MARK_VIEWED: &str = r"
+mutation($id: ID!, $path: String!) {
+  markFileAsViewed(input: { pullRequestId: $id, path: $path }) { clientMutationId }
+}
+";
+const UNMARK_VIEWED: &str = r"
+mutation($id: ID!, $path: String!) {
+  unmarkFileAsViewed(input: { pullRequestId: $id, path: $path }) { clientMutationId }
+}
+";
+
+// Later pages of a PR's file list, 100 files at a time (GitHub lists 3000 at most)
+const FILES_QUERY: &str = r"
+query($owner: String!, $name: String!, $number: Int!, $cursor: String!) {
+  repository(owner: $owner, name: $name) { pullRequest(number: $number) {
+    files(first: 100, after: $cursor) {
+      nodes { path additions deletions viewerViewedState }
+      pageInfo { hasNextPage endCursor }
+    }
+  } }
+}
+";
+
+// Later pages of a PR's review threads
+const THREADS_QUERY: &str = r"
+query($owner: String!, $name: String!, $number: Int!, $cursor: String!) {
+  repository(owner: $owner, name: $name) { pullRequest(number: $number) {
+    reviewThreads(first: 100, after: $cursor) {
+      nodes { id isResolved path line startLine diffSide
+        comments(first: 50) {
+          nodes { author { login } body createdAt }
+          pageInfo { hasNextPage endCursor }
+        }
+      }
+      pageInfo { hasNextPage endCursor }
+    }
+  } }
+}
+";
+
+// Later pages of the comments on one review thread
+const THREAD_COMMENTS_QUERY: &str = r"
+query($id: ID!, $cursor: String!) {
+  node(id: $id) { ... on PullRequestReviewThread {
+    comments(first: 100, after: $cursor) {
+      nodes { author { login } body createdAt }
+      pageInfo { hasNextPage endCursor }
+    }
+  } }
+}
+";
+
+// Later pages of the head commit's CI checks
+const CONTEXTS_QUERY: &str = r"
+query($owner: String!, $name: String!, $number: Int!, $cursor: String!) {
+  repository(owner: $owner, name: $name) { pullRequest(number: $number) {
+    commits(last: 1) { nodes { commit {
+      statusCheckRollup { contexts(first: 100, after: $cursor) {
+        nodes {
+          ... on CheckRun { name status conclusion detailsUrl }
+          ... on StatusContext { context state targetUrl }
+        }
+        pageInfo { hasNextPage endCursor }
+      } }
+    } } }
+  } }
+}
+";
+
+// GitHub refuses whole-PR diffs touching more files than this; larger PRs load per file
+pub const MAX_DIFF_FILES: usize = 300;
+// Files per page of the REST PR files listing, which carries each file's patch
+const FILES_PER_PAGE: usize = 100;
+
+// Just enough to tell whether a cached copy of the PR is still current, plus the draft
+// state and labels, which change without a new commit
+const HEAD_QUERY: &str = r"
+query($owner: String!, $name: String!, $number: Int!) {
+  repository(owner: $owner, name: $name) { pullRequest(number: $number) {
+    url headRefOid isDraft labels(first: 30) { nodes { name color } }
+  } }
+}
+";
+
+// Retries of a `gh` call hitting a secondary rate limit or a GitHub server error; the waits
+// double from `RETRY_BASE`, so the last retry starts about 15s after the first attempt
+const MAX_RETRIES: u32 = 3;
+const RETRY_BASE: Duration = Duration::from_secs(2);
+// While the TUI is up a retry freezes the screen, so it gets a single short one instead
+const INTERACTIVE_RETRY: Duration = Duration::from_secs(1);
+static INTERACTIVE: AtomicBool = AtomicBool::new(false);
+// Time spent waiting to retry since the app last asked, so it can say why it stalled
+static RETRY_WAIT: Mutex<Duration> = Mutex::new(Duration::ZERO);
+
+// Latest quota reported per API resource (`core`, `graphql`, ...), from `gh api` headers
+static RATE_LIMITS: Mutex<BTreeMap<String, RateLimit>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PrInfo {
     pub number: u64,
     pub title: String,
     pub body: String,
     pub state: String,
+    #[serde(rename = "isDraft", default)]
+    pub is_draft: bool,
     pub author: Author,
     pub url: String,
     #[serde(rename = "changedFiles")]
@@ -15,83 +171,1756 @@ pub struct PrInfo {
     pub additions: u64,
     pub deletions: u64,
     pub files: Vec<PrFile>,
+    // Branch the PR merges into, and the commit of it the PR was last compared against
+    #[serde(rename = "baseRefName", default)]
+    pub base_ref_name: String,
+    #[serde(rename = "baseRefOid", default)]
+    pub base_ref_oid: String,
+    // Commit the PR branch points at; cached PR data is reused while it stays the same
+    #[serde(rename = "headRefOid", default)]
+    pub head_ref_oid: String,
+    // GraphQL node ID, which mutations on the PR take
+    #[serde(rename = "id", default)]
+    pub node_id: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Where a PR's branch currently points, as fetched by [`GitHubClient::fetch_head`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrHead {
+    pub url: String,
+    pub oid: String,
+    pub is_draft: bool,
+    pub labels: Vec<Label>,
+}
+
+/// GitHub API quota of one resource, from the `X-RateLimit-*` response headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimit {
+    pub resource: String,
+    pub remaining: u64,
+    pub limit: u64,
+    // Unix time the quota refills
+    pub reset: u64,
+}
+
+impl RateLimit {
+    fn from_headers(headers: &str) -> Option<Self> {
+        let mut values = HashMap::new();
+        for line in headers.lines() {
+            if let Some((key, value)) = line.split_once(':') {
+                values.insert(key.trim().to_ascii_lowercase(), value.trim());
+            }
+        }
+        let number = |key: &str| values.get(key)?.parse().ok();
+        Some(Self {
+            resource: values
+                .get("x-ratelimit-resource")
+                .map_or("core", |r| r)
+                .to_string(),
+            remaining: number("x-ratelimit-remaining")?,
+            limit: number("x-ratelimit-limit")?,
+            reset: number("x-ratelimit-reset").unwrap_or(0),
+        })
+    }
+
+    /// Under a tenth of the quota left.
+    #[must_use]
+    pub const fn is_low(&self) -> bool {
+        self.remaining * 10 < self.limit
+    }
+
+    /// Status bar text, e.g. `graphql 4890/5000`, with the refill time once the quota runs low.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let summary = format!("{} {}/{}", self.resource, self.remaining, self.limit);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        if self.is_low() && self.reset > now {
+            format!("{summary} (resets in {}m)", (self.reset - now).div_ceil(60))
+        } else {
+            summary
+        }
+    }
+}
+
+/// Diff lines per file of a REST PR files listing, with the git headers the patches lack.
+fn parse_file_patches(json: &Value) -> HashMap<String, Vec<String>> {
+    json.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|file| {
+            let path = file["filename"].as_str()?;
+            let old = file["previous_filename"].as_str().unwrap_or(path);
+            let Some(hunks) = file["patch"].as_str() else {
+                let note = "GitHub sends no diff for this file (binary or too large)";
+                return Some((path.to_string(), vec![note.to_string()]));
+            };
+            let (from, to) = match file["status"].as_str() {
+                Some("added") => ("/dev/null".to_string(), format!("b/{path}")),
+                Some("removed") => (format!("a/{old}"), "/dev/null".to_string()),
+                _ => (format!("a/{old}"), format!("b/{path}")),
+            };
+            let lines = [
+                format!("diff --git a/{old} b/{path}"),
+                format!("--- {from}"),
+                format!("+++ {to}"),
+            ]
+            .into_iter()
+            .chain(hunks.lines().map(String::from))
+            .collect();
+            Some((path.to_string(), lines))
+        })
+        .collect()
+}
+
+/// Whether `gh pr diff` failed because GitHub will not render a diff that large.
+#[must_use]
+pub fn diff_too_large(error: &str) -> bool {
+    ["too_large", "exceeded the maximum", "HTTP 406"]
+        .iter()
+        .any(|hint| error.contains(hint))
+}
+
+/// Whether `error` came from `gh` failing to reach GitHub at all.
+#[must_use]
+pub fn is_offline(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<GhError>()
+        .is_some_and(|e| e.kind == GhErrorKind::Network)
+}
+
+/// Splits `gh api --include` output into the header block and the body.
+fn split_response(out: &[u8]) -> (String, &[u8]) {
+    let blank = out
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .map(|i| (i, 4))
+        .or_else(|| out.windows(2).position(|w| w == b"\n\n").map(|i| (i, 2)));
+    match blank {
+        Some((i, len)) if out.starts_with(b"HTTP") => (
+            String::from_utf8_lossy(&out[..i]).to_string(),
+            &out[i + len..],
+        ),
+        _ => (String::new(), out),
+    }
+}
+
+impl PrInfo {
+    /// `owner/repo` parsed from the PR URL (e.g. `https://github.com/owner/repo/pull/123`).
+    #[must_use]
+    pub fn repo_slug(&self) -> Option<&str> {
+        self.url
+            .trim_start_matches("https://github.com/")
+            .split("/pull/")
+            .next()
+            .filter(|slug| slug.contains('/'))
+    }
+}
+
+/// How far the base branch moved on since the PR's base commit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BaseDrift {
+    pub branch: String,
+    // The PR's base commit, and how many commits the branch tip is ahead of it
+    pub base_oid: String,
+    pub behind: u64,
+    // Patch text of every file changed on the branch since; `None` when GitHub omits it
+    pub files: BTreeMap<String, Option<String>>,
+}
+
+impl BaseDrift {
+    /// Drifted files the PR changes too, where integration problems hide.
+    #[must_use]
+    pub fn overlapping<'a>(&'a self, info: &'a PrInfo) -> Vec<&'a str> {
+        info.files
+            .iter()
+            .map(|f| f.path.as_str())
+            .filter(|path| self.files.contains_key(*path))
+            .collect()
+    }
+
+    /// Dashboard warning, e.g. `base is 57 commits behind main, 2 files also changed there`.
+    #[must_use]
+    pub fn warning(&self, info: &PrInfo) -> String {
+        let overlapping = self.overlapping(info).len();
+        let mut warning = format!(
+            "base is {} commit{} behind {}",
+            self.behind,
+            if self.behind == 1 { "" } else { "s" },
+            self.branch
+        );
+        if overlapping > 0 {
+            warning = format!("{warning}, {overlapping} changed file(s) also changed there");
+        }
+        warning
+    }
+
+    /// Logic view lines for `path`: a summary of the drift, then that file's patch if it drifted.
+    #[must_use]
+    pub fn lines(&self, info: &PrInfo, path: &str) -> Vec<String> {
+        let overlapping = self.overlapping(info);
+        let mut lines = vec![
+            format!(
+                " {} moved {} commit(s) past this PR's base ({:.7})",
+                self.branch, self.behind, self.base_oid
+            ),
+            String::new(),
+        ];
+        match self.files.get(path) {
+            Some(Some(diff_text)) => {
+                lines.push(format!(
+                    " Changes to {path} on {} since the base:",
+                    self.branch
+                ));
+                lines.extend(diff_text.lines().map(|l| {
+                    if l.starts_with("@@") {
+                        format!(" {l}")
+                    } else {
+                        l.to_string()
+                    }
+                }));
+            }
+            Some(None) => lines.push(format!(
+                " {path} changed on {} since the base (no patch available)",
+                self.branch
+            )),
+            None => lines.push(format!(
+                " {path} has not changed on {} since the base",
+                self.branch
+            )),
+        }
+        lines.push(String::new());
+        lines.push(format!(" Files changed on {}:", self.branch));
+        lines.extend(self.files.keys().map(|file| {
+            let mark = if overlapping.contains(&file.as_str()) {
+                "!"
+            } else {
+                " "
+            };
+            format!(" {mark} {file}")
+        }));
+        lines
+    }
+}
+
+/// A check-run annotation (lint/test failure) attached to a file line.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CheckAnnotation {
+    pub path: String,
+    pub line: usize,
+    pub level: String,
+    pub title: String,
+    pub message: String,
+    pub check_name: String,
+}
+
+/// A review conversation anchored to one line of the PR diff.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ReviewThread {
+    pub path: String,
+    pub line: usize,
+    // First line of a comment spanning several lines, which ends at `line`
+    #[serde(default)]
+    pub start_line: Option<usize>,
+    // Anchored to the old file (a removed line) rather than the new one
+    pub left: bool,
+    pub resolved: bool,
+    pub comments: Vec<ReviewComment>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ReviewComment {
+    pub author: String,
+    pub body: String,
+    // ISO 8601, as GitHub reports it
+    pub created_at: String,
+}
+
+impl ReviewComment {
+    /// A comment as the REST API returns it, e.g. after posting one.
+    #[must_use]
+    pub fn from_rest(json: &Value) -> Self {
+        Self {
+            author: json["user"]["login"]
+                .as_str()
+                .unwrap_or("ghost")
+                .to_string(),
+            body: json["body"].as_str().unwrap_or_default().to_string(),
+            created_at: json["created_at"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+
+    /// `createdAt` shortened for display, e.g. `2024-05-01 14:03`.
+    #[must_use]
+    pub fn timestamp(&self) -> String {
+        self.created_at
+            .get(..16)
+            .map_or_else(|| self.created_at.clone(), |t| t.replace('T', " "))
+    }
+}
+
+/// One CI check (check run or commit status) on the PR's head commit.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CheckStatus {
+    pub name: String,
+    pub state: CiState,
+    // Where the check's logs live, when it reports one
+    pub url: Option<String>,
+}
+
+/// Per-file review state for a PR, fetched once when the PR is loaded.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct PrReviewData {
+    pub unresolved_threads: HashMap<String, usize>,
+    pub viewed: HashSet<String>,
+    pub annotations: Vec<CheckAnnotation>,
+    // Threads on outdated lines (no current `line`) are left out
+    pub threads: Vec<ReviewThread>,
+    // Failing checks first, then pending, then passing
+    pub checks: Vec<CheckStatus>,
+    // Logins (or `org/team` slugs) asked for a review who have not given one yet
+    pub reviewers: Vec<String>,
+    pub assignees: Vec<String>,
+    pub labels: Vec<Label>,
+}
+
+/// A repository label, as put on issues and PRs.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Label {
+    pub name: String,
+    // Hex RGB without the `#`, e.g. `d73a4a`
+    pub color: String,
+}
+
+impl PrReviewData {
+    #[must_use]
+    pub fn annotation_count(&self, path: &str) -> usize {
+        self.annotations.iter().filter(|a| a.path == path).count()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Author {
     pub login: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PrFile {
     pub path: String,
     pub additions: u64,
     pub deletions: u64,
 }
 
+/// State of one CI check, or combined over all of a PR's checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CiState {
+    Passing,
+    Failing,
+    Pending,
+    // No checks reported
+    None,
+}
+
+impl CiState {
+    /// Outcome of one check run or commit status from a `statusCheckRollup` entry.
+    fn of_check(check: &Value) -> Self {
+        // Check runs report `conclusion` once completed; commit statuses report `state`
+        let outcome = check["conclusion"]
+            .as_str()
+            .filter(|c| !c.is_empty())
+            .or_else(|| check["state"].as_str())
+            .unwrap_or_default();
+        match outcome {
+            "SUCCESS" | "NEUTRAL" | "SKIPPED" => Self::Passing,
+            "FAILURE" | "ERROR" | "TIMED_OUT" | "CANCELLED" | "ACTION_REQUIRED"
+            | "STARTUP_FAILURE" => Self::Failing,
+            _ => Self::Pending,
+        }
+    }
+
+    /// Failing if any check failed, else pending if any is still running.
+    #[must_use]
+    pub fn of_rollup(checks: &[Value]) -> Self {
+        let states: Vec<Self> = checks.iter().map(Self::of_check).collect();
+        if states.is_empty() {
+            Self::None
+        } else if states.contains(&Self::Failing) {
+            Self::Failing
+        } else if states.contains(&Self::Pending) {
+            Self::Pending
+        } else {
+            Self::Passing
+        }
+    }
+
+    /// Sort key putting what needs attention first.
+    #[must_use]
+    pub const fn rank(self) -> u8 {
+        match self {
+            Self::Failing => 0,
+            Self::Pending => 1,
+            Self::Passing => 2,
+            Self::None => 3,
+        }
+    }
+
+    #[must_use]
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Self::Passing => "✔",
+            Self::Failing => "✘",
+            Self::Pending => "●",
+            Self::None => "-",
+        }
+    }
+}
+
+/// One open PR as listed by `gh pr list`.
+#[derive(Debug, Deserialize, Clone)]
+pub struct PrSummary {
+    pub number: u64,
+    pub title: String,
+    pub author: Author,
+    pub url: String,
+    #[serde(rename = "statusCheckRollup", default)]
+    pub checks: Vec<Value>,
+    // Only reported by `gh search prs`, which spans repositories
+    #[serde(default)]
+    pub repository: Option<RepoName>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RepoName {
+    #[serde(rename = "nameWithOwner")]
+    pub name_with_owner: String,
+}
+
+impl PrSummary {
+    #[must_use]
+    pub fn ci_state(&self) -> CiState {
+        CiState::of_rollup(&self.checks)
+    }
+}
+
+/// `owner/repo` of a GitHub repository URL that names no PR, e.g. `https://github.com/o/r`.
+#[must_use]
+pub fn repo_url_slug(input: &str) -> Option<String> {
+    let rest = input
+        .trim()
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .strip_prefix("github.com/")?;
+    let rest = rest.trim_end_matches('/').trim_end_matches(".git");
+    let (owner, repo) = rest.split_once('/')?;
+    (!owner.is_empty() && !repo.is_empty() && !repo.contains('/'))
+        .then(|| format!("{owner}/{repo}"))
+}
+
+/// `owner/repo` of a GitHub remote URL, over HTTPS or SSH.
+#[must_use]
+pub fn remote_slug(url: &str) -> Option<String> {
+    let url = url.trim();
+    let url = url
+        .strip_prefix("git@github.com:")
+        .or_else(|| url.strip_prefix("ssh://git@github.com/"))
+        .map_or_else(|| url.to_string(), |rest| format!("github.com/{rest}"));
+    repo_url_slug(&url)
+}
+
+/// A pull request reference as typed on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrRef {
+    /// Full PR URL, e.g. `https://github.com/owner/repo/pull/123`
+    Url(String),
+    /// PR number in the current repository, e.g. `123`
+    Number(u64),
+    /// PR number in another repository, e.g. `owner/repo#123`
+    InRepo { repo: String, number: u64 },
+    /// PR whose head is the given branch, e.g. `feature/login`
+    Branch(String),
+}
+
+impl PrRef {
+    /// Parses an explicit PR reference (URL, number, or `owner/repo#N`).
+    ///
+    /// Branch names are ambiguous with local paths, so they are never produced here;
+    /// callers fall back to [`PrRef::Branch`] themselves.
+    #[must_use]
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if input.starts_with("http") || input.contains("github.com") || input.contains("/pull/") {
+            return Some(Self::Url(input.to_string()));
+        }
+        if let Ok(number) = input.trim_start_matches('#').parse::<u64>() {
+            return Some(Self::Number(number));
+        }
+        if let Some((repo, number)) = input.split_once('#')
+            && let Ok(number) = number.parse::<u64>()
+            && repo.split('/').count() == 2
+            && repo.split('/').all(|part| !part.is_empty())
+        {
+            return Some(Self::InRepo {
+                repo: repo.to_string(),
+                number,
+            });
+        }
+        None
+    }
+
+    /// `gh api graphql` variables locating this PR. A bare number relies on `gh` filling in
+    /// `{owner}` and `{repo}` from the current repository; branch names locate nothing.
+    // `{owner}`/`{repo}` are `gh` placeholders, not format arguments
+    #[allow(clippy::literal_string_with_formatting_args)]
+    fn graphql_args(&self) -> Option<Vec<String>> {
+        let (flag, owner, name, number) = match self {
+            Self::Url(url) => {
+                let (repo, rest) = url.split_once("/pull/")?;
+                let mut parts = repo.trim_end_matches('/').rsplit('/');
+                let name = parts.next()?.to_string();
+                let owner = parts.next()?.to_string();
+                let number = rest.split(['/', '#', '?']).next()?.parse().ok()?;
+                ("-f", owner, name, number)
+            }
+            Self::InRepo { repo, number } => {
+                let (owner, name) = repo.split_once('/')?;
+                ("-f", owner.to_string(), name.to_string(), *number)
+            }
+            Self::Number(number) => ("-F", "{owner}".to_string(), "{repo}".to_string(), *number),
+            Self::Branch(_) => return None,
+        };
+        Some(vec![
+            flag.into(),
+            format!("owner={owner}"),
+            flag.into(),
+            format!("name={name}"),
+            "-F".into(),
+            format!("number={number}"),
+        ])
+    }
+
+    /// The PR's URL as GitHub reports it, worked out without asking GitHub. Bare numbers need
+    /// the `owner/repo` slug of the repository they are in; branches cannot be resolved.
+    #[must_use]
+    pub fn offline_url(&self, slug: Option<&str>) -> Option<String> {
+        let (repo, number) = match self {
+            Self::Url(url) => {
+                let (repo, rest) = url.split_once("/pull/")?;
+                let number: u64 = rest.split(['/', '#', '?']).next()?.parse().ok()?;
+                return Some(format!("{}/pull/{number}", repo.trim_end_matches('/')));
+            }
+            Self::InRepo { repo, number } => (repo.as_str(), *number),
+            Self::Number(number) => (slug?, *number),
+            Self::Branch(_) => return None,
+        };
+        Some(format!("https://github.com/{repo}/pull/{number}"))
+    }
+
+    /// Arguments identifying this PR for `gh pr <subcommand>`.
+    fn gh_args(&self) -> Vec<String> {
+        match self {
+            Self::Url(url) => vec![url.clone()],
+            Self::Number(number) => vec![number.to_string()],
+            Self::InRepo { repo, number } => {
+                vec![number.to_string(), "--repo".to_string(), repo.clone()]
+            }
+            Self::Branch(branch) => vec![branch.clone()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GhErrorKind {
+    NotInstalled,
+    NotAuthenticated,
+    NotFound,
+    RateLimited,
+    // 5xx responses, which usually go away on their own
+    ServerError,
+    // GitHub could not be reached at all: no connection, DNS failure, timeout
+    Network,
+    Other,
+}
+
+impl GhErrorKind {
+    fn classify(stderr: &str) -> Self {
+        let lower = stderr.to_lowercase();
+        if lower.contains("rate limit") {
+            Self::RateLimited
+        } else if [
+            "http 500",
+            "http 502",
+            "http 503",
+            "http 504",
+            "server error",
+        ]
+        .iter()
+        .any(|s| lower.contains(s))
+        {
+            Self::ServerError
+        } else if [
+            "error connecting to",
+            "could not resolve host",
+            "no such host",
+            "dial tcp",
+            "connection refused",
+            "network is unreachable",
+            "i/o timeout",
+            "tls handshake timeout",
+            // `gh auth status` without a connection
+            "timeout trying to log in",
+        ]
+        .iter()
+        .any(|s| lower.contains(s))
+        {
+            Self::Network
+        } else if lower.contains("auth login")
+            || lower.contains("not logged")
+            || lower.contains("401")
+        {
+            Self::NotAuthenticated
+        } else if lower.contains("could not resolve")
+            || lower.contains("no pull requests found")
+            || lower.contains("not found")
+        {
+            Self::NotFound
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// A failed `gh` invocation, kept structured so the UI can show the command and fixes.
+#[derive(Debug, Clone)]
+pub struct GhError {
+    pub command: String,
+    pub stderr: String,
+    pub kind: GhErrorKind,
+}
+
+impl GhError {
+    /// Suggested next steps for the user, most likely first.
+    #[must_use]
+    pub const fn remediation(&self) -> &'static [&'static str] {
+        match self.kind {
+            GhErrorKind::NotInstalled => &[
+                "Install the GitHub CLI: https://cli.github.com",
+                "Make sure `gh` is on your PATH",
+            ],
+            GhErrorKind::NotAuthenticated => &[
+                "Run `gh auth login`",
+                "For GitHub Enterprise, set GH_HOST (e.g. `export GH_HOST=github.example.com`)",
+            ],
+            GhErrorKind::NotFound => &[
+                "Check the PR number, URL, or branch name",
+                "Run glim inside the repository, or use owner/repo#123",
+                "For GitHub Enterprise, set GH_HOST",
+            ],
+            GhErrorKind::RateLimited => &[
+                "Wait for the API quota in the footer to refill, then retry",
+                "Other tools using the same `gh` login share its quota",
+            ],
+            GhErrorKind::ServerError => &[
+                "GitHub is having trouble; retry in a minute",
+                "Check https://www.githubstatus.com",
+            ],
+            GhErrorKind::Network => &[
+                "Check your network connection, VPN or proxy",
+                "PRs opened before can still be reviewed offline from the cache",
+            ],
+            GhErrorKind::Other => &[
+                "Re-run the command above in a shell to see the full output",
+                "Check `gh auth status` and your network connection",
+            ],
+        }
+    }
+}
+
+impl GhError {
+    /// Worth trying again after a pause: a secondary rate limit, which lifts within a minute
+    /// unlike a spent quota, or a server error of a call that changes nothing.
+    fn is_transient(&self, read_only: bool) -> bool {
+        match self.kind {
+            GhErrorKind::RateLimited => self.stderr.to_lowercase().contains("secondary"),
+            GhErrorKind::ServerError => read_only,
+            _ => false,
+        }
+    }
+}
+
+/// `gh` invocations that only read: API calls other than GraphQL mutations and those given a
+/// `--method` (GraphQL queries are sent without one), and the listing subcommands.
+fn is_read_only(args: &[String]) -> bool {
+    let words: Vec<&str> = args.iter().take(2).map(String::as_str).collect();
+    let mutation = |arg: &String| {
+        arg.strip_prefix("query=")
+            .is_some_and(|query| query.trim_start().starts_with("mutation"))
+    };
+    match words.as_slice() {
+        ["api", ..] => !args.iter().any(|arg| arg == "--method" || mutation(arg)),
+        words => matches!(
+            words,
+            ["search" | "auth", ..] | ["pr", "view" | "list" | "diff"]
+        ),
+    }
+}
+
+/// Pause before retry `attempt` (from 0): doubling from [`RETRY_BASE`], plus up to half as
+/// much again taken from `jitter`, so clients that hit a limit together do not retry together.
+fn retry_delay(attempt: u32, jitter: u64) -> Duration {
+    let base = RETRY_BASE * 2u32.pow(attempt);
+    let spread = u64::try_from(base.as_millis() / 2).unwrap_or(u64::MAX);
+    base + Duration::from_millis(jitter % (spread + 1))
+}
+
+/// Cheap randomness for [`retry_delay`], from the clock.
+fn jitter() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| u64::from(d.subsec_nanos()))
+}
+
+impl fmt::Display for GhError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            GhErrorKind::NotInstalled => write!(f, "GitHub CLI (gh) could not be executed"),
+            GhErrorKind::NotAuthenticated => write!(f, "GitHub CLI is not logged in"),
+            GhErrorKind::NotFound => write!(f, "Pull request not found"),
+            GhErrorKind::RateLimited => {
+                write!(f, "GitHub API rate limit exceeded")?;
+                let spent = GitHubClient::rate_limits()
+                    .into_iter()
+                    .find(|limit| limit.remaining == 0);
+                spent.map_or(Ok(()), |limit| write!(f, " ({})", limit.summary()))
+            }
+            GhErrorKind::ServerError => write!(f, "GitHub failed to answer `{}`", self.command),
+            GhErrorKind::Network => write!(f, "GitHub could not be reached: {}", self.stderr),
+            GhErrorKind::Other => write!(f, "`{}` failed: {}", self.command, self.stderr),
+        }
+    }
+}
+
+impl std::error::Error for GhError {}
+
 #[derive(Debug)]
 pub struct GitHubClient;
 
 impl GitHubClient {
+    /// Caps retries at one short pause from now on, for calls the UI waits on.
+    pub fn set_interactive() {
+        INTERACTIVE.store(true, Ordering::Relaxed);
+    }
+
+    /// Time spent waiting to retry calls since this was last asked, if any.
+    #[must_use]
+    pub fn take_retry_wait() -> Option<Duration> {
+        let mut waited = RETRY_WAIT.lock().ok()?;
+        Some(std::mem::take(&mut *waited)).filter(|waited| !waited.is_zero())
+    }
+
+    /// Runs `gh` with `args`, returning stdout or a [`GhError`] describing the failure.
+    fn run(args: &[String]) -> Result<Vec<u8>, GhError> {
+        Self::run_in(args, None)
+    }
+
+    /// [`Self::run`] in `dir` instead of the current directory.
+    ///
+    /// Secondary rate limits are waited out, with growing pauses; so are server errors, but
+    /// only for calls that change nothing, since a failed edit may still have gone through.
+    /// Once [`Self::set_interactive`] was called there is one short retry at most.
+    fn run_in(args: &[String], dir: Option<&Path>) -> Result<Vec<u8>, GhError> {
+        let interactive = INTERACTIVE.load(Ordering::Relaxed);
+        let retries = if interactive { 1 } else { MAX_RETRIES };
+        let mut attempt = 0;
+        loop {
+            match Self::run_once(args, dir) {
+                Err(e) if attempt < retries && e.is_transient(is_read_only(args)) => {
+                    let delay = if interactive {
+                        INTERACTIVE_RETRY
+                    } else {
+                        retry_delay(attempt, jitter())
+                    };
+                    if let Ok(mut waited) = RETRY_WAIT.lock() {
+                        *waited += delay;
+                    }
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn run_once(args: &[String], dir: Option<&Path>) -> Result<Vec<u8>, GhError> {
+        let command = format!("gh {}", args.join(" "));
+        let mut gh = Command::new("gh");
+        if let Some(dir) = dir {
+            gh.current_dir(dir);
+        }
+        let output = gh.args(args).output().map_err(|e| GhError {
+            command: command.clone(),
+            stderr: e.to_string(),
+            kind: GhErrorKind::NotInstalled,
+        })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(GhError {
+                kind: GhErrorKind::classify(&stderr),
+                command,
+                stderr,
+            });
+        }
+        Ok(output.stdout)
+    }
+
+    /// Runs `gh api --include` with `args`, recording the rate-limit headers and returning
+    /// only the body.
+    fn api(args: &[String]) -> Result<Vec<u8>, GhError> {
+        let mut full = vec!["api".to_string(), "--include".to_string()];
+        full.extend_from_slice(args);
+        let stdout = Self::run(&full)?;
+        let (headers, body) = split_response(&stdout);
+        if let Some(limit) = RateLimit::from_headers(&headers)
+            && let Ok(mut limits) = RATE_LIMITS.lock()
+        {
+            limits.insert(limit.resource.clone(), limit);
+        }
+        Ok(body.to_vec())
+    }
+
     /// Check if `gh` CLI is available and logged in
     ///
     /// # Errors
     /// Returns error if `gh` command is missing or not logged in.
     pub fn check_auth() -> Result<()> {
-        let status = Command::new("gh")
-            .arg("auth")
-            .arg("status")
-            .output()
-            .context("Failed to execute 'gh' command. Is GitHub CLI installed?")?;
-
-        if !status.status.success() {
-            anyhow::bail!("GitHub CLI is not logged in. Please run 'gh auth login'.");
-        }
+        Self::run(&["auth".into(), "status".into()]).map_err(|mut e| {
+            if matches!(e.kind, GhErrorKind::NotFound | GhErrorKind::Other) {
+                e.kind = GhErrorKind::NotAuthenticated;
+            }
+            e
+        })?;
         Ok(())
     }
 
-    /// Fetch PR metadata using `gh pr view`
+    /// Fetch PR metadata together with its review threads, viewed state, and CI checks in one
+    /// `gh api graphql` query. Branch names are first resolved to a PR with `gh pr view`.
     ///
     /// # Errors
     /// Returns error if `gh` command fails or JSON parsing fails.
-    pub fn fetch_pr_info(pr_ref: &str) -> Result<PrInfo> {
-        // First get general info
-        let output = Command::new("gh")
-            .arg("pr")
-            .arg("view")
-            .arg(pr_ref)
-            .arg("--json")
-            .arg("number,title,body,state,author,url,changedFiles,additions,deletions,files")
-            .output()
-            .context("Failed to fetch PR info")?;
+    pub fn fetch_pr(pr_ref: &PrRef) -> Result<(PrInfo, PrReviewData)> {
+        let mut json = Self::graphql(PR_QUERY, pr_ref, &[])?;
+        Self::fetch_remaining_pages(&mut json)?;
+        Ok((Self::parse_pr_info(&json)?, Self::parse_review_data(&json)))
+    }
 
-        if !output.status.success() {
-            let err = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("gh command failed: {err}");
+    /// Appends the pages after the first of the PR's files, review threads, thread comments
+    /// and CI checks to the PR in `json`.
+    fn fetch_remaining_pages(json: &mut Value) -> Result<()> {
+        let pr = &mut json["data"]["repository"]["pullRequest"];
+        let url = PrRef::Url(pr["url"].as_str().context("PR has no URL")?.to_string());
+        let on_pr = |query: &str, cursor: &str| {
+            let vars = ["-f".to_string(), format!("cursor={cursor}")];
+            Self::graphql(query, &url, &vars)
+                .map(|mut page| page["data"]["repository"]["pullRequest"].take())
+        };
+        append_pages(&mut pr["files"], |cursor| {
+            Ok(on_pr(FILES_QUERY, cursor)?["files"].take())
+        })?;
+        append_pages(&mut pr["reviewThreads"], |cursor| {
+            Ok(on_pr(THREADS_QUERY, cursor)?["reviewThreads"].take())
+        })?;
+        for thread in pr["reviewThreads"]["nodes"]
+            .as_array_mut()
+            .into_iter()
+            .flatten()
+        {
+            let id = thread["id"].as_str().unwrap_or_default().to_string();
+            append_pages(&mut thread["comments"], |cursor| {
+                let args = [
+                    "graphql".to_string(),
+                    "-f".to_string(),
+                    format!("query={THREAD_COMMENTS_QUERY}"),
+                    "-f".to_string(),
+                    format!("id={id}"),
+                    "-f".to_string(),
+                    format!("cursor={cursor}"),
+                ];
+                let mut page: Value = serde_json::from_slice(&Self::api(&args)?)
+                    .context("Failed to parse PR JSON")?;
+                Ok(page["data"]["node"]["comments"].take())
+            })?;
         }
+        for commit in pr["commits"]["nodes"].as_array_mut().into_iter().flatten() {
+            append_pages(
+                &mut commit["commit"]["statusCheckRollup"]["contexts"],
+                |cursor| {
+                    let mut page = on_pr(CONTEXTS_QUERY, cursor)?;
+                    Ok(
+                        page["commits"]["nodes"][0]["commit"]["statusCheckRollup"]["contexts"]
+                            .take(),
+                    )
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    /// URL and head commit of the PR, a query far cheaper than [`Self::fetch_pr`].
+    ///
+    /// # Errors
+    /// Returns error if `gh` command fails or the PR does not exist.
+    pub fn fetch_head(pr_ref: &PrRef) -> Result<PrHead> {
+        let json = Self::graphql(HEAD_QUERY, pr_ref, &[])?;
+        let pr = &json["data"]["repository"]["pullRequest"];
+        Ok(PrHead {
+            url: pr["url"].as_str().context("PR has no URL")?.to_string(),
+            oid: pr["headRefOid"].as_str().unwrap_or_default().to_string(),
+            is_draft: pr["isDraft"] == true,
+            labels: Self::parse_labels(pr),
+        })
+    }
+
+    /// Runs a GraphQL `query` taking the PR's `owner`, `name` and `number`, plus `extra`
+    /// variables.
+    fn graphql(query: &str, pr_ref: &PrRef, extra: &[String]) -> Result<Value> {
+        let vars = match pr_ref.graphql_args() {
+            Some(vars) => vars,
+            None => PrRef::Url(Self::fetch_pr_url(pr_ref)?)
+                .graphql_args()
+                .context("Cannot determine repository from PR URL")?,
+        };
+        let mut args = vec![
+            "graphql".to_string(),
+            "-f".to_string(),
+            format!("query={query}"),
+        ];
+        args.extend(vars);
+        args.extend_from_slice(extra);
+        let stdout = Self::api(&args)?;
+        serde_json::from_slice(&stdout).context("Failed to parse PR JSON")
+    }
+
+    /// Quota left per API resource, as of the last `gh api` call.
+    #[must_use]
+    pub fn rate_limits() -> Vec<RateLimit> {
+        RATE_LIMITS
+            .lock()
+            .map(|limits| limits.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// URL of the PR `pr_ref` names, via `gh pr view`.
+    fn fetch_pr_url(pr_ref: &PrRef) -> Result<String> {
+        let mut args = vec!["pr".to_string(), "view".to_string()];
+        args.extend(pr_ref.gh_args());
+        args.extend(["--json".to_string(), "url".to_string()]);
+        let stdout = Self::run(&args)?;
+        let json: Value = serde_json::from_slice(&stdout).context("Failed to parse PR JSON")?;
+        json["url"]
+            .as_str()
+            .map(String::from)
+            .context("PR has no URL")
+    }
 
-        let info: PrInfo =
-            serde_json::from_slice(&output.stdout).context("Failed to parse PR JSON")?;
+    /// Lists open PRs of `repo` (`owner/repo`), or of the current directory's repository.
+    ///
+    /// # Errors
+    /// Returns error if `gh` command fails or JSON parsing fails.
+    pub fn list_prs(repo: Option<&str>) -> Result<Vec<PrSummary>> {
+        let mut args: Vec<String> = ["pr", "list", "--state", "open", "--limit", "100"]
+            .map(String::from)
+            .to_vec();
+        if let Some(repo) = repo {
+            args.push("--repo".into());
+            args.push(repo.to_string());
+        }
+        args.push("--json".into());
+        args.push("number,title,author,url,statusCheckRollup".into());
+        let stdout = Self::run(&args)?;
+        serde_json::from_slice(&stdout).context("Failed to parse PR list JSON")
+    }
 
-        Ok(info)
+    /// Open PRs requesting a review from the logged-in user, oldest first, in `repos`
+    /// (`owner/repo`) or anywhere when empty.
+    ///
+    /// # Errors
+    /// Returns error if `gh` command fails or JSON parsing fails.
+    pub fn review_requests(repos: &[String]) -> Result<Vec<PrSummary>> {
+        let mut args: Vec<String> = [
+            "search",
+            "prs",
+            "--review-requested",
+            "@me",
+            "--state",
+            "open",
+            "--sort",
+            "created",
+            "--order",
+            "asc",
+            "--limit",
+            "100",
+        ]
+        .map(String::from)
+        .to_vec();
+        for repo in repos {
+            args.push("--repo".into());
+            args.push(repo.clone());
+        }
+        args.push("--json".into());
+        args.push("number,title,author,url,repository".into());
+        let stdout = Self::run(&args)?;
+        serde_json::from_slice(&stdout).context("Failed to parse PR search JSON")
     }
 
     /// Fetch PR diff content using `gh pr diff`
     ///
     /// # Errors
     /// Returns error if `gh` command fails.
-    pub fn fetch_pr_diff(pr_ref: &str) -> Result<String> {
+    pub fn fetch_pr_diff(info: &PrInfo) -> Result<String> {
+        let stdout = Self::run(&["pr".into(), "diff".into(), info.url.clone()])?;
+
+        Ok(String::from_utf8_lossy(&stdout).to_string())
+    }
+
+    /// Per-file diff lines for page `page` (from 1) of the PR's file list, for PRs too large
+    /// for [`Self::fetch_pr_diff`]. Files GitHub sends no patch for map to a note saying so.
+    ///
+    /// # Errors
+    /// Returns error if the PR URL cannot be parsed, `gh` fails, or the response is malformed.
+    pub fn fetch_file_patches(info: &PrInfo, page: usize) -> Result<HashMap<String, Vec<String>>> {
+        let slug = info
+            .repo_slug()
+            .context("Cannot determine repository from PR URL")?;
+        let stdout = Self::api(&[format!(
+            "repos/{slug}/pulls/{}/files?per_page={FILES_PER_PAGE}&page={page}",
+            info.number
+        )])?;
+        let json: Value =
+            serde_json::from_slice(&stdout).context("Failed to parse PR files JSON")?;
+        Ok(parse_file_patches(&json))
+    }
+
+    /// Page of the REST file listing `path` is most likely on, going by its place in `info`.
+    #[must_use]
+    pub fn file_page(info: &PrInfo, path: &str) -> usize {
+        info.files
+            .iter()
+            .position(|f| f.path == path)
+            .map_or(1, |i| i / FILES_PER_PAGE + 1)
+    }
+
+    /// Pages in the REST file listing of `info`.
+    #[must_use]
+    pub const fn file_pages(info: &PrInfo) -> usize {
+        info.files.len().div_ceil(FILES_PER_PAGE)
+    }
+
+    /// Fetches a commit of `repo` (`owner/repo`) and its diff against the first parent.
+    ///
+    /// # Errors
+    /// Returns error if `gh` fails, e.g. for an unknown SHA, or the response is malformed.
+    pub fn fetch_commit(repo: &str, sha: &str) -> Result<(CommitInfo, String)> {
+        let endpoint = format!("repos/{repo}/commits/{sha}");
+        let stdout = Self::api(std::slice::from_ref(&endpoint))?;
+        let json: Value = serde_json::from_slice(&stdout).context("Failed to parse commit JSON")?;
+        let diff = Self::api(&[
+            "-H".into(),
+            "Accept: application/vnd.github.diff".into(),
+            endpoint,
+        ])?;
+        Ok((
+            CommitInfo::from_github(&json, repo),
+            String::from_utf8_lossy(&diff).to_string(),
+        ))
+    }
+
+    /// Fetches a comparison of two refs of `repo` (`base...head`) and its aggregated diff.
+    ///
+    /// # Errors
+    /// Returns error if `gh` fails, e.g. for an unknown ref, or the response is malformed.
+    pub fn fetch_compare(repo: &str, range: &str) -> Result<(CommitInfo, String)> {
+        let endpoint = format!("repos/{repo}/compare/{range}");
+        let stdout = Self::api(std::slice::from_ref(&endpoint))?;
+        let json: Value =
+            serde_json::from_slice(&stdout).context("Failed to parse compare JSON")?;
+        let diff = Self::api(&[
+            "-H".into(),
+            "Accept: application/vnd.github.diff".into(),
+            endpoint,
+        ])?;
+        Ok((
+            CommitInfo::from_github_compare(&json, repo, range),
+            String::from_utf8_lossy(&diff).to_string(),
+        ))
+    }
+
+    /// Submits a review of the PR with `gh pr review`.
+    ///
+    /// # Errors
+    /// Returns error if `gh` fails, e.g. when approving one's own PR.
+    pub fn submit_review(info: &PrInfo, event: ReviewEvent, body: &str) -> Result<()> {
+        let mut args = vec![
+            "pr".to_string(),
+            "review".to_string(),
+            info.url.clone(),
+            event.flag().to_string(),
+        ];
+        if !body.is_empty() {
+            args.push("--body".into());
+            args.push(body.to_string());
+        }
+        Self::run(&args)?;
+        Ok(())
+    }
+
+    /// Posts `body` as a review comment on `line` of `path` at the PR's head commit, on the
+    /// old side of the diff when `left`, and returns the comment as GitHub stored it.
+    ///
+    /// # Errors
+    /// Returns error if the PR URL cannot be parsed, `gh` fails (e.g. for a line outside the
+    /// diff), or the response is malformed.
+    pub fn post_line_comment(
+        info: &PrInfo,
+        path: &str,
+        line: usize,
+        left: bool,
+        body: &str,
+    ) -> Result<ReviewComment> {
+        let slug = info
+            .repo_slug()
+            .context("Cannot determine repository from PR URL")?;
+        let field = |name: &str, value: &str| ["-f".to_string(), format!("{name}={value}")];
+        let mut args = vec![
+            "--method".to_string(),
+            "POST".to_string(),
+            format!("repos/{slug}/pulls/{}/comments", info.number),
+            "-F".to_string(),
+            format!("line={line}"),
+        ];
+        args.extend(field("body", body));
+        args.extend(field("commit_id", &info.head_ref_oid));
+        args.extend(field("path", path));
+        args.extend(field("side", if left { "LEFT" } else { "RIGHT" }));
+        let stdout = Self::api(&args)?;
+        let json: Value =
+            serde_json::from_slice(&stdout).context("Failed to parse comment JSON")?;
+        Ok(ReviewComment::from_rest(&json))
+    }
+
+    /// Ticks (or with `viewed` false, clears) the "Viewed" box of `path` on the PR for the
+    /// signed-in user.
+    ///
+    /// # Errors
+    /// Returns error if the PR's node ID is unknown (PR data cached by an older glim) or `gh`
+    /// fails.
+    pub fn set_viewed(info: &PrInfo, path: &str, viewed: bool) -> Result<()> {
+        if info.node_id.is_empty() {
+            bail!("PR data predates viewed-state sync; reload with --refresh");
+        }
+        let mutation = if viewed { MARK_VIEWED } else { UNMARK_VIEWED };
+        Self::api(&[
+            "graphql".to_string(),
+            "-f".to_string(),
+            format!("query={mutation}"),
+            "-f".to_string(),
+            format!("id={}", info.node_id),
+            "-f".to_string(),
+            format!("path={path}"),
+        ])?;
+        Ok(())
+    }
+
+    /// People who can be asked for a review or assigned: the members of the organization
+    /// owning the PR's repository, or the repository's assignable users when a person owns
+    /// it. Sorted case-insensitively.
+    ///
+    /// # Errors
+    /// Returns error if the PR URL cannot be parsed or `gh` fails.
+    pub fn fetch_people(info: &PrInfo) -> Result<Vec<String>> {
+        let slug = info
+            .repo_slug()
+            .context("Cannot determine repository from PR URL")?;
+        let owner = slug.split('/').next().unwrap_or(slug);
+        let list = |endpoint: String| {
+            Self::run(&[
+                "api".into(),
+                "--paginate".into(),
+                endpoint,
+                "--jq".into(),
+                ".[].login".into(),
+            ])
+        };
+        let stdout = list(format!("orgs/{owner}/members?per_page=100"))
+            .or_else(|_| list(format!("repos/{slug}/assignees?per_page=100")))?;
+        let mut people: Vec<String> = String::from_utf8_lossy(&stdout)
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(String::from)
+            .collect();
+        people.sort_by_key(|p| p.to_lowercase());
+        Ok(people)
+    }
+
+    /// Requests a review from `login` or assigns them, via `gh pr edit`.
+    ///
+    /// # Errors
+    /// Returns error if `gh` fails, e.g. for the PR's author or someone without access.
+    pub fn add_person(info: &PrInfo, role: PeopleRole, login: &str) -> Result<()> {
+        Self::run(&[
+            "pr".into(),
+            "edit".into(),
+            info.url.clone(),
+            role.flag().into(),
+            login.to_string(),
+        ])?;
+        Ok(())
+    }
+
+    /// Every label of the PR's repository, sorted case-insensitively.
+    ///
+    /// # Errors
+    /// Returns error if the PR URL cannot be parsed, `gh` fails, or the response is malformed.
+    pub fn fetch_labels(info: &PrInfo) -> Result<Vec<Label>> {
+        let slug = info
+            .repo_slug()
+            .context("Cannot determine repository from PR URL")?;
+        let stdout = Self::run(&[
+            "api".into(),
+            "--paginate".into(),
+            format!("repos/{slug}/labels?per_page=100"),
+            "--jq".into(),
+            ".[] | {name, color}".into(),
+        ])?;
+        let mut labels = serde_json::Deserializer::from_slice(&stdout)
+            .into_iter::<Label>()
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to parse labels JSON")?;
+        labels.sort_by_key(|l| l.name.to_lowercase());
+        Ok(labels)
+    }
+
+    /// Puts label `name` on the PR or takes it off, via `gh pr edit`.
+    ///
+    /// # Errors
+    /// Returns error if `gh` fails, e.g. without triage access to the repository.
+    pub fn set_label(info: &PrInfo, name: &str, on: bool) -> Result<()> {
+        let flag = if on { "--add-label" } else { "--remove-label" };
+        Self::run(&[
+            "pr".into(),
+            "edit".into(),
+            info.url.clone(),
+            flag.into(),
+            name.to_string(),
+        ])?;
+        Ok(())
+    }
+
+    /// Converts the PR to a draft, or marks it ready for review, via `gh pr ready`.
+    ///
+    /// # Errors
+    /// Returns error if `gh` fails, e.g. without write access to the repository.
+    pub fn set_draft(info: &PrInfo, draft: bool) -> Result<()> {
+        let mut args = vec!["pr".to_string(), "ready".into(), info.url.clone()];
+        if draft {
+            args.push("--undo".into());
+        }
+        Self::run(&args)?;
+        Ok(())
+    }
+
+    /// Fetches the PR's head and checks it out in the clone at `dir`, via `gh pr checkout`.
+    ///
+    /// # Errors
+    /// Returns error if `gh` fails, e.g. when local changes would be overwritten.
+    pub fn checkout_pr(info: &PrInfo, dir: &Path) -> Result<()> {
+        // The fetch must not stop to ask for credentials on the terminal the TUI owns
         let output = Command::new("gh")
-            .arg("pr")
-            .arg("diff")
-            .arg(pr_ref)
+            .args(["pr", "checkout", &info.url])
+            .current_dir(dir)
+            .stdin(Stdio::null())
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .env("GH_PROMPT_DISABLED", "1")
             .output()
-            .context("Failed to fetch PR diff")?;
-
+            .context("GitHub CLI (gh) could not be executed")?;
         if !output.status.success() {
-            anyhow::bail!("Failed to get diff");
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("`gh pr checkout` failed: {}", stderr.trim());
         }
+        Ok(())
+    }
+
+    /// Compares the PR's base commit with the tip of its base branch via `gh api`.
+    ///
+    /// Returns `None` when the base is current or `gh` did not report the base commit.
+    ///
+    /// # Errors
+    /// Returns error if the PR URL cannot be parsed, `gh` fails, or the response is malformed.
+    pub fn fetch_base_drift(info: &PrInfo) -> Result<Option<BaseDrift>> {
+        if info.base_ref_oid.is_empty() || info.base_ref_name.is_empty() {
+            return Ok(None);
+        }
+        let slug = info
+            .repo_slug()
+            .context("Cannot determine repository from PR URL")?;
+        let stdout = Self::api(&[format!(
+            "repos/{slug}/compare/{}...{}",
+            info.base_ref_oid, info.base_ref_name
+        )])?;
+        let json: Value =
+            serde_json::from_slice(&stdout).context("Failed to parse compare JSON")?;
+        Ok(Self::parse_base_drift(&json, info))
+    }
+
+    fn parse_base_drift(json: &Value, info: &PrInfo) -> Option<BaseDrift> {
+        let behind = json["ahead_by"].as_u64().filter(|&n| n > 0)?;
+        let files = json["files"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|file| {
+                let path = file["filename"].as_str()?.to_string();
+                Some((path, file["patch"].as_str().map(str::to_string)))
+            })
+            .collect();
+        Some(BaseDrift {
+            branch: info.base_ref_name.clone(),
+            base_oid: info.base_ref_oid.clone(),
+            behind,
+            files,
+        })
+    }
+
+    fn parse_pr_info(json: &Value) -> Result<PrInfo> {
+        let mut pr = json["data"]["repository"]["pullRequest"].clone();
+        // `files` is a connection here but a plain list in `gh pr view --json`
+        pr["files"] = pr["files"]["nodes"].take();
+        // Deleted accounts come back as `null`; `gh` shows them as `ghost`
+        if pr["author"].is_null() {
+            pr["author"] = serde_json::json!({ "login": "ghost" });
+        }
+        serde_json::from_value(pr).context("Failed to parse PR JSON")
+    }
+
+    /// Labels of the PR `pr`, from a `labels { nodes { name color } }` connection.
+    fn parse_labels(pr: &Value) -> Vec<Label> {
+        serde_json::from_value(pr["labels"]["nodes"].clone()).unwrap_or_default()
+    }
+
+    fn parse_review_data(json: &Value) -> PrReviewData {
+        let pr = &json["data"]["repository"]["pullRequest"];
+        let nodes = |v: &Value| v["nodes"].as_array().cloned().unwrap_or_default();
+        let mut data = PrReviewData::default();
+
+        for file in nodes(&pr["files"]) {
+            if file["viewerViewedState"] == "VIEWED"
+                && let Some(path) = file["path"].as_str()
+            {
+                data.viewed.insert(path.to_string());
+            }
+        }
+
+        for request in nodes(&pr["reviewRequests"]) {
+            let reviewer = &request["requestedReviewer"];
+            if let Some(name) = reviewer["login"]
+                .as_str()
+                .or_else(|| reviewer["combinedSlug"].as_str())
+            {
+                data.reviewers.push(name.to_string());
+            }
+        }
+        data.assignees = nodes(&pr["assignees"])
+            .iter()
+            .filter_map(|a| a["login"].as_str().map(String::from))
+            .collect();
+        data.labels = Self::parse_labels(pr);
+
+        for thread in nodes(&pr["reviewThreads"]) {
+            let Some(path) = thread["path"].as_str() else {
+                continue;
+            };
+            let resolved = thread["isResolved"] == true;
+            if !resolved {
+                *data.unresolved_threads.entry(path.to_string()).or_insert(0) += 1;
+            }
+            if let Some(line) = thread["line"].as_u64() {
+                data.threads.push(ReviewThread {
+                    path: path.to_string(),
+                    line: line as usize,
+                    start_line: thread["startLine"].as_u64().map(|l| l as usize),
+                    left: thread["diffSide"] == "LEFT",
+                    resolved,
+                    comments: nodes(&thread["comments"])
+                        .iter()
+                        .map(|c| ReviewComment {
+                            author: c["author"]["login"].as_str().unwrap_or("ghost").to_string(),
+                            body: c["body"].as_str().unwrap_or_default().to_string(),
+                            created_at: c["createdAt"].as_str().unwrap_or_default().to_string(),
+                        })
+                        .collect(),
+                });
+            }
+        }
+
+        for commit in nodes(&pr["commits"]) {
+            for check in nodes(&commit["commit"]["statusCheckRollup"]["contexts"]) {
+                let name = check["name"].as_str().or_else(|| check["context"].as_str());
+                let url = check["detailsUrl"]
+                    .as_str()
+                    .or_else(|| check["targetUrl"].as_str())
+                    .filter(|u| !u.is_empty());
+                if let Some(name) = name {
+                    data.checks.push(CheckStatus {
+                        name: name.to_string(),
+                        state: CiState::of_check(&check),
+                        url: url.map(str::to_string),
+                    });
+                }
+            }
+            for suite in nodes(&commit["commit"]["checkSuites"]) {
+                for run in nodes(&suite["checkRuns"]) {
+                    let check_name = run["name"].as_str().unwrap_or_default();
+                    for ann in nodes(&run["annotations"]) {
+                        let Some(path) = ann["path"].as_str() else {
+                            continue;
+                        };
+                        data.annotations.push(CheckAnnotation {
+                            path: path.to_string(),
+                            line: ann["location"]["start"]["line"].as_u64().unwrap_or(0) as usize,
+                            level: ann["annotationLevel"]
+                                .as_str()
+                                .unwrap_or("NOTICE")
+                                .to_string(),
+                            title: ann["title"].as_str().unwrap_or_default().to_string(),
+                            message: ann["message"].as_str().unwrap_or_default().to_string(),
+                            check_name: check_name.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        data.checks.sort_by_key(|c| c.state.rank());
+        data
+    }
+}
+
+/// Appends the later pages of a GraphQL connection (`nodes` plus `pageInfo`) to it, each
+/// fetched by `next` from the cursor the previous page ended at.
+fn append_pages(connection: &mut Value, mut next: impl FnMut(&str) -> Result<Value>) -> Result<()> {
+    while connection["pageInfo"]["hasNextPage"] == true {
+        let cursor = connection["pageInfo"]["endCursor"]
+            .as_str()
+            .context("PR list has no cursor")?
+            .to_string();
+        let mut page = next(&cursor)?;
+        if let (Some(all), Some(more)) = (
+            connection["nodes"].as_array_mut(),
+            page["nodes"].as_array_mut(),
+        ) {
+            all.append(more);
+        }
+        // A page without `pageInfo` (an error, say) ends the walk
+        connection["pageInfo"] = page["pageInfo"].take();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CiState, GhError, GhErrorKind, GitHubClient, MARK_VIEWED, PrInfo, PrRef, RateLimit,
+        append_pages, diff_too_large, is_read_only, parse_file_patches, remote_slug, repo_url_slug,
+        retry_delay, split_response,
+    };
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[test]
+    fn reports_base_drift() {
+        let info: PrInfo = serde_json::from_value(json!({
+            "number": 7, "title": "t", "body": "", "state": "OPEN",
+            "author": { "login": "a" }, "url": "https://github.com/o/r/pull/7",
+            "changedFiles": 1, "additions": 1, "deletions": 0,
+            "files": [{ "path": "src/lib.rs", "additions": 1, "deletions": 0 }],
+            "baseRefName": "main", "baseRefOid": "0123456789abcdef",
+        }))
+        .expect("PR JSON");
+        let compare = json!({
+            "ahead_by": 57,
+            "files": [
+                { "filename": "src/lib.rs", "patch": "@@ -1 +1 @@\n-a\n+b" },
+                { "filename": "README.md" },
+            ],
+        });
+        let drift = GitHubClient::parse_base_drift(&compare, &info).expect("drift");
+        assert_eq!(
+            drift.warning(&info),
+            "base is 57 commits behind main, 1 changed file(s) also changed there"
+        );
+        assert!(
+            drift
+                .lines(&info, "src/lib.rs")
+                .contains(&" @@ -1 +1 @@".to_string())
+        );
+        assert!(GitHubClient::parse_base_drift(&json!({ "ahead_by": 0 }), &info).is_none());
+    }
+
+    #[test]
+    fn keeps_current_review_threads() {
+        let json = json!({ "data": { "repository": { "pullRequest": {
+            "reviewThreads": { "nodes": [
+                { "isResolved": false, "path": "src/lib.rs", "line": 12, "startLine": 10,
+                  "diffSide": "RIGHT",
+                  "comments": { "nodes": [{ "author": { "login": "ana" }, "body": "Why?",
+                                            "createdAt": "2024-05-01T14:03:27Z" }] } },
+                { "isResolved": true, "path": "src/lib.rs", "line": null, "diffSide": "LEFT",
+                  "comments": { "nodes": [] } },
+            ] },
+            "reviewRequests": { "nodes": [
+                { "requestedReviewer": { "login": "bo" } },
+                { "requestedReviewer": { "combinedSlug": "o/core" } },
+            ] },
+            "assignees": { "nodes": [{ "login": "ana" }] },
+            "labels": { "nodes": [{ "name": "bug", "color": "d73a4a" }] },
+        } } } });
+        let data = GitHubClient::parse_review_data(&json);
+        assert_eq!(
+            (data.reviewers, data.assignees),
+            (
+                vec!["bo".to_string(), "o/core".to_string()],
+                vec!["ana".to_string()]
+            )
+        );
+        assert_eq!(
+            (data.labels[0].name.as_str(), data.labels[0].color.as_str()),
+            ("bug", "d73a4a")
+        );
+        assert_eq!(data.unresolved_threads.get("src/lib.rs"), Some(&1));
+        assert_eq!(data.threads.len(), 1);
+        assert_eq!(data.threads[0].start_line, Some(10));
+        let comment = &data.threads[0].comments[0];
+        assert_eq!(
+            (comment.author.as_str(), comment.timestamp()),
+            ("ana", "2024-05-01 14:03".to_string())
+        );
+    }
+
+    #[test]
+    fn summarises_check_rollups() {
+        let rollup =
+            |checks: serde_json::Value| CiState::of_rollup(checks.as_array().expect("array"));
+        assert_eq!(rollup(json!([])), CiState::None);
+        assert_eq!(
+            rollup(
+                json!([{ "status": "COMPLETED", "conclusion": "SUCCESS" }, { "state": "SUCCESS" }])
+            ),
+            CiState::Passing
+        );
+        assert_eq!(
+            rollup(json!([{ "status": "IN_PROGRESS", "conclusion": "" }, { "state": "SUCCESS" }])),
+            CiState::Pending
+        );
+        assert_eq!(
+            rollup(json!([{ "status": "IN_PROGRESS", "conclusion": "" }, { "state": "ERROR" }])),
+            CiState::Failing
+        );
+        let json = json!({ "data": { "repository": { "pullRequest": { "commits": { "nodes": [
+            { "commit": { "statusCheckRollup": { "contexts": { "nodes": [
+                { "name": "build", "status": "COMPLETED", "conclusion": "SUCCESS",
+                  "detailsUrl": "https://ci/1" },
+                { "context": "lint", "state": "FAILURE", "targetUrl": "https://ci/2" },
+            ] } } } },
+        ] } } } } });
+        let checks = GitHubClient::parse_review_data(&json).checks;
+        assert_eq!(
+            checks
+                .iter()
+                .map(|c| (c.name.as_str(), c.state))
+                .collect::<Vec<_>>(),
+            [("lint", CiState::Failing), ("build", CiState::Passing)]
+        );
+        assert_eq!(checks[0].url.as_deref(), Some("https://ci/2"));
+        assert_eq!(
+            repo_url_slug("https://github.com/o/r/"),
+            Some("o/r".to_string())
+        );
+        assert_eq!(repo_url_slug("https://github.com/o/r/pull/1"), None);
+        assert_eq!(
+            remote_slug("git@github.com:o/r.git"),
+            Some("o/r".to_string())
+        );
+        assert_eq!(remote_slug("https://gitlab.com/o/r.git"), None);
+    }
+
+    #[test]
+    fn parses_pr_shorthands() {
+        assert_eq!(PrRef::parse("1234"), Some(PrRef::Number(1234)));
+        assert_eq!(PrRef::parse("#42"), Some(PrRef::Number(42)));
+        assert_eq!(
+            PrRef::parse("owner/repo#7"),
+            Some(PrRef::InRepo {
+                repo: "owner/repo".to_string(),
+                number: 7
+            })
+        );
+        assert!(matches!(
+            PrRef::parse("https://github.com/o/r/pull/1"),
+            Some(PrRef::Url(_))
+        ));
+        assert_eq!(PrRef::parse("feature/login"), None);
+        assert_eq!(PrRef::parse("./some/dir"), None);
+        assert_eq!(
+            PrRef::Url("https://github.com/o/r/pull/12/files".into()).graphql_args(),
+            Some(
+                ["-f", "owner=o", "-f", "name=r", "-F", "number=12"]
+                    .map(String::from)
+                    .to_vec()
+            )
+        );
+        assert_eq!(
+            PrRef::Number(3).graphql_args(),
+            Some(
+                ["-F", "owner={owner}", "-F", "name={repo}", "-F", "number=3"]
+                    .map(String::from)
+                    .to_vec()
+            )
+        );
+        assert_eq!(PrRef::Branch("feature/login".into()).graphql_args(), None);
+        assert_eq!(
+            PrRef::Url("https://github.com/o/r/pull/12/files".into()).offline_url(None),
+            Some("https://github.com/o/r/pull/12".to_string())
+        );
+        assert_eq!(
+            PrRef::Number(3).offline_url(Some("o/r")),
+            Some("https://github.com/o/r/pull/3".to_string())
+        );
+        assert_eq!(PrRef::Number(3).offline_url(None), None);
+    }
+
+    #[test]
+    fn records_rate_limit_headers() {
+        let out = b"HTTP/2.0 200 OK\r\nX-Ratelimit-Limit: 5000\r\nX-Ratelimit-Remaining: 312\r\n\
+                    X-Ratelimit-Reset: 0\r\nX-Ratelimit-Resource: graphql\r\n\r\n{\"data\":1}";
+        let (headers, body) = split_response(out);
+        assert_eq!(body, b"{\"data\":1}");
+        let limit = RateLimit::from_headers(&headers).expect("rate limit");
+        assert_eq!(
+            (limit.summary(), limit.is_low()),
+            ("graphql 312/5000".to_string(), true)
+        );
+        assert_eq!(split_response(b"{}"), (String::new(), &b"{}"[..]));
+    }
+
+    #[test]
+    fn retries_only_transient_failures() {
+        let error = |stderr: &str| GhError {
+            command: String::new(),
+            stderr: stderr.to_string(),
+            kind: GhErrorKind::classify(stderr),
+        };
+        let secondary = error("HTTP 403: You have exceeded a secondary rate limit.");
+        let spent = error("HTTP 403: API rate limit exceeded for user ID 1.");
+        let outage = error("HTTP 502: Bad Gateway (https://api.github.com/graphql)");
+        assert_eq!(spent.kind, GhErrorKind::RateLimited);
+        assert!(secondary.is_transient(false));
+        assert!(!spent.is_transient(true));
+        assert!(outage.is_transient(true) && !outage.is_transient(false));
+        assert!(!error("HTTP 404: Not Found").is_transient(true));
+        let offline = error("error connecting to api.github.com\ncheck your internet connection");
+        assert_eq!(offline.kind, GhErrorKind::Network);
+        assert_eq!(
+            error("Could not resolve to a PullRequest with the number of 9.").kind,
+            GhErrorKind::NotFound
+        );
+
+        let args = |words: &[&str]| words.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert!(is_read_only(&args(&["pr", "diff", "https://x"])));
+        assert!(!is_read_only(&args(&["pr", "edit", "https://x"])));
+        assert!(is_read_only(&args(&["api", "graphql", "-f", "query=q"])));
+        assert!(!is_read_only(&args(&[
+            "api",
+            "--method",
+            "POST",
+            "repos/o/r"
+        ])));
+        let mark = format!("query={MARK_VIEWED}");
+        assert!(!is_read_only(&args(&["api", "graphql", "-f", &mark])));
+
+        assert_eq!(retry_delay(0, 0), Duration::from_secs(2));
+        assert_eq!(retry_delay(2, 4000), Duration::from_secs(12));
+        assert_eq!(retry_delay(2, 4001), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn appends_later_pages() {
+        let mut threads = json!({
+            "nodes": [1],
+            "pageInfo": { "hasNextPage": true, "endCursor": "a" },
+        });
+        let mut cursors = Vec::new();
+        append_pages(&mut threads, |cursor| {
+            cursors.push(cursor.to_string());
+            Ok(if cursor == "a" {
+                json!({ "nodes": [2, 3], "pageInfo": { "hasNextPage": true, "endCursor": "b" } })
+            } else {
+                json!({ "nodes": [4], "pageInfo": { "hasNextPage": false, "endCursor": null } })
+            })
+        })
+        .expect("pages");
+        assert_eq!(threads["nodes"], json!([1, 2, 3, 4]));
+        assert_eq!(cursors, ["a", "b"]);
+    }
+
+    #[test]
+    fn reads_pr_info_from_graphql() {
+        let json = json!({ "data": { "repository": { "pullRequest": {
+            "id": "PR_kw7", "number": 7, "title": "t", "body": "", "state": "OPEN", "author": null,
+            "isDraft": true, "url": "https://github.com/o/r/pull/7", "changedFiles": 1, "additions": 1,
+            "deletions": 0, "baseRefName": "main", "baseRefOid": "0123",
+            "files": { "nodes": [{ "path": "src/lib.rs", "additions": 1, "deletions": 0,
+                                   "viewerViewedState": "VIEWED" }] },
+        } } } });
+        let info = GitHubClient::parse_pr_info(&json).expect("PR info");
+        assert_eq!(GitHubClient::file_page(&info, "src/lib.rs"), 1);
+        assert_eq!(
+            (info.author.login.as_str(), info.files[0].path.as_str()),
+            ("ghost", "src/lib.rs")
+        );
+        assert!(info.is_draft);
+        assert_eq!(info.node_id, "PR_kw7");
+        assert!(
+            GitHubClient::parse_review_data(&json)
+                .viewed
+                .contains("src/lib.rs")
+        );
+    }
 
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    #[test]
+    fn rebuilds_headers_of_file_patches() {
+        let patches = parse_file_patches(&json!([
+            { "filename": "new.rs", "status": "added", "patch": "@@ -0,0 +1 @@\n+fn f() {}" },
+            { "filename": "b.rs", "previous_filename": "a.rs", "status": "renamed",
+              "patch": "@@ -1 +1 @@\n-x\n+y" },
+            { "filename": "logo.png", "status": "modified" },
+        ]));
+        assert_eq!(
+            patches["new.rs"],
+            [
+                "diff --git a/new.rs b/new.rs",
+                "--- /dev/null",
+                "+++ b/new.rs",
+                "@@ -0,0 +1 @@",
+                "+fn f() {}"
+            ]
+        );
+        assert_eq!(
+            patches["b.rs"][..3],
+            ["diff --git a/a.rs b/b.rs", "--- a/a.rs", "+++ b/b.rs"]
+        );
+        assert_eq!(patches["logo.png"].len(), 1);
+        assert!(diff_too_large(
+            "HTTP 406: Sorry, the diff exceeded the maximum number of files (300)"
+        ));
     }
 }
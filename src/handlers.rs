@@ -1,66 +1,402 @@
 use crate::app::{App, InputMode, ZoomLevel};
-use crossterm::event::{Event, KeyCode, KeyEventKind};
+use crate::conflict::Side;
+use crate::macros::MacroStep;
+use crate::multiplexer::PaneCommand;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+// Columns moved per h/l press in the unwrapped Logic view
+const PAN_COLUMNS: isize = 8;
 
 pub fn handle_event(app: &mut App, event: &Event) -> bool {
-    if let Event::Key(key) = event
-        && key.kind == KeyEventKind::Press
-    {
-        // Handle Editing Mode (Search)
-        if app.input_mode == InputMode::Editing {
-            match key.code {
-                KeyCode::Enter => {
-                    app.exit_search();
-                }
-                KeyCode::Esc => {
-                    app.cancel_search();
-                }
-                KeyCode::Backspace => {
-                    app.search_query.pop();
-                    app.update_search();
-                }
-                KeyCode::Char(c) => {
-                    app.search_query.push(c);
-                    app.update_search();
+    let Event::Key(key) = event else {
+        return true;
+    };
+    if key.kind != KeyEventKind::Press {
+        return true;
+    }
+    // First-run setup owns the keyboard until it is saved or skipped
+    if let Some(onboarding) = app.onboarding.as_mut() {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => onboarding.next(),
+            KeyCode::Char('k') | KeyCode::Up => onboarding.previous(),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                if onboarding.activate() {
+                    app.finish_onboarding(true);
                 }
-                _ => {}
             }
-            return true;
+            KeyCode::Esc => app.finish_onboarding(false),
+            _ => {}
         }
+        return true;
+    }
+    // Macro keys are resolved before dispatch so recordings capture what the app saw; in the
+    // search bar, the review, comment and note composers and the people and label pickers `Q`
+    // and `@` are just text
+    let normal_mode = app.input_mode == InputMode::Normal
+        && app.review_draft.is_none()
+        && app.comment_draft.is_none()
+        && app.note_draft.is_none()
+        && app.people_picker.is_none()
+        && app.label_picker.is_none();
+    match app.macros.intercept(*key, normal_mode) {
+        MacroStep::Pass => dispatch(app, key),
+        MacroStep::Consumed => true,
+        MacroStep::Replay(keys) => keys.iter().all(|k| dispatch(app, k)),
+    }
+}
 
-        // Handle Normal Mode
+fn dispatch(app: &mut App, key: &KeyEvent) -> bool {
+    // Handle Editing Mode (Search)
+    if app.input_mode == InputMode::Editing {
         match key.code {
-            KeyCode::Char('q') | KeyCode::Esc => {
-                return false;
+            KeyCode::Enter => {
+                app.exit_search();
             }
-            KeyCode::Char('j') | KeyCode::Down => {
-                app.next();
+            KeyCode::Esc => {
+                app.cancel_search();
             }
-            KeyCode::Char('k') | KeyCode::Up => {
-                app.previous();
+            KeyCode::Up if app.is_symbol_search() => app.move_symbol_hit(false),
+            KeyCode::Down if app.is_symbol_search() => app.move_symbol_hit(true),
+            KeyCode::Backspace => {
+                app.search_query.pop();
+                app.update_search();
             }
-            KeyCode::Enter => {
-                app.zoom_in();
+            KeyCode::Char(c) => {
+                app.search_query.push(c);
+                app.update_search();
             }
-            KeyCode::Backspace | KeyCode::Left => {
-                app.zoom_out();
+            _ => {}
+        }
+        return true;
+    }
+
+    app.notice = None;
+
+    // The review composer owns the keyboard; everything but its own keys is typed
+    if let Some(draft) = app.review_draft.as_mut() {
+        match key.code {
+            KeyCode::Esc => app.review_draft = None,
+            KeyCode::Tab => draft.event = draft.event.next(),
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.submit_review();
             }
-            KeyCode::Char(' ') => {
-                app.toggle_stage();
+            _ => {
+                draft.message.input(*key);
             }
-            KeyCode::Char('+' | '=') => {
-                app.increase_context();
+        }
+        return true;
+    }
+
+    if let Some(draft) = app.comment_draft.as_mut() {
+        match key.code {
+            KeyCode::Esc => app.comment_draft = None,
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.submit_line_comment();
             }
-            KeyCode::Char('-' | '_') => {
-                app.decrease_context();
+            _ => {
+                draft.message.input(*key);
             }
-            // Search Trigger
-            KeyCode::Char('/') => {
-                if app.zoom_level == ZoomLevel::Structure {
-                    app.enter_search();
-                }
+        }
+        return true;
+    }
+
+    if let Some(draft) = app.note_draft.as_mut() {
+        match key.code {
+            KeyCode::Esc => app.note_draft = None,
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.save_line_note();
+            }
+            _ => {
+                draft.message.input(*key);
             }
+        }
+        return true;
+    }
+
+    // Handle y/n confirmation for destructive actions
+    if app.pending_confirm.is_some() {
+        if matches!(key.code, KeyCode::Char('y' | 'Y')) {
+            app.confirm_pending();
+        } else {
+            app.pending_confirm = None;
+        }
+        return true;
+    }
+
+    if handle_popup(app, key) {
+        return true;
+    }
+
+    // Loading failed: only retry or quit make sense
+    if app.source.is_none() && app.error_msg.is_some() {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('r') => app.retry(),
+            KeyCode::Backspace => app.return_to_queue(),
+            _ => {}
+        }
+        return true;
+    }
+
+    // Started without a PR: pick one of the open ones
+    if let Some(picker) = app.pr_picker.as_mut() {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => picker.next(),
+            KeyCode::Char('k') | KeyCode::Up => picker.previous(),
+            KeyCode::Enter => app.open_picked_pr(),
+            KeyCode::Char('r') => app.retry(),
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            _ => {}
+        }
+        return true;
+    }
+
+    // Handle Normal Mode
+    handle_normal(app, key)
+}
+
+/// Keys for an open list popup; false when none is open.
+fn handle_popup(app: &mut App, key: &KeyEvent) -> bool {
+    // Handle Quick Actions popup
+    if let Some(menu) = app.action_menu.as_mut() {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => menu.next(),
+            KeyCode::Char('k') | KeyCode::Up => menu.previous(),
+            KeyCode::Enter => app.run_selected_action(),
+            KeyCode::Esc | KeyCode::Char('.' | 'q') => app.action_menu = None,
+            _ => {}
+        }
+        return true;
+    }
+
+    // CI checks popup: Enter opens the check's details page
+    if app.checks_cursor.is_some() {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => app.move_checks_cursor(1),
+            KeyCode::Char('k') | KeyCode::Up => app.move_checks_cursor(-1),
+            KeyCode::Enter => app.open_check_details(),
+            KeyCode::Esc | KeyCode::Char('C' | 'q') => app.checks_cursor = None,
+            _ => {}
+        }
+        return true;
+    }
+
+    // Reviewer / assignee picker: typing narrows the list
+    if let Some(picker) = app.people_picker.as_mut() {
+        match key.code {
+            KeyCode::Down => picker.next(),
+            KeyCode::Up => picker.previous(),
+            KeyCode::Tab => picker.role = picker.role.next(),
+            KeyCode::Backspace => picker.pop(),
+            KeyCode::Char(c) => picker.push(c),
+            KeyCode::Enter => app.add_person(),
+            KeyCode::Esc => app.people_picker = None,
+            _ => {}
+        }
+        return true;
+    }
+
+    // Label picker: typing narrows the list, Enter puts a label on or takes it off
+    if let Some(picker) = app.label_picker.as_mut() {
+        match key.code {
+            KeyCode::Down => picker.next(),
+            KeyCode::Up => picker.previous(),
+            KeyCode::Backspace => picker.pop(),
+            KeyCode::Char(c) => picker.push(c),
+            KeyCode::Enter => app.toggle_label(),
+            KeyCode::Esc => app.label_picker = None,
+            _ => {}
+        }
+        return true;
+    }
+
+    // PR description popup
+    if app.description_scroll.is_some() {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => app.scroll_description(1),
+            KeyCode::Char('k') | KeyCode::Up => app.scroll_description(-1),
+            KeyCode::PageDown => app.scroll_description(10),
+            KeyCode::PageUp => app.scroll_description(-10),
+            KeyCode::Esc | KeyCode::Char('D' | 'q') => app.description_scroll = None,
             _ => {}
         }
+        return true;
+    }
+
+    // Profile picker: Enter switches to the highlighted profile
+    if app.profile_cursor.is_some() {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => app.move_profile_cursor(1),
+            KeyCode::Char('k') | KeyCode::Up => app.move_profile_cursor(-1),
+            KeyCode::Enter => app.switch_profile(),
+            KeyCode::Esc | KeyCode::Char('P' | 'q') => app.profile_cursor = None,
+            _ => {}
+        }
+        return true;
+    }
+
+    // References popup: Enter opens the reference in $EDITOR
+    if let Some(panel) = app.impact_panel.as_mut() {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => panel.next(),
+            KeyCode::Char('k') | KeyCode::Up => panel.previous(),
+            KeyCode::Enter => app.open_reference(),
+            KeyCode::Esc | KeyCode::Char('I' | 'q') => app.impact_panel = None,
+            _ => {}
+        }
+        return true;
+    }
+
+    // Handle Branch panel popup
+    if let Some(panel) = app.branch_panel.as_mut() {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => panel.next(),
+            KeyCode::Char('k') | KeyCode::Up => panel.previous(),
+            KeyCode::Enter => app.checkout_selected_branch(),
+            KeyCode::Esc | KeyCode::Char('B' | 'q') => app.branch_panel = None,
+            _ => {}
+        }
+        return true;
+    }
+    false
+}
+
+fn handle_normal(app: &mut App, key: &KeyEvent) -> bool {
+    if app.zoom_level == ZoomLevel::Logic && handle_logic(app, key) {
+        return true;
+    }
+    if handle_openers(app, key) {
+        return true;
+    }
+    match key.code {
+        // Jump list; most terminals send Ctrl-i as Tab, so it only works where they differ
+        KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.jump_back();
+        }
+        KeyCode::Char('i') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.jump_forward();
+        }
+        KeyCode::Esc if app.structure_anchor.is_some() => {
+            app.structure_anchor = None;
+        }
+        KeyCode::Char('q') | KeyCode::Esc => {
+            return false;
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.next();
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.previous();
+        }
+        KeyCode::Enter => {
+            app.zoom_in();
+        }
+        KeyCode::Backspace | KeyCode::Left => {
+            app.zoom_out();
+        }
+        KeyCode::Char(' ') => {
+            app.toggle_stage();
+        }
+        KeyCode::Char('a') if app.zoom_level == ZoomLevel::Structure => {
+            app.stage_all();
+        }
+        KeyCode::Char('u') if app.zoom_level == ZoomLevel::Structure => {
+            app.unstage_all();
+        }
+        KeyCode::Char('v') if app.zoom_level == ZoomLevel::Structure => {
+            app.toggle_structure_visual();
+        }
+        KeyCode::Char('g') if app.zoom_level == ZoomLevel::Structure => {
+            app.toggle_cluster();
+        }
+        KeyCode::Char('U') => {
+            app.undo_staging();
+        }
+        KeyCode::Tab => {
+            app.switch_section();
+        }
+        KeyCode::Char('+' | '=') => {
+            app.increase_context();
+        }
+        KeyCode::Char('-' | '_') => {
+            app.decrease_context();
+        }
+        KeyCode::Char('d') if app.zoom_level == ZoomLevel::Structure => {
+            app.request_discard();
+        }
+        KeyCode::Char('E') => {
+            app.export_review();
+        }
+        KeyCode::Char('H') => {
+            app.export_handoff();
+        }
+        KeyCode::Char('Y') => {
+            app.copy_summary();
+        }
+        KeyCode::Char('M') => {
+            app.toggle_merge_base();
+        }
+        KeyCode::Char('S') => {
+            app.toggle_safe_mode();
+        }
+        // Search Trigger
+        KeyCode::Char('/') if app.zoom_level == ZoomLevel::Structure => {
+            app.enter_search();
+        }
+        _ => {}
+    }
+    true
+}
+
+/// Keys opening a popup or handing the selection to another tool, in every view.
+fn handle_openers(app: &mut App, key: &KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('B') => app.open_branch_panel(),
+        KeyCode::Char('R') => app.open_review_draft(),
+        KeyCode::Char('C') => app.open_checks(),
+        KeyCode::Char('P') => app.open_profiles(),
+        KeyCode::Char('D') => app.open_description(),
+        KeyCode::Char('A') => app.open_people_picker(),
+        KeyCode::Char('L') => app.open_label_picker(),
+        KeyCode::Char('F') => app.cycle_verdict(),
+        KeyCode::Char('K') => app.toggle_bookmark(),
+        KeyCode::Char('\'') => app.next_bookmark(),
+        KeyCode::Char('I') => app.open_impact(),
+        KeyCode::Char('O') => app.run_pane_command(PaneCommand::Open),
+        KeyCode::Char('W') => app.run_pane_command(PaneCommand::Send),
+        KeyCode::Char('.') => app.open_action_menu(),
+        _ => return false,
+    }
+    true
+}
+
+/// Keys that only mean something in the Logic view; `false` falls through to the global keys.
+fn handle_logic(app: &mut App, key: &KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc if app.logic_anchor.is_some() => app.logic_anchor = None,
+        KeyCode::Char('d') => app.request_discard_hunk(),
+        KeyCode::Char('o') if app.conflict.is_some() => app.resolve_conflict(Side::Ours),
+        KeyCode::Char('t') if app.conflict.is_some() => app.resolve_conflict(Side::Theirs),
+        KeyCode::Char('t') => app.cycle_diff_scope(),
+        KeyCode::Char('w') if app.conflict.is_some() => app.write_conflict(),
+        KeyCode::Char('z') => app.toggle_wrap(),
+        KeyCode::PageDown => app.logic_page(true),
+        KeyCode::PageUp => app.logic_page(false),
+        KeyCode::Char('x') => app.toggle_changes_only(),
+        KeyCode::Char('h') => app.pan(-PAN_COLUMNS),
+        KeyCode::Char('l') => app.pan(PAN_COLUMNS),
+        KeyCode::Char('m') if app.history.as_ref().is_some_and(|h| h.open.is_some()) => {
+            app.cycle_merge_view();
+        }
+        KeyCode::Char('c') if app.history.is_some() => app.request_cherry_pick(),
+        KeyCode::Char('V') => app.toggle_logic_visual(),
+        KeyCode::Char('y') => app.copy_logic_selection(),
+        KeyCode::Char('b') => app.toggle_blame(),
+        KeyCode::Char('a') if app.pr_review().is_some() => app.request_apply_suggestion(),
+        KeyCode::Char('c') if app.pr_review().is_some() => app.open_line_comment(),
+        KeyCode::Char('v') if app.pr_review().is_some() => app.toggle_viewed(),
+        KeyCode::Char('n') => app.open_line_note(),
+        _ => return false,
     }
     true
 }
@@ -0,0 +1,211 @@
+// Handoff bundles: one reviewer's verdicts, notes, bookmarks, seen files and place in a
+// changeset, as a file a colleague can import to carry on with the same PR or commit
+use crate::jumps::Location;
+use crate::notes::{LineNote, Notes};
+use crate::utils::{file_name, get_data_dir};
+use crate::verdicts::{Verdict, Verdicts};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What one reviewer hands to the next.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Handoff {
+    // Repository workdir, PR URL or commit, as for sessions; imports must match it
+    pub source: String,
+    pub reviewer: String,
+    // Unix time of the export
+    pub exported_at: i64,
+    pub verdicts: BTreeMap<String, Verdict>,
+    // Where the reviewer stopped
+    pub location: Option<Location>,
+    // Absent from bundles exported before they carried notes, bookmarks and seen files
+    #[serde(default)]
+    pub notes: BTreeMap<String, Vec<LineNote>>,
+    #[serde(default)]
+    pub bookmarks: Vec<Location>,
+    // Files the reviewer opened in the Logic view, sorted
+    #[serde(default)]
+    pub viewed: Vec<String>,
+}
+
+/// How an import went: verdicts taken over, and those left as they were because the
+/// importer had already given a different one; notes and bookmarks added, and files newly
+/// marked seen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Merge {
+    pub added: usize,
+    pub kept: usize,
+    pub notes: usize,
+    pub viewed: usize,
+}
+
+impl Handoff {
+    /// A bundle of the reviewer's state, signed with the git `user.name` when there is one.
+    #[must_use]
+    pub fn new(
+        verdicts: &Verdicts,
+        notes: &Notes,
+        viewed: &HashSet<String>,
+        location: Option<Location>,
+    ) -> Self {
+        let reviewer = git2::Config::open_default()
+            .and_then(|config| config.get_string("user.name"))
+            .or_else(|_| std::env::var("USER"))
+            .unwrap_or_else(|_| "someone".to_string());
+        Self {
+            source: verdicts.source.clone(),
+            reviewer,
+            exported_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs().cast_signed()),
+            verdicts: verdicts.files.clone(),
+            location,
+            notes: notes.files.clone(),
+            bookmarks: notes.bookmarks.clone(),
+            viewed: {
+                let mut viewed: Vec<String> = viewed.iter().cloned().collect();
+                viewed.sort_unstable();
+                viewed
+            },
+        }
+    }
+
+    /// File a bundle is exported to under the data directory, e.g.
+    /// `handoffs/glim--12-ana.json` for PR #12.
+    #[must_use]
+    pub fn path(&self, name: &str) -> PathBuf {
+        let file = file_name(&format!("{name}-{}.json", self.reviewer));
+        get_data_dir().join("handoffs").join(file)
+    }
+
+    /// Writes the bundle to `path` as JSON.
+    ///
+    /// # Errors
+    /// Returns an error if the directory or file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Cannot write {}", path.display()))
+    }
+
+    /// Reads the bundle at `path`, which must be for `source`.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed, or is for another changeset.
+    pub fn load(path: &Path, source: &str) -> Result<Self> {
+        let text =
+            fs::read_to_string(path).with_context(|| format!("Cannot read {}", path.display()))?;
+        let handoff: Self = serde_json::from_str(&text)
+            .with_context(|| format!("{} is not a handoff bundle", path.display()))?;
+        if handoff.source != source {
+            bail!("{} is for {}, not {source}", path.display(), handoff.source);
+        }
+        Ok(handoff)
+    }
+
+    /// Takes over the verdicts on files `verdicts` has none for, the notes (signed with the
+    /// reviewer's name) on lines `notes` has none on, the bookmarks, and the seen files; the
+    /// importer's own win.
+    pub fn merge_into(
+        &self,
+        verdicts: &mut Verdicts,
+        notes: &mut Notes,
+        viewed: &mut HashSet<String>,
+    ) -> Merge {
+        let mut merge = Merge::default();
+        for (path, &verdict) in &self.verdicts {
+            match verdicts.get(path) {
+                None => {
+                    verdicts.set(path, Some(verdict));
+                    merge.added += 1;
+                }
+                Some(own) if own != verdict => merge.kept += 1,
+                Some(_) => {}
+            }
+        }
+        let theirs = Notes {
+            source: self.source.clone(),
+            files: self
+                .notes
+                .iter()
+                .map(|(path, notes)| {
+                    let signed = notes
+                        .iter()
+                        .map(|note| LineNote {
+                            text: format!("{}: {}", self.reviewer, note.text),
+                            ..note.clone()
+                        })
+                        .collect();
+                    (path.clone(), signed)
+                })
+                .collect(),
+            bookmarks: self.bookmarks.clone(),
+        };
+        merge.notes = notes.merge(&theirs);
+        let before = viewed.len();
+        viewed.extend(self.viewed.iter().cloned());
+        merge.viewed = viewed.len() - before;
+        merge
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Handoff, Merge};
+    use crate::notes::{LineNote, Notes};
+    use crate::verdicts::{Verdict, Verdicts};
+    use std::collections::{BTreeMap, HashSet};
+
+    #[test]
+    fn merges_review_state_keeping_the_importers_own() {
+        let handoff = Handoff {
+            source: "https://github.com/o/r/pull/1".into(),
+            reviewer: "ana".into(),
+            exported_at: 0,
+            verdicts: BTreeMap::from([
+                ("a.rs".to_string(), Verdict::Approve),
+                ("b.rs".to_string(), Verdict::Blocked),
+                ("c.rs".to_string(), Verdict::NeedsWork),
+            ]),
+            location: None,
+            notes: BTreeMap::from([(
+                "a.rs".to_string(),
+                vec![LineNote {
+                    line: 4,
+                    left: false,
+                    text: "off by one?".into(),
+                }],
+            )]),
+            bookmarks: Vec::new(),
+            viewed: vec!["a.rs".into(), "b.rs".into()],
+        };
+        let mut own = Verdicts {
+            source: handoff.source.clone(),
+            files: BTreeMap::from([
+                ("b.rs".to_string(), Verdict::Approve),
+                ("c.rs".to_string(), Verdict::NeedsWork),
+            ]),
+        };
+        let mut notes = Notes::default();
+        let mut viewed = HashSet::from(["b.rs".to_string()]);
+        assert_eq!(
+            handoff.merge_into(&mut own, &mut notes, &mut viewed),
+            Merge {
+                added: 1,
+                kept: 1,
+                notes: 1,
+                viewed: 1,
+            }
+        );
+        assert_eq!(own.get("a.rs"), Some(Verdict::Approve));
+        assert_eq!(own.get("b.rs"), Some(Verdict::Approve));
+        assert_eq!(notes.get("a.rs", 4, false), Some("ana: off by one?"));
+        assert!(viewed.contains("a.rs"));
+    }
+}
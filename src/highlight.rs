@@ -0,0 +1,237 @@
+// Syntax colours for the Logic view: the lines on screen at once, the whole file on a
+// background thread, so opening a long diff never waits for the highlighter
+use crate::config::DEFAULT_SYNTAX_THEME;
+use std::cell::{Ref, RefCell};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Style, Theme, ThemeSet};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+
+// Loading these takes longer than drawing a frame; do it once for the whole process
+pub static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+pub static THEMES: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
+
+// Lines the worker highlights between handing results over
+const BATCH_LINES: usize = 256;
+
+/// Syntax-coloured runs of one line, diff prefix excluded.
+pub type StyledLine = Vec<(Style, String)>;
+
+/// The syntect theme called `name`, or the default when there is none by that name.
+#[must_use]
+pub fn theme(name: &str) -> &'static Theme {
+    THEMES
+        .themes
+        .get(name)
+        .unwrap_or_else(|| &THEMES.themes[DEFAULT_SYNTAX_THEME])
+}
+
+/// Syntax definition for `path`, by extension.
+#[must_use]
+pub fn syntax_for(path: &str) -> &'static SyntaxReference {
+    let ext = std::path::Path::new(path)
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("rs");
+    SYNTAXES
+        .find_syntax_by_extension(ext)
+        .unwrap_or_else(|| SYNTAXES.find_syntax_plain_text())
+}
+
+/// A diff line's content, as the highlighter sees it.
+fn content(line: &str) -> &str {
+    line.get(1..).unwrap_or_default()
+}
+
+fn highlight(highlighter: &mut HighlightLines, line: &str) -> StyledLine {
+    highlighter
+        .highlight_line(content(line), &SYNTAXES)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(style, text)| (style, text.to_string()))
+        .collect()
+}
+
+/// One diff being highlighted in the background.
+#[derive(Debug)]
+struct Job {
+    // Highlighted lines the worker has not handed over yet, in order
+    done: Arc<Mutex<Vec<StyledLine>>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Job {
+    fn spawn(lines: &[String], syntax: &'static SyntaxReference, theme: &'static Theme) -> Self {
+        let done = Arc::new(Mutex::new(Vec::new()));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (out, stop, lines) = (Arc::clone(&done), Arc::clone(&cancel), lines.to_vec());
+        std::thread::spawn(move || {
+            let mut highlighter = HighlightLines::new(syntax, theme);
+            for batch in lines.chunks(BATCH_LINES) {
+                if stop.load(Ordering::Relaxed) {
+                    return;
+                }
+                let styled: Vec<StyledLine> = batch
+                    .iter()
+                    .map(|line| highlight(&mut highlighter, line))
+                    .collect();
+                match out.lock() {
+                    Ok(mut done) => done.extend(styled),
+                    Err(_) => return,
+                }
+            }
+        });
+        Self { done, cancel }
+    }
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        // The diff on screen changed; the worker's results would go nowhere
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// What the highlighter holds for the diff in the Logic view.
+#[derive(Debug, Default)]
+struct State {
+    // Hash of the lines, syntax and theme, taken when they are loaded
+    key: u64,
+    len: usize,
+    syntax: String,
+    theme: String,
+    job: Option<Job>,
+    // The worker's lines from the top, then any highlighted on the spot ahead of it
+    styled: Vec<Option<StyledLine>>,
+    ready: usize,
+}
+
+/// Highlighting of the diff in the Logic view, kept across frames.
+#[derive(Debug, Default)]
+pub struct Highlighter {
+    state: RefCell<State>,
+}
+
+impl Highlighter {
+    /// Starts highlighting `lines` in the background, unless they are what it has already.
+    pub fn load(&self, lines: &[String], syntax: &'static SyntaxReference, theme_name: &str) {
+        let mut hasher = DefaultHasher::new();
+        (lines, &syntax.name, theme_name).hash(&mut hasher);
+        let key = hasher.finish();
+        let mut state = self.state.borrow_mut();
+        if state.job.is_some() && state.key == key {
+            return;
+        }
+        *state = State {
+            key,
+            len: lines.len(),
+            syntax: syntax.name.clone(),
+            theme: theme_name.to_string(),
+            job: Some(Job::spawn(lines, syntax, theme(theme_name))),
+            styled: vec![None; lines.len()],
+            ready: 0,
+        };
+    }
+
+    /// Syntax colours for the `visible` lines of `lines`, which should be what was last
+    /// loaded; a different count, syntax or theme loads them again.
+    ///
+    /// Lines the worker has not reached are highlighted on the spot, from a fresh highlighter
+    /// state at the window's first line; a multi-line string or comment opened above it can
+    /// come out miscoloured until the worker catches up.
+    pub fn lines(
+        &self,
+        lines: &[String],
+        syntax: &'static SyntaxReference,
+        theme_name: &str,
+        visible: Range<usize>,
+    ) -> Ref<'_, [Option<StyledLine>]> {
+        let outdated = {
+            let loaded = self.state.borrow();
+            loaded.len != lines.len() || loaded.syntax != syntax.name || loaded.theme != theme_name
+        };
+        if outdated {
+            self.load(lines, syntax, theme_name);
+        }
+        let end = visible.end.min(lines.len());
+        let start = visible.start.min(end);
+        {
+            let mut state = self.state.borrow_mut();
+            state.collect();
+            let mut highlighter: Option<HighlightLines> = None;
+            let from = start.max(state.ready).min(end);
+            for (slot, line) in state.styled[from..end].iter_mut().zip(&lines[from..end]) {
+                if slot.is_none() {
+                    let highlighter = highlighter
+                        .get_or_insert_with(|| HighlightLines::new(syntax, theme(theme_name)));
+                    *slot = Some(highlight(highlighter, line));
+                }
+            }
+        }
+        Ref::map(self.state.borrow(), |state| &state.styled[start..end])
+    }
+
+    /// Whether the worker has highlighted every line loaded.
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        let mut state = self.state.borrow_mut();
+        state.collect();
+        state.ready == state.len
+    }
+}
+
+impl State {
+    /// Takes over the lines the worker finished since the last frame.
+    fn collect(&mut self) {
+        let Some(done) = self.job.as_ref().and_then(|job| {
+            job.done
+                .lock()
+                .ok()
+                .map(|mut done| std::mem::take(&mut *done))
+        }) else {
+            return;
+        };
+        for line in done {
+            if let Some(slot) = self.styled.get_mut(self.ready) {
+                *slot = Some(line);
+                self.ready += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Highlighter, syntax_for};
+    use crate::config::DEFAULT_SYNTAX_THEME;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn highlights_the_viewport_then_the_rest() {
+        let lines: Vec<String> = (0..2000).map(|i| format!("+let x{i} = {i};")).collect();
+        let highlighter = Highlighter::default();
+        let syntax = syntax_for("a.rs");
+        highlighter.load(&lines, syntax, DEFAULT_SYNTAX_THEME);
+        {
+            let first = highlighter.lines(&lines, syntax, DEFAULT_SYNTAX_THEME, 1000..1010);
+            assert_eq!(first.len(), 10);
+            assert!(first.iter().all(Option::is_some));
+        }
+
+        let deadline = Instant::now() + Duration::from_secs(20);
+        while !highlighter.is_done() {
+            assert!(Instant::now() < deadline, "worker never finished");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let styled = highlighter.lines(&lines, syntax, DEFAULT_SYNTAX_THEME, 5..6);
+        let text: String = styled[0]
+            .iter()
+            .flatten()
+            .map(|(_, t)| t.as_str())
+            .collect();
+        assert_eq!(text, "let x5 = 5;");
+    }
+}
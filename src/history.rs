@@ -0,0 +1,364 @@
+// Per-file commit history in the Logic view, with merge commits reviewable per parent or combined
+use crate::app::App;
+use git2::{Blob, Commit, DiffOptions, Oid, Repository};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+// Title and blank line above the first entry of the listing
+pub const LIST_HEADER: usize = 2;
+
+/// How a merge commit's diff is shown; ordinary commits always use their only parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeView {
+    // Against one parent; `Parent(0)` is the first parent, i.e. what the merge brought in
+    Parent(usize),
+    // Only the places where the result differs from every parent (`git diff --cc`)
+    Combined,
+}
+
+impl MergeView {
+    /// Next mode for a commit with `parents` parents: each parent in turn, then combined.
+    #[must_use]
+    pub const fn cycle(self, parents: usize) -> Self {
+        match self {
+            Self::Parent(n) if n + 1 < parents => Self::Parent(n + 1),
+            Self::Parent(_) => Self::Combined,
+            Self::Combined => Self::Parent(0),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub id: Oid,
+    pub parents: usize,
+    // Reachable from HEAD; other entries come from local branches and can be cherry-picked
+    pub on_head: bool,
+    pub line: String,
+}
+
+/// The history listing for one file and the commit opened from it, if any.
+#[derive(Debug, Clone)]
+pub struct FileHistory {
+    pub path: PathBuf,
+    pub entries: Vec<HistoryEntry>,
+    // Entry whose diff replaces the listing, with the listing cursor to return to
+    pub open: Option<(usize, usize)>,
+    pub merge_view: MergeView,
+    // Shown under the listing, e.g. that a shallow clone cuts the history short
+    pub note: Option<String>,
+    // Still being walked in the background; the entries come with the finished walk
+    pub loading: bool,
+}
+
+impl FileHistory {
+    /// Walks HEAD and the local branches for commits touching `path` (compared with their
+    /// first parent).
+    ///
+    /// # Errors
+    /// Returns an error if the revision walk or a tree diff fails.
+    pub fn load(repo: &Repository, path: &Path) -> Result<Self, git2::Error> {
+        const MAX_ENTRIES: usize = 100;
+        const MAX_SCANNED: usize = 5000;
+
+        let mut walk = repo.revwalk()?;
+        walk.push_head()?;
+        walk.push_glob("refs/heads")?;
+        walk.set_sorting(git2::Sort::TIME)?;
+        let head = repo.head()?.peel_to_commit()?.id();
+
+        let mut entries = Vec::new();
+        for oid in walk.take(MAX_SCANNED) {
+            let commit = repo.find_commit(oid?)?;
+            let tree = commit.tree()?;
+            let parent_tree = commit.parent(0).ok().map(|p| p.tree()).transpose()?;
+
+            let mut opts = DiffOptions::new();
+            opts.pathspec(path);
+            let diff =
+                repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut opts))?;
+            if diff.deltas().len() == 0 {
+                continue;
+            }
+
+            let id = commit.id().to_string();
+            let parents = commit.parent_count();
+            let on_head = commit.id() == head || repo.graph_descendant_of(head, commit.id())?;
+            entries.push(HistoryEntry {
+                id: commit.id(),
+                parents,
+                on_head,
+                line: format!(
+                    " {} {}{}{} ({})",
+                    &id[..7],
+                    if on_head { "" } else { "[other branch] " },
+                    if parents > 1 { "[merge] " } else { "" },
+                    commit.summary().unwrap_or_default(),
+                    commit.author().name().unwrap_or("unknown")
+                ),
+            });
+            if entries.len() >= MAX_ENTRIES {
+                break;
+            }
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            entries,
+            open: None,
+            merge_view: MergeView::Parent(0),
+            note: None,
+            loading: false,
+        })
+    }
+
+    /// The listing to show for `path` while [`Self::load`] runs.
+    #[must_use]
+    pub fn reading(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            entries: Vec::new(),
+            open: None,
+            merge_view: MergeView::Parent(0),
+            note: None,
+            loading: true,
+        }
+    }
+
+    /// Lines of the listing as shown in the Logic view.
+    #[must_use]
+    pub fn listing(&self, display_path: &str) -> Vec<String> {
+        // Each line starts with the blank a diff line has as its prefix
+        let mut lines = vec![format!(" History of {display_path}"), String::new()];
+        if self.loading {
+            lines.push(" Reading the commits that touch this file…".into());
+        } else if self.entries.is_empty() {
+            lines.push(" No commits touch this file.".into());
+        }
+        lines.extend(self.entries.iter().map(|e| e.line.clone()));
+        if let Some(note) = &self.note {
+            lines.extend([String::new(), format!(" {note}")]);
+        }
+        lines
+    }
+
+    /// Entry under a listing cursor.
+    #[must_use]
+    pub fn entry_at(&self, cursor: usize) -> Option<usize> {
+        cursor
+            .checked_sub(LIST_HEADER)
+            .filter(|&i| i < self.entries.len())
+    }
+
+    /// The opened commit's change to the file, headed by a summary of what it is compared with.
+    ///
+    /// # Errors
+    /// Returns an error if the commit, its parents or the file's blobs cannot be read.
+    pub fn commit_diff(
+        &self,
+        repo: &Repository,
+        index: usize,
+        context_lines: u32,
+    ) -> Result<Vec<String>, git2::Error> {
+        let commit = repo.find_commit(self.entries[index].id)?;
+        let id = commit.id().to_string();
+        let mut lines = vec![
+            format!(" {} {}", &id[..7], commit.summary().unwrap_or_default()),
+            format!(" Author: {}", commit.author().name().unwrap_or("unknown")),
+        ];
+        let parents: Vec<Commit> = commit.parents().collect();
+        let short = |c: &Commit| c.id().to_string()[..7].to_string();
+
+        if parents.len() > 1 {
+            lines.push(match self.merge_view {
+                MergeView::Parent(n) => format!(
+                    " Merge of {} parents: against parent {} of {} ({}) | [m] next parent / combined",
+                    parents.len(),
+                    n + 1,
+                    parents.len(),
+                    short(&parents[n])
+                ),
+                MergeView::Combined => format!(
+                    " Merge of {} parents: combined, only what differs from all of them | [m] first parent",
+                    parents.len()
+                ),
+            });
+        }
+        lines.push(String::new());
+
+        let body = match self.merge_view {
+            MergeView::Combined if parents.len() > 1 => {
+                self.combined_diff(repo, &commit, &parents, context_lines)?
+            }
+            MergeView::Parent(n) => {
+                let parent_tree = parents.get(n).map(Commit::tree).transpose()?;
+                let mut opts = DiffOptions::new();
+                opts.pathspec(&self.path).context_lines(context_lines);
+                let diff = repo.diff_tree_to_tree(
+                    parent_tree.as_ref(),
+                    Some(&commit.tree()?),
+                    Some(&mut opts),
+                )?;
+                App::patch_lines(&diff)
+                    .into_iter()
+                    .flat_map(|(_, lines)| lines)
+                    .collect()
+            }
+            MergeView::Combined => Vec::new(),
+        };
+        if body.is_empty() {
+            lines.push(match self.merge_view {
+                MergeView::Parent(_) => " The file is identical in this parent.".into(),
+                MergeView::Combined => " Every change here comes from one of the parents.".into(),
+            });
+        }
+        lines.extend(body);
+        Ok(lines)
+    }
+
+    fn combined_diff(
+        &self,
+        repo: &Repository,
+        commit: &Commit,
+        parents: &[Commit],
+        context_lines: u32,
+    ) -> Result<Vec<String>, git2::Error> {
+        let blob_of = |c: &Commit| -> Result<Option<Blob<'_>>, git2::Error> {
+            match c.tree()?.get_path(&self.path) {
+                Ok(entry) => entry.to_object(repo)?.peel_to_blob().map(Some),
+                Err(_) => Ok(None),
+            }
+        };
+        let merged = blob_of(commit)?;
+        let mut changes = Vec::new();
+        for parent in parents {
+            changes.push(parent_changes(blob_of(parent)?.as_ref(), merged.as_ref())?);
+        }
+        let text = merged
+            .as_ref()
+            .map(|b| String::from_utf8_lossy(b.content()).to_string())
+            .unwrap_or_default();
+        let result: Vec<&str> = text.lines().collect();
+        Ok(combined_lines(&result, &changes, context_lines as usize))
+    }
+}
+
+/// What one parent would need changed to become the merge result, by result line.
+#[derive(Debug, Default)]
+pub struct ParentChanges {
+    // Result lines (1-based) that are not in this parent
+    pub added: HashSet<usize>,
+    // Parent lines missing from the result, keyed by the result line they preceded
+    pub removed: BTreeMap<usize, Vec<String>>,
+}
+
+fn parent_changes(
+    parent: Option<&Blob>,
+    merged: Option<&Blob>,
+) -> Result<ParentChanges, git2::Error> {
+    let mut changes = ParentChanges::default();
+    let mut opts = DiffOptions::new();
+    opts.context_lines(0);
+    let old = parent.map_or(&[][..], Blob::content);
+    let new = merged.map_or(&[][..], Blob::content);
+    let patch = git2::Patch::from_buffers(old, None, new, None, Some(&mut opts))?;
+    for h in 0..patch.num_hunks() {
+        let (hunk, count) = patch.hunk(h)?;
+        // With no new lines, `new_start` names the line before the gap
+        let anchor = hunk.new_start() as usize + usize::from(hunk.new_lines() == 0);
+        for l in 0..count {
+            let line = patch.line_in_hunk(h, l)?;
+            let content = String::from_utf8_lossy(line.content())
+                .trim_end()
+                .to_string();
+            match line.origin() {
+                '+' => {
+                    if let Some(n) = line.new_lineno() {
+                        changes.added.insert(n as usize);
+                    }
+                }
+                '-' => changes.removed.entry(anchor).or_default().push(content),
+                _ => {}
+            }
+        }
+    }
+    Ok(changes)
+}
+
+/// Combined-diff lines for the merge result `result`: one marker column per parent.
+///
+/// Like `git diff --cc`, only regions where the result differs from every parent are shown,
+/// i.e. conflict resolutions and changes made in the merge itself.
+#[must_use]
+pub fn combined_lines(result: &[&str], changes: &[ParentChanges], context: usize) -> Vec<String> {
+    let end = result.len() + 1;
+    let interesting: BTreeSet<usize> = (1..=end)
+        .filter(|n| {
+            changes
+                .iter()
+                .all(|c| c.added.contains(n) || c.removed.contains_key(n))
+        })
+        .collect();
+    let marker = "@".repeat(changes.len() + 1);
+
+    // Interesting lines with their context, overlapping windows merged into one hunk
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &n in &interesting {
+        let (start, stop) = (n.saturating_sub(context).max(1), (n + context).min(end));
+        match ranges.last_mut() {
+            Some((_, last)) if start <= *last + 1 => *last = stop,
+            _ => ranges.push((start, stop)),
+        }
+    }
+
+    let mut lines = Vec::new();
+    for (start, stop) in ranges {
+        let length = (start..=stop).filter(|&n| n <= result.len()).count();
+        lines.push(format!("{marker} +{start},{length} {marker}"));
+        for line in start..=stop {
+            for (i, change) in changes.iter().enumerate() {
+                for removed in change.removed.get(&line).into_iter().flatten() {
+                    let columns: String = (0..changes.len())
+                        .map(|j| if j == i { '-' } else { ' ' })
+                        .collect();
+                    lines.push(format!("{columns}{removed}"));
+                }
+            }
+            if let Some(text) = result.get(line - 1) {
+                let columns: String = changes
+                    .iter()
+                    .map(|c| if c.added.contains(&line) { '+' } else { ' ' })
+                    .collect();
+                lines.push(format!("{columns}{text}"));
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MergeView, ParentChanges, combined_lines};
+
+    #[test]
+    fn combined_shows_only_lines_new_to_every_parent() {
+        let result = ["a", "ours", "resolved", "z"];
+        // Parent 1 lacks lines 2-3; parent 2 lacks only the resolution
+        let first = ParentChanges {
+            added: [2, 3].into(),
+            ..ParentChanges::default()
+        };
+        let second = ParentChanges {
+            added: [3].into(),
+            removed: [(3, vec!["theirs".to_string()])].into(),
+        };
+        let lines = combined_lines(&result, &[first, second], 0);
+        assert_eq!(lines, vec!["@@@ +3,1 @@@", " -theirs", "++resolved"]);
+    }
+
+    #[test]
+    fn merge_view_cycles_through_parents() {
+        assert_eq!(MergeView::Parent(0).cycle(2), MergeView::Parent(1));
+        assert_eq!(MergeView::Parent(1).cycle(2), MergeView::Combined);
+        assert_eq!(MergeView::Combined.cycle(2), MergeView::Parent(0));
+    }
+}
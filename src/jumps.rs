@@ -0,0 +1,91 @@
+// Vim-style jump list: Ctrl-o goes back to where a jump started, Ctrl-i forward again
+use crate::app::{StructureSection, ZoomLevel};
+use serde::{Deserialize, Serialize};
+
+// Oldest entries are dropped beyond this
+const MAX_JUMPS: usize = 100;
+
+/// A place the user can return to: view, selected item and Logic cursor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    pub zoom: ZoomLevel,
+    pub section: StructureSection,
+    // `(path, text)` of the selected Structure item, so symbols resolve after rescans
+    pub item: Option<(String, String)>,
+    // Raw selection index, used in Galaxy and when the item is gone
+    pub index: usize,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct JumpList {
+    entries: Vec<Location>,
+    // Equals `entries.len()` unless the user is walking through the list
+    position: usize,
+}
+
+impl JumpList {
+    /// Records the location a jump is leaving; forward history is discarded.
+    pub fn push(&mut self, location: Location) {
+        self.entries.truncate(self.position);
+        if self.entries.last() != Some(&location) {
+            self.entries.push(location);
+        }
+        if self.entries.len() > MAX_JUMPS {
+            self.entries.remove(0);
+        }
+        self.position = self.entries.len();
+    }
+
+    /// Steps back, remembering `current` so [`Self::forward`] can return to it.
+    pub fn back(&mut self, current: Location) -> Option<Location> {
+        if self.position == self.entries.len() {
+            if self.entries.last() != Some(&current) {
+                self.entries.push(current);
+            }
+            self.position = self.entries.len() - 1;
+        }
+        if self.position == 0 {
+            return None;
+        }
+        self.position -= 1;
+        self.entries.get(self.position).cloned()
+    }
+
+    pub fn forward(&mut self) -> Option<Location> {
+        if self.position + 1 >= self.entries.len() {
+            return None;
+        }
+        self.position += 1;
+        self.entries.get(self.position).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JumpList, Location};
+    use crate::app::{StructureSection, ZoomLevel};
+
+    fn at(line: usize) -> Location {
+        Location {
+            zoom: ZoomLevel::Logic,
+            section: StructureSection::Unstaged,
+            item: None,
+            index: 0,
+            line,
+        }
+    }
+
+    #[test]
+    fn walks_back_and_forward() {
+        let mut jumps = JumpList::default();
+        jumps.push(at(1));
+        jumps.push(at(2));
+        assert_eq!(jumps.back(at(3)), Some(at(2)));
+        assert_eq!(jumps.back(at(2)), Some(at(1)));
+        assert_eq!(jumps.back(at(1)), None);
+        assert_eq!(jumps.forward(), Some(at(2)));
+        assert_eq!(jumps.forward(), Some(at(3)));
+        assert_eq!(jumps.forward(), None);
+    }
+}
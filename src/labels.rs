@@ -0,0 +1,83 @@
+// Putting labels on the PR under review and taking them off
+use crate::github::Label;
+
+/// The repository's labels, narrowed by what has been typed.
+#[derive(Debug, Clone)]
+pub struct LabelPicker {
+    pub labels: Vec<Label>,
+    pub filter: String,
+    // Position within `matches()`
+    pub selected: usize,
+}
+
+impl LabelPicker {
+    #[must_use]
+    pub const fn new(labels: Vec<Label>) -> Self {
+        Self {
+            labels,
+            filter: String::new(),
+            selected: 0,
+        }
+    }
+
+    /// Labels whose name contains the filter, ignoring case.
+    #[must_use]
+    pub fn matches(&self) -> Vec<&Label> {
+        let filter = self.filter.to_lowercase();
+        self.labels
+            .iter()
+            .filter(|l| l.name.to_lowercase().contains(&filter))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn current(&self) -> Option<&Label> {
+        self.matches().get(self.selected).copied()
+    }
+
+    pub fn next(&mut self) {
+        if self.selected + 1 < self.matches().len() {
+            self.selected += 1;
+        }
+    }
+
+    pub const fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.filter.push(c);
+        self.selected = 0;
+    }
+
+    pub fn pop(&mut self) {
+        self.filter.pop();
+        self.selected = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LabelPicker;
+    use crate::github::Label;
+
+    #[test]
+    fn narrows_labels_as_you_type() {
+        let label = |name: &str| Label {
+            name: name.to_string(),
+            color: "ededed".to_string(),
+        };
+        let mut picker = LabelPicker::new(vec![label("bug"), label("Docs"), label("good first")]);
+        picker.push('D');
+        picker.push('o');
+        assert_eq!(picker.current().map(|l| l.name.as_str()), Some("Docs"));
+        picker.next();
+        assert_eq!(picker.selected, 0);
+        picker.pop();
+        picker.next();
+        assert_eq!(
+            picker.current().map(|l| l.name.as_str()),
+            Some("good first")
+        );
+    }
+}
@@ -0,0 +1,197 @@
+// Git LFS pointer files: summarise the object change instead of diffing pointer text
+use crate::utils::format_size;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+const SPEC_PREFIX: &str = "version https://git-lfs.github.com/spec/";
+
+/// The `oid` and `size` a pointer file stands in for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LfsPointer {
+    // Hex sha256 of the real content
+    pub oid: String,
+    pub size: u64,
+}
+
+impl LfsPointer {
+    /// Reads the `oid sha256:` and `size` keys of pointer text.
+    #[must_use]
+    pub fn parse<'a>(lines: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        let (mut oid, mut size) = (None, None);
+        for line in lines {
+            let line = line.trim();
+            if let Some(hex) = line.strip_prefix("oid sha256:") {
+                oid = Some(hex.to_string());
+            } else if let Some(bytes) = line.strip_prefix("size ") {
+                size = bytes.parse().ok();
+            }
+        }
+        Some(Self {
+            oid: oid?,
+            size: size?,
+        })
+    }
+
+    /// Where `git lfs` keeps the object under `git_dir` once downloaded.
+    #[must_use]
+    pub fn storage_path(&self, git_dir: &Path) -> Option<PathBuf> {
+        let (a, b) = (self.oid.get(..2)?, self.oid.get(2..4)?);
+        Some(git_dir.join("lfs/objects").join(a).join(b).join(&self.oid))
+    }
+}
+
+/// What the Logic view shows instead of a pointer diff.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LfsChange {
+    // `None` when the file was added / deleted
+    pub old: Option<LfsPointer>,
+    pub new: Option<LfsPointer>,
+    // Local object store facts; `None` for PR sources
+    pub local: Option<LocalObject>,
+}
+
+/// The new object as found in the local LFS store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalObject {
+    pub installed: bool,
+    // `None` when the object is not downloaded
+    pub content: Option<&'static str>,
+}
+
+impl LfsChange {
+    /// Recognises a pointer-file patch (as glim or `gh pr diff` print it).
+    #[must_use]
+    pub fn from_patch(lines: &[String]) -> Option<Self> {
+        let body: Vec<&str> = lines
+            .iter()
+            .skip_while(|l| !l.trim_start().starts_with("@@"))
+            .skip(1)
+            .map(String::as_str)
+            .collect();
+        if !body
+            .iter()
+            .any(|l| l.get(1..).is_some_and(|t| t.starts_with(SPEC_PREFIX)))
+        {
+            return None;
+        }
+        let side = |skip: char| {
+            LfsPointer::parse(
+                body.iter()
+                    .filter(|l| !l.starts_with(skip) && !l.starts_with("@@"))
+                    .map(|l| l.get(1..).unwrap_or_default()),
+            )
+        };
+        Some(Self {
+            old: side('+'),
+            new: side('-'),
+            local: None,
+        })
+    }
+
+    /// Looks the new object up in the repository's LFS store.
+    #[must_use]
+    pub fn with_store(mut self, git_dir: &Path) -> Self {
+        let content = self
+            .new
+            .as_ref()
+            .and_then(|p| p.storage_path(git_dir))
+            .and_then(|path| sniff(&path));
+        self.local = Some(LocalObject {
+            installed: lfs_installed(),
+            content,
+        });
+        self
+    }
+
+    /// Display lines for the summary panel.
+    #[must_use]
+    pub fn lines(&self) -> Vec<String> {
+        let title = match (&self.old, &self.new) {
+            (None, Some(_)) => "LFS object added",
+            (Some(_), None) => "LFS object removed",
+            _ => "LFS object changed",
+        };
+        let size = |p: &Option<LfsPointer>| {
+            p.as_ref()
+                .map_or_else(|| "-".to_string(), |p| format_size(p.size))
+        };
+        let oid = |p: &Option<LfsPointer>| {
+            p.as_ref()
+                .map_or_else(|| "-".to_string(), |p| format!("sha256:{:.12}…", p.oid))
+        };
+        let mut out = vec![
+            format!("{title} (size {} → {})", size(&self.old), size(&self.new)),
+            String::new(),
+            format!("Object before: {}", oid(&self.old)),
+            format!("Object after:  {}", oid(&self.new)),
+        ];
+        match &self.local {
+            Some(LocalObject {
+                content: Some(kind),
+                ..
+            }) => out.push(format!("Content:       {kind}, downloaded")),
+            Some(LocalObject {
+                installed: true, ..
+            }) if self.new.is_some() => {
+                out.push("Content:       not downloaded (git lfs pull)".to_string());
+            }
+            Some(LocalObject {
+                installed: false, ..
+            }) => out.push(
+                "Content:       Git LFS is not installed; only the pointer is here".to_string(),
+            ),
+            _ => {}
+        }
+        out
+    }
+}
+
+/// Whether `git lfs` can be run; checked once per process.
+fn lfs_installed() -> bool {
+    static INSTALLED: OnceLock<bool> = OnceLock::new();
+    *INSTALLED.get_or_init(|| {
+        Command::new("git")
+            .args(["lfs", "version"])
+            .output()
+            .is_ok_and(|o| o.status.success())
+    })
+}
+
+/// `binary` or `text` for a downloaded object, using git's NUL heuristic.
+fn sniff(path: &Path) -> Option<&'static str> {
+    let mut head = Vec::new();
+    File::open(path)
+        .ok()?
+        .take(8000)
+        .read_to_end(&mut head)
+        .ok()?;
+    Some(if head.contains(&0) { "binary" } else { "text" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LfsChange;
+
+    #[test]
+    fn summarises_pointer_patches() {
+        let lines: Vec<String> = [
+            " diff --git a/logo.png b/logo.png",
+            " @@ -1,3 +1,3 @@",
+            " version https://git-lfs.github.com/spec/v1",
+            "-oid sha256:aaaa1111",
+            "-size 1024",
+            "+oid sha256:bbbb2222",
+            "+size 2048",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        let change = LfsChange::from_patch(&lines).expect("pointer patch");
+        assert_eq!(change.old.map(|p| p.size), Some(1024));
+        assert_eq!(change.new.map(|p| p.oid), Some("bbbb2222".to_string()));
+        assert_eq!(LfsChange::from_patch(&lines[..2]), None);
+    }
+}
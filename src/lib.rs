@@ -1,11 +1,53 @@
+pub mod actions;
+pub mod api_surface;
 pub mod app;
+pub mod bench;
+pub mod branches;
+pub mod cargo;
+pub mod churn;
+pub mod clone;
+pub mod commit;
+pub mod config;
+pub mod conflict;
+pub mod diff;
 pub mod event;
+pub mod export;
 pub mod github;
 pub mod handlers;
+pub mod handoff;
+pub mod highlight;
+pub mod history;
+pub mod jumps;
+pub mod labels;
+pub mod lfs;
+pub mod macros;
+pub mod markdown;
+pub mod mechanical;
+pub mod multiplexer;
+pub mod notes;
+pub mod onboarding;
+pub mod packages;
+pub mod panels;
+pub mod people;
+pub mod pr_cache;
+pub mod pr_picker;
+pub mod review;
+pub mod safe_mode;
+pub mod search;
 pub mod semantics;
+pub mod server;
+pub mod session;
+pub mod spelling;
+pub mod staging;
+pub mod stats;
+pub mod suggestions;
+pub mod symbol_index;
+pub mod theme;
 pub mod tui;
 pub mod ui;
 pub mod utils;
+pub mod verdicts;
+pub mod watcher;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,123 @@
+// Vim-style keyboard macros: `Q<reg>` records, `Q` stops, `@<reg>` replays
+use crossterm::event::{KeyCode, KeyEvent};
+use std::collections::HashMap;
+
+// A macro key waiting for its register
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pending {
+    Record,
+    Replay,
+}
+
+/// What the event handler should do with a key after the recorder has seen it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MacroStep {
+    /// Not a macro key; dispatch it as usual
+    Pass,
+    /// Swallowed by the recorder (register prompt, start/stop)
+    Consumed,
+    /// Dispatch these keys in order, bypassing the recorder
+    Replay(Vec<KeyEvent>),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MacroRecorder {
+    registers: HashMap<char, Vec<KeyEvent>>,
+    pending: Option<Pending>,
+    recording: Option<(char, Vec<KeyEvent>)>,
+    last_replayed: Option<char>,
+}
+
+impl MacroRecorder {
+    /// Register currently being recorded into, for the status bar.
+    #[must_use]
+    pub fn recording(&self) -> Option<char> {
+        self.recording.as_ref().map(|(register, _)| *register)
+    }
+
+    /// Feeds one key press through the recorder.
+    ///
+    /// `normal_mode` is false while typing into the search bar, where `Q` and `@`
+    /// are ordinary characters.
+    pub fn intercept(&mut self, key: KeyEvent, normal_mode: bool) -> MacroStep {
+        match self.pending.take() {
+            Some(Pending::Record) => {
+                if let KeyCode::Char(register) = key.code
+                    && register.is_ascii_alphanumeric()
+                {
+                    self.recording = Some((register, Vec::new()));
+                }
+                return MacroStep::Consumed;
+            }
+            Some(Pending::Replay) => {
+                let register = match key.code {
+                    KeyCode::Char('@') => self.last_replayed,
+                    KeyCode::Char(c) => Some(c),
+                    _ => None,
+                };
+                let Some(keys) = register.and_then(|r| self.registers.get(&r)).cloned() else {
+                    return MacroStep::Consumed;
+                };
+                self.last_replayed = register;
+                // Expand inline so a macro that replays another never recurses
+                if let Some((_, recorded)) = &mut self.recording {
+                    recorded.extend(keys.iter().copied());
+                }
+                return MacroStep::Replay(keys);
+            }
+            None => {}
+        }
+
+        match key.code {
+            KeyCode::Char('Q') if normal_mode => {
+                if let Some((register, keys)) = self.recording.take() {
+                    self.registers.insert(register, keys);
+                } else {
+                    self.pending = Some(Pending::Record);
+                }
+                MacroStep::Consumed
+            }
+            KeyCode::Char('@') if normal_mode => {
+                self.pending = Some(Pending::Replay);
+                MacroStep::Consumed
+            }
+            _ => {
+                if let Some((_, recorded)) = &mut self.recording {
+                    recorded.push(key);
+                }
+                MacroStep::Pass
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MacroRecorder, MacroStep};
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    fn press(recorder: &mut MacroRecorder, c: char) -> MacroStep {
+        recorder.intercept(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE), true)
+    }
+
+    #[test]
+    fn records_and_replays_registers() {
+        let mut recorder = MacroRecorder::default();
+        for c in ['Q', 'a'] {
+            assert_eq!(press(&mut recorder, c), MacroStep::Consumed);
+        }
+        assert_eq!(recorder.recording(), Some('a'));
+        assert_eq!(press(&mut recorder, 'j'), MacroStep::Pass);
+        assert_eq!(press(&mut recorder, '+'), MacroStep::Pass);
+        assert_eq!(press(&mut recorder, 'Q'), MacroStep::Consumed);
+        assert_eq!(recorder.recording(), None);
+
+        press(&mut recorder, '@');
+        let MacroStep::Replay(keys) = press(&mut recorder, 'a') else {
+            panic!("expected a replay");
+        };
+        assert_eq!(keys.len(), 2);
+        press(&mut recorder, '@');
+        assert!(matches!(press(&mut recorder, '@'), MacroStep::Replay(_)));
+    }
+}
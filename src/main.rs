@@ -1,49 +1,338 @@
 use anyhow::Result;
-use clap::Parser;
-use glim::app::App;
+use clap::{Parser, Subcommand};
+use git2::Repository;
+use glim::app::{App, ZoomLevel};
+use glim::bench::FixtureSize;
+use glim::config::{Config, Overrides};
+use glim::event::AppEvent;
+use glim::github::GitHubClient;
+use glim::onboarding::{Capabilities, Onboarding};
+use glim::server::Endpoint;
+use glim::session::Autosave;
+use glim::theme::ColorMode;
 use glim::tui::Tui;
+use glim::watcher::RepoWatcher;
+use serde_json::json;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Path to the git repository to analyze
     #[arg(default_value = ".")]
     path: PathBuf,
+
+    /// View to open on start (overrides `start_view` in config.toml)
+    #[arg(long, value_enum)]
+    view: Option<ZoomLevel>,
+
+    /// Colour scheme (overrides `color_mode` in config.toml)
+    #[arg(long, value_enum)]
+    color_mode: Option<ColorMode>,
+
+    /// Diff the working tree against merge-base(HEAD, BRANCH) instead of HEAD
+    #[arg(long, value_name = "BRANCH")]
+    base: Option<String>,
+
+    /// Only scan changed paths matching GLOB (repeatable; adds to `scan.include`)
+    #[arg(long, value_name = "GLOB")]
+    include: Vec<String>,
+
+    /// Skip changed paths matching GLOB, e.g. `node_modules` (repeatable; adds to `scan.exclude`)
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Pick one of the open PRs to review (of PATH when given as `owner/repo` or a URL)
+    #[arg(long)]
+    prs: bool,
+
+    /// Lay profile NAME from config.toml over the settings (switch at runtime with `P`)
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Open at FILE[:LINE], or move an already running glim on this repository there
+    #[arg(long, value_name = "FILE[:LINE]")]
+    select: Option<String>,
+
+    /// Fetch PRs from GitHub even when a cached copy is current
+    #[arg(long)]
+    refresh: bool,
+
+    /// Take over the verdicts and place of a colleague's handoff bundle (exported with `H`)
+    #[arg(long, value_name = "FILE")]
+    import: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Time the analysis pipeline on generated fixtures
+    #[command(hide = true)]
+    Bench {
+        /// Number of changed files in the fixture repository
+        #[arg(long, default_value_t = 200)]
+        files: usize,
+        /// Functions per fixture file
+        #[arg(long, default_value_t = 50)]
+        functions: usize,
+        /// Runs per stage; the median is reported
+        #[arg(long, default_value_t = 10)]
+        iterations: usize,
+    },
+    /// Open a generated changeset, to reproduce performance issues on a shared workload
+    #[command(hide = true)]
+    Stress {
+        /// Number of changed files
+        #[arg(long, default_value_t = 1000)]
+        files: usize,
+        /// Lines per file; about a quarter of them change
+        #[arg(long, default_value_t = 400)]
+        lines: usize,
+    },
+    /// Work through the open PRs awaiting your review, one by one
+    Queue {
+        /// Repositories (`owner/repo`) to look in, besides `queue_repos` in config.toml
+        repos: Vec<String>,
+    },
+    /// Serve the changeset model as JSON-RPC over a local socket, for editor plugins
+    Serve {
+        /// Path to the git repository to analyze
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Socket to listen on [default: glim-<pid>.sock in the temp directory]
+        #[arg(long)]
+        socket: Option<PathBuf>,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Parse command line arguments
-    let args = Args::parse();
+    let mut args = Args::parse();
 
     // Initialize logging
     tracing_subscriber::fmt::init();
 
+    // Load configuration before touching the terminal so errors stay readable
+    let first_run = !Config::path().exists();
+    let mut config = load_config(&mut args)?;
+
+    // The stress repository lives as long as the session looking at it, and goes with it on
+    // every way out
+    let mut stress_repo = None;
+    let queue = match args.command {
+        Some(Command::Queue { repos }) => Some(repos),
+        Some(Command::Stress { files, lines }) => {
+            let repo = glim::bench::stress_repo(FixtureSize::with_lines(files, lines))?;
+            args.path = stress_repo.insert(repo).path().to_path_buf();
+            None
+        }
+        Some(Command::Bench {
+            files,
+            functions,
+            iterations,
+        }) => {
+            let size = FixtureSize { files, functions };
+            print!("{}", glim::bench::run(size, iterations)?);
+            return Ok(());
+        }
+        Some(Command::Serve { path, socket }) => return serve(path, socket, config),
+        None => None,
+    };
+
+    // An editor keybinding runs `glim --select`; a running session takes it over
+    let socket = glim::server::instance_socket(&args.path);
+    let selection = args.select.as_deref().map(glim::server::parse_selection);
+    if let (Some(socket), Some((file, line))) = (&socket, &selection)
+        && glim::server::is_listening(socket)
+        && let Some(workdir) = Repository::discover(&args.path)
+            .ok()
+            .and_then(|r| r.workdir().map(Path::to_path_buf))
+    {
+        let path = glim::server::repo_relative(file, &workdir);
+        glim::server::request(socket, "reveal", &json!({ "path": path, "line": line }))?;
+        println!("glim: showing {path} in the running session");
+        return Ok(());
+    }
+
     // Initialize the terminal interface
     let backend = ratatui::backend::CrosstermBackend::new(io::stdout());
     let terminal = ratatui::Terminal::new(backend)?;
-    let mut tui = Tui::new(terminal);
+    let mut tui = Tui::new(terminal, config.terminal_integration);
 
+    glim::tui::install_panic_hook();
     tui.enter()?;
+    GitHubClient::set_interactive();
 
     // Create application state with the specified path
-    let mut app = App::new(args.path);
+    let mut app = if let Some(repos) = queue {
+        config.queue_repos.extend(repos);
+        App::with_review_queue(config)
+    } else if args.prs {
+        let repo = (args.path != Path::new(".")).then(|| {
+            let path = args.path.to_string_lossy();
+            glim::github::repo_url_slug(&path).unwrap_or_else(|| path.to_string())
+        });
+        App::with_pr_picker(repo, config)
+    } else {
+        App::with_config(args.path, config)
+    };
+    if first_run {
+        app.onboarding = Some(Onboarding::new(Capabilities::detect(), app.config.clone()));
+    }
+    start_at(
+        &mut app,
+        selection,
+        args.import.as_deref(),
+        socket.as_deref(),
+    );
+    let mut autosave = Autosave::new();
+
+    // Later `glim --select` calls (and editor plugins) reach this session through the socket
+    let endpoint = socket.and_then(|socket| Endpoint::bind(&socket).ok());
+
+    // Watch the repository so edits made elsewhere show up without a manual refresh
+    let started = app
+        .local_root()
+        .filter(|_| app.config.watch)
+        .map(RepoWatcher::new)
+        .transpose();
+    let mut watcher = started.unwrap_or_else(|e| {
+        app.notice = Some(format!("Not watching for changes: {e}"));
+        None
+    });
 
-    // Main event loop
+    let result = event_loop(
+        &mut tui,
+        &mut app,
+        endpoint.as_ref(),
+        &mut autosave,
+        watcher.as_mut(),
+    );
+
+    // Exit gracefully; the terminal and any safe-mode stash come back even when the loop failed
+    autosave.finish(&app);
+    let exited = tui.exit();
+    let restored = app.restore_safe_mode().map_err(|e| {
+        e.context("Could not restore the safe mode stash; recover it with `git stash pop`")
+    });
+    result.and(exited).and(restored)
+}
+
+/// Draws and handles input until the user quits.
+fn event_loop(
+    tui: &mut Tui<ratatui::backend::CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    endpoint: Option<&Endpoint>,
+    autosave: &mut Autosave,
+    mut watcher: Option<&mut RepoWatcher>,
+) -> Result<()> {
     loop {
-        tui.draw(&app)?;
+        if let Some(waited) = GitHubClient::take_retry_wait() {
+            app.notice.get_or_insert_with(|| {
+                format!("GitHub was busy; waited {}s to retry", waited.as_secs())
+            });
+        }
+        tui.draw(app)?;
+        tui.report(&app.terminal_title(), app.local_root())?;
 
         // Handle events
         if let Some(event) = tui.next_event()
-            && !glim::handlers::handle_event(&mut app, &event)
+            && !glim::handlers::handle_event(app, &event)
         {
             break;
         }
+
+        if let Some(endpoint) = endpoint {
+            endpoint.handle_pending(app);
+        }
+
+        autosave.tick(app);
+        app.poll_index();
+        app.poll_history_fetch();
+        app.poll_history();
+
+        // Rescan in the loop, never while drawing, once the watcher's batch settles
+        match watcher.as_mut().and_then(|w| w.poll()) {
+            Some(AppEvent::FilesChanged(paths)) => app.on_files_changed(&paths),
+            Some(AppEvent::WatchFailed(e)) => {
+                app.notice = Some(format!("Watching for changes failed: {e}"));
+            }
+            _ => {}
+        }
+
+        // Hand the terminal to the editor, then pick up any edits
+        if let Some((path, line)) = app.pending_editor.take() {
+            tui.exit()?;
+            let result = glim::utils::open_in_editor(&path, line);
+            tui.enter()?;
+            tui.clear()?;
+            if let Err(e) = result {
+                app.notice = Some(format!("Failed to launch editor: {e}"));
+            }
+            app.refresh_local();
+        }
     }
+    Ok(())
+}
+
+/// Moves a new session to the `--select`ed file or the place an imported handoff bundle
+/// stopped at, or else offers back the session an earlier run left behind.
+fn start_at(
+    app: &mut App,
+    selection: Option<(PathBuf, Option<usize>)>,
+    import: Option<&Path>,
+    socket: Option<&Path>,
+) {
+    if let Some((file, line)) = selection
+        && let Some(workdir) = app.local_root().map(Path::to_path_buf)
+    {
+        let path = glim::server::repo_relative(&file, &workdir);
+        if !app.reveal(&path, line) {
+            app.notice = Some(format!("{path} has no changes"));
+        }
+    }
+    if let Some(bundle) = import {
+        app.import_handoff(bundle);
+    }
+    // A live session on this repository would look like an interrupted one; only offer
+    // recovery when nothing answers on its socket
+    if app.onboarding.is_none()
+        && app.notice.is_none()
+        && !socket.is_some_and(glim::server::is_listening)
+    {
+        app.offer_session_restore();
+    }
+}
+
+/// The config file with the chosen profile and the command-line overrides laid over it.
+fn load_config(args: &mut Args) -> Result<Config> {
+    let mut config = Config::load()?;
+    if let Some(name) = &args.profile {
+        config = config.with_profile(name)?;
+    }
+    // Kept apart from the file's settings, so switching profiles keeps them and saving skips them
+    let mut config = config.with_overrides(&Overrides {
+        start_view: args.view,
+        color_mode: args.color_mode,
+        base_branch: args.base.take(),
+        include: std::mem::take(&mut args.include),
+        exclude: std::mem::take(&mut args.exclude),
+    })?;
+    config.refresh = args.refresh;
+    Ok(config)
+}
 
-    // Exit gracefully
-    tui.exit()?;
+/// Serves `path` over the socket instead of starting the TUI.
+fn serve(path: PathBuf, socket: Option<PathBuf>, config: Config) -> Result<()> {
+    let socket = socket
+        .unwrap_or_else(|| std::env::temp_dir().join(format!("glim-{}.sock", std::process::id())));
+    let endpoint = Endpoint::bind(&socket)?;
+    let mut app = App::with_config(path, config);
+    println!("glim: listening on {}", endpoint.socket().display());
+    endpoint.run(&mut app);
     Ok(())
 }
@@ -0,0 +1,333 @@
+// Markdown (as in PR descriptions) turned into styled lines: headings, lists, task boxes,
+// quotes, code and the common inline marks; and the heading outline of changed docs
+use crate::semantics::SymbolChange;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Styled lines for `text`; runs of blank lines collapse into one and HTML comments (PR
+/// template hints) are dropped.
+#[must_use]
+pub fn render(text: &str) -> Vec<Line<'static>> {
+    let code = Style::default().fg(Color::Yellow);
+    let mut lines: Vec<Line<'static>> = Vec::new();
+    let mut in_code = false;
+    for raw in strip_comments(text).lines() {
+        let line = raw.trim_end();
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            lines.push(Line::styled(format!("  {line}"), code));
+            continue;
+        }
+        if trimmed.is_empty() {
+            if lines.last().is_some_and(|l| l.width() > 0) {
+                lines.push(Line::default());
+            }
+            continue;
+        }
+        lines.push(block(line));
+    }
+    while lines.last().is_some_and(|l| l.width() == 0) {
+        lines.pop();
+    }
+    lines
+}
+
+/// One line outside code blocks: a heading, rule, list item, quote or paragraph text.
+fn block(line: &str) -> Line<'static> {
+    let trimmed = line.trim_start();
+    let indent = " ".repeat(line.len() - trimmed.len());
+    if let Some((level, title)) = atx_heading(trimmed) {
+        let mut style = Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD);
+        if level == 1 {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        return Line::from(inline(title, style));
+    }
+    if trimmed.len() >= 3
+        && ['-', '*', '_']
+            .into_iter()
+            .any(|mark| trimmed.chars().all(|c| c == ' ' || c == mark))
+    {
+        return Line::styled("─".repeat(40), Style::default().fg(Color::DarkGray));
+    }
+    let (marker, rest) = list_marker(trimmed);
+    if let Some(marker) = marker {
+        let mut spans = vec![Span::styled(
+            format!("{indent}{marker}"),
+            Style::default().fg(Color::Yellow),
+        )];
+        spans.extend(inline(rest, Style::default()));
+        return Line::from(spans);
+    }
+    if let Some(quote) = trimmed.strip_prefix('>') {
+        let style = Style::default()
+            .fg(Color::Gray)
+            .add_modifier(Modifier::ITALIC);
+        let mut spans = vec![Span::styled(format!("{indent}│ "), style)];
+        spans.extend(inline(quote.trim_start(), style));
+        return Line::from(spans);
+    }
+    let mut spans = inline(trimmed, Style::default());
+    if !indent.is_empty() {
+        spans.insert(0, Span::raw(indent));
+    }
+    Line::from(spans)
+}
+
+/// The bullet, task box or number shown for a list item, and the item's text.
+fn list_marker(text: &str) -> (Option<String>, &str) {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(item) = text.strip_prefix(bullet) {
+            for (task, marker) in [("[ ] ", "☐ "), ("[x] ", "☑ "), ("[X] ", "☑ ")] {
+                if let Some(item) = item.strip_prefix(task) {
+                    return (Some(marker.to_string()), item);
+                }
+            }
+            return (Some("• ".to_string()), item);
+        }
+    }
+    let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+    if digits > 0
+        && let Some(item) = text[digits..].strip_prefix(". ")
+    {
+        return (Some(format!("{}. ", &text[..digits])), item);
+    }
+    (None, text)
+}
+
+/// Code spans, bold, italics, and links (shown as their text) within one line.
+fn inline(text: &str, base: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    let mut previous = ' ';
+    while let Some(c) = rest.chars().next() {
+        let marked = match c {
+            '`' => delimited(rest, "`").map(|(inner, len)| {
+                (
+                    inner,
+                    base.fg(Color::Yellow).remove_modifier(Modifier::BOLD),
+                    len,
+                )
+            }),
+            '*' | '_' if rest.len() > 1 && rest[1..].starts_with(c) => delimited(rest, &rest[..2])
+                .map(|(inner, len)| (inner, base.add_modifier(Modifier::BOLD), len)),
+            // `snake_case` words are not emphasis
+            '*' | '_' if !previous.is_alphanumeric() => delimited(rest, &rest[..1])
+                .map(|(inner, len)| (inner, base.add_modifier(Modifier::ITALIC), len)),
+            '[' | '!' => link(rest).map(|(inner, len)| {
+                (
+                    inner,
+                    base.fg(Color::Blue).add_modifier(Modifier::UNDERLINED),
+                    len,
+                )
+            }),
+            _ => None,
+        };
+        if let Some((inner, style, len)) = marked {
+            if !plain.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut plain), base));
+            }
+            spans.push(Span::styled(inner, style));
+            rest = &rest[len..];
+            previous = ' ';
+            continue;
+        }
+        plain.push(c);
+        previous = c;
+        rest = &rest[c.len_utf8()..];
+    }
+    if !plain.is_empty() {
+        spans.push(Span::styled(plain, base));
+    }
+    spans
+}
+
+/// Text between `mark` at the start of `text` and its next occurrence, plus the bytes the
+/// whole run takes; `None` when unclosed or empty.
+fn delimited(text: &str, mark: &str) -> Option<(String, usize)> {
+    let body = &text[mark.len()..];
+    let end = body.find(mark).filter(|&end| end > 0)?;
+    Some((body[..end].to_string(), mark.len() * 2 + end))
+}
+
+/// `[text](url)` or `![alt](url)` at the start of `text`: the text to show (images as
+/// `[image: alt]`) and the bytes the link takes.
+fn link(text: &str) -> Option<(String, usize)> {
+    let image = text.starts_with('!');
+    let start = usize::from(image);
+    let body = text[start..].strip_prefix('[')?;
+    let close = body.find("](")?;
+    let end = body[close..].find(')')? + close;
+    let label = &body[..close];
+    let shown = if image {
+        format!("[image: {label}]")
+    } else {
+        label.to_string()
+    };
+    Some((shown, start + 1 + end + 1))
+}
+
+/// Level and title of a `## Title` heading line, leading spaces already trimmed.
+fn atx_heading(trimmed: &str) -> Option<(usize, &str)> {
+    let hashes = trimmed.bytes().take_while(|&b| b == b'#').count();
+    ((1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' '))
+        .then(|| (hashes, trimmed[hashes..].trim()))
+}
+
+/// Headings of a Markdown document as symbols of kind `h1` to `h6`, each spanning its
+/// section: up to the next heading of the same or a higher level.
+#[must_use]
+pub fn outline(text: &str) -> Vec<SymbolChange> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut headings: Vec<(usize, String, usize)> = Vec::new();
+    let mut fence: Option<&str> = None;
+    // YAML front matter, whose closing `---` would read as a heading underline
+    let mut i = if lines.first().is_some_and(|l| l.trim_end() == "---") {
+        lines
+            .iter()
+            .skip(1)
+            .position(|l| l.trim_end() == "---")
+            .map_or(0, |end| end + 2)
+    } else {
+        0
+    };
+    while i < lines.len() {
+        let trimmed = lines[i].trim();
+        if let Some(open) = fence {
+            if trimmed.starts_with(open) {
+                fence = None;
+            }
+        } else if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+        } else if let Some((level, title)) = atx_heading(trimmed) {
+            let title = title.trim_end_matches('#').trim_end();
+            headings.push((level, title.to_string(), i));
+        } else if let Some(level) = lines.get(i + 1).and_then(|next| underline_level(next))
+            && !trimmed.is_empty()
+            && !trimmed.starts_with(['-', '*', '>', '|', '<'])
+        {
+            headings.push((level, trimmed.to_string(), i));
+            i += 1;
+        }
+        i += 1;
+    }
+    headings
+        .iter()
+        .enumerate()
+        .map(|(n, (level, title, line))| {
+            let end = headings[n + 1..]
+                .iter()
+                .find(|(next, _, _)| next <= level)
+                .map_or(lines.len(), |(_, _, next_line)| *next_line);
+            SymbolChange {
+                name: title.clone(),
+                kind: format!("h{level}"),
+                start_line: line + 1,
+                end_line: end.max(line + 1),
+                complexity: None,
+            }
+        })
+        .collect()
+}
+
+/// Level of a setext heading underlined by `line` (`===` or `---`).
+fn underline_level(line: &str) -> Option<usize> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() || line.len() - line.trim_start().len() > 3 {
+        return None;
+    }
+    if trimmed.bytes().all(|b| b == b'=') {
+        Some(1)
+    } else if trimmed.bytes().all(|b| b == b'-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// `text` without `<!-- ... -->` comments, which may span lines.
+fn strip_comments(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("<!--") {
+        out.push_str(&rest[..start]);
+        rest = rest[start..]
+            .find("-->")
+            .map_or("", |end| &rest[start + end + 3..]);
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{outline, render};
+    use ratatui::style::Modifier;
+
+    fn text(line: &ratatui::text::Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn renders_pr_description() {
+        let lines = render(
+            "## Summary\r\n<!-- describe your change -->\nAdds `glim queue`, see [docs](https://x.io).\n\n\n- [x] Tests\n- [ ] Docs\n  * nested_item\n1. first\n> note\n```\nlet **x** = 1;\n```\n---",
+        );
+        let texts: Vec<String> = lines.iter().map(text).collect();
+        assert_eq!(
+            texts,
+            [
+                "Summary",
+                "",
+                "Adds glim queue, see docs.",
+                "",
+                "☑ Tests",
+                "☐ Docs",
+                "  • nested_item",
+                "1. first",
+                "│ note",
+                "  let **x** = 1;",
+                &"─".repeat(40),
+            ]
+        );
+        assert!(
+            lines[0].spans[0]
+                .style
+                .add_modifier
+                .contains(Modifier::BOLD)
+        );
+        assert!(
+            lines[2].spans[3]
+                .style
+                .add_modifier
+                .contains(Modifier::UNDERLINED)
+        );
+    }
+
+    #[test]
+    fn outlines_headings_with_their_sections() {
+        let doc = "---\ntitle: x\n---\n# Guide\nintro\n## Install\n```sh\n# not a heading\n```\n\
+                   Usage\n-----\nrun it\n## Notes ##\n";
+        let outline: Vec<(String, String, usize, usize)> = outline(doc)
+            .into_iter()
+            .map(|s| (s.kind, s.name, s.start_line, s.end_line))
+            .collect();
+        let expected = [
+            ("h1", "Guide", 4, 13),
+            ("h2", "Install", 6, 9),
+            ("h2", "Usage", 10, 12),
+            ("h2", "Notes", 13, 13),
+        ];
+        assert_eq!(
+            outline,
+            expected.map(|(k, n, a, b)| (k.to_string(), n.to_string(), a, b))
+        );
+    }
+}
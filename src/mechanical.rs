@@ -0,0 +1,112 @@
+// Mechanical changes: the same edit repeated across many files, e.g. an import rename
+use std::collections::{BTreeMap, HashMap};
+use std::hash::BuildHasher;
+
+// Edits changing more lines than this are treated as real work, never folded
+const MAX_PATTERN_LINES: usize = 40;
+
+/// Files whose diffs make the same edit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cluster {
+    // The shared edit, as produced by `pattern`
+    pub key: String,
+    // Sorted; the first one stands in for the rest while the cluster is folded
+    pub paths: Vec<String>,
+}
+
+impl Cluster {
+    #[must_use]
+    pub fn representative(&self) -> &str {
+        &self.paths[0]
+    }
+}
+
+/// The changed lines of a file diff, hunk by hunk and without indentation or line
+/// numbers; `None` when nothing changed or too much did to count as mechanical.
+#[must_use]
+pub fn pattern(diff: &[String]) -> Option<String> {
+    let mut lines: Vec<&str> = Vec::new();
+    let mut changed = 0;
+    let mut in_hunk = false;
+    for line in diff {
+        // Local diffs carry their headers behind a space, GitHub's as they are
+        if line.trim_start().starts_with("@@") {
+            in_hunk = true;
+            lines.push("@@");
+            continue;
+        }
+        if !in_hunk {
+            continue;
+        }
+        if line.starts_with(['+', '-']) {
+            changed += 1;
+            let (sign, content) = line.split_at(1);
+            lines.push(sign);
+            lines.push(content.trim());
+        }
+    }
+    (changed > 0 && changed <= MAX_PATTERN_LINES).then(|| lines.join("\n"))
+}
+
+/// Groups of at least `min_files` files making the same edit, ordered by representative.
+#[must_use]
+pub fn clusters<S: BuildHasher>(
+    diffs: &HashMap<String, Vec<String>, S>,
+    min_files: usize,
+) -> Vec<Cluster> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (path, diff) in diffs {
+        if let Some(key) = pattern(diff) {
+            groups.entry(key).or_default().push(path.clone());
+        }
+    }
+    let mut clusters: Vec<Cluster> = groups
+        .into_iter()
+        .filter(|(_, paths)| paths.len() >= min_files.max(2))
+        .map(|(key, mut paths)| {
+            paths.sort();
+            Cluster { key, paths }
+        })
+        .collect();
+    clusters.sort_by(|a, b| a.paths.cmp(&b.paths));
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::clusters;
+    use std::collections::HashMap;
+
+    fn diff(hunk: &str, lines: &[&str]) -> Vec<String> {
+        [" diff --git a/x b/x", " --- a/x", " +++ b/x", hunk]
+            .iter()
+            .chain(lines)
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn groups_files_making_the_same_edit() {
+        let rename = ["-use crate::old::Thing;", "+use crate::new::Thing;", " "];
+        let diffs = HashMap::from([
+            ("src/b.rs".to_string(), diff(" @@ -1,2 +1,2 @@", &rename)),
+            ("src/a.rs".to_string(), diff(" @@ -3,2 +3,2 @@", &rename)),
+            (
+                "src/c.rs".to_string(),
+                diff(
+                    " @@ -9,2 +9,2 @@",
+                    &["-    use crate::old::Thing;", "+    use crate::new::Thing;"],
+                ),
+            ),
+            (
+                "src/d.rs".to_string(),
+                diff(" @@ -1 +1 @@", &["-fn old() {}", "+fn new() {}"]),
+            ),
+        ]);
+        let found = clusters(&diffs, 3);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].paths, ["src/a.rs", "src/b.rs", "src/c.rs"]);
+        assert_eq!(found[0].representative(), "src/a.rs");
+        assert_eq!(clusters(&diffs, 4), []);
+    }
+}
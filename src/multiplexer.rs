@@ -0,0 +1,140 @@
+// tmux / zellij helpers: open the selected file in a pane next to glim, or hand it to an
+// editor already running in another pane
+use crate::config::PaneConfig;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// The terminal multiplexer glim runs inside, detected from the variables it exports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Multiplexer {
+    Tmux,
+    Zellij,
+}
+
+impl Multiplexer {
+    #[must_use]
+    pub fn detect() -> Option<Self> {
+        if std::env::var_os("TMUX").is_some() {
+            Some(Self::Tmux)
+        } else if std::env::var_os("ZELLIJ").is_some() {
+            Some(Self::Zellij)
+        } else {
+            None
+        }
+    }
+
+    #[allow(clippy::literal_string_with_formatting_args)]
+    const fn default_template(self, command: PaneCommand) -> &'static str {
+        match (self, command) {
+            (Self::Tmux, PaneCommand::Open) => {
+                "tmux split-window -h -c {root} ${EDITOR:-vi} +{line} {path}"
+            }
+            // `{last}` is the pane that was active before glim's
+            (Self::Tmux, PaneCommand::Send) => {
+                "tmux send-keys -t '{last}' Escape ':e +{line} '{path} Enter"
+            }
+            (Self::Zellij, PaneCommand::Open) => {
+                "zellij edit --direction right --line-number {line} {path}"
+            }
+            // Zellij cannot address a pane, so this assumes the editor sits left of glim
+            (Self::Zellij, PaneCommand::Send) => {
+                "zellij action move-focus left && zellij action write 27 \
+                 && zellij action write-chars ':e +{line} '{path} && zellij action write 13"
+            }
+        }
+    }
+}
+
+/// What to do with the selected file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneCommand {
+    /// Open it in an editor in a new pane beside glim
+    Open,
+    /// Send it to the editor in an existing pane
+    Send,
+}
+
+impl PaneCommand {
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Open => "Open in adjacent pane",
+            Self::Send => "Send to editor pane",
+        }
+    }
+}
+
+/// The configured command template for `command`, else the built-in one for the multiplexer
+/// glim runs in; `None` outside tmux and zellij with nothing configured.
+#[must_use]
+pub fn template(config: &PaneConfig, command: PaneCommand) -> Option<String> {
+    let configured = match command {
+        PaneCommand::Open => &config.open,
+        PaneCommand::Send => &config.send,
+    };
+    if configured.is_empty() {
+        Multiplexer::detect().map(|m| m.default_template(command).to_string())
+    } else {
+        Some(configured.clone())
+    }
+}
+
+/// Fills in `{path}`, `{line}` and `{root}`; paths are shell-quoted.
+#[must_use]
+#[allow(clippy::literal_string_with_formatting_args)]
+pub fn expand(template: &str, path: &Path, line: usize, root: &Path) -> String {
+    template
+        .replace("{path}", &shell_quote(&path.to_string_lossy()))
+        .replace("{root}", &shell_quote(&root.to_string_lossy()))
+        .replace("{line}", &line.to_string())
+}
+
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// Runs an expanded template through the shell in `dir`.
+///
+/// # Errors
+/// Returns the command's error output when it cannot start or fails.
+pub fn run(command: &str, dir: &Path) -> Result<(), String> {
+    let output = Command::new("sh")
+        .args(["-c", command])
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("cannot run `{command}`: {e}"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(stderr.lines().next().map_or_else(
+            || format!("`{command}` failed ({})", output.status),
+            str::to_string,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Multiplexer, PaneCommand, expand};
+    use std::path::Path;
+
+    #[test]
+    fn expands_templates_with_quoted_paths() {
+        let template = Multiplexer::Tmux.default_template(PaneCommand::Send);
+        assert_eq!(
+            expand(template, Path::new("/src/it's.rs"), 42, Path::new("/src")),
+            r"tmux send-keys -t '{last}' Escape ':e +42 ''/src/it'\''s.rs' Enter"
+        );
+        assert_eq!(
+            expand(
+                "nvim --server {root}/nvim.sock --remote {path}",
+                Path::new("/r/a b.rs"),
+                1,
+                Path::new("/r")
+            ),
+            "nvim --server '/r'/nvim.sock --remote '/r/a b.rs'"
+        );
+    }
+}
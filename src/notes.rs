@@ -0,0 +1,185 @@
+// Private line notes and bookmarks, kept per repository or PR across runs and never sent
+// anywhere
+use crate::jumps::Location;
+use crate::utils::{fnv1a, get_data_dir};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// A note on one line of a file's diff.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineNote {
+    pub line: usize,
+    // On a removed line, numbered in the old file
+    pub left: bool,
+    pub text: String,
+}
+
+/// Line notes by file path, and bookmarked places, for one repository or PR.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notes {
+    // Repository workdir or PR URL, as for sessions
+    pub source: String,
+    pub files: BTreeMap<String, Vec<LineNote>>,
+    // In the order they were set
+    #[serde(default)]
+    pub bookmarks: Vec<Location>,
+}
+
+impl Notes {
+    /// File the notes for `source` are kept in, under the data directory.
+    #[must_use]
+    pub fn path(source: &str) -> PathBuf {
+        get_data_dir()
+            .join("notes")
+            .join(format!("{:016x}.json", fnv1a(source.as_bytes())))
+    }
+
+    /// The notes left earlier on `source`, or none yet.
+    #[must_use]
+    pub fn load(source: &str) -> Self {
+        fs::read_to_string(Self::path(source))
+            .ok()
+            .and_then(|text| serde_json::from_str::<Self>(&text).ok())
+            .filter(|notes| notes.source == source)
+            .unwrap_or_else(|| Self {
+                source: source.to_string(),
+                ..Self::default()
+            })
+    }
+
+    /// Writes atomically; an empty set removes the file.
+    ///
+    /// # Errors
+    /// Returns an error if the notes directory or file cannot be written.
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path(&self.source);
+        if self.is_empty() {
+            return match fs::remove_file(path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(tmp, path)
+    }
+
+    /// The note on `line` of `path`, if there is one.
+    #[must_use]
+    pub fn get(&self, path: &str, line: usize, left: bool) -> Option<&str> {
+        self.files
+            .get(path)?
+            .iter()
+            .find(|n| n.line == line && n.left == left)
+            .map(|n| n.text.as_str())
+    }
+
+    /// Notes on `path`, in line order.
+    #[must_use]
+    pub fn on(&self, path: &str) -> &[LineNote] {
+        self.files.get(path).map_or(&[], Vec::as_slice)
+    }
+
+    /// Puts `text` on the line, replacing any note there; empty text removes it.
+    pub fn set(&mut self, path: &str, line: usize, left: bool, text: &str) {
+        let notes = self.files.entry(path.to_string()).or_default();
+        notes.retain(|n| n.line != line || n.left != left);
+        if !text.is_empty() {
+            notes.push(LineNote {
+                line,
+                left,
+                text: text.to_string(),
+            });
+            notes.sort_by_key(|n| (n.line, n.left));
+        }
+        if notes.is_empty() {
+            self.files.remove(path);
+        }
+    }
+
+    #[must_use]
+    pub fn note_count(&self) -> usize {
+        self.files.values().map(Vec::len).sum()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.bookmarks.is_empty()
+    }
+
+    /// Bookmarks `location`, or removes the bookmark already on its item and line; `true`
+    /// when it is bookmarked now.
+    pub fn toggle_bookmark(&mut self, location: Location) -> bool {
+        let before = self.bookmarks.len();
+        self.bookmarks.retain(|b| !same_place(b, &location));
+        if self.bookmarks.len() < before {
+            return false;
+        }
+        self.bookmarks.push(location);
+        true
+    }
+
+    /// The bookmark after the one at `location`, wrapping around, with its 1-based position.
+    #[must_use]
+    pub fn next_bookmark(&self, location: &Location) -> Option<(usize, &Location)> {
+        let next = self
+            .bookmarks
+            .iter()
+            .position(|b| same_place(b, location))
+            .map_or(0, |i| (i + 1) % self.bookmarks.len());
+        self.bookmarks.get(next).map(|b| (next + 1, b))
+    }
+
+    /// Adds the notes and bookmarks of `other` that this set has no counterpart for; notes
+    /// already on a line are kept. Returns how many were added.
+    pub fn merge(&mut self, other: &Self) -> usize {
+        let mut added = 0;
+        for (path, notes) in &other.files {
+            for note in notes {
+                if self.get(path, note.line, note.left).is_none() {
+                    self.set(path, note.line, note.left, &note.text);
+                    added += 1;
+                }
+            }
+        }
+        for bookmark in &other.bookmarks {
+            if !self.bookmarks.iter().any(|b| same_place(b, bookmark)) {
+                self.bookmarks.push(bookmark.clone());
+                added += 1;
+            }
+        }
+        added
+    }
+}
+
+/// Bookmarks are told apart by view, item and line; the raw selection index shifts as files
+/// change.
+fn same_place(a: &Location, b: &Location) -> bool {
+    a.zoom == b.zoom && a.item == b.item && a.line == b.line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Notes;
+
+    #[test]
+    fn replaces_and_removes_notes() {
+        let mut notes = Notes::default();
+        notes.set("src/a.rs", 12, false, "check the bounds");
+        notes.set("src/a.rs", 3, true, "why was this removed?");
+        notes.set("src/a.rs", 12, false, "bounds are fine");
+        assert_eq!(notes.note_count(), 2);
+        assert_eq!(notes.get("src/a.rs", 12, false), Some("bounds are fine"));
+        assert_eq!(notes.get("src/a.rs", 12, true), None);
+        assert_eq!(notes.on("src/a.rs")[0].line, 3);
+        notes.set("src/a.rs", 12, false, "");
+        notes.set("src/a.rs", 3, true, "");
+        assert!(notes.is_empty());
+    }
+}
@@ -0,0 +1,109 @@
+// First-run setup: detect terminal/tool capabilities and write an initial config
+use crate::app::ZoomLevel;
+use crate::config::Config;
+use std::process::Command;
+
+/// What the environment supports, probed once at first launch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Capabilities {
+    pub gh: bool,
+    pub truecolor: bool,
+    // Terminals don't report fonts; this is a guess from known Nerd Font-bundling terminals
+    pub nerd_fonts: bool,
+    pub clipboard: bool,
+}
+
+impl Capabilities {
+    #[must_use]
+    pub fn detect() -> Self {
+        let gh = Command::new("gh")
+            .arg("--version")
+            .output()
+            .is_ok_and(|o| o.status.success());
+        let truecolor =
+            std::env::var("COLORTERM").is_ok_and(|v| matches!(v.as_str(), "truecolor" | "24bit"));
+        let nerd_fonts = std::env::var("TERM_PROGRAM")
+            .is_ok_and(|v| matches!(v.as_str(), "WezTerm" | "ghostty"))
+            || std::env::var("NERD_FONT").is_ok();
+        let clipboard = arboard::Clipboard::new().is_ok();
+        Self {
+            gh,
+            truecolor,
+            nerd_fonts,
+            clipboard,
+        }
+    }
+}
+
+/// One adjustable line of the setup screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupChoice {
+    StartView,
+    Truecolor,
+    NerdFonts,
+    Save,
+}
+
+impl SetupChoice {
+    pub const ALL: [Self; 4] = [
+        Self::StartView,
+        Self::Truecolor,
+        Self::NerdFonts,
+        Self::Save,
+    ];
+}
+
+/// State of the first-run screen: detected capabilities and the config being built.
+#[derive(Debug, Clone)]
+pub struct Onboarding {
+    pub capabilities: Capabilities,
+    pub config: Config,
+    pub selected: usize,
+}
+
+impl Onboarding {
+    /// Starts from `config` with the display options matched to what was detected.
+    #[must_use]
+    pub const fn new(capabilities: Capabilities, mut config: Config) -> Self {
+        config.truecolor = capabilities.truecolor;
+        config.nerd_fonts = capabilities.nerd_fonts;
+        Self {
+            capabilities,
+            config,
+            selected: 0,
+        }
+    }
+
+    pub const fn next(&mut self) {
+        if self.selected + 1 < SetupChoice::ALL.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub const fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    #[must_use]
+    pub const fn current(&self) -> SetupChoice {
+        SetupChoice::ALL[self.selected]
+    }
+
+    /// Cycles the highlighted option; returns true when the user picked Save.
+    pub const fn activate(&mut self) -> bool {
+        match self.current() {
+            SetupChoice::StartView => {
+                self.config.start_view = match self.config.start_view {
+                    ZoomLevel::Galaxy => ZoomLevel::Structure,
+                    ZoomLevel::Structure => ZoomLevel::Logic,
+                    ZoomLevel::Logic => ZoomLevel::Galaxy,
+                };
+            }
+            SetupChoice::Truecolor => self.config.truecolor = !self.config.truecolor,
+            SetupChoice::NerdFonts => self.config.nerd_fonts = !self.config.nerd_fonts,
+            SetupChoice::Save => return true,
+        }
+        false
+    }
+}
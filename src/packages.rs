@@ -0,0 +1,193 @@
+// Monorepo packages (npm / yarn / pnpm workspaces and Go modules) as Galaxy modules
+use crate::utils::glob_match;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+// How deep below the root package manifests are looked for
+const MAX_DEPTH: usize = 5;
+// Directories never holding workspace packages of their own
+const SKIPPED_DIRS: &[&str] = &["node_modules", "vendor", "target", "dist", "build"];
+
+/// One package of a JavaScript workspace or one Go module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    pub name: String,
+    // `None` for Go modules, which are versioned by tags
+    pub version: Option<String>,
+    // Repository-relative directory of the manifest
+    pub dir: String,
+}
+
+impl Package {
+    /// Galaxy module name, e.g. `@acme/ui v1.2.0` or `github.com/acme/api`.
+    #[must_use]
+    pub fn label(&self) -> String {
+        self.version
+            .as_ref()
+            .map_or_else(|| self.name.clone(), |v| format!("{} v{v}", self.name))
+    }
+
+    fn contains(&self, path: &str) -> bool {
+        self.dir.is_empty()
+            || path
+                .strip_prefix(&self.dir)
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+}
+
+/// The packages found under a repository root.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Packages {
+    pub packages: Vec<Package>,
+}
+
+impl Packages {
+    /// Finds `package.json` workspace members and `go.mod` modules under `root`.
+    #[must_use]
+    pub fn detect(root: &Path) -> Self {
+        let patterns = workspace_patterns(root);
+        let mut packages = Vec::new();
+        let mut dirs = Vec::new();
+        collect_dirs(root, "", 0, &mut dirs);
+        for dir in dirs {
+            let path = root.join(&dir);
+            if !dir.is_empty()
+                && workspace_member(&patterns, &dir)
+                && let Some(package) = npm_package(&path.join("package.json"), &dir)
+            {
+                packages.push(package);
+            }
+            if let Some(name) = go_module(&path.join("go.mod")) {
+                packages.push(Package {
+                    name,
+                    version: None,
+                    dir,
+                });
+            }
+        }
+        Self { packages }
+    }
+
+    /// The innermost package containing `path`.
+    #[must_use]
+    pub fn package_of(&self, path: &str) -> Option<&Package> {
+        self.packages
+            .iter()
+            .filter(|p| p.contains(path))
+            .max_by_key(|p| p.dir.len())
+    }
+}
+
+/// Directories below `dir` (relative to `root`), skipping hidden and dependency folders.
+fn collect_dirs(root: &Path, dir: &str, depth: usize, out: &mut Vec<String>) {
+    out.push(dir.to_string());
+    if depth == MAX_DEPTH {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(root.join(dir)) else {
+        return;
+    };
+    let mut children: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()))
+        .collect();
+    children.sort();
+    for child in children {
+        let path = if dir.is_empty() {
+            child
+        } else {
+            format!("{dir}/{child}")
+        };
+        collect_dirs(root, &path, depth + 1, out);
+    }
+}
+
+/// Member globs from the root `package.json` (`workspaces`) or `pnpm-workspace.yaml`.
+fn workspace_patterns(root: &Path) -> Vec<String> {
+    let mut patterns = Vec::new();
+    if let Ok(text) = fs::read_to_string(root.join("package.json"))
+        && let Ok(json) = serde_json::from_str::<Value>(&text)
+    {
+        let workspaces = &json["workspaces"];
+        // Either a list or yarn's `{ "packages": [...] }`
+        let list = workspaces
+            .as_array()
+            .or_else(|| workspaces["packages"].as_array());
+        patterns.extend(
+            list.into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(String::from),
+        );
+    }
+    if let Ok(text) = fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+        patterns.extend(pnpm_patterns(&text));
+    }
+    patterns
+}
+
+/// The `packages:` list of a `pnpm-workspace.yaml`, without a YAML parser.
+fn pnpm_patterns(text: &str) -> Vec<String> {
+    text.lines()
+        .skip_while(|line| line.trim_end() != "packages:")
+        .skip(1)
+        .take_while(|line| line.trim().is_empty() || line.starts_with([' ', '\t', '-']))
+        .filter_map(|line| line.trim().strip_prefix('-'))
+        .map(|item| item.trim().trim_matches(['\'', '"']).to_string())
+        .collect()
+}
+
+/// Whether `dir` matches a member glob and no `!`-negated one.
+fn workspace_member(patterns: &[String], dir: &str) -> bool {
+    let matches = |pattern: &str| {
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+        // Without a `/`, a pattern names a top-level directory, not a name at any depth
+        (pattern.contains('/') || !dir.contains('/')) && glob_match(pattern, dir)
+    };
+    let (excluded, included): (Vec<&String>, Vec<&String>) =
+        patterns.iter().partition(|p| p.starts_with('!'));
+    included.iter().any(|p| matches(p)) && !excluded.iter().any(|p| matches(&p[1..]))
+}
+
+fn npm_package(manifest: &Path, dir: &str) -> Option<Package> {
+    let json: Value = serde_json::from_str(&fs::read_to_string(manifest).ok()?).ok()?;
+    let name = json["name"]
+        .as_str()
+        .map_or_else(|| dir.to_string(), String::from);
+    Some(Package {
+        name,
+        version: json["version"].as_str().map(String::from),
+        dir: dir.to_string(),
+    })
+}
+
+/// The module path declared by a `go.mod`.
+fn go_module(manifest: &Path) -> Option<String> {
+    fs::read_to_string(manifest)
+        .ok()?
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("module "))
+        .map(|path| path.trim().trim_matches('"').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pnpm_patterns, workspace_member};
+
+    #[test]
+    fn matches_workspace_globs() {
+        let patterns = pnpm_patterns(
+            "packages:\n  - 'packages/*'\n  - \"apps/**\"\n  - '!packages/legacy'\nother: 1\n",
+        );
+        assert_eq!(patterns, ["packages/*", "apps/**", "!packages/legacy"]);
+        assert!(workspace_member(&patterns, "packages/ui"));
+        assert!(workspace_member(&patterns, "apps/web/admin"));
+        assert!(!workspace_member(&patterns, "packages/legacy"));
+        assert!(!workspace_member(&patterns, "packages/ui/src"));
+        assert!(workspace_member(&["tools".to_string()], "tools"));
+        assert!(!workspace_member(&["tools".to_string()], "x/tools"));
+    }
+}
@@ -0,0 +1,116 @@
+// Config-defined Galaxy panels: the output of a user command shown next to the overview
+use crate::config::PanelConfig;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// A panel command still running after this is killed, so a hung CLI cannot freeze startup
+const PANEL_TIMEOUT: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// One panel as rendered: its output lines, or why there are none.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Panel {
+    pub title: String,
+    // Rows of output shown at most
+    pub height: u16,
+    pub output: Result<Vec<String>, String>,
+}
+
+impl Panel {
+    /// Runs the panel's command through the shell in `dir`, with `GLIM_SOURCE` set to the
+    /// repository root or PR URL under review.
+    #[must_use]
+    pub fn run(config: &PanelConfig, dir: &Path, source: &str) -> Self {
+        Self {
+            title: config.title.clone(),
+            height: config.height,
+            output: capture(&config.command, dir, source),
+        }
+    }
+}
+
+fn capture(command: &str, dir: &Path, source: &str) -> Result<Vec<String>, String> {
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let mut child = Command::new(shell)
+        .args([flag, command])
+        .current_dir(dir)
+        .env("GLIM_SOURCE", source)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("cannot run `{command}`: {e}"))?;
+    // Read on a thread so a chatty command never blocks on a full pipe while we wait
+    let mut stdout = child.stdout.take().ok_or("no stdout")?;
+    let reader = thread::spawn(move || {
+        let mut out = Vec::new();
+        let _ = stdout.read_to_end(&mut out);
+        out
+    });
+
+    let deadline = Instant::now() + PANEL_TIMEOUT;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => thread::sleep(POLL_INTERVAL),
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "`{command}` timed out after {}s",
+                    PANEL_TIMEOUT.as_secs()
+                ));
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+    let out = reader.join().unwrap_or_default();
+    if !status.success() {
+        return Err(format!("`{command}` failed ({status})"));
+    }
+    Ok(String::from_utf8_lossy(&out)
+        .lines()
+        .map(strip_ansi)
+        .collect())
+}
+
+/// Drops ANSI escape sequences (colours, cursor moves) that would print as garbage.
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // CSI sequences end at the first byte in `@`..=`~`
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::strip_ansi;
+
+    #[test]
+    fn strips_colour_codes() {
+        assert_eq!(
+            strip_ansi("\u{1b}[1;32mRust\u{1b}[0m   12 files"),
+            "Rust   12 files"
+        );
+    }
+}
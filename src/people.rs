@@ -0,0 +1,123 @@
+// Requesting reviews from and assigning people to the PR under review
+use crate::github::PrReviewData;
+
+/// What picking someone does to the PR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeopleRole {
+    Reviewer,
+    Assignee,
+}
+
+impl PeopleRole {
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Reviewer => "Request review",
+            Self::Assignee => "Assign",
+        }
+    }
+
+    /// `gh pr edit` flag adding someone in this role.
+    #[must_use]
+    pub const fn flag(self) -> &'static str {
+        match self {
+            Self::Reviewer => "--add-reviewer",
+            Self::Assignee => "--add-assignee",
+        }
+    }
+
+    /// Switched with Tab in the picker.
+    #[must_use]
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Reviewer => Self::Assignee,
+            Self::Assignee => Self::Reviewer,
+        }
+    }
+
+    /// People the PR already has in this role.
+    #[must_use]
+    pub fn current(self, review: &PrReviewData) -> &[String] {
+        match self {
+            Self::Reviewer => &review.reviewers,
+            Self::Assignee => &review.assignees,
+        }
+    }
+}
+
+/// The people to pick from, narrowed by what has been typed.
+#[derive(Debug, Clone)]
+pub struct PeoplePicker {
+    pub role: PeopleRole,
+    pub candidates: Vec<String>,
+    pub filter: String,
+    // Position within `matches()`
+    pub selected: usize,
+}
+
+impl PeoplePicker {
+    #[must_use]
+    pub const fn new(candidates: Vec<String>) -> Self {
+        Self {
+            role: PeopleRole::Reviewer,
+            candidates,
+            filter: String::new(),
+            selected: 0,
+        }
+    }
+
+    /// Candidates whose login contains the filter, ignoring case.
+    #[must_use]
+    pub fn matches(&self) -> Vec<&str> {
+        let filter = self.filter.to_lowercase();
+        self.candidates
+            .iter()
+            .filter(|c| c.to_lowercase().contains(&filter))
+            .map(String::as_str)
+            .collect()
+    }
+
+    #[must_use]
+    pub fn current(&self) -> Option<&str> {
+        self.matches().get(self.selected).copied()
+    }
+
+    pub fn next(&mut self) {
+        if self.selected + 1 < self.matches().len() {
+            self.selected += 1;
+        }
+    }
+
+    pub const fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.filter.push(c);
+        self.selected = 0;
+    }
+
+    pub fn pop(&mut self) {
+        self.filter.pop();
+        self.selected = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PeoplePicker;
+
+    #[test]
+    fn narrows_candidates_as_you_type() {
+        let mut picker =
+            PeoplePicker::new(["ana", "Bob", "bodhi", "carl"].map(String::from).to_vec());
+        picker.push('B');
+        picker.push('o');
+        assert_eq!(picker.matches(), ["Bob", "bodhi"]);
+        picker.next();
+        picker.next();
+        assert_eq!(picker.current(), Some("bodhi"));
+        picker.pop();
+        assert_eq!((picker.selected, picker.matches().len()), (0, 2));
+    }
+}
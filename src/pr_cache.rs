@@ -0,0 +1,141 @@
+// Fetched PRs kept on disk, so reopening one whose branch has not moved skips downloading its
+// diff again, and one fetched before can still be reviewed without a connection
+use crate::github::{PrInfo, PrReviewData};
+use crate::utils::{fnv1a, get_data_dir};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Entries not saved for this long are deleted on the next save
+const MAX_AGE: Duration = Duration::from_hours(30 * 24);
+
+/// Everything loaded for a PR except the base drift, which moves with the base branch. The
+/// diff is reused while the head commit is unchanged; the review data only stands in offline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedPr {
+    pub info: PrInfo,
+    pub review: PrReviewData,
+    pub diff: String,
+    // Patches of PRs too large for one diff, added page by page as their files are opened
+    #[serde(default)]
+    pub patches: HashMap<String, Vec<String>>,
+    // Unix time of the fetch, shown when the copy stands in for GitHub
+    #[serde(default)]
+    pub fetched_at: i64,
+}
+
+impl CachedPr {
+    /// A PR just fetched from GitHub.
+    #[must_use]
+    pub fn new(info: PrInfo, review: PrReviewData, diff: String) -> Self {
+        let fetched_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs().cast_signed());
+        Self {
+            info,
+            review,
+            diff,
+            patches: HashMap::new(),
+            fetched_at,
+        }
+    }
+
+    /// File the PR at `url` is cached in, under the data directory.
+    #[must_use]
+    pub fn path(url: &str) -> PathBuf {
+        get_data_dir()
+            .join("pr-cache")
+            .join(format!("{:016x}.json", fnv1a(url.as_bytes())))
+    }
+
+    /// The cached copy of the PR at `url`, if it was fetched at head commit `head`.
+    #[must_use]
+    pub fn load(url: &str, head: &str) -> Option<Self> {
+        Self::load_latest(url).filter(|cached| !head.is_empty() && cached.info.head_ref_oid == head)
+    }
+
+    /// The last copy of the PR at `url` cached, whatever its head has become since; for
+    /// reviewing offline.
+    #[must_use]
+    pub fn load_latest(url: &str) -> Option<Self> {
+        let cached: Self = serde_json::from_str(&fs::read_to_string(Self::path(url)).ok()?).ok()?;
+        (cached.info.url == url).then_some(cached)
+    }
+
+    /// Adds patches fetched after the PR was cached, while the cached copy is still at `head`.
+    ///
+    /// # Errors
+    /// Returns an error if the cache file cannot be written.
+    pub fn add_patches(
+        url: &str,
+        head: &str,
+        patches: &HashMap<String, Vec<String>>,
+    ) -> io::Result<()> {
+        let Some(mut cached) = Self::load(url, head) else {
+            return Ok(());
+        };
+        cached.patches.extend(
+            patches
+                .iter()
+                .map(|(path, lines)| (path.clone(), lines.clone())),
+        );
+        cached.save()
+    }
+
+    /// Writes atomically, so an interrupted save never leaves a truncated entry, and deletes
+    /// entries of PRs not opened for a while.
+    ///
+    /// # Errors
+    /// Returns an error if the cache directory or file cannot be written.
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path(&self.info.url);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+            prune(dir, SystemTime::now());
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string(self)?)?;
+        fs::rename(tmp, path)
+    }
+}
+
+/// Deletes cache entries in `dir` last written more than [`MAX_AGE`] before `now`.
+fn prune(dir: &Path, now: SystemTime) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .is_ok_and(|modified| now.duration_since(modified).unwrap_or_default() > MAX_AGE);
+        if stale {
+            // Another glim may be pruning too
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_AGE, prune};
+    use std::fs;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn prunes_entries_older_than_the_max_age() {
+        let dir = std::env::temp_dir().join(format!("glim-pr-cache-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("temp dir");
+        let entry = dir.join("0000000000000001.json");
+        fs::write(&entry, "{}").expect("entry written");
+        let now = SystemTime::now();
+        prune(&dir, now + Duration::from_hours(24));
+        assert!(entry.exists());
+        prune(&dir, now + MAX_AGE + Duration::from_mins(1));
+        assert!(!entry.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
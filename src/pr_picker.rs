@@ -0,0 +1,100 @@
+// Choosing which open PR to review when glim is started without one, or working through
+// the PRs awaiting my review (`glim queue`)
+use crate::github::PrSummary;
+use std::collections::HashSet;
+
+/// The open PRs of a repository and the highlighted row.
+#[derive(Debug, Clone, Default)]
+pub struct PrPicker {
+    // `owner/repo`, or `None` for the repository of the current directory
+    pub repo: Option<String>,
+    pub entries: Vec<PrSummary>,
+    pub selected: usize,
+    // Set in review queue mode: URLs of the PRs reviewed so far this session
+    pub queue: Option<HashSet<String>>,
+}
+
+impl PrPicker {
+    /// The review queue, before its PRs are listed.
+    #[must_use]
+    pub fn review_queue() -> Self {
+        Self {
+            queue: Some(HashSet::new()),
+            ..Self::default()
+        }
+    }
+
+    pub const fn next(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub const fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    #[must_use]
+    pub fn current(&self) -> Option<&PrSummary> {
+        self.entries.get(self.selected)
+    }
+
+    #[must_use]
+    pub fn is_reviewed(&self, pr: &PrSummary) -> bool {
+        self.queue
+            .as_ref()
+            .is_some_and(|done| done.contains(&pr.url))
+    }
+
+    /// Reviewed and total PRs in queue mode.
+    #[must_use]
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        let reviewed = self
+            .entries
+            .iter()
+            .filter(|pr| self.is_reviewed(pr))
+            .count();
+        self.queue.as_ref().map(|_| (reviewed, self.entries.len()))
+    }
+
+    /// Ticks off the PR at `url` and highlights the next one still to review.
+    pub fn mark_reviewed(&mut self, url: &str) {
+        if let Some(done) = self.queue.as_mut() {
+            done.insert(url.to_string());
+        }
+        if let Some(next) = (0..self.entries.len())
+            .map(|i| (self.selected + i) % self.entries.len())
+            .find(|&i| !self.is_reviewed(&self.entries[i]))
+        {
+            self.selected = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PrPicker;
+    use serde_json::json;
+
+    #[test]
+    fn advances_past_reviewed_prs() {
+        let entries = (1..=3)
+            .map(|n| {
+                serde_json::from_value(json!({
+                    "number": n, "title": "t", "author": { "login": "a" },
+                    "url": format!("https://github.com/o/r/pull/{n}"),
+                }))
+                .expect("PR JSON")
+            })
+            .collect();
+        let mut queue = PrPicker {
+            entries,
+            selected: 1,
+            ..PrPicker::review_queue()
+        };
+        queue.mark_reviewed("https://github.com/o/r/pull/2");
+        assert_eq!((queue.selected, queue.progress()), (2, Some((1, 3))));
+        queue.mark_reviewed("https://github.com/o/r/pull/3");
+        assert_eq!(queue.selected, 0);
+    }
+}
@@ -0,0 +1,123 @@
+// Composing a PR review (approve / request changes / comment) before it goes to GitHub
+use ratatui::style::{Modifier, Style};
+use tui_textarea::TextArea;
+
+/// The verdict a submitted review carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewEvent {
+    Comment,
+    Approve,
+    RequestChanges,
+}
+
+impl ReviewEvent {
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Comment => "Comment",
+            Self::Approve => "Approve",
+            Self::RequestChanges => "Request changes",
+        }
+    }
+
+    /// `gh pr review` flag for this verdict.
+    #[must_use]
+    pub const fn flag(self) -> &'static str {
+        match self {
+            Self::Comment => "--comment",
+            Self::Approve => "--approve",
+            Self::RequestChanges => "--request-changes",
+        }
+    }
+
+    /// GitHub rejects comments and change requests without a body; approvals may be bare.
+    #[must_use]
+    pub const fn needs_body(self) -> bool {
+        !matches!(self, Self::Approve)
+    }
+
+    /// Cycled with Tab in the composer.
+    #[must_use]
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Comment => Self::Approve,
+            Self::Approve => Self::RequestChanges,
+            Self::RequestChanges => Self::Comment,
+        }
+    }
+}
+
+/// The review being written: verdict plus a multi-line message.
+#[derive(Debug, Clone)]
+pub struct ReviewDraft {
+    pub event: ReviewEvent,
+    pub message: TextArea<'static>,
+}
+
+fn message_area(placeholder: &str) -> TextArea<'static> {
+    let mut message = TextArea::default();
+    message.set_cursor_line_style(Style::default());
+    message.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+    message.set_placeholder_text(placeholder);
+    message
+}
+
+fn text(message: &TextArea<'_>) -> String {
+    message.lines().join("\n").trim().to_string()
+}
+
+impl Default for ReviewDraft {
+    fn default() -> Self {
+        Self {
+            event: ReviewEvent::Comment,
+            message: message_area("Leave a comment"),
+        }
+    }
+}
+
+impl ReviewDraft {
+    #[must_use]
+    pub fn body(&self) -> String {
+        text(&self.message)
+    }
+}
+
+/// A comment being written on one line of the PR's diff.
+#[derive(Debug, Clone)]
+pub struct CommentDraft {
+    pub path: String,
+    pub line: usize,
+    // On a removed line, numbered in the old file
+    pub left: bool,
+    pub message: TextArea<'static>,
+}
+
+impl CommentDraft {
+    #[must_use]
+    pub fn new(path: String, line: usize, left: bool) -> Self {
+        Self {
+            path,
+            line,
+            left,
+            message: message_area("Comment on this line"),
+        }
+    }
+
+    /// A private note on the line, starting from the `text` already there.
+    #[must_use]
+    pub fn note(path: String, line: usize, left: bool, text: &str) -> Self {
+        let mut message = message_area("Private note on this line");
+        message.insert_str(text);
+        Self {
+            path,
+            line,
+            left,
+            message,
+        }
+    }
+
+    #[must_use]
+    pub fn body(&self) -> String {
+        text(&self.message)
+    }
+}
@@ -0,0 +1,109 @@
+// Session-scoped stash that protects the working tree during review excursions
+use anyhow::{Context, Result};
+use git2::{Oid, Repository, Signature, StashApplyOptions, StashFlags};
+
+const STASH_MESSAGE: &str = "glim safe mode";
+
+/// A working tree parked in the stash, plus the branch it belongs on.
+#[derive(Debug, Clone)]
+pub struct SafeMode {
+    pub stash: Oid,
+    pub head: Option<String>,
+}
+
+impl SafeMode {
+    /// Stashes all changes (untracked included) and remembers the current branch.
+    ///
+    /// # Errors
+    /// Returns an error if there is nothing to stash or libgit2 refuses the stash.
+    pub fn begin(repo: &mut Repository) -> Result<Self> {
+        let head = repo
+            .head()
+            .ok()
+            .filter(git2::Reference::is_branch)
+            .and_then(|h| h.name().map(ToString::to_string));
+        let signature = repo
+            .signature()
+            .or_else(|_| Signature::now("glim", "glim@localhost"))?;
+        let stash = repo
+            .stash_save(
+                &signature,
+                STASH_MESSAGE,
+                Some(StashFlags::INCLUDE_UNTRACKED),
+            )
+            .context("Nothing to stash")?;
+        Ok(Self { stash, head })
+    }
+
+    /// Returns to the original branch and pops the stash back onto the working tree, staged
+    /// changes back into the index.
+    ///
+    /// # Errors
+    /// Returns an error if the branch cannot be checked out or the stash no longer applies.
+    pub fn restore(&self, repo: &mut Repository) -> Result<()> {
+        if let Some(head) = &self.head
+            && repo
+                .head()
+                .ok()
+                .and_then(|h| h.name().map(ToString::to_string))
+                .as_ref()
+                != Some(head)
+        {
+            repo.set_head(head)?;
+            repo.checkout_head(Some(git2::build::CheckoutBuilder::new().safe()))?;
+        }
+
+        let mut index = None;
+        repo.stash_foreach(|i, _, oid| {
+            if *oid == self.stash {
+                index = Some(i);
+                false
+            } else {
+                true
+            }
+        })?;
+        let index = index.context("Safe mode stash was dropped outside glim")?;
+        let mut options = StashApplyOptions::new();
+        options.reinstantiate_index();
+        repo.stash_pop(index, Some(&mut options))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SafeMode;
+    use git2::{Repository, Signature, Status};
+    use std::fs;
+
+    #[test]
+    fn restores_staged_changes_as_staged() {
+        let dir = std::env::temp_dir().join(format!("glim-safe-mode-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut repo = Repository::init(&dir).expect("init");
+        fs::write(dir.join("a.txt"), "one\n").expect("write");
+        let mut index = repo.index().expect("index");
+        index.add_path("a.txt".as_ref()).expect("add");
+        index.write().expect("write index");
+        let tree = repo
+            .find_tree(index.write_tree().expect("tree"))
+            .expect("tree");
+        let sig = Signature::now("t", "t@example.com").expect("signature");
+        repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+            .expect("commit");
+        drop(tree);
+
+        fs::write(dir.join("a.txt"), "two\n").expect("write");
+        index.add_path("a.txt".as_ref()).expect("stage");
+        index.write().expect("write index");
+        fs::write(dir.join("b.txt"), "new\n").expect("write");
+
+        let safe = SafeMode::begin(&mut repo).expect("begin");
+        assert!(!dir.join("b.txt").exists());
+        safe.restore(&mut repo).expect("restore");
+        let status = |path: &str| repo.status_file(path.as_ref()).expect("status");
+        assert_eq!(status("a.txt"), Status::INDEX_MODIFIED);
+        assert_eq!(status("b.txt"), Status::WT_NEW);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
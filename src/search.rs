@@ -0,0 +1,116 @@
+// Structure search queries: plain text, plus symbol scopes like `fn:parse` or `kind:impl`
+use crate::app::StructureItem;
+
+/// One whitespace-separated part of a query; an item must match every term.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Term {
+    // Case-insensitive substring of the row text (or, next to a symbol scope, its path)
+    Text(String),
+    // `<kind>:<name>`, `sym:<name>` (any kind) or `kind:<kind>` (any name)
+    Symbol { kind: Option<String>, name: String },
+}
+
+/// A parsed search query.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SearchQuery {
+    terms: Vec<Term>,
+}
+
+impl SearchQuery {
+    #[must_use]
+    pub fn parse(query: &str) -> Self {
+        let terms = query
+            .split_whitespace()
+            .map(|word| {
+                let word = word.to_lowercase();
+                match word.split_once(':') {
+                    Some(("kind", kind)) => Term::Symbol {
+                        kind: Some(kind.to_string()),
+                        name: String::new(),
+                    },
+                    Some(("sym", name)) => Term::Symbol {
+                        kind: None,
+                        name: name.to_string(),
+                    },
+                    // Only bare words scope by kind, so `src/app.rs:12` stays a text search
+                    Some((kind, name))
+                        if !kind.is_empty() && kind.chars().all(char::is_alphanumeric) =>
+                    {
+                        Term::Symbol {
+                            kind: Some(kind.to_string()),
+                            name: name.to_string(),
+                        }
+                    }
+                    _ => Term::Text(word),
+                }
+            })
+            .collect();
+        Self { terms }
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Whether the query restricts results to symbols of some kind or name.
+    #[must_use]
+    pub fn is_symbol_scoped(&self) -> bool {
+        self.terms.iter().any(|t| matches!(t, Term::Symbol { .. }))
+    }
+
+    #[must_use]
+    pub fn matches(&self, item: &StructureItem) -> bool {
+        // Next to a symbol scope, plain words narrow by file: `fn:parse src/cli`
+        let by_path = self.is_symbol_scoped();
+        self.terms.iter().all(|term| match term {
+            Term::Text(text) => {
+                item.text.to_lowercase().contains(text)
+                    || (by_path && item.path.to_lowercase().contains(text))
+            }
+            Term::Symbol { kind, name } => {
+                !item.is_file
+                    && kind
+                        .as_ref()
+                        .is_none_or(|k| item.status.eq_ignore_ascii_case(k))
+                    && item
+                        .symbol_name()
+                        .is_some_and(|n| n.to_lowercase().contains(name))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SearchQuery;
+    use crate::app::StructureItem;
+
+    fn symbol(kind: &str, name: &str) -> StructureItem {
+        StructureItem {
+            text: format!("  {kind} {name}"),
+            path: "src/app.rs".to_string(),
+            status: kind.to_string(),
+            ..StructureItem::default()
+        }
+    }
+
+    #[test]
+    fn scopes_by_kind_and_name() {
+        let parse = symbol("fn", "parse_args");
+        let app = symbol("struct", "App");
+        let file = StructureItem {
+            text: "src/app.rs".to_string(),
+            is_file: true,
+            ..StructureItem::default()
+        };
+
+        let query = SearchQuery::parse("fn:parse");
+        assert!(query.matches(&parse) && !query.matches(&app) && !query.matches(&file));
+        assert!(SearchQuery::parse("kind:STRUCT").matches(&app));
+        assert!(SearchQuery::parse("sym:app").matches(&app));
+        assert!(SearchQuery::parse("app").matches(&file));
+        assert!(SearchQuery::parse("fn:parse src/").matches(&parse));
+        assert!(!SearchQuery::parse("src/app.rs:12").is_symbol_scoped());
+    }
+}
@@ -1,18 +1,150 @@
+use crate::config::SymbolQuery;
+use crate::markdown;
+use anyhow::{Result, anyhow, bail};
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::fs;
+use std::path::Path;
 use streaming_iterator::StreamingIterator;
-use tree_sitter::{Language, Parser, Query, QueryCursor};
+use tree_sitter::{InputEdit, Language, Node, Parser, Point, Query, QueryCursor, Tree};
+
+// Files larger than this are listed but not parsed for symbols
+const MAX_ANALYSIS_BYTES: u64 = 1024 * 1024;
+// Leading bytes inspected for NUL when sniffing binary content (same window as git)
+const BINARY_SNIFF_BYTES: usize = 8000;
+
+// Each pattern captures the symbol's node under its kind (`@fn`, `@class`, ...) and its name
+// as `@name`; methods may capture `@receiver` too, shown before the name
+const RUST_QUERY: &str = r"
+(function_item name: (identifier) @name) @fn
+(struct_item name: (type_identifier) @name) @struct
+(impl_item type: (type_identifier) @name) @impl
+(enum_item name: (type_identifier) @name) @enum
+(trait_item name: (type_identifier) @name) @trait
+(mod_item name: (identifier) @name) @mod
+(const_item name: (identifier) @name) @const
+(static_item name: (identifier) @name) @static
+(macro_definition name: (identifier) @name) @macro
+";
+
+// Shared by TypeScript and TSX, whose grammars name these nodes alike
+const TYPESCRIPT_QUERY: &str = r#"
+(function_declaration name: (identifier) @name) @fn
+(generator_function_declaration name: (identifier) @name) @fn
+(class_declaration name: (type_identifier) @name) @class
+(abstract_class_declaration name: (type_identifier) @name) @class
+(interface_declaration name: (type_identifier) @name) @interface
+(export_statement
+  declaration: (lexical_declaration
+    kind: "const"
+    (variable_declarator name: (identifier) @name))) @const
+"#;
+
+const GO_QUERY: &str = r"
+(function_declaration name: (identifier) @name) @fn
+(method_declaration
+  receiver: (parameter_list (parameter_declaration type: (_) @receiver))
+  name: (field_identifier) @name) @fn
+(type_spec name: (type_identifier) @name type: (struct_type)) @struct
+(type_spec name: (type_identifier) @name type: (interface_type)) @interface
+(type_spec
+  name: (type_identifier) @name
+  type: [
+    (type_identifier) (qualified_type) (generic_type) (pointer_type) (slice_type)
+    (array_type) (map_type) (channel_type) (function_type)
+  ]) @type
+(type_alias name: (type_identifier) @name) @type
+";
+
+// Documents outlined by their headings rather than parsed with a grammar
+const OUTLINE_EXTENSIONS: &[&str] = &["md", "markdown"];
+
+// Captures naming a symbol rather than giving its kind; those starting with `_` only feed
+// predicates
+const PART_CAPTURES: &[&str] = &["name", "receiver"];
+
+fn is_part(capture: &str) -> bool {
+    PART_CAPTURES.contains(&capture) || capture.starts_with('_')
+}
+
+/// A language the analyzer parses, with the query picking out its symbols.
+struct Grammar {
+    // As in `[symbol_queries.<name>]`
+    name: &'static str,
+    extensions: &'static [&'static str],
+    language: Language,
+    parser: Parser,
+    builtin: &'static str,
+    query: Query,
+}
+
+impl Grammar {
+    fn new(
+        name: &'static str,
+        extensions: &'static [&'static str],
+        language: Language,
+        builtin: &'static str,
+    ) -> Self {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&language)
+            .expect("Error loading grammar");
+        let query = Query::new(&language, builtin).expect("Error compiling symbol query");
+        Self {
+            name,
+            extensions,
+            language,
+            parser,
+            builtin,
+            query,
+        }
+    }
+
+    /// Lays the user's `[symbol_queries]` entry over the built-in patterns.
+    fn customize(&mut self, custom: &SymbolQuery) -> Result<()> {
+        let source = if custom.replace {
+            custom.query.clone()
+        } else {
+            format!("{}\n{}", self.builtin, custom.query)
+        };
+        self.query = Query::new(&self.language, &source)
+            .map_err(|e| anyhow!("symbol_queries.{}: {e}", self.name))?;
+        Ok(())
+    }
+
+    fn handles(&self, ext: &str) -> bool {
+        self.extensions.iter().any(|e| ext.eq_ignore_ascii_case(e))
+    }
+}
+
+// Edits rewriting more than this share of a file are parsed from scratch; the old tree
+// would save next to nothing
+const MAX_INCREMENTAL_SHARE: f64 = 0.5;
+
+/// A file as last analyzed: the tree for incremental re-parses, and the symbols to hand back
+/// while its content stays the same.
+struct Parsed {
+    tree: Tree,
+    // The edit against it is what makes the re-parse incremental
+    source: String,
+    symbols: Vec<SymbolChange>,
+}
 
 pub struct SemanticAnalyzer {
-    rust_parser: Parser,
-    #[allow(dead_code)]
-    ts_parser: Parser,
-    rust_query: Query,
+    grammars: Vec<Grammar>,
+    trees: HashMap<String, Parsed>,
 }
 
 impl fmt::Debug for SemanticAnalyzer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let extensions: Vec<&str> = self
+            .grammars
+            .iter()
+            .flat_map(|g| g.extensions.iter().copied())
+            .collect();
         f.debug_struct("SemanticAnalyzer")
-            .field("rust_query", &self.rust_query)
+            .field("extensions", &extensions)
+            .field("cached_trees", &self.cached())
             .finish_non_exhaustive()
     }
 }
@@ -23,89 +155,259 @@ impl Default for SemanticAnalyzer {
     }
 }
 
+/// Extension of `path`, if it has one.
+fn extension(path: &str) -> Option<&str> {
+    std::path::Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+}
+
+/// Reads a file for symbol analysis, refusing oversized or binary content.
+///
+/// # Errors
+/// Returns why the file was skipped: unreadable, too large, binary or not UTF-8.
+pub fn read_analyzable(path: &Path) -> Result<String, &'static str> {
+    let meta = fs::metadata(path).map_err(|_| "unreadable")?;
+    if meta.len() > MAX_ANALYSIS_BYTES {
+        return Err("too large");
+    }
+    let bytes = fs::read(path).map_err(|_| "unreadable")?;
+    if bytes.iter().take(BINARY_SNIFF_BYTES).any(|&b| b == 0) {
+        return Err("binary");
+    }
+    String::from_utf8(bytes).map_err(|_| "not utf-8")
+}
+
+/// Whether `path` is outlined by headings rather than parsed into code symbols.
+#[must_use]
+pub fn is_outlined(path: &str) -> bool {
+    extension(path).is_some_and(|ext| {
+        OUTLINE_EXTENSIONS
+            .iter()
+            .any(|e| ext.eq_ignore_ascii_case(e))
+    })
+}
+
 impl SemanticAnalyzer {
     #[must_use]
     #[allow(clippy::missing_panics_doc)]
     pub fn new() -> Self {
-        let mut rust_parser = Parser::new();
-        let rust_lang = Language::from(tree_sitter_rust::LANGUAGE);
-        rust_parser
-            .set_language(&rust_lang)
-            .expect("Error loading Rust grammar");
-
-        let mut ts_parser = Parser::new();
-        let ts_lang = Language::from(tree_sitter_typescript::LANGUAGE_TYPESCRIPT);
-        ts_parser
-            .set_language(&ts_lang)
-            .expect("Error loading TypeScript grammar");
-
-        let rust_query = Query::new(
-            &rust_lang,
-            r"
-            (function_item name: (identifier) @name) @func
-            (struct_item name: (type_identifier) @name) @struct
-            (impl_item type: (type_identifier) @name) @impl
-            ",
-        )
-        .expect("Error compiling Rust query");
-
         Self {
-            rust_parser,
-            ts_parser,
-            rust_query,
+            grammars: vec![
+                Grammar::new(
+                    "rust",
+                    &["rs"],
+                    Language::from(tree_sitter_rust::LANGUAGE),
+                    RUST_QUERY,
+                ),
+                Grammar::new(
+                    "typescript",
+                    &["ts", "mts", "cts"],
+                    Language::from(tree_sitter_typescript::LANGUAGE_TYPESCRIPT),
+                    TYPESCRIPT_QUERY,
+                ),
+                Grammar::new(
+                    "tsx",
+                    &["tsx"],
+                    Language::from(tree_sitter_typescript::LANGUAGE_TSX),
+                    TYPESCRIPT_QUERY,
+                ),
+                Grammar::new(
+                    "go",
+                    &["go"],
+                    Language::from(tree_sitter_go::LANGUAGE),
+                    GO_QUERY,
+                ),
+            ],
+            trees: HashMap::new(),
+        }
+    }
+
+    /// The built-in grammars with the user's `queries` added to (or, with `replace`, put in
+    /// place of) their patterns. Where a user pattern and a built-in one capture the same
+    /// node, the user's kind wins.
+    ///
+    /// # Errors
+    /// Returns an error for a language the analyzer does not parse or a query that does not
+    /// compile.
+    pub fn with_queries(queries: &BTreeMap<String, SymbolQuery>) -> Result<Self> {
+        let mut analyzer = Self::new();
+        for (name, custom) in queries {
+            let Some(grammar) = analyzer.grammars.iter_mut().find(|g| g.name == name) else {
+                let known: Vec<&str> = analyzer.grammars.iter().map(|g| g.name).collect();
+                bail!(
+                    "symbol_queries.{name}: unknown language (one of {})",
+                    known.join(", ")
+                );
+            };
+            grammar.customize(custom)?;
         }
+        Ok(analyzer)
+    }
+
+    /// Whether `path` has an extension the analyzer can parse.
+    #[must_use]
+    pub fn supports(&self, path: &str) -> bool {
+        self.grammar_index(path).is_some() || is_outlined(path)
+    }
+
+    fn grammar_index(&self, path: &str) -> Option<usize> {
+        let ext = extension(path)?;
+        self.grammars.iter().position(|g| g.handles(ext))
     }
 
     pub fn analyze(&mut self, path: &str, content: &str) -> Vec<SymbolChange> {
-        if std::path::Path::new(path)
-            .extension()
-            .is_some_and(|ext| ext.eq_ignore_ascii_case("rs"))
+        if is_outlined(path) {
+            return markdown::outline(content);
+        }
+        let Some(index) = self.grammar_index(path) else {
+            return vec![];
+        };
+        if let Some(parsed) = self.trees.get(path)
+            && parsed.source == content
         {
-            self.analyze_rust(content)
-        } else {
-            vec![]
+            return parsed.symbols.clone();
         }
+        let tree = self.parse_incremental(index, path, content);
+        let symbols = find_symbols(&self.grammars[index].query, &tree, content);
+        self.trees.insert(
+            path.to_string(),
+            Parsed {
+                tree,
+                source: content.to_string(),
+                symbols: symbols.clone(),
+            },
+        );
+        symbols
     }
 
-    fn analyze_rust(&mut self, content: &str) -> Vec<SymbolChange> {
-        let tree = self
-            .rust_parser
-            .parse(content, None)
-            .expect("Failed to parse content");
-        let mut cursor = QueryCursor::new();
+    /// Identifiers in `content` with their 1-based lines, for finding references to symbols
+    /// by name; empty for files without a grammar.
+    pub fn identifiers(&mut self, path: &str, content: &str) -> Vec<(String, usize)> {
+        if self.grammar_index(path).is_none() {
+            return vec![];
+        }
+        // Parses (or finds the cached tree) as a side effect
+        self.analyze(path, content);
+        self.trees
+            .get(path)
+            .map_or_else(Vec::new, |parsed| identifiers_in(&parsed.tree, content))
+    }
 
-        let mut symbols = Vec::new();
+    /// Drops the cached tree for `path`, e.g. when the file was deleted.
+    pub fn forget(&mut self, path: &str) {
+        self.trees.remove(path);
+    }
 
-        let mut matches = cursor.matches(&self.rust_query, tree.root_node(), content.as_bytes());
+    /// Keeps cached trees only for paths `keep` accepts, e.g. those still changed after a
+    /// rescan.
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+        self.trees.retain(|path, _| keep(path));
+    }
 
-        while let Some(m) = matches.next() {
-            let node = m.captures[0].node;
-            let kind = match node.kind() {
-                "function_item" => "fn",
-                "struct_item" => "struct",
-                "impl_item" => "impl",
-                _ => "code",
-            };
+    /// How many files have a tree kept for them.
+    #[must_use]
+    pub fn cached(&self) -> usize {
+        self.trees.len()
+    }
+
+    /// Parses `content` with grammar `index`, reusing the previous tree for `path` when the
+    /// edit since is small enough for that to pay off.
+    fn parse_incremental(&mut self, index: usize, path: &str, content: &str) -> Tree {
+        let old_tree = self.trees.remove(path).and_then(|parsed| {
+            let Parsed {
+                mut tree, source, ..
+            } = parsed;
+            let edit = compute_edit(&source, content);
+            let rewritten =
+                (edit.old_end_byte - edit.start_byte).max(edit.new_end_byte - edit.start_byte);
+            (rewritten as f64 <= source.len().max(content.len()) as f64 * MAX_INCREMENTAL_SHARE)
+                .then(|| {
+                    tree.edit(&edit);
+                    tree
+                })
+        });
+        self.grammars[index]
+            .parser
+            .parse(content, old_tree.as_ref())
+            .expect("Failed to parse content")
+    }
+}
+
+/// Leaves of `tree` naming something (`identifier`, `type_identifier`, `field_identifier`,
+/// ...), including those inside macro token trees.
+fn identifiers_in(tree: &Tree, content: &str) -> Vec<(String, usize)> {
+    let mut identifiers = Vec::new();
+    let mut cursor = tree.walk();
+    loop {
+        let node = cursor.node();
+        if node.child_count() == 0
+            && node.kind().ends_with("identifier")
+            && let Ok(text) = node.utf8_text(content.as_bytes())
+        {
+            identifiers.push((text.to_string(), node.start_position().row + 1));
+        }
+        if cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return identifiers;
+            }
+        }
+    }
+}
+
+/// Symbols the matches of `query` pick out of `tree`; where patterns capture the same node,
+/// the later one wins.
+fn find_symbols(query: &Query, tree: &Tree, content: &str) -> Vec<SymbolChange> {
+    let names = query.capture_names();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(query, tree.root_node(), content.as_bytes());
 
-            let name_node = m
-                .captures
+    let mut symbols: Vec<SymbolChange> = Vec::new();
+    // Node id -> (pattern, index in `symbols`)
+    let mut seen: HashMap<usize, (usize, usize)> = HashMap::new();
+    while let Some(m) = matches.next() {
+        let part = |name: &str| {
+            m.captures
                 .iter()
-                .find(|c| c.index == 1)
-                .map_or(node, |c| c.node);
-            let name = name_node
-                .utf8_text(content.as_bytes())
-                .unwrap_or("unknown")
-                .to_string();
-
-            symbols.push(SymbolChange {
-                name,
-                kind: kind.to_string(),
-                start_line: node.start_position().row + 1,
-                end_line: node.end_position().row + 1,
-            });
+                .find(|c| names[c.index as usize] == name)
+                .and_then(|c| c.node.utf8_text(content.as_bytes()).ok())
+        };
+        let Some(symbol) = m
+            .captures
+            .iter()
+            .find(|c| !is_part(names[c.index as usize]))
+        else {
+            continue;
+        };
+        let node = symbol.node;
+        let name = part("name")
+            .or_else(|| node.utf8_text(content.as_bytes()).ok())
+            .unwrap_or("unknown");
+        // Go methods read `(*Server) Handle`
+        let name = part("receiver").map_or_else(
+            || name.to_string(),
+            |receiver| format!("({receiver}) {name}"),
+        );
+        let change = SymbolChange {
+            name,
+            kind: names[symbol.index as usize].to_string(),
+            start_line: node.start_position().row + 1,
+            end_line: node.end_position().row + 1,
+            complexity: Some(Complexity::of(node)),
+        };
+        match seen.get(&node.id()) {
+            Some(&(pattern, at)) if pattern <= m.pattern_index => symbols[at] = change,
+            Some(_) => {}
+            None => {
+                seen.insert(node.id(), (m.pattern_index, symbols.len()));
+                symbols.push(change);
+            }
         }
-        symbols
     }
+    symbols
 }
 
 #[derive(Debug, Clone)]
@@ -114,4 +416,332 @@ pub struct SymbolChange {
     pub kind: String,
     pub start_line: usize,
     pub end_line: usize,
+    // Code symbols only; Markdown headings have none
+    pub complexity: Option<Complexity>,
+}
+
+// Nodes that take another path through the code, in any of the grammars: each `if`, loop,
+// match arm / switch case, ternary and catch
+const BRANCH_KINDS: &[&str] = &[
+    "if_expression",
+    "if_statement",
+    "match_arm",
+    "switch_case",
+    "expression_case",
+    "type_case",
+    "communication_case",
+    "ternary_expression",
+    "catch_clause",
+    "while_expression",
+    "while_statement",
+    "for_expression",
+    "for_statement",
+    "for_in_statement",
+    "do_statement",
+    "loop_expression",
+];
+
+// Nodes whose body sits one level deeper
+const NESTING_KINDS: &[&str] = &[
+    "if_expression",
+    "if_statement",
+    "match_expression",
+    "switch_statement",
+    "expression_switch_statement",
+    "type_switch_statement",
+    "select_statement",
+    "while_expression",
+    "while_statement",
+    "for_expression",
+    "for_statement",
+    "for_in_statement",
+    "do_statement",
+    "loop_expression",
+];
+
+/// Size and shape of a symbol's code, to tell the scary functions of a big change apart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Complexity {
+    pub lines: usize,
+    // Deepest nesting of conditionals and loops
+    pub depth: usize,
+    pub branches: usize,
+}
+
+impl Complexity {
+    /// Metrics of the code under `node`.
+    #[must_use]
+    pub fn of(node: Node) -> Self {
+        let mut complexity = Self {
+            lines: node.end_position().row - node.start_position().row + 1,
+            ..Self::default()
+        };
+        complexity.measure(node, 0);
+        complexity
+    }
+
+    fn measure(&mut self, node: Node, depth: usize) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            let kind = child.kind();
+            if BRANCH_KINDS.contains(&kind) {
+                self.branches += 1;
+            }
+            // `else if` continues its chain rather than nesting in it
+            let else_if = kind.starts_with("if_")
+                && child
+                    .parent()
+                    .is_some_and(|p| p.kind() == "else_clause" || p.kind() == "if_statement");
+            let depth = depth + usize::from(NESTING_KINDS.contains(&kind) && !else_if);
+            self.depth = self.depth.max(depth);
+            self.measure(child, depth);
+        }
+    }
+
+    /// 2 for a symbol worth a careful look, 1 for a busy one, 0 otherwise.
+    #[must_use]
+    pub const fn severity(&self) -> u8 {
+        if self.branches >= 15 || self.depth >= 5 || self.lines >= 150 {
+            2
+        } else if self.branches >= 7 || self.depth >= 3 || self.lines >= 60 {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+impl fmt::Display for Complexity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}L b{} d{}", self.lines, self.branches, self.depth)
+    }
+}
+
+/// Describes the change from `old` to `new` as a single edit spanning everything between
+/// their common prefix and common suffix.
+fn compute_edit(old: &str, new: &str) -> InputEdit {
+    let prefix = old
+        .bytes()
+        .zip(new.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let suffix = old
+        .bytes()
+        .rev()
+        .zip(new.bytes().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    InputEdit {
+        start_byte: prefix,
+        old_end_byte: old.len() - suffix,
+        new_end_byte: new.len() - suffix,
+        start_position: point_at(old, prefix),
+        old_end_position: point_at(old, old.len() - suffix),
+        new_end_position: point_at(new, new.len() - suffix),
+    }
+}
+
+/// Row/column (in bytes) of `byte` within `text`.
+fn point_at(text: &str, byte: usize) -> Point {
+    let before = &text.as_bytes()[..byte];
+    let row = before.split(|&b| b == b'\n').count() - 1;
+    let column = before
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(byte, |nl| byte - nl - 1);
+    Point { row, column }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SemanticAnalyzer, compute_edit};
+    use crate::config::SymbolQuery;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn edit_spans_only_the_changed_region() {
+        let edit = compute_edit("fn a() {}\nfn b() {}\n", "fn a() {}\nfn bc() {}\n");
+        assert_eq!(edit.start_byte, 14);
+        assert_eq!(edit.old_end_byte, 14);
+        assert_eq!(edit.new_end_byte, 15);
+        assert_eq!(edit.start_position.row, 1);
+        assert_eq!(edit.start_position.column, 4);
+    }
+
+    #[test]
+    fn incremental_reparse_matches_fresh_parse() {
+        let mut analyzer = SemanticAnalyzer::new();
+        analyzer.analyze("lib.rs", "fn a() {}\n");
+        let updated = "fn a() {}\nstruct B;\n";
+        let incremental = analyzer.analyze("lib.rs", updated);
+        let fresh = SemanticAnalyzer::new().analyze("lib.rs", updated);
+        assert_eq!(incremental.len(), fresh.len());
+        assert_eq!(incremental.len(), 2);
+
+        // Unchanged content comes from the cache; a rewrite parses afresh
+        assert_eq!(analyzer.analyze("lib.rs", updated).len(), 2);
+        let rewritten = analyzer.analyze("lib.rs", "enum E {}\n");
+        assert_eq!(rewritten[0].name, "E");
+        assert_eq!(analyzer.cached(), 1);
+        analyzer.retain(|path| path != "lib.rs");
+        assert_eq!(analyzer.cached(), 0);
+    }
+
+    #[test]
+    fn finds_rust_items_of_every_kind() {
+        let source = "enum E { A }\ntrait T {}\nmod m {\n    const C: u8 = 1;\n}\n\
+                      static S: u8 = 2;\nmacro_rules! mac { () => {}; }\n";
+        let symbols: Vec<String> = SemanticAnalyzer::new()
+            .analyze("lib.rs", source)
+            .into_iter()
+            .map(|s| format!("{} {}", s.kind, s.name))
+            .collect();
+        assert_eq!(
+            symbols,
+            [
+                "enum E",
+                "trait T",
+                "mod m",
+                "const C",
+                "static S",
+                "macro mac"
+            ]
+        );
+    }
+
+    #[test]
+    fn user_queries_add_kinds_and_override_builtins() {
+        let mut queries = BTreeMap::new();
+        queries.insert(
+            "rust".to_string(),
+            SymbolQuery {
+                query: r#"((attribute_item (attribute (identifier) @_attr (#eq? @_attr "test")))
+                           . (function_item name: (identifier) @name) @test)"#
+                    .to_string(),
+                replace: false,
+            },
+        );
+        let source = "fn helper() {}\n#[test]\nfn checks() {}\nstruct S;\n";
+        let kinds = |analyzer: &mut SemanticAnalyzer| -> Vec<String> {
+            analyzer
+                .analyze("lib.rs", source)
+                .into_iter()
+                .map(|s| format!("{} {}", s.kind, s.name))
+                .collect()
+        };
+        let mut added = SemanticAnalyzer::with_queries(&queries).expect("valid query");
+        assert_eq!(kinds(&mut added), ["fn helper", "test checks", "struct S"]);
+
+        queries.get_mut("rust").expect("rust").replace = true;
+        let mut replaced = SemanticAnalyzer::with_queries(&queries).expect("valid query");
+        assert_eq!(kinds(&mut replaced), ["test checks"]);
+
+        queries.insert(
+            "cobol".to_string(),
+            SymbolQuery {
+                query: String::new(),
+                replace: false,
+            },
+        );
+        assert!(SemanticAnalyzer::with_queries(&queries).is_err());
+    }
+
+    #[test]
+    fn finds_typescript_symbols() {
+        let source = "export const LIMIT = 3;\nconst local = 1;\n\
+                      export function parse(s: string) {}\n\
+                      interface Props { a: number }\n\
+                      class App {}\n";
+        let mut analyzer = SemanticAnalyzer::new();
+        let symbols: Vec<(String, String)> = analyzer
+            .analyze("src/app.tsx", source)
+            .into_iter()
+            .map(|s| (s.kind, s.name))
+            .collect();
+        let expected = [
+            ("const", "LIMIT"),
+            ("fn", "parse"),
+            ("interface", "Props"),
+            ("class", "App"),
+        ];
+        assert_eq!(
+            symbols,
+            expected.map(|(kind, name)| (kind.to_string(), name.to_string()))
+        );
+        assert!(analyzer.supports("a.ts") && !analyzer.supports("a.js"));
+        assert_eq!(analyzer.analyze("lib.rs", "fn a() {}\n")[0].name, "a");
+    }
+
+    #[test]
+    fn finds_go_symbols_with_receivers() {
+        let source = "package api\n\
+                      type Server struct{ port int }\n\
+                      type Handler interface{ Handle() }\n\
+                      type ID string\n\
+                      func New() *Server { return nil }\n\
+                      func (s *Server) Handle() {}\n";
+        let symbols: Vec<String> = SemanticAnalyzer::new()
+            .analyze("api/server.go", source)
+            .into_iter()
+            .map(|s| format!("{} {}", s.kind, s.name))
+            .collect();
+        assert_eq!(
+            symbols,
+            [
+                "struct Server",
+                "interface Handler",
+                "type ID",
+                "fn New",
+                "fn (*Server) Handle"
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_identifiers_with_lines() {
+        let source =
+            "use crate::app::App;\nfn run(app: &App) {\n    app.zoom_in();\n    log!(app);\n}\n";
+        let mut analyzer = SemanticAnalyzer::new();
+        let identifiers = analyzer.identifiers("src/run.rs", source);
+        let on = |line: usize| -> Vec<&str> {
+            identifiers
+                .iter()
+                .filter(|(_, l)| *l == line)
+                .map(|(name, _)| name.as_str())
+                .collect()
+        };
+        assert_eq!(on(1), ["app", "App"]);
+        assert_eq!(on(2), ["run", "app", "App"]);
+        assert_eq!(on(3), ["app", "zoom_in"]);
+        assert_eq!(on(4), ["log", "app"]);
+        assert_eq!(analyzer.identifiers("README.md", "# App\n"), []);
+    }
+
+    #[test]
+    fn measures_branches_and_nesting() {
+        let source = "fn f(x: u8) -> u8 {\n\
+                      \x20   if x > 1 {\n\
+                      \x20       for _ in 0..x {\n\
+                      \x20           match x { 2 => {}, _ => {} }\n\
+                      \x20       }\n\
+                      \x20   } else if x == 1 {\n\
+                      \x20   }\n\
+                      \x20   x\n\
+                      }\n";
+        let symbols = SemanticAnalyzer::new().analyze("lib.rs", source);
+        let complexity = symbols[0].complexity.unwrap_or_default();
+        // if, else if, for and two arms; the match sits under the if and the for
+        assert_eq!(complexity.to_string(), "9L b5 d3");
+        assert_eq!(complexity.severity(), 1);
+
+        let go = "package p\nfunc g(x int) {\n\tif x > 0 {\n\t} else if x < 0 {\n\t}\n\tswitch x {\n\tcase 1:\n\tdefault:\n\t}\n}\n";
+        let symbols = SemanticAnalyzer::new().analyze("p.go", go);
+        assert_eq!(
+            symbols[0].complexity.map(|c| (c.branches, c.depth)),
+            Some((3, 1))
+        );
+    }
 }
@@ -0,0 +1,423 @@
+// `glim serve`: the changeset model over a local socket as newline-delimited JSON-RPC 2.0
+use crate::app::{App, ZoomLevel};
+use anyhow::{Context, Result};
+use git2::Repository;
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread;
+use std::time::Duration;
+
+// A client that sends nothing for this long is disconnected, freeing its thread
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+// How long a request waits for the app to answer (it may be busy, e.g. suspended for $EDITOR)
+// and for the client to take the response
+const REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// A request read by the listener thread, answered on the thread that owns the `App`.
+#[derive(Debug)]
+struct Request {
+    line: String,
+    reply: Sender<Option<String>>,
+}
+
+/// A bound local socket whose requests are queued for the owner of the `App`.
+///
+/// The TUI drains it between frames; `glim serve` blocks on it. The socket file is
+/// removed on drop.
+#[derive(Debug)]
+pub struct Endpoint {
+    socket: PathBuf,
+    requests: Receiver<Request>,
+}
+
+impl Endpoint {
+    /// Binds `socket`, replacing a stale socket file but never a live instance's.
+    ///
+    /// # Errors
+    /// Returns an error if another glim answers on `socket` or binding fails.
+    #[cfg(unix)]
+    pub fn bind(socket: &Path) -> Result<Self> {
+        use std::os::unix::net::UnixListener;
+
+        if socket.exists() {
+            if is_listening(socket) {
+                anyhow::bail!("Another glim is already listening on {}", socket.display());
+            }
+            // Left behind by a crashed instance; bind would fail on it
+            std::fs::remove_file(socket)
+                .with_context(|| format!("Failed to remove stale socket {}", socket.display()))?;
+        }
+        let listener = UnixListener::bind(socket)
+            .with_context(|| format!("Failed to bind {}", socket.display()))?;
+
+        let (tx, requests) = channel();
+        thread::spawn(move || {
+            // Each client gets its own thread, so a slow or idle one cannot hold up the rest
+            for stream in listener.incoming().flatten() {
+                let tx = tx.clone();
+                thread::spawn(move || serve_client(stream, &tx));
+            }
+        });
+        Ok(Self {
+            socket: socket.to_path_buf(),
+            requests,
+        })
+    }
+
+    /// Local sockets are only implemented for Unix domain sockets so far.
+    ///
+    /// # Errors
+    /// Always returns an error on this platform.
+    #[cfg(not(unix))]
+    pub fn bind(_socket: &Path) -> Result<Self> {
+        anyhow::bail!("Local sockets need Unix domain sockets, which this platform lacks")
+    }
+
+    #[must_use]
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+
+    /// Answers every queued request without blocking; returns whether there were any.
+    pub fn handle_pending(&self, app: &mut App) -> bool {
+        let mut handled = false;
+        while let Ok(request) = self.requests.try_recv() {
+            let _ = request.reply.send(respond(app, &request.line));
+            handled = true;
+        }
+        handled
+    }
+
+    /// Answers requests until the listener thread goes away.
+    pub fn run(&self, app: &mut App) {
+        while let Ok(request) = self.requests.recv() {
+            let _ = request.reply.send(respond(app, &request.line));
+        }
+    }
+}
+
+/// Queues one client's requests for the app and writes back the answers, until the client
+/// hangs up, goes quiet or the app stops answering.
+#[cfg(unix)]
+fn serve_client(stream: std::os::unix::net::UnixStream, tx: &Sender<Request>) {
+    if stream.set_read_timeout(Some(IDLE_TIMEOUT)).is_err()
+        || stream.set_write_timeout(Some(REPLY_TIMEOUT)).is_err()
+    {
+        return;
+    }
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (reply, response) = channel();
+        if tx.send(Request { line, reply }).is_err() {
+            return;
+        }
+        match response.recv_timeout(REPLY_TIMEOUT) {
+            Ok(Some(response)) => {
+                if writeln!(writer, "{response}").is_err() {
+                    break;
+                }
+            }
+            Ok(None) => {}
+            Err(_) => break,
+        }
+    }
+}
+
+impl Drop for Endpoint {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+/// Socket a TUI session on the repository at `path` listens on, inside its `.git` directory.
+#[must_use]
+pub fn instance_socket(path: &Path) -> Option<PathBuf> {
+    Repository::discover(path)
+        .ok()
+        .map(|repo| repo.path().join("glim.sock"))
+}
+
+/// Whether a glim session answers on `socket` (a leftover socket file does not).
+#[must_use]
+pub fn is_listening(socket: &Path) -> bool {
+    #[cfg(unix)]
+    return std::os::unix::net::UnixStream::connect(socket).is_ok();
+    #[cfg(not(unix))]
+    return false;
+}
+
+/// Sends one request to a listening glim and returns its `result`.
+///
+/// # Errors
+/// Returns an error if nothing answers on `socket` or the call itself failed.
+#[cfg(unix)]
+pub fn request(socket: &Path, method: &str, params: &Value) -> Result<Value> {
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(socket)
+        .with_context(|| format!("No glim is listening on {}", socket.display()))?;
+    // A session that is stuck (or suspended behind an editor) must not hang the caller
+    stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+    stream.set_write_timeout(Some(REPLY_TIMEOUT))?;
+    let call = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    writeln!(stream, "{call}")?;
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .with_context(|| format!("glim on {} did not answer", socket.display()))?;
+    let mut response: Value = serde_json::from_str(&line).context("Malformed response")?;
+    if let Some(message) = response.pointer("/error/message").and_then(Value::as_str) {
+        anyhow::bail!("{message}");
+    }
+    Ok(response["result"].take())
+}
+
+/// Local sockets are only implemented for Unix domain sockets so far.
+///
+/// # Errors
+/// Always returns an error on this platform.
+#[cfg(not(unix))]
+pub fn request(_socket: &Path, _method: &str, _params: &Value) -> Result<Value> {
+    anyhow::bail!("Local sockets need Unix domain sockets, which this platform lacks")
+}
+
+/// Splits an editor position like `src/app.rs:42` into a path and an optional line.
+#[must_use]
+pub fn parse_selection(spec: &str) -> (PathBuf, Option<usize>) {
+    match spec.rsplit_once(':') {
+        Some((path, line)) if !path.is_empty() => line.parse().map_or_else(
+            |_| (PathBuf::from(spec), None),
+            |line| (PathBuf::from(path), Some(line)),
+        ),
+        _ => (PathBuf::from(spec), None),
+    }
+}
+
+/// `path` (absolute, or relative to the current directory) relative to `workdir`, as glim lists it.
+#[must_use]
+pub fn repo_relative(path: &Path, workdir: &Path) -> String {
+    let absolute =
+        std::env::current_dir().map_or_else(|_| path.to_path_buf(), |cwd| cwd.join(path));
+    let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+    canonical(&absolute)
+        .strip_prefix(canonical(workdir))
+        .map_or_else(|_| path.to_path_buf(), Path::to_path_buf)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Answers one request line; notifications (no `id`) get no response.
+#[must_use]
+pub fn respond(app: &mut App, line: &str) -> Option<String> {
+    let Ok(request) = serde_json::from_str::<Value>(line) else {
+        return Some(error(&Value::Null, PARSE_ERROR, "Parse error").to_string());
+    };
+    let id = request.get("id").cloned();
+    let method = request.get("method").and_then(Value::as_str).unwrap_or("");
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = call(app, method, &params);
+    let id = id?;
+    Some(
+        match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error(&id, code, &message),
+        }
+        .to_string(),
+    )
+}
+
+fn error(id: &Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn call(app: &mut App, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+    let path = || {
+        params
+            .get("path")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| (INVALID_PARAMS, "Missing string param `path`".to_string()))
+    };
+    match method {
+        "summary" => {
+            let info = &app.dashboard_info;
+            Ok(json!({
+                "repo": info.repo_name,
+                "branch": info.branch_name,
+                "warnings": info.warnings,
+                "description": info.description,
+                "stats": info.stats,
+                "breakdown": info.breakdown,
+                "error": app.error_msg,
+            }))
+        }
+        "modules" => Ok(app
+            .modules
+            .iter()
+            .map(|m| json!({ "name": m.name, "heat": m.heat, "description": m.description }))
+            .collect()),
+        "files" => Ok(app
+            .structures
+            .iter()
+            .filter(|s| s.is_file)
+            .map(|s| {
+                json!({
+                    "path": s.path,
+                    "status": s.status,
+                    "staged": s.is_staged,
+                    "unstaged": s.is_unstaged,
+                })
+            })
+            .collect()),
+        "symbols" => {
+            let filter = params.get("path").and_then(Value::as_str);
+            Ok(app
+                .structures
+                .iter()
+                .filter(|s| !s.is_file && filter.is_none_or(|p| s.path == p))
+                .map(|s| {
+                    json!({
+                        "path": s.path,
+                        "kind": s.status,
+                        "text": s.text.trim(),
+                        "line": s.line_no,
+                    })
+                })
+                .collect())
+        }
+        "diff" => {
+            let path = path()?;
+            app.diff_of(&path)
+                .map(|lines| json!(lines))
+                .ok_or_else(|| (INVALID_PARAMS, format!("`{path}` is not in the changeset")))
+        }
+        "reveal" => {
+            let path = path()?;
+            let line = params
+                .get("line")
+                .and_then(Value::as_u64)
+                .map(|l| l as usize);
+            if app.reveal(&path, line) {
+                Ok(state(app))
+            } else {
+                Err((INVALID_PARAMS, format!("`{path}` is not in the changeset")))
+            }
+        }
+        "state" => Ok(state(app)),
+        "refresh" => {
+            app.refresh_local();
+            Ok(state(app))
+        }
+        _ => Err((METHOD_NOT_FOUND, format!("Unknown method `{method}`"))),
+    }
+}
+
+/// Where the (headless) navigation currently is.
+fn state(app: &App) -> Value {
+    let zoom = match app.zoom_level {
+        ZoomLevel::Galaxy => "galaxy",
+        ZoomLevel::Structure => "structure",
+        ZoomLevel::Logic => "logic",
+    };
+    let selected = (app.zoom_level != ZoomLevel::Galaxy)
+        .then(|| app.selected_item())
+        .flatten();
+    json!({
+        "zoom": zoom,
+        "path": selected.map(|s| &s.path),
+        "cursor": app.logic_cursor,
+        "line": selected.and_then(|_| {
+            crate::diff::new_line_numbers(&app.logic_view_content)
+                .get(app.logic_cursor)
+                .copied()
+                .flatten()
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_selection, respond};
+    use crate::app::App;
+    use crate::bench::{FixtureSize, fixture_repo};
+    use std::path::PathBuf;
+
+    #[test]
+    fn splits_editor_positions() {
+        assert_eq!(
+            parse_selection("src/app.rs:42"),
+            (PathBuf::from("src/app.rs"), Some(42))
+        );
+        assert_eq!(
+            parse_selection("README.md"),
+            (PathBuf::from("README.md"), None)
+        );
+        assert_eq!(parse_selection("C:notes"), (PathBuf::from("C:notes"), None));
+    }
+
+    #[test]
+    fn reports_protocol_errors() {
+        let mut app = App::new(std::env::temp_dir().join("glim-no-such-repo"));
+        assert!(respond(&mut app, "not json").is_some_and(|r| r.contains("-32700")));
+        assert!(
+            respond(&mut app, r#"{"jsonrpc":"2.0","id":1,"method":"nope"}"#)
+                .is_some_and(|r| r.contains("-32601"))
+        );
+        assert!(
+            respond(&mut app, r#"{"jsonrpc":"2.0","id":2,"method":"diff"}"#)
+                .is_some_and(|r| r.contains("-32602"))
+        );
+        assert_eq!(
+            respond(&mut app, r#"{"jsonrpc":"2.0","method":"state"}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn diff_leaves_the_view_alone() {
+        let dir = std::env::temp_dir().join(format!("glim-server-diff-{}", std::process::id()));
+        fixture_repo(
+            &dir,
+            FixtureSize {
+                files: 3,
+                functions: 2,
+            },
+        )
+        .expect("fixture");
+        let mut app = App::new(dir.clone());
+        app.zoom_in();
+        app.search_query = "f1".to_string();
+        app.update_search();
+        let (query, selected, lines) = (
+            app.search_query.clone(),
+            app.selected_index,
+            app.logic_view_content.clone(),
+        );
+
+        let reply = respond(
+            &mut app,
+            r#"{"jsonrpc":"2.0","id":1,"method":"diff","params":{"path":"src/m2/f2.rs"}}"#,
+        )
+        .expect("reply");
+        assert!(reply.contains("x + 1"), "{reply}");
+        assert_eq!(app.search_query, query);
+        assert_eq!(app.selected_index, selected);
+        assert_eq!(app.logic_view_content, lines);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
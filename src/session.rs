@@ -0,0 +1,218 @@
+// Autosaved review state per repository or PR, offered back after a crash or killed terminal
+use crate::app::{App, DiffScope};
+use crate::jumps::Location;
+use crate::notes::{LineNote, Notes};
+use crate::utils::{fnv1a, get_data_dir};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+// How often the main loop may write a changed session to disk
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Where the review was and how the views were set up.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    // Repository workdir or PR URL the session belongs to
+    pub source: String,
+    pub location: Location,
+    pub search_query: String,
+    pub context_lines: u32,
+    pub diff_scope: DiffScope,
+    pub wrap_lines: bool,
+    // Absent from sessions saved before changes-only mode existed
+    #[serde(default)]
+    pub changes_only: bool,
+    pub show_blame: bool,
+    // Files looked at in the Logic view, sorted
+    #[serde(default)]
+    pub opened: Vec<String>,
+    // Private line notes by file, and bookmarks, as in the notes kept for the source
+    #[serde(default)]
+    pub notes: BTreeMap<String, Vec<LineNote>>,
+    #[serde(default)]
+    pub bookmarks: Vec<Location>,
+}
+
+impl Session {
+    /// The session to save for `app`; `None` until a source is loaded.
+    #[must_use]
+    pub fn of(app: &App) -> Option<Self> {
+        Some(Self {
+            source: app.session_key()?,
+            location: app.current_location(),
+            search_query: app.search_query.clone(),
+            context_lines: app.context_lines,
+            diff_scope: app.diff_scope,
+            wrap_lines: app.wrap_lines,
+            changes_only: app.changes_only,
+            show_blame: app.show_blame,
+            opened: {
+                let mut opened: Vec<String> = app.opened.iter().cloned().collect();
+                opened.sort_unstable();
+                opened
+            },
+            notes: app.notes.files.clone(),
+            bookmarks: app.notes.bookmarks.clone(),
+        })
+    }
+
+    /// File the session for `source` is kept in, under the data directory.
+    #[must_use]
+    pub fn path(source: &str) -> PathBuf {
+        get_data_dir()
+            .join("sessions")
+            .join(format!("{:016x}.json", fnv1a(source.as_bytes())))
+    }
+
+    /// The session a previous run left behind for `source`, if it did not exit cleanly.
+    #[must_use]
+    pub fn load(source: &str) -> Option<Self> {
+        let session: Self =
+            serde_json::from_str(&fs::read_to_string(Self::path(source)).ok()?).ok()?;
+        (session.source == source).then_some(session)
+    }
+
+    /// Writes atomically, so a crash mid-save keeps the previous session.
+    ///
+    /// # Errors
+    /// Returns an error if the sessions directory or file cannot be written.
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path(&self.source);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(tmp, path)
+    }
+
+    /// Forgets the session for `source`, e.g. after a clean exit.
+    pub fn discard(source: &str) {
+        let _ = fs::remove_file(Self::path(source));
+    }
+
+    /// The session's notes and bookmarks, to merge into those kept for its source.
+    #[must_use]
+    pub fn notes(&self) -> Notes {
+        Notes {
+            source: self.source.clone(),
+            files: self.notes.clone(),
+            bookmarks: self.bookmarks.clone(),
+        }
+    }
+
+    /// Short description for the restore prompt, e.g. `Logic view of src/app.rs`.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        let view = format!("{:?}", self.location.zoom);
+        self.location.item.as_ref().map_or_else(
+            || format!("{view} view"),
+            |(path, _)| format!("{view} view of {path}"),
+        )
+    }
+}
+
+/// Throttled autosave driven by the main loop.
+#[derive(Debug)]
+pub struct Autosave {
+    last_saved: Option<Session>,
+    last_check: Instant,
+}
+
+impl Default for Autosave {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Autosave {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            last_saved: None,
+            last_check: Instant::now(),
+        }
+    }
+
+    /// Saves the session when it changed and the interval has passed since the last check.
+    pub fn tick(&mut self, app: &App) {
+        if self.last_check.elapsed() < AUTOSAVE_INTERVAL {
+            return;
+        }
+        self.last_check = Instant::now();
+        let session = Session::of(app);
+        if session.is_some() && session != self.last_saved {
+            // Best effort: a read-only data directory only loses crash recovery
+            if session.as_ref().is_some_and(|s| s.save().is_ok()) {
+                self.last_saved = session;
+            }
+        }
+    }
+
+    /// Clean exit: nothing to recover next time.
+    pub fn finish(&self, app: &App) {
+        if let Some(source) = app.session_key() {
+            Session::discard(&source);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Session;
+    use crate::app::{DiffScope, StructureSection, ZoomLevel};
+    use crate::jumps::Location;
+    use crate::notes::LineNote;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn round_trips_review_state() {
+        let location = Location {
+            zoom: ZoomLevel::Logic,
+            section: StructureSection::Unstaged,
+            item: Some(("src/a.rs".into(), "fn load".into())),
+            index: 3,
+            line: 12,
+        };
+        let session = Session {
+            source: "/work/glim".into(),
+            location: location.clone(),
+            search_query: "load".into(),
+            context_lines: 5,
+            diff_scope: DiffScope::Unstaged,
+            wrap_lines: true,
+            changes_only: true,
+            show_blame: false,
+            opened: vec!["src/a.rs".into(), "src/b.rs".into()],
+            notes: BTreeMap::from([(
+                "src/a.rs".into(),
+                vec![LineNote {
+                    line: 12,
+                    left: false,
+                    text: "check the bounds".into(),
+                }],
+            )]),
+            bookmarks: vec![location],
+        };
+        let json = serde_json::to_string(&session).expect("session serializes");
+        let restored: Session = serde_json::from_str(&json).expect("session deserializes");
+        assert_eq!(restored, session);
+        assert_eq!(
+            restored.notes().get("src/a.rs", 12, false),
+            Some("check the bounds")
+        );
+
+        // Sessions saved before notes and bookmarks existed still load
+        let old = json
+            .split(",\"notes\"")
+            .next()
+            .map(|head| format!("{head}}}"))
+            .expect("json has fields");
+        let restored: Session = serde_json::from_str(&old).expect("old session deserializes");
+        assert!(restored.notes.is_empty() && restored.bookmarks.is_empty());
+    }
+}
@@ -0,0 +1,227 @@
+// Typo spotting for added prose: comments, doc comments and Markdown lines
+use std::ops::Range;
+use std::path::Path;
+
+// Frequent misspellings and their corrections, sorted for binary search. A fixed list
+// instead of a dictionary: identifiers and jargon never produce false alarms.
+const MISSPELLINGS: &[(&str, &str)] = &[
+    ("accomodate", "accommodate"),
+    ("acheive", "achieve"),
+    ("adress", "address"),
+    ("agressive", "aggressive"),
+    ("alot", "a lot"),
+    ("apparant", "apparent"),
+    ("arguement", "argument"),
+    ("asynchonous", "asynchronous"),
+    ("availabe", "available"),
+    ("becuase", "because"),
+    ("begining", "beginning"),
+    ("beleive", "believe"),
+    ("calender", "calendar"),
+    ("comming", "coming"),
+    ("commited", "committed"),
+    ("commiting", "committing"),
+    ("compatability", "compatibility"),
+    ("compatable", "compatible"),
+    ("completly", "completely"),
+    ("concurent", "concurrent"),
+    ("configuation", "configuration"),
+    ("consistant", "consistent"),
+    ("continous", "continuous"),
+    ("correspondance", "correspondence"),
+    ("curent", "current"),
+    ("definately", "definitely"),
+    ("dependancy", "dependency"),
+    ("desciption", "description"),
+    ("destory", "destroy"),
+    ("diffrent", "different"),
+    ("doesnt", "doesn't"),
+    ("dont", "don't"),
+    ("enviroment", "environment"),
+    ("exisiting", "existing"),
+    ("explicitely", "explicitly"),
+    ("fucntion", "function"),
+    ("funtion", "function"),
+    ("garantee", "guarantee"),
+    ("happend", "happened"),
+    ("heirarchy", "hierarchy"),
+    ("identifer", "identifier"),
+    ("immediatly", "immediately"),
+    ("implmentation", "implementation"),
+    ("independant", "independent"),
+    ("initalize", "initialize"),
+    ("intial", "initial"),
+    ("isnt", "isn't"),
+    ("lenght", "length"),
+    ("libary", "library"),
+    ("maintainance", "maintenance"),
+    ("managment", "management"),
+    ("neccessary", "necessary"),
+    ("necesary", "necessary"),
+    ("occured", "occurred"),
+    ("occurence", "occurrence"),
+    ("occurrance", "occurrence"),
+    ("ommit", "omit"),
+    ("paramater", "parameter"),
+    ("paramter", "parameter"),
+    ("parrallel", "parallel"),
+    ("persistant", "persistent"),
+    ("posible", "possible"),
+    ("preceeding", "preceding"),
+    ("prefered", "preferred"),
+    ("presense", "presence"),
+    ("previos", "previous"),
+    ("proccess", "process"),
+    ("recieve", "receive"),
+    ("recieved", "received"),
+    ("recomend", "recommend"),
+    ("refered", "referred"),
+    ("reponse", "response"),
+    ("repositry", "repository"),
+    ("retreive", "retrieve"),
+    ("retrun", "return"),
+    ("seperate", "separate"),
+    ("seperated", "separated"),
+    ("seperator", "separator"),
+    ("succesful", "successful"),
+    ("successfull", "successful"),
+    ("sucess", "success"),
+    ("supress", "suppress"),
+    ("suprise", "surprise"),
+    ("teh", "the"),
+    ("thier", "their"),
+    ("threshhold", "threshold"),
+    ("tommorow", "tomorrow"),
+    ("transfered", "transferred"),
+    ("truely", "truly"),
+    ("udpate", "update"),
+    ("untill", "until"),
+    ("upate", "update"),
+    ("usefull", "useful"),
+    ("verison", "version"),
+    ("visable", "visible"),
+    ("whith", "with"),
+    ("wich", "which"),
+    ("wierd", "weird"),
+    ("writting", "writing"),
+];
+
+/// A suspect word: its byte range in the checked text and the likely intended spelling.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Typo {
+    pub range: Range<usize>,
+    pub suggestion: &'static str,
+}
+
+/// Likely typos in the prose part of one line of `path`: the whole line for Markdown and
+/// plain text, the comment for code. Inline code spans and identifiers are skipped.
+#[must_use]
+pub fn typos(path: &str, text: &str) -> Vec<Typo> {
+    let Some(start) = prose_start(path, text) else {
+        return vec![];
+    };
+    let mut found = Vec::new();
+    let mut in_code = false;
+    let mut word: Option<usize> = None;
+    for (i, c) in text[start..]
+        .char_indices()
+        .map(|(i, c)| (start + i, c))
+        .chain([(text.len(), ' ')])
+    {
+        if c.is_alphanumeric() || c == '_' || c == '\'' {
+            word.get_or_insert(i);
+            continue;
+        }
+        if let Some(from) = word.take()
+            && !in_code
+            && let Some(suggestion) = lookup(text[from..i].trim_end_matches('\''))
+        {
+            found.push(Typo {
+                range: from..from + text[from..i].trim_end_matches('\'').len(),
+                suggestion,
+            });
+        }
+        if c == '`' {
+            in_code = !in_code;
+        }
+    }
+    found
+}
+
+/// Correction for a word written in lower case or capitalised; anything else (identifiers,
+/// acronyms, words with digits) is left alone.
+fn lookup(word: &str) -> Option<&'static str> {
+    let mut chars = word.chars();
+    let first = chars.next()?;
+    if !first.is_ascii_alphabetic() || !chars.all(|c| c.is_ascii_lowercase() || c == '\'') {
+        return None;
+    }
+    let lower = word.to_ascii_lowercase();
+    MISSPELLINGS
+        .binary_search_by_key(&lower.as_str(), |&(wrong, _)| wrong)
+        .ok()
+        .map(|i| MISSPELLINGS[i].1)
+}
+
+/// Byte offset where prose begins in `text`, or `None` when the line holds only code.
+fn prose_start(path: &str, text: &str) -> Option<usize> {
+    let path = Path::new(path);
+    let name = path.file_name()?.to_string_lossy();
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "md" | "markdown" | "mdx" | "rst" | "txt" | "adoc" => Some(0),
+        "rs" | "ts" | "tsx" | "js" | "jsx" | "mjs" | "go" | "c" | "h" | "cc" | "cpp" | "hpp"
+        | "java" | "kt" | "swift" | "cs" | "scala" | "dart" | "proto" => {
+            let trimmed = text.trim_start();
+            if trimmed.starts_with("/*") || trimmed.starts_with('*') {
+                return Some(text.len() - trimmed.len());
+            }
+            // `://` is a URL inside a string, not a comment
+            text.match_indices("//")
+                .find(|&(i, _)| i == 0 || text.as_bytes()[i - 1] != b':')
+                .map(|(i, _)| i)
+        }
+        "py" | "sh" | "bash" | "zsh" | "rb" | "toml" | "yaml" | "yml" | "pl" | "r" | "ex"
+        | "exs" | "nix" => hash_comment(text),
+        "sql" | "lua" | "hs" => text.find("--"),
+        _ if name == "Dockerfile" || name == "Makefile" => hash_comment(text),
+        _ => None,
+    }
+}
+
+/// A `#` at the start of the line or after whitespace.
+fn hash_comment(text: &str) -> Option<usize> {
+    text.match_indices('#')
+        .find(|&(i, _)| i == 0 || text.as_bytes()[i - 1].is_ascii_whitespace())
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MISSPELLINGS, typos};
+
+    fn words<'a>(path: &str, text: &'a str) -> Vec<(&'a str, &'static str)> {
+        typos(path, text)
+            .into_iter()
+            .map(|t| (&text[t.range], t.suggestion))
+            .collect()
+    }
+
+    #[test]
+    fn flags_typos_in_prose_only() {
+        assert!(MISSPELLINGS.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(
+            words("src/lib.rs", "let recieve = 1; // Recieve teh data"),
+            [("Recieve", "receive"), ("teh", "the")]
+        );
+        assert_eq!(
+            words("README.md", "Use `seperate` to seperate, see https://x.io"),
+            [("seperate", "separate")]
+        );
+        assert_eq!(words("app.py", "x = '#teh' # fine_teh TEH"), []);
+        assert_eq!(words("src/lib.rs", "let url = \"http://teh\";"), []);
+    }
+}
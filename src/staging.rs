@@ -0,0 +1,184 @@
+// Undo for index operations: each stage / unstage remembers the entries it replaced, and
+// how much of each hunk the index already has
+use crate::diff::line_numbers;
+use git2::{IndexEntry, Repository};
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+use std::path::{Path, PathBuf};
+
+// Staging actions `U` can step back through
+const UNDO_DEPTH: usize = 20;
+
+/// The index entries of some paths as they were before an operation.
+#[derive(Debug)]
+pub struct IndexSnapshot {
+    // What the operation did, for the undo notice (e.g. `staging src/app.rs`)
+    pub description: String,
+    // `None` when the path was not in the index
+    entries: Vec<(PathBuf, Option<IndexEntry>)>,
+}
+
+impl IndexSnapshot {
+    /// Records the current stage-0 entries of `paths`.
+    ///
+    /// # Errors
+    /// Returns an error if the index cannot be read.
+    pub fn capture(
+        repo: &Repository,
+        paths: &[PathBuf],
+        description: String,
+    ) -> Result<Self, git2::Error> {
+        let mut index = repo.index()?;
+        index.read(false)?;
+        let entries = paths
+            .iter()
+            .map(|path| (path.clone(), index.get_path(path, 0)))
+            .collect();
+        Ok(Self {
+            description,
+            entries,
+        })
+    }
+
+    /// Puts the recorded entries back, leaving every other path alone.
+    ///
+    /// # Errors
+    /// Returns an error if the index cannot be read, updated or written.
+    pub fn restore(self, repo: &Repository) -> Result<(), git2::Error> {
+        let mut index = repo.index()?;
+        index.read(false)?;
+        for (path, entry) in self.entries {
+            match entry {
+                Some(entry) => index.add(&entry)?,
+                None => remove_if_present(&mut index, &path)?,
+            }
+        }
+        index.write()
+    }
+}
+
+fn remove_if_present(index: &mut git2::Index, path: &Path) -> Result<(), git2::Error> {
+    if index.get_path(path, 0).is_some() {
+        index.remove_path(path)?;
+    }
+    Ok(())
+}
+
+/// Most recent index operations first out; the oldest fall off past `UNDO_DEPTH`.
+#[derive(Debug, Default)]
+pub struct StagingUndo {
+    stack: Vec<IndexSnapshot>,
+}
+
+impl StagingUndo {
+    pub fn push(&mut self, snapshot: IndexSnapshot) {
+        if self.stack.len() == UNDO_DEPTH {
+            self.stack.remove(0);
+        }
+        self.stack.push(snapshot);
+    }
+
+    pub fn pop(&mut self) -> Option<IndexSnapshot> {
+        self.stack.pop()
+    }
+
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+}
+
+/// How much of one hunk of the HEAD-to-working-tree diff the index already has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HunkStage {
+    Staged,
+    Partial,
+    Unstaged,
+}
+
+impl HunkStage {
+    const fn of(staged: usize, unstaged: usize) -> Option<Self> {
+        match (staged, unstaged) {
+            (0, 0) => None,
+            (_, 0) => Some(Self::Staged),
+            (0, _) => Some(Self::Unstaged),
+            _ => Some(Self::Partial),
+        }
+    }
+}
+
+/// The stage of the hunk each line of a HEAD-to-working-tree diff belongs to; `None` outside
+/// hunks.
+///
+/// `staged_removed` are the HEAD lines the index has dropped, `unstaged_added` the working
+/// tree lines the index does not have yet. A removed line is staged when the index dropped it
+/// too, an added line unless the index still lacks it.
+#[must_use]
+pub fn hunk_stages<S: BuildHasher>(
+    lines: &[String],
+    staged_removed: &HashSet<usize, S>,
+    unstaged_added: &HashSet<usize, S>,
+) -> Vec<Option<HunkStage>> {
+    let mut marks = vec![None; lines.len()];
+    // Start of the current hunk, with its staged and unstaged line counts
+    let mut hunk: Option<(usize, usize, usize)> = None;
+    let mut finish = |hunk: Option<(usize, usize, usize)>, end: usize| {
+        if let Some((start, staged, unstaged)) = hunk {
+            marks[start..end].fill(HunkStage::of(staged, unstaged));
+        }
+    };
+    for (i, (line, (old, new))) in lines.iter().zip(line_numbers(lines)).enumerate() {
+        if line.trim_start().starts_with("@@") {
+            finish(hunk, i);
+            hunk = Some((i, 0, 0));
+            continue;
+        }
+        let Some((_, staged, unstaged)) = hunk.as_mut() else {
+            continue;
+        };
+        let in_index = match (old, new) {
+            (Some(old), None) => staged_removed.contains(&old),
+            (None, Some(new)) => !unstaged_added.contains(&new),
+            _ => continue,
+        };
+        if in_index {
+            *staged += 1;
+        } else {
+            *unstaged += 1;
+        }
+    }
+    finish(hunk, lines.len());
+    marks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HunkStage, hunk_stages};
+    use std::collections::HashSet;
+
+    #[test]
+    fn tells_staged_hunks_apart() {
+        let lines: Vec<String> = [
+            " diff --git a/f b/f",
+            " @@ -1,2 +1,2 @@",
+            "-one",
+            "+ONE",
+            " two",
+            " @@ -10,2 +10,2 @@",
+            "-ten",
+            "+TEN",
+            "+eleven",
+            " @@ -20 +21 @@",
+            "+twenty",
+        ]
+        .map(String::from)
+        .to_vec();
+        // `one` -> `ONE` is staged; of the second hunk only the removal is
+        let staged_removed = HashSet::from([1, 10]);
+        let unstaged_added = HashSet::from([10, 11, 21]);
+        let stages = hunk_stages(&lines, &staged_removed, &unstaged_added);
+        assert_eq!(stages[0], None);
+        assert_eq!(stages[1..5], [Some(HunkStage::Staged); 4]);
+        assert_eq!(stages[5..9], [Some(HunkStage::Partial); 4]);
+        assert_eq!(stages[9..], [Some(HunkStage::Unstaged); 2]);
+    }
+}
@@ -0,0 +1,142 @@
+// Changeset breakdown by language and by test/source/docs classification
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileClass {
+    Source,
+    Test,
+    Docs,
+}
+
+impl FileClass {
+    /// Classifies a path with the usual layout heuristics (`tests/`, `_test.go`, `*.md`, ...).
+    #[must_use]
+    pub fn of(path: &str) -> Self {
+        let lower = path.to_lowercase();
+        let file_name = Path::new(&lower)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let is_docs = lower.starts_with("docs/")
+            || lower.contains("/docs/")
+            || [".md", ".rst", ".adoc", ".txt"]
+                .iter()
+                .any(|ext| file_name.ends_with(ext));
+        if is_docs {
+            return Self::Docs;
+        }
+
+        let is_test = lower.starts_with("tests/")
+            || lower.starts_with("test/")
+            || lower.contains("/tests/")
+            || lower.contains("/test/")
+            || lower.contains("__tests__/")
+            || file_name.starts_with("test_")
+            || ["_test.", ".test.", ".spec.", "_spec."]
+                .iter()
+                .any(|marker| file_name.contains(marker));
+        if is_test { Self::Test } else { Self::Source }
+    }
+}
+
+/// Human-readable language name for a path, keyed on extension.
+#[must_use]
+pub fn language_of(path: &str) -> &'static str {
+    let ext = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    match ext.as_str() {
+        "rs" => "Rust",
+        "ts" | "tsx" => "TypeScript",
+        "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+        "go" => "Go",
+        "py" => "Python",
+        "rb" => "Ruby",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "hpp" | "cxx" => "C++",
+        "md" | "rst" | "adoc" => "Docs",
+        "toml" | "yaml" | "yml" | "json" => "Config",
+        "sh" | "bash" | "zsh" => "Shell",
+        _ => "Other",
+    }
+}
+
+/// Line counts aggregated per language and per [`FileClass`].
+#[derive(Debug, Clone, Default)]
+pub struct ChangeBreakdown {
+    pub by_language: BTreeMap<&'static str, (u64, u64)>,
+    pub source: u64,
+    pub tests: u64,
+    pub docs: u64,
+}
+
+impl ChangeBreakdown {
+    /// Builds a breakdown from `(path, additions, deletions)` triples.
+    pub fn from_files<'a>(files: impl IntoIterator<Item = (&'a str, u64, u64)>) -> Self {
+        let mut breakdown = Self::default();
+        for (path, additions, deletions) in files {
+            let entry = breakdown
+                .by_language
+                .entry(language_of(path))
+                .or_insert((0, 0));
+            entry.0 += additions;
+            entry.1 += deletions;
+
+            let changed = additions + deletions;
+            match FileClass::of(path) {
+                FileClass::Source => breakdown.source += changed,
+                FileClass::Test => breakdown.tests += changed,
+                FileClass::Docs => breakdown.docs += changed,
+            }
+        }
+        breakdown
+    }
+
+    /// One-line summary, e.g. `Rust +800/-120, Config +3/-1 | tests 35%, docs 2%`.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let mut languages: Vec<_> = self.by_language.iter().collect();
+        languages.sort_by_key(|(_, (a, d))| std::cmp::Reverse(a + d));
+
+        let mut out = languages
+            .iter()
+            .map(|(lang, (a, d))| format!("{lang} +{a}/-{d}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let total = self.source + self.tests + self.docs;
+        if total > 0 {
+            let pct = |n: u64| n * 100 / total;
+            let _ = write!(
+                out,
+                " | tests {}%, docs {}%",
+                pct(self.tests),
+                pct(self.docs)
+            );
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChangeBreakdown, FileClass};
+
+    #[test]
+    fn classifies_and_summarizes() {
+        assert_eq!(FileClass::of("src/app.rs"), FileClass::Source);
+        assert_eq!(FileClass::of("tests/cli.rs"), FileClass::Test);
+        assert_eq!(FileClass::of("pkg/server_test.go"), FileClass::Test);
+        assert_eq!(FileClass::of("README.md"), FileClass::Docs);
+
+        let breakdown = ChangeBreakdown::from_files([("src/a.rs", 60, 10), ("tests/b.rs", 30, 0)]);
+        assert_eq!(breakdown.summary(), "Rust +90/-10 | tests 30%, docs 0%");
+    }
+}
@@ -0,0 +1,139 @@
+// GitHub "suggested changes": replacement lines proposed in a review comment, applied to the
+// local clone of the PR's repository
+use crate::github::ReviewThread;
+
+/// A suggestion replacing lines `start..=end` (1-based, new side of the PR) of `path`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub path: String,
+    pub start: usize,
+    pub end: usize,
+    pub lines: Vec<String>,
+}
+
+/// Contents of the ` ```suggestion ` blocks in a comment body, in order.
+#[must_use]
+pub fn blocks(body: &str) -> Vec<Vec<String>> {
+    let mut found = Vec::new();
+    let mut current: Option<(String, Vec<String>)> = None;
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if let Some((fence, lines)) = current.as_mut() {
+            // The closing fence is at least as long as the opening one
+            if trimmed.starts_with(fence.as_str()) && trimmed.trim_start_matches('`').is_empty() {
+                found.push(std::mem::take(lines));
+                current = None;
+            } else {
+                lines.push(line.trim_end_matches('\r').to_string());
+            }
+        } else {
+            let fence: String = trimmed.chars().take_while(|&c| c == '`').collect();
+            if fence.len() >= 3 && trimmed[fence.len()..].trim() == "suggestion" {
+                current = Some((fence, Vec::new()));
+            }
+        }
+    }
+    found
+}
+
+impl Suggestion {
+    /// The latest suggestion in `thread`; threads on removed lines cannot carry one.
+    #[must_use]
+    pub fn from_thread(thread: &ReviewThread) -> Option<Self> {
+        if thread.left {
+            return None;
+        }
+        let lines = thread
+            .comments
+            .iter()
+            .rev()
+            .find_map(|comment| blocks(&comment.body).pop())?;
+        Some(Self {
+            path: thread.path.clone(),
+            start: thread.start_line.unwrap_or(thread.line).min(thread.line),
+            end: thread.line,
+            lines,
+        })
+    }
+
+    /// `start-end`, or just the line for one-line suggestions.
+    #[must_use]
+    pub fn range(&self) -> String {
+        if self.start == self.end {
+            self.start.to_string()
+        } else {
+            format!("{}-{}", self.start, self.end)
+        }
+    }
+
+    /// Whether `text` already has the suggested lines where the suggestion puts them; how
+    /// applied suggestions are told apart without keeping track of them.
+    #[must_use]
+    pub fn is_applied(&self, text: &str) -> bool {
+        let lines: Vec<&str> = text.lines().collect();
+        let start = self.start.saturating_sub(1);
+        // A suggestion deleting lines leaves nothing to recognise
+        !self.lines.is_empty()
+            && lines
+                .get(start..start + self.lines.len())
+                .is_some_and(|found| found.iter().zip(&self.lines).all(|(a, b)| *a == b))
+    }
+
+    /// `text` with the suggestion applied, or `None` when lines `start..=end` are no longer
+    /// `expected`, the lines the reviewer commented on.
+    #[must_use]
+    pub fn apply(&self, text: &str, expected: &[String]) -> Option<String> {
+        let lines: Vec<&str> = text.split_inclusive('\n').collect();
+        let range = self.start.checked_sub(1)?..self.end;
+        let current = lines.get(range.clone())?;
+        let unchanged = current.len() == expected.len()
+            && current
+                .iter()
+                .zip(expected)
+                .all(|(line, want)| line.trim_end_matches(['\r', '\n']) == want);
+        if !unchanged {
+            return None;
+        }
+        let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+        // Keep the file's lack of a final newline when the suggestion replaces its last line
+        let last_has_newline = current.last().is_some_and(|line| line.ends_with('\n'));
+        let mut out: String = lines[..range.start].concat();
+        for (i, line) in self.lines.iter().enumerate() {
+            out.push_str(line);
+            if i + 1 < self.lines.len() || last_has_newline {
+                out.push_str(newline);
+            }
+        }
+        out.push_str(&lines[range.end..].concat());
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Suggestion, blocks};
+
+    #[test]
+    fn reads_and_applies_suggestions() {
+        let body = "Tighter:\n```suggestion\nlet b = 2;\n```\nand\n````suggestion\n```\n````";
+        assert_eq!(
+            blocks(body),
+            [vec!["let b = 2;".to_string()], vec!["```".to_string()]]
+        );
+
+        let suggestion = Suggestion {
+            path: "src/lib.rs".into(),
+            start: 2,
+            end: 3,
+            lines: vec!["let b = 2;".into()],
+        };
+        let text = "fn f() {\nlet a = 1;\nlet b = a + 1;\n}\n";
+        let expected = ["let a = 1;".to_string(), "let b = a + 1;".to_string()];
+        let applied = suggestion.apply(text, &expected).expect("applies");
+        assert_eq!(applied, "fn f() {\nlet b = 2;\n}\n");
+        assert!(suggestion.is_applied(&applied));
+        assert!(!suggestion.is_applied(text));
+        assert_eq!(suggestion.apply(&applied, &expected), None);
+        assert_eq!(suggestion.range(), "2-3");
+    }
+}
@@ -0,0 +1,581 @@
+// Symbols of every tracked file and the names each one mentions, so `/@name` reaches
+// definitions outside the changeset and changed symbols can count their references. A worker
+// thread builds it once per repository and then re-reads only the paths that changed. Names
+// are kept as hashes; the files that may mention a symbol are parsed again to place them.
+use crate::config::SymbolQuery;
+use crate::semantics::{self, SemanticAnalyzer, SymbolChange};
+use crate::utils::fnv1a;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, TryRecvError, channel};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Modification time and size a file was indexed at.
+pub type Stamp = (SystemTime, u64);
+
+/// One symbol somewhere in the repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedSymbol {
+    pub path: String,
+    pub kind: String,
+    pub name: String,
+    pub line: usize,
+}
+
+/// A line naming a symbol outside its own definition.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Reference {
+    pub path: String,
+    pub line: usize,
+}
+
+/// References to each changed symbol, keyed by its path and line.
+pub type Impact = HashMap<(String, usize), Vec<Reference>>;
+
+#[derive(Debug, Clone)]
+struct IndexedFile {
+    stamp: Stamp,
+    symbols: Vec<SymbolChange>,
+    // Sorted hashes of the identifiers the file mentions
+    names: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SymbolIndex {
+    files: HashMap<String, IndexedFile>,
+}
+
+/// The name references use for `symbol`: Go methods are called without their receiver.
+fn bare_name(name: &str) -> &str {
+    name.rsplit(") ").next().unwrap_or(name)
+}
+
+impl SymbolIndex {
+    /// Whether `path` was indexed at `stamp`, so it need not be read again.
+    #[must_use]
+    pub fn is_current(&self, path: &str, stamp: Stamp) -> bool {
+        self.files.get(path).is_some_and(|file| file.stamp == stamp)
+    }
+
+    /// Replaces what is known about `path`.
+    pub fn insert(
+        &mut self,
+        path: String,
+        stamp: Stamp,
+        symbols: Vec<SymbolChange>,
+        identifiers: &[(String, usize)],
+    ) {
+        let mut names: Vec<u64> = identifiers
+            .iter()
+            .map(|(name, _)| fnv1a(name.as_bytes()))
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        self.files.insert(
+            path,
+            IndexedFile {
+                stamp,
+                symbols,
+                names,
+            },
+        );
+    }
+
+    /// Forgets `path`, e.g. once it was deleted.
+    pub fn remove(&mut self, path: &str) {
+        self.files.remove(path);
+    }
+
+    /// Drops files `keep` rejects, e.g. those no longer tracked.
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+        self.files.retain(|path, _| keep(path));
+    }
+
+    /// Number of files indexed.
+    #[must_use]
+    pub fn files(&self) -> usize {
+        self.files.len()
+    }
+
+    /// The symbol of `path` starting on `line`.
+    #[must_use]
+    pub fn symbol_at(&self, path: &str, line: usize) -> Option<&SymbolChange> {
+        self.files
+            .get(path)?
+            .symbols
+            .iter()
+            .find(|symbol| symbol.start_line == line)
+    }
+
+    /// Up to `limit` symbols whose name contains `query` (case-insensitively): exact names
+    /// first, then those starting with it, each by path.
+    #[must_use]
+    pub fn search(&self, query: &str, limit: usize) -> Vec<IndexedSymbol> {
+        let query = query.to_lowercase();
+        let mut hits: Vec<(u8, &str, &SymbolChange)> = self
+            .files
+            .iter()
+            .flat_map(|(path, file)| file.symbols.iter().map(move |s| (path.as_str(), s)))
+            .filter_map(|(path, symbol)| {
+                let name = symbol.name.to_lowercase();
+                let bare = bare_name(&name);
+                let rank = if bare == query {
+                    0
+                } else if bare.starts_with(&query) {
+                    1
+                } else if name.contains(&query) {
+                    2
+                } else {
+                    return None;
+                };
+                Some((rank, path, symbol))
+            })
+            .collect();
+        hits.sort_by(|(a_rank, a_path, a), (b_rank, b_path, b)| {
+            (a_rank, a_path, a.start_line).cmp(&(b_rank, b_path, b.start_line))
+        });
+        hits.into_iter()
+            .take(limit)
+            .map(|(_, path, symbol)| IndexedSymbol {
+                path: path.to_string(),
+                kind: symbol.kind.clone(),
+                name: symbol.name.clone(),
+                line: symbol.start_line,
+            })
+            .collect()
+    }
+
+    /// Files mentioning any of `symbols` by name, by path; a hash collision can add a few that
+    /// do not.
+    #[must_use]
+    pub fn mentioning(&self, symbols: &[&SymbolChange]) -> Vec<String> {
+        let wanted: Vec<u64> = symbols
+            .iter()
+            .map(|symbol| fnv1a(bare_name(&symbol.name).as_bytes()))
+            .collect();
+        let mut paths: Vec<String> = self
+            .files
+            .iter()
+            .filter(|(_, file)| {
+                wanted
+                    .iter()
+                    .any(|hash| file.names.binary_search(hash).is_ok())
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        paths.sort();
+        paths
+    }
+}
+
+/// References to each of `targets` (a path and a symbol defined there) in `candidates`.
+///
+/// The candidates are read from under `root` and parsed again. A reference is a line naming the
+/// symbol anywhere but inside its own definition, one per line, by path.
+pub fn find_references(
+    root: &Path,
+    analyzer: &mut SemanticAnalyzer,
+    candidates: &[String],
+    targets: &[(&str, &SymbolChange)],
+) -> Vec<Vec<Reference>> {
+    let files = candidates.iter().filter_map(|path| {
+        let content = semantics::read_analyzable(&root.join(path)).ok()?;
+        let identifiers = analyzer.identifiers(path, &content);
+        // The analyzer keeps trees only for the changeset
+        analyzer.forget(path);
+        Some((path.as_str(), identifiers))
+    });
+    match_references(targets, files)
+}
+
+/// References to each of `targets` among `files`, each a path and its identifiers by line.
+fn match_references<'a>(
+    targets: &[(&str, &SymbolChange)],
+    files: impl Iterator<Item = (&'a str, Vec<(String, usize)>)>,
+) -> Vec<Vec<Reference>> {
+    let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, (_, symbol)) in targets.iter().enumerate() {
+        by_name.entry(bare_name(&symbol.name)).or_default().push(i);
+    }
+    let mut found: Vec<HashSet<Reference>> = vec![HashSet::new(); targets.len()];
+    for (path, identifiers) in files {
+        for (name, line) in identifiers {
+            let Some(indices) = by_name.get(name.as_str()) else {
+                continue;
+            };
+            for &i in indices {
+                let (target_path, symbol) = targets[i];
+                let inside =
+                    target_path == path && (symbol.start_line..=symbol.end_line).contains(&line);
+                if !inside {
+                    found[i].insert(Reference {
+                        path: path.to_string(),
+                        line,
+                    });
+                }
+            }
+        }
+    }
+    found
+        .into_iter()
+        .map(|references| {
+            let mut references: Vec<Reference> = references.into_iter().collect();
+            references.sort();
+            references
+        })
+        .collect()
+}
+
+/// Paths the index worker is asked to look at again.
+#[derive(Debug, Clone, Default)]
+pub struct IndexRequest {
+    // List every tracked file too, dropping the files no longer tracked
+    pub full: bool,
+    pub paths: HashSet<String>,
+}
+
+impl IndexRequest {
+    /// Every tracked file, plus `paths` (e.g. untracked changes).
+    #[must_use]
+    pub fn full(paths: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            full: true,
+            paths: paths.into_iter().collect(),
+        }
+    }
+
+    /// Only `paths`, which may have changed or gone since the last run.
+    #[must_use]
+    pub fn paths(paths: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            full: false,
+            paths: paths.into_iter().collect(),
+        }
+    }
+
+    /// Adds what `other` asks for to this request.
+    pub fn merge(&mut self, other: Self) {
+        self.full |= other.full;
+        self.paths.extend(other.paths);
+    }
+}
+
+/// One run of the index worker, which hands back the references to the changed symbols.
+#[derive(Debug)]
+pub struct IndexJob {
+    full: bool,
+    result: Receiver<Impact>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl IndexJob {
+    /// Brings `index` up to date on a background thread as `request` asks, then finds
+    /// references to `targets` (changed symbols, each a path and its first line).
+    #[must_use]
+    pub fn spawn(
+        index: Arc<Mutex<SymbolIndex>>,
+        root: PathBuf,
+        queries: BTreeMap<String, SymbolQuery>,
+        request: IndexRequest,
+        targets: Vec<(String, usize)>,
+    ) -> Self {
+        let (tx, result) = channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (full, stop) = (request.full, Arc::clone(&cancel));
+        std::thread::spawn(move || {
+            let mut analyzer = SemanticAnalyzer::with_queries(&queries)
+                .unwrap_or_else(|_| SemanticAnalyzer::new());
+            let mut paths = request.paths;
+            if request.full {
+                paths.extend(tracked_files(&root));
+                if let Ok(mut index) = index.lock() {
+                    index.retain(|path| paths.contains(path));
+                }
+            }
+            for path in &paths {
+                if stop.load(Ordering::Relaxed) {
+                    return;
+                }
+                reindex(&index, &mut analyzer, &root, path);
+            }
+            let impact = impact_of(&index, &mut analyzer, &root, &targets);
+            // The app may have moved on and dropped the job
+            let _ = tx.send(impact);
+        });
+        Self {
+            full,
+            result,
+            cancel,
+        }
+    }
+
+    /// Whether this run lists every tracked file rather than a few paths.
+    #[must_use]
+    pub const fn is_full(&self) -> bool {
+        self.full
+    }
+
+    /// The references found, once the worker is done (none if it died).
+    #[must_use]
+    pub fn finished(&self) -> Option<Impact> {
+        match self.result.try_recv() {
+            Ok(impact) => Some(impact),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Impact::new()),
+        }
+    }
+}
+
+impl Drop for IndexJob {
+    fn drop(&mut self) {
+        // A new repository or query set replaced the index this worker fills
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Paths in the git index of the repository at `root`.
+fn tracked_files(root: &Path) -> Vec<String> {
+    git2::Repository::open(root)
+        .and_then(|repo| repo.index())
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|entry| String::from_utf8(entry.path).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Re-reads `path` unless its modification time and size are unchanged, or forgets it when it
+/// is gone or has no symbols to offer. The lock is not held while parsing.
+fn reindex(index: &Mutex<SymbolIndex>, analyzer: &mut SemanticAnalyzer, root: &Path, path: &str) {
+    let full = root.join(path);
+    let indexable = !semantics::is_outlined(path) && analyzer.supports(path);
+    let stamp = fs::metadata(&full).ok().filter(|_| indexable).map(|meta| {
+        (
+            meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            meta.len(),
+        )
+    });
+    let current = stamp.is_some_and(|stamp| {
+        index
+            .lock()
+            .is_ok_and(|index| index.is_current(path, stamp))
+    });
+    if current {
+        return;
+    }
+    let parsed = stamp
+        .zip(semantics::read_analyzable(&full).ok())
+        .map(|(stamp, content)| {
+            let symbols = analyzer.analyze(path, &content);
+            let identifiers = analyzer.identifiers(path, &content);
+            analyzer.forget(path);
+            (stamp, symbols, identifiers)
+        });
+    if let Ok(mut index) = index.lock() {
+        match parsed {
+            Some((stamp, symbols, identifiers)) => {
+                index.insert(path.to_string(), stamp, symbols, &identifiers);
+            }
+            None => index.remove(path),
+        }
+    }
+}
+
+/// References to `targets` (each a path and a first line) from anywhere in `index`.
+fn impact_of(
+    index: &Mutex<SymbolIndex>,
+    analyzer: &mut SemanticAnalyzer,
+    root: &Path,
+    targets: &[(String, usize)],
+) -> Impact {
+    let (symbols, candidates) = {
+        let Ok(index) = index.lock() else {
+            return Impact::new();
+        };
+        let symbols: Vec<(&str, SymbolChange)> = targets
+            .iter()
+            .filter_map(|(path, line)| Some((path.as_str(), index.symbol_at(path, *line)?.clone())))
+            .collect();
+        let candidates = index.mentioning(&symbols.iter().map(|(_, s)| s).collect::<Vec<_>>());
+        (symbols, candidates)
+    };
+    let targets: Vec<(&str, &SymbolChange)> = symbols.iter().map(|(p, s)| (*p, s)).collect();
+    let references = find_references(root, analyzer, &candidates, &targets);
+    targets
+        .iter()
+        .zip(references)
+        .map(|((path, symbol), refs)| ((path.to_string(), symbol.start_line), refs))
+        .collect()
+}
+
+/// The references of one symbol listed with `I`, each with its line of code.
+#[derive(Debug, Clone)]
+pub struct ImpactPanel {
+    pub symbol: String,
+    pub entries: Vec<(Reference, String)>,
+    pub selected: usize,
+}
+
+impl ImpactPanel {
+    /// Panel for `references`, reading their lines from the working tree under `root`.
+    #[must_use]
+    pub fn new(symbol: String, references: Vec<Reference>, root: &Path) -> Self {
+        let mut files: HashMap<String, Vec<String>> = HashMap::new();
+        let entries = references
+            .into_iter()
+            .map(|reference| {
+                let lines = files.entry(reference.path.clone()).or_insert_with(|| {
+                    fs::read_to_string(root.join(&reference.path))
+                        .map(|text| text.lines().map(str::to_string).collect())
+                        .unwrap_or_default()
+                });
+                let code = lines
+                    .get(reference.line - 1)
+                    .map_or_else(String::new, |line| line.trim().to_string());
+                (reference, code)
+            })
+            .collect();
+        Self {
+            symbol,
+            entries,
+            selected: 0,
+        }
+    }
+
+    pub const fn next(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub const fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    #[must_use]
+    pub fn current(&self) -> Option<&(Reference, String)> {
+        self.entries.get(self.selected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SymbolIndex, match_references};
+    use crate::semantics::SymbolChange;
+    use std::time::SystemTime;
+
+    fn symbol(kind: &str, name: &str, start_line: usize, end_line: usize) -> SymbolChange {
+        SymbolChange {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            start_line,
+            end_line,
+            complexity: None,
+        }
+    }
+
+    fn identifiers(names: &[(&str, usize)]) -> Vec<(String, usize)> {
+        names
+            .iter()
+            .map(|(name, line)| ((*name).to_string(), *line))
+            .collect()
+    }
+
+    fn index() -> SymbolIndex {
+        let mut index = SymbolIndex::default();
+        let stamp = (SystemTime::UNIX_EPOCH, 0);
+        index.insert(
+            "src/a.rs".to_string(),
+            stamp,
+            vec![
+                symbol("fn", "parse_args", 3, 5),
+                symbol("fn", "reparse", 9, 12),
+            ],
+            &identifiers(&[("parse_args", 3), ("parse_args", 10)]),
+        );
+        index.insert(
+            "src/b.rs".to_string(),
+            stamp,
+            vec![symbol("struct", "Parse", 1, 1)],
+            &identifiers(&[("parse_args", 7), ("Parse", 1)]),
+        );
+        index.insert(
+            "api/s.go".to_string(),
+            stamp,
+            vec![symbol("fn", "(*S) parseAll", 4, 6)],
+            &identifiers(&[("parseAll", 9)]),
+        );
+        index
+    }
+
+    #[test]
+    fn ranks_exact_then_prefix_then_substring() {
+        let index = index();
+        assert_eq!(index.files(), 3);
+        let names: Vec<String> = index
+            .search("PARSE", 10)
+            .into_iter()
+            .map(|s| format!("{}:{} {}", s.path, s.line, s.name))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "src/b.rs:1 Parse",
+                "api/s.go:4 (*S) parseAll",
+                "src/a.rs:3 parse_args",
+                "src/a.rs:9 reparse"
+            ]
+        );
+        assert_eq!(index.search("parse", 1).len(), 1);
+    }
+
+    #[test]
+    fn narrows_references_to_files_mentioning_the_name() {
+        let index = index();
+        let parse_args = index.symbol_at("src/a.rs", 3).expect("indexed").clone();
+        let method = index.symbol_at("api/s.go", 4).expect("indexed").clone();
+        assert_eq!(index.mentioning(&[&parse_args]), ["src/a.rs", "src/b.rs"]);
+        assert_eq!(index.mentioning(&[&method]), ["api/s.go"]);
+    }
+
+    #[test]
+    fn references_skip_the_definition_and_repeats() {
+        let index = index();
+        let parse_args = index.symbol_at("src/a.rs", 3).expect("indexed").clone();
+        let method = index.symbol_at("api/s.go", 4).expect("indexed").clone();
+        let files = vec![
+            (
+                "src/a.rs",
+                identifiers(&[
+                    ("parse_args", 3),
+                    ("parse_args", 4),
+                    ("parse_args", 10),
+                    ("parse_args", 10),
+                ]),
+            ),
+            ("src/b.rs", identifiers(&[("parse_args", 7), ("Parse", 1)])),
+            ("api/s.go", identifiers(&[("parseAll", 9)])),
+        ];
+        let found = match_references(
+            &[("src/a.rs", &parse_args), ("api/s.go", &method)],
+            files.into_iter(),
+        );
+        let lines: Vec<Vec<String>> = found
+            .iter()
+            .map(|refs| {
+                refs.iter()
+                    .map(|r| format!("{}:{}", r.path, r.line))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            [vec!["src/a.rs:10", "src/b.rs:7"], vec!["api/s.go:9"]]
+        );
+    }
+}
@@ -0,0 +1,373 @@
+// Diff colours, taken from the user's `color.diff.*` gitconfig where set
+use crate::config::DiffColors;
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+
+type Rgb = (u8, u8, u8);
+
+// Accents the theme background is tinted toward for added / removed lines
+const ADDED_ACCENT: Rgb = (0x2e, 0xa0, 0x43);
+const REMOVED_ACCENT: Rgb = (0xd7, 0x3a, 0x49);
+// Tint steps tried when a background is too close to the text colour, and the faintest
+// tint kept so added and removed lines stay distinguishable
+const TINT_STEP: f32 = 0.05;
+const MIN_TINT: f32 = 0.1;
+
+/// Per-slot overrides for the Logic view; `None` keeps glim's own look for that slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DiffTheme {
+    // `color.diff.new` / `color.diff.old`: added and removed lines
+    pub added: Option<Style>,
+    pub removed: Option<Style>,
+    // `color.diff.context` (or the older `color.diff.plain`)
+    pub context: Option<Style>,
+    // `color.diff.meta`: file headers such as `diff --git` and `index`
+    pub meta: Option<Style>,
+    // `color.diff.frag`: `@@` hunk headers
+    pub frag: Option<Style>,
+}
+
+impl DiffTheme {
+    /// Reads the `color.diff.*` slots from `config`; unparsable values are ignored like git does.
+    #[must_use]
+    pub fn from_git_config(config: &git2::Config) -> Self {
+        let slot = |name: &str| {
+            config
+                .get_string(&format!("color.diff.{name}"))
+                .ok()
+                .and_then(|value| parse_git_color(&value))
+        };
+        Self {
+            added: slot("new"),
+            removed: slot("old"),
+            context: slot("context").or_else(|| slot("plain")),
+            meta: slot("meta"),
+            frag: slot("frag"),
+        }
+    }
+}
+
+/// How colour is used: glim's palette, pure black and white, or no colour at all with
+/// state carried by symbols and emphasis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorMode {
+    #[default]
+    Normal,
+    HighContrast,
+    Monochrome,
+}
+
+impl ColorMode {
+    /// The configured mode, or monochrome when `NO_COLOR` is set (<https://no-color.org>).
+    #[must_use]
+    pub fn effective(self) -> Self {
+        if self == Self::Normal && std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            Self::Monochrome
+        } else {
+            self
+        }
+    }
+
+    /// Emphasis marking added / removed Logic view lines, which lose their backgrounds.
+    #[must_use]
+    pub fn diff_emphasis(self, prefix: &str) -> Modifier {
+        match (self, prefix) {
+            (Self::Normal, _) => Modifier::empty(),
+            (_, "+") => Modifier::BOLD,
+            (_, "-") => Modifier::ITALIC,
+            _ => Modifier::empty(),
+        }
+    }
+
+    /// Repaints a drawn frame: colours that carry meaning become emphasis (red bold and
+    /// underlined, green bold), the grey background of selected rows becomes reverse video,
+    /// and everything else is white on black or the terminal's own colours.
+    pub fn apply(self, buffer: &mut Buffer) {
+        if self == Self::Normal {
+            return;
+        }
+        for cell in &mut buffer.content {
+            let mut emphasis = match cell.fg {
+                Color::Red | Color::LightRed => Modifier::BOLD | Modifier::UNDERLINED,
+                Color::Green | Color::LightGreen => Modifier::BOLD,
+                // Dimmed grey would lose the contrast high-contrast mode is for
+                Color::DarkGray if self == Self::Monochrome => Modifier::DIM,
+                _ => Modifier::empty(),
+            };
+            if cell.bg == Color::DarkGray {
+                emphasis |= Modifier::REVERSED;
+            }
+            cell.modifier |= emphasis;
+            (cell.fg, cell.bg) = match self {
+                Self::HighContrast => (Color::White, Color::Black),
+                _ => (Color::Reset, Color::Reset),
+            };
+        }
+    }
+}
+
+/// Logic view line backgrounds, matched to the syntax theme the lines are highlighted with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DiffBackgrounds {
+    pub added: Option<Color>,
+    pub removed: Option<Color>,
+    // The theme's own background, so syntax colours keep the contrast they were designed for
+    pub context: Option<Color>,
+}
+
+impl DiffBackgrounds {
+    /// Tints the theme background toward green / red, backing off the tint until the theme's
+    /// text colour stays readable; colours set in `colors` are used as given.
+    #[must_use]
+    pub fn derive(theme: &syntect::highlighting::Theme, colors: &DiffColors) -> Self {
+        let rgb = |c: syntect::highlighting::Color| (c.r, c.g, c.b);
+        let base = theme.settings.background.map(rgb);
+        let text = theme.settings.foreground.map_or_else(
+            || {
+                if base.is_some_and(|b| luminance(b) > 0.5) {
+                    (0, 0, 0)
+                } else {
+                    (0xff, 0xff, 0xff)
+                }
+            },
+            rgb,
+        );
+        let pick = |value: &Option<String>, derived: Option<Rgb>| {
+            value
+                .as_deref()
+                .and_then(|v| color(&v.to_ascii_lowercase()))
+                .or_else(|| derived.map(|(r, g, b)| Color::Rgb(r, g, b)))
+        };
+        let tinted = |accent| {
+            readable_tint(
+                base.unwrap_or((0, 0, 0)),
+                accent,
+                text,
+                colors.tint,
+                colors.min_contrast,
+            )
+        };
+        Self {
+            added: pick(&colors.added, Some(tinted(ADDED_ACCENT))),
+            removed: pick(&colors.removed, Some(tinted(REMOVED_ACCENT))),
+            context: pick(&colors.context, base),
+        }
+    }
+}
+
+/// `base` moved `tint` of the way toward `accent`, reduced until `text` reaches `min_contrast`.
+fn readable_tint(base: Rgb, accent: Rgb, text: Rgb, tint: f32, min_contrast: f32) -> Rgb {
+    let mut tint = tint.clamp(0.0, 1.0);
+    loop {
+        let bg = blend(base, accent, tint);
+        // Keep a faint tint even when the theme itself misses the target
+        if contrast(text, bg) >= min_contrast || tint - TINT_STEP < MIN_TINT {
+            return bg;
+        }
+        tint -= TINT_STEP;
+    }
+}
+
+fn blend(from: Rgb, to: Rgb, amount: f32) -> Rgb {
+    let mix = |a: u8, b: u8| {
+        (f32::from(b) - f32::from(a))
+            .mul_add(amount, f32::from(a))
+            .round() as u8
+    };
+    (mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+/// WCAG relative luminance.
+fn luminance((r, g, b): Rgb) -> f32 {
+    let channel = |c: u8| {
+        let c = f32::from(c) / 255.0;
+        if c <= 0.039_28 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.0722f32.mul_add(
+        channel(b),
+        0.2126f32.mul_add(channel(r), 0.7152 * channel(g)),
+    )
+}
+
+/// WCAG contrast ratio, from 1 (identical) to 21 (black on white).
+fn contrast(a: Rgb, b: Rgb) -> f32 {
+    let (la, lb) = (luminance(a), luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// Badge in a GitHub label's colour (`d73a4a`), with black or white text, whichever reads
+/// better on it.
+#[must_use]
+pub fn label_style(hex: &str) -> Style {
+    let Some(Color::Rgb(r, g, b)) = color(&format!("#{hex}")) else {
+        return Style::default().fg(Color::White).bg(Color::DarkGray);
+    };
+    let text = if contrast((0, 0, 0), (r, g, b)) >= contrast((255, 255, 255), (r, g, b)) {
+        Color::Black
+    } else {
+        Color::White
+    };
+    Style::default().fg(text).bg(Color::Rgb(r, g, b))
+}
+
+/// Parses a git colour value such as `bold red`, `green black`, `#ff8800 ul` or `208`.
+///
+/// The first colour is the foreground and the second the background; `normal` skips a slot.
+#[must_use]
+pub fn parse_git_color(value: &str) -> Option<Style> {
+    let mut style = Style::default();
+    let mut colors = 0;
+    for word in value.split_whitespace() {
+        let word = word.to_ascii_lowercase();
+        if let Some(modifier) = attribute(&word) {
+            style = style.add_modifier(modifier);
+            continue;
+        }
+        // `no-bold` / `nobold` switch an attribute off, which is already the default here
+        if let Some(rest) = word.strip_prefix("no-").or_else(|| word.strip_prefix("no"))
+            && attribute(rest).is_some()
+        {
+            continue;
+        }
+        let color = if word == "normal" {
+            None
+        } else {
+            Some(color(&word)?)
+        };
+        match (colors, color) {
+            (0, Some(c)) => style = style.fg(c),
+            (1, Some(c)) => style = style.bg(c),
+            (0 | 1, None) => {}
+            _ => return None,
+        }
+        colors += 1;
+    }
+    Some(style)
+}
+
+fn attribute(word: &str) -> Option<Modifier> {
+    Some(match word {
+        "bold" => Modifier::BOLD,
+        "dim" => Modifier::DIM,
+        "italic" => Modifier::ITALIC,
+        "ul" | "underline" => Modifier::UNDERLINED,
+        "blink" => Modifier::SLOW_BLINK,
+        "reverse" => Modifier::REVERSED,
+        "strike" => Modifier::CROSSED_OUT,
+        _ => return None,
+    })
+}
+
+fn color(word: &str) -> Option<Color> {
+    if let Some(hex) = word.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+    }
+    if let Ok(index) = word.parse::<u8>() {
+        return Some(Color::Indexed(index));
+    }
+    let (bright, name) = word
+        .strip_prefix("bright")
+        .map_or((false, word), |rest| (true, rest));
+    Some(match (name, bright) {
+        ("default", false) => Color::Reset,
+        ("black", false) => Color::Black,
+        ("black", true) => Color::DarkGray,
+        ("red", false) => Color::Red,
+        ("red", true) => Color::LightRed,
+        ("green", false) => Color::Green,
+        ("green", true) => Color::LightGreen,
+        ("yellow", false) => Color::Yellow,
+        ("yellow", true) => Color::LightYellow,
+        ("blue", false) => Color::Blue,
+        ("blue", true) => Color::LightBlue,
+        ("magenta", false) => Color::Magenta,
+        ("magenta", true) => Color::LightMagenta,
+        ("cyan", false) => Color::Cyan,
+        ("cyan", true) => Color::LightCyan,
+        ("white", false) => Color::Gray,
+        ("white", true) => Color::White,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColorMode, contrast, parse_git_color, readable_tint};
+    use ratatui::buffer::Buffer;
+    use ratatui::layout::Rect;
+    use ratatui::style::{Color, Modifier, Style};
+
+    #[test]
+    fn encodes_colours_as_emphasis() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 3, 1));
+        buffer[(0, 0)].set_style(Style::default().fg(Color::Red));
+        buffer[(1, 0)].set_style(Style::default().fg(Color::DarkGray));
+        buffer[(2, 0)].set_style(Style::default().fg(Color::Cyan).bg(Color::DarkGray));
+        ColorMode::Monochrome.apply(&mut buffer);
+        let modifiers: Vec<Modifier> = buffer.content.iter().map(|c| c.modifier).collect();
+        assert_eq!(
+            modifiers,
+            [
+                Modifier::BOLD | Modifier::UNDERLINED,
+                Modifier::DIM,
+                Modifier::REVERSED
+            ]
+        );
+        assert!(
+            buffer
+                .content
+                .iter()
+                .all(|c| c.fg == Color::Reset && c.bg == Color::Reset)
+        );
+
+        ColorMode::HighContrast.apply(&mut buffer);
+        assert_eq!(
+            (buffer[(1, 0)].fg, buffer[(1, 0)].bg),
+            (Color::White, Color::Black)
+        );
+    }
+
+    #[test]
+    fn backs_off_tint_for_contrast() {
+        let (white, black) = ((0xff, 0xff, 0xff), (0, 0, 0));
+        assert!((contrast(white, black) - 21.0).abs() < 0.01);
+
+        // Dark text on a light theme: a strong red tint would make it hard to read
+        let light = (0xff, 0xff, 0xff);
+        let text = (0x32, 0x32, 0x32);
+        let strong = readable_tint(light, (0xd7, 0x3a, 0x49), text, 0.9, 4.5);
+        assert!(contrast(text, strong) >= 4.5);
+        assert_ne!(strong, light);
+    }
+
+    #[test]
+    fn parses_git_color_values() {
+        assert_eq!(
+            parse_git_color("bold green"),
+            Some(
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD)
+            )
+        );
+        assert_eq!(
+            parse_git_color("normal #203020"),
+            Some(Style::default().bg(Color::Rgb(0x20, 0x30, 0x20)))
+        );
+        assert_eq!(
+            parse_git_color("208 brightblack no-bold"),
+            Some(Style::default().fg(Color::Indexed(208)).bg(Color::DarkGray))
+        );
+        assert_eq!(parse_git_color("chartreuse"), None);
+    }
+}
@@ -2,14 +2,29 @@ use anyhow::Result;
 use crossterm::{
     event::{self, Event},
     execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    terminal::{
+        EnterAlternateScreen, LeaveAlternateScreen, SetTitle, disable_raw_mode, enable_raw_mode,
+    },
 };
 use ratatui::{Terminal, backend::Backend};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::{fmt, io, time::Duration};
 
+// xterm title stack: save the shell's title on entry and put it back on exit
+const PUSH_TITLE: &str = "\x1b[22;0t";
+const POP_TITLE: &str = "\x1b[23;0t";
+// OSC 133 (FinalTerm semantic prompts): glim's run shows up as one command's output block
+const OUTPUT_START: &str = "\x1b]133;C\x07";
+const OUTPUT_END: &str = "\x1b]133;D\x07";
+
 pub struct Tui<B: Backend> {
     terminal: Terminal<B>,
     pub events: Box<dyn Iterator<Item = Event> + Send>,
+    // Title, directory and semantic markers for the terminal (`terminal_integration`)
+    integration: bool,
+    // Title and directory last reported, so they are only sent when they change
+    reported: Option<(String, Option<PathBuf>)>,
 }
 
 impl<B: Backend> fmt::Debug for Tui<B> {
@@ -21,10 +36,12 @@ impl<B: Backend> fmt::Debug for Tui<B> {
 }
 
 impl<B: Backend> Tui<B> {
-    pub fn new(terminal: Terminal<B>) -> Self {
+    pub fn new(terminal: Terminal<B>, integration: bool) -> Self {
         Self {
             terminal,
             events: Box::new(std::iter::empty()),
+            integration,
+            reported: None,
         }
     }
 
@@ -33,6 +50,11 @@ impl<B: Backend> Tui<B> {
     /// # Errors
     /// Returns an error if raw mode execution fails.
     pub fn enter(&mut self) -> Result<()> {
+        if self.integration {
+            let mut out = io::stdout();
+            write!(out, "{PUSH_TITLE}{OUTPUT_START}")?;
+            out.flush()?;
+        }
         enable_raw_mode()?;
         execute!(io::stdout(), EnterAlternateScreen)?;
         Ok(())
@@ -48,9 +70,46 @@ impl<B: Backend> Tui<B> {
         self.terminal
             .show_cursor()
             .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        if self.integration {
+            let mut out = io::stdout();
+            write!(out, "{OUTPUT_END}{POP_TITLE}")?;
+            out.flush()?;
+            // Whatever runs next (an editor, the shell) sets its own title
+            self.reported = None;
+        }
+        Ok(())
+    }
+
+    /// Tells the terminal what glim shows: the window / tab title (OSC 2) and the directory
+    /// under review (OSC 7), which multiplexers and shell integrations pick up.
+    ///
+    /// # Errors
+    /// Returns an error if writing to the terminal fails.
+    pub fn report(&mut self, title: &str, dir: Option<&Path>) -> Result<()> {
+        let current = (title.to_string(), dir.map(Path::to_path_buf));
+        if !self.integration || self.reported.as_ref() == Some(&current) {
+            return Ok(());
+        }
+        let mut out = io::stdout();
+        execute!(out, SetTitle(title))?;
+        if let Some(dir) = dir {
+            write!(out, "{}", working_directory(dir))?;
+            out.flush()?;
+        }
+        self.reported = Some(current);
         Ok(())
     }
 
+    /// Forces a full redraw, e.g. after an external program used the screen.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal cannot be cleared.
+    pub fn clear(&mut self) -> Result<()> {
+        self.terminal
+            .clear()
+            .map_err(|e| anyhow::anyhow!(e.to_string()))
+    }
+
     /// Draws the UI.
     ///
     /// # Errors
@@ -70,3 +129,44 @@ impl<B: Backend> Tui<B> {
         None
     }
 }
+
+/// Puts the terminal back before a panic message prints, so the message (and anything printed
+/// while unwinding, like the safe-mode restore) lands on a usable screen.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = execute!(io::stdout(), LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+        previous(info);
+    }));
+}
+
+/// OSC 7 sequence reporting `dir` as a `file://` URL on this host.
+fn working_directory(dir: &Path) -> String {
+    let host = std::env::var("HOSTNAME").unwrap_or_default();
+    let path: String = dir
+        .to_string_lossy()
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+                char::from(byte).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect();
+    format!("\x1b]7;file://{host}{path}\x1b\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::working_directory;
+    use std::path::Path;
+
+    #[test]
+    fn encodes_directory_url() {
+        let osc = working_directory(Path::new("/home/me/my repo"));
+        assert!(osc.starts_with("\x1b]7;file://"));
+        assert!(osc.ends_with("/home/me/my%20repo\x1b\\"));
+    }
+}
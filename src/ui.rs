@@ -1,16 +1,55 @@
-use crate::app::{App, InputMode, ZoomLevel};
+use crate::actions::ActionMenu;
+use crate::api_surface::{ApiBreak, ApiSymbol};
+use crate::app::{App, InputMode, StructureItem, StructureSection, ZoomLevel, wrapped_rows};
+use crate::branches::BranchPanel;
+use crate::conflict::{ConflictFile, LineKind};
+use crate::diff::{is_meta_line, mode_badge};
+use crate::github::{CheckAnnotation, CheckStatus, CiState, ReviewThread};
+use crate::highlight::{self, StyledLine};
+use crate::labels::LabelPicker;
+use crate::markdown;
+use crate::notes::LineNote;
+use crate::onboarding::{Onboarding, SetupChoice};
+use crate::panels::Panel;
+use crate::people::PeoplePicker;
+use crate::pr_picker::PrPicker;
+use crate::review::{CommentDraft, ReviewDraft, ReviewEvent};
+use crate::spelling::typos;
+use crate::staging::HunkStage;
+use crate::symbol_index::ImpactPanel;
+use crate::theme::{DiffBackgrounds, label_style};
+use crate::verdicts::Verdict;
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, List, ListItem, Paragraph, Row, Table, Wrap},
+    widgets::{
+        Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table, Wrap,
+    },
 };
-use syntect::easy::HighlightLines;
-use syntect::highlighting::ThemeSet;
-use syntect::parsing::SyntaxSet;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::rc::Rc;
+
+// CI checks listed on the Galaxy dashboard before the rest collapse into a "more" row
+const MAX_CHECK_ROWS: usize = 6;
+// Rows of the PR description shown on the Galaxy dashboard; `D` opens the rest
+const MAX_DESCRIPTION_ROWS: usize = 8;
+// Newly public symbols listed on the Galaxy dashboard; exports list them all
+const MAX_API_ROWS: usize = 6;
 
 pub fn render(app: &App, frame: &mut Frame) {
+    draw(app, frame);
+    app.config.color_mode.effective().apply(frame.buffer_mut());
+}
+
+fn draw(app: &App, frame: &mut Frame) {
+    if let Some(onboarding) = &app.onboarding {
+        render_onboarding(onboarding, frame);
+        return;
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -23,6 +62,362 @@ pub fn render(app: &App, frame: &mut Frame) {
     render_header(app, frame, chunks[0]);
     render_main(app, frame, chunks[1]);
     render_footer(app, frame, chunks[2]);
+
+    if let Some(menu) = &app.action_menu {
+        render_action_menu(menu, frame, chunks[1]);
+    }
+    if let Some(panel) = &app.branch_panel {
+        render_branch_panel(panel, frame, chunks[1]);
+    }
+    if let Some(panel) = &app.impact_panel {
+        render_impact_panel(panel, frame, chunks[1]);
+    }
+    if let Some(cursor) = app.checks_cursor {
+        render_checks_popup(app.pr_checks(), cursor, frame, chunks[1]);
+    }
+    if let Some(cursor) = app.profile_cursor {
+        render_profile_picker(app, cursor, frame, chunks[1]);
+    }
+    if let Some(picker) = &app.people_picker {
+        render_people_picker(app, picker, frame, chunks[1]);
+    }
+    if let Some(picker) = &app.label_picker {
+        render_label_picker(app, picker, frame, chunks[1]);
+    }
+    if let (Some(scroll), Some(body)) = (app.description_scroll, app.pr_description()) {
+        render_description_popup(body, scroll, frame, chunks[1]);
+    }
+    if let Some(draft) = &app.review_draft {
+        render_review_draft(draft, frame, chunks[1]);
+    }
+    if let Some(draft) = &app.comment_draft {
+        render_comment_draft(draft, "Comment", "Post", frame, chunks[1]);
+    }
+    if let Some(draft) = &app.note_draft {
+        render_comment_draft(draft, "Private note", "Save", frame, chunks[1]);
+    }
+    if let Some(pending) = &app.pending_confirm {
+        render_confirm(&pending.prompt(), frame, chunks[1]);
+    }
+}
+
+/// Verdict tabs above the message editor, with the composer keys below.
+fn render_review_draft(draft: &ReviewDraft, frame: &mut Frame, area: Rect) {
+    let popup = centered_rect(72, 14, area);
+    frame.render_widget(Clear, popup);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Submit Review ")
+        .style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+    let tabs: Vec<Span> = [
+        ReviewEvent::Comment,
+        ReviewEvent::Approve,
+        ReviewEvent::RequestChanges,
+    ]
+    .into_iter()
+    .map(|event| {
+        let style = if event == draft.event {
+            Style::default()
+                .bg(Color::DarkGray)
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        Span::styled(format!(" {} ", event.label()), style)
+    })
+    .collect();
+    frame.render_widget(Paragraph::new(Line::from(tabs)), rows[0]);
+    frame.render_widget(&draft.message, rows[1]);
+    frame.render_widget(
+        Paragraph::new("[Tab] Verdict  [Ctrl-s] Submit  [Esc] Cancel")
+            .style(Style::default().fg(Color::DarkGray)),
+        rows[2],
+    );
+}
+
+/// Message editor for a line comment or note, titled with the line it goes on.
+fn render_comment_draft(
+    draft: &CommentDraft,
+    kind: &str,
+    action: &str,
+    frame: &mut Frame,
+    area: Rect,
+) {
+    let popup = centered_rect(72, 12, area);
+    frame.render_widget(Clear, popup);
+    let side = if draft.left { " (old)" } else { "" };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {kind} on {}:{}{side} ", draft.path, draft.line))
+        .style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+    frame.render_widget(&draft.message, rows[0]);
+    frame.render_widget(
+        Paragraph::new(format!("[Ctrl-s] {action}  [Esc] Cancel"))
+            .style(Style::default().fg(Color::DarkGray)),
+        rows[1],
+    );
+}
+
+fn render_onboarding(onboarding: &Onboarding, frame: &mut Frame) {
+    fn check(ok: bool, label: &str, hint: &'static str) -> Line<'static> {
+        let (mark, color) = if ok {
+            ("✔", Color::Green)
+        } else {
+            ("✘", Color::Red)
+        };
+        Line::from(vec![
+            Span::styled(format!("  {mark} "), Style::default().fg(color)),
+            Span::styled(format!("{label:<22}"), Style::default().fg(Color::White)),
+            Span::styled(
+                if ok { "" } else { hint },
+                Style::default().fg(Color::DarkGray),
+            ),
+        ])
+    }
+    let caps = onboarding.capabilities;
+    let on_off = |on: bool| if on { "on" } else { "off" };
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "Welcome to glim! Here is what this terminal supports:",
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        check(
+            caps.gh,
+            "GitHub CLI (gh)",
+            "install gh to review pull requests",
+        ),
+        check(
+            caps.truecolor,
+            "Truecolor",
+            "colours fall back to the 256-colour palette",
+        ),
+        check(
+            caps.nerd_fonts,
+            "Nerd Fonts (guessed)",
+            "plain symbol icons will be used",
+        ),
+        check(
+            caps.clipboard,
+            "Clipboard",
+            "copy actions will report an error",
+        ),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Settings (Enter to change):",
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+    ];
+    for (i, choice) in SetupChoice::ALL.iter().enumerate() {
+        let text = match choice {
+            SetupChoice::StartView => {
+                format!("Start view: {:?}", onboarding.config.start_view)
+            }
+            SetupChoice::Truecolor => format!("Truecolor: {}", on_off(onboarding.config.truecolor)),
+            SetupChoice::NerdFonts => {
+                format!("Nerd Font icons: {}", on_off(onboarding.config.nerd_fonts))
+            }
+            SetupChoice::Save => "Save config and start".to_string(),
+        };
+        let style = if i == onboarding.selected {
+            Style::default().bg(Color::DarkGray).fg(Color::White)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        let prefix = if i == onboarding.selected { "> " } else { "  " };
+        lines.push(Line::from(Span::styled(format!("{prefix}{text}"), style)));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "[j/k] Move  [Enter] Change / Save  [Esc] Skip",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let popup = centered_rect(76, lines.len() as u16 + 2, frame.area());
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" First-run Setup ")
+                .style(Style::default().fg(Color::Cyan)),
+        ),
+        popup,
+    );
+}
+
+/// `Color::Rgb`, or its nearest 256-colour palette entry when truecolor is off.
+fn rgb(app: &App, r: u8, g: u8, b: u8) -> Color {
+    if app.config.truecolor {
+        return Color::Rgb(r, g, b);
+    }
+    let level = |c: u8| (u16::from(c) * 5 / 255) as u8;
+    Color::Indexed(16 + 36 * level(r) + 6 * level(g) + level(b))
+}
+
+fn render_confirm(prompt: &str, frame: &mut Frame, area: Rect) {
+    let width = (prompt.chars().count() as u16 + 4).max(30);
+    let popup = centered_rect(width, 5, area);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(Span::styled(prompt, Style::default().fg(Color::White))),
+            Line::from(""),
+            Line::from(Span::styled(
+                "[y] Confirm   [any other key] Cancel",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ])
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Confirm ")
+                .style(Style::default().fg(Color::Red)),
+        ),
+        popup,
+    );
+}
+
+/// A `width` x `height` rectangle centred in `area`, clamped to fit.
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn render_action_menu(menu: &ActionMenu, frame: &mut Frame, area: Rect) {
+    let items: Vec<ListItem> = menu
+        .actions
+        .iter()
+        .enumerate()
+        .map(|(i, action)| {
+            let (prefix, style) = if i == menu.selected {
+                ("> ", Style::default().bg(Color::DarkGray).fg(Color::White))
+            } else {
+                ("  ", Style::default().fg(Color::Gray))
+            };
+            ListItem::new(format!("{prefix}{}", action.label())).style(style)
+        })
+        .collect();
+
+    let popup = centered_rect(32, menu.actions.len() as u16 + 2, area);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Actions ")
+                .style(Style::default().fg(Color::Cyan)),
+        ),
+        popup,
+    );
+}
+
+fn render_branch_panel(panel: &BranchPanel, frame: &mut Frame, area: Rect) {
+    let items: Vec<ListItem> = panel
+        .entries
+        .iter()
+        .map(|branch| {
+            let marker = if branch.is_head { "* " } else { "  " };
+            let name_style = if branch.is_remote {
+                Style::default().fg(Color::Red)
+            } else if branch.is_head {
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let mut spans = vec![Span::raw(marker), Span::styled(&branch.name, name_style)];
+            if let Some((ahead, behind)) = branch.ahead_behind
+                && (ahead, behind) != (0, 0)
+            {
+                spans.push(Span::styled(
+                    format!("  ↑{ahead} ↓{behind}"),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let popup = centered_rect(60, panel.entries.len() as u16 + 2, area);
+    let mut state = ListState::default().with_selected(Some(panel.selected));
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(
+        List::new(items)
+            .highlight_style(Style::default().bg(Color::DarkGray))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Branches [Enter] Checkout ")
+                    .style(Style::default().fg(Color::Cyan)),
+            ),
+        popup,
+        &mut state,
+    );
+}
+
+fn render_impact_panel(panel: &ImpactPanel, frame: &mut Frame, area: Rect) {
+    let items: Vec<ListItem> = panel
+        .entries
+        .iter()
+        .map(|(reference, code)| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{}:{}  ", reference.path, reference.line),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(code.clone(), Style::default().fg(Color::White)),
+            ]))
+        })
+        .collect();
+
+    let popup = centered_rect(80, (panel.entries.len() as u16).min(20) + 2, area);
+    let mut state = ListState::default().with_selected(Some(panel.selected));
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(
+        List::new(items)
+            .highlight_style(Style::default().bg(Color::DarkGray))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(
+                        " {} referenced from {} place{} [Enter] Open in $EDITOR ",
+                        panel.symbol,
+                        panel.entries.len(),
+                        if panel.entries.len() == 1 { "" } else { "s" }
+                    ))
+                    .style(Style::default().fg(Color::Cyan)),
+            ),
+        popup,
+        &mut state,
+    );
 }
 
 fn render_header(app: &App, frame: &mut Frame, area: Rect) {
@@ -48,27 +443,84 @@ fn render_header(app: &App, frame: &mut Frame, area: Rect) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(inner_area);
 
-    // Left: Title
-    let title_span = Span::styled(
+    // Left: Breadcrumbs leading to the current view title
+    let crumbs = app.breadcrumbs();
+    let mut spans: Vec<Span> = crumbs[..crumbs.len() - 1]
+        .iter()
+        .map(|c| Span::styled(format!("{c} › "), Style::default().fg(Color::DarkGray)))
+        .collect();
+    spans.push(Span::styled(
         title,
         Style::default()
             .add_modifier(Modifier::BOLD)
             .fg(Color::White),
-    );
-    frame.render_widget(Paragraph::new(title_span), chunks[0]);
+    ));
+    frame.render_widget(Paragraph::new(Line::from(spans)), chunks[0]);
 
     // Right: Status (Right Aligned)
     let status = format!(
         "{} - {}",
         app.dashboard_info.repo_name, app.dashboard_info.branch_name
     );
-    let status_paragraph = Paragraph::new(Span::styled(status, Style::default().fg(Color::Gray)))
-        .alignment(Alignment::Right);
+    let mut status_spans = vec![];
+    if app.safe_mode.is_some() {
+        status_spans.push(Span::styled(
+            "🛡 SAFE MODE  ",
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    if app.pr_is_draft() {
+        status_spans.push(Span::styled(
+            "◌ DRAFT  ",
+            Style::default()
+                .fg(Color::Gray)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    if let Some(register) = app.macros.recording() {
+        status_spans.push(Span::styled(
+            format!("● REC @{register}  "),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if let Some((opened, total)) = app.review_progress() {
+        let color = if opened == total {
+            Color::Green
+        } else {
+            Color::Gray
+        };
+        status_spans.push(Span::styled(
+            format!("{opened}/{total} files reviewed  "),
+            Style::default().fg(color),
+        ));
+    }
+    status_spans.push(Span::styled(status, Style::default().fg(Color::Gray)));
+    if !app.dashboard_info.warnings.is_empty() {
+        status_spans.push(Span::styled(
+            format!(" ⚠ {}", app.dashboard_info.warnings.join("; ")),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    let status_paragraph = Paragraph::new(Line::from(status_spans)).alignment(Alignment::Right);
 
     frame.render_widget(status_paragraph, chunks[1]);
 }
 
 fn render_main(app: &App, frame: &mut Frame, area: Rect) {
+    if app.source.is_none()
+        && let Some(msg) = &app.error_msg
+    {
+        render_error(app, msg, frame, area);
+        return;
+    }
+
+    if let Some(picker) = &app.pr_picker {
+        render_pr_picker(picker, frame, area);
+        return;
+    }
+
     match app.zoom_level {
         ZoomLevel::Galaxy => render_galaxy(app, frame, area),
         ZoomLevel::Structure => render_structure(app, frame, area),
@@ -76,22 +528,487 @@ fn render_main(app: &App, frame: &mut Frame, area: Rect) {
     }
 }
 
-fn render_galaxy(app: &App, frame: &mut Frame, area: Rect) {
-    // Split into Dashboard info (Top) and Heatmap (Bottom)
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(8), // Dashboard Info
-            Constraint::Min(0),    // Heatmap List
+/// Open PRs with their CI state, number, author and title.
+fn render_pr_picker(picker: &PrPicker, frame: &mut Frame, area: Rect) {
+    let items: Vec<ListItem> = picker
+        .entries
+        .iter()
+        .enumerate()
+        .map(|(i, pr)| {
+            let ci = pr.ci_state();
+            let ci_color = match ci {
+                CiState::Passing => Color::Green,
+                CiState::Failing => Color::Red,
+                CiState::Pending => Color::Yellow,
+                CiState::None => Color::DarkGray,
+            };
+            let (prefix, style) = if i == picker.selected {
+                ("> ", Style::default().bg(Color::DarkGray))
+            } else {
+                ("  ", Style::default())
+            };
+            let reviewed = picker.is_reviewed(pr);
+            let title_style = if reviewed {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD)
+            };
+            let mut spans = vec![Span::styled(prefix, Style::default().fg(Color::Yellow))];
+            if picker.queue.is_some() {
+                spans.push(Span::styled(
+                    if reviewed {
+                        "✔ reviewed  "
+                    } else {
+                        "            "
+                    },
+                    Style::default().fg(Color::Green),
+                ));
+            }
+            spans.push(Span::styled(
+                format!("{} ", ci.symbol()),
+                Style::default().fg(ci_color),
+            ));
+            if let Some(repo) = &pr.repository {
+                spans.push(Span::styled(
+                    format!("{:<24} ", repo.name_with_owner),
+                    Style::default().fg(Color::Gray),
+                ));
+            }
+            spans.extend([
+                Span::styled(
+                    format!("#{:<6}", pr.number),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(
+                    format!("{:<16} ", pr.author.login),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::styled(pr.title.as_str(), title_style),
+            ]);
+            ListItem::new(Line::from(spans)).style(style)
+        })
+        .collect();
+    let title = match picker.progress() {
+        Some((reviewed, total)) => format!(" Review Queue: {reviewed} of {total} reviewed "),
+        None => format!(
+            " Open Pull Requests{} ",
+            picker
+                .repo
+                .as_ref()
+                .map_or_else(String::new, |repo| format!(" in {repo}"))
+        ),
+    };
+    frame.render_widget(
+        List::new(items).block(Block::default().borders(Borders::ALL).title(title)),
+        area,
+    );
+}
+
+fn render_error(app: &App, msg: &str, frame: &mut Frame, area: Rect) {
+    let heading = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(Span::styled(
+            msg,
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    if let Some(err) = &app.gh_error {
+        lines.push(Line::from(Span::styled("Command", heading)));
+        lines.push(Line::from(format!("  $ {}", err.command)));
+        lines.push(Line::from(""));
+        if !err.stderr.is_empty() {
+            lines.push(Line::from(Span::styled("Output", heading)));
+            lines.extend(err.stderr.lines().map(|l| {
+                Line::from(Span::styled(
+                    format!("  {l}"),
+                    Style::default().fg(Color::Gray),
+                ))
+            }));
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled("Suggested fixes", heading)));
+        lines.extend(
+            err.remediation()
+                .iter()
+                .map(|fix| Line::from(format!("  • {fix}"))),
+        );
+        lines.push(Line::from(""));
+    }
+
+    lines.push(Line::from(Span::styled(
+        "[r] Retry   [q] Quit",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Failed to Load ")
+                    .style(Style::default().fg(Color::Red)),
+            )
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+/// One check as a list row: state symbol, name, and its details URL dimmed.
+fn check_line(check: &CheckStatus) -> Line<'static> {
+    let color = match check.state {
+        CiState::Passing => Color::Green,
+        CiState::Failing => Color::Red,
+        CiState::Pending => Color::Yellow,
+        CiState::None => Color::DarkGray,
+    };
+    Line::from(vec![
+        Span::styled(
+            format!("{} ", check.state.symbol()),
+            Style::default().fg(color),
+        ),
+        Span::styled(
+            format!("{:<32} ", check.name),
+            Style::default().fg(Color::White),
+        ),
+        Span::styled(
+            check.url.clone().unwrap_or_default(),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ])
+}
+
+/// The PR's CI checks on the Galaxy dashboard, most urgent first.
+fn render_checks(checks: &[CheckStatus], frame: &mut Frame, area: Rect) {
+    let count = |state| checks.iter().filter(|c| c.state == state).count();
+    let title = format!(
+        " CI Checks: {} failing, {} pending, {} passing [C] Open ",
+        count(CiState::Failing),
+        count(CiState::Pending),
+        count(CiState::Passing)
+    );
+    let mut lines: Vec<Line> = checks.iter().take(MAX_CHECK_ROWS).map(check_line).collect();
+    if checks.len() > MAX_CHECK_ROWS
+        && let Some(last) = lines.last_mut()
+    {
+        *last = Line::styled(
+            format!("… {} more", checks.len() - MAX_CHECK_ROWS + 1),
+            Style::default().fg(Color::DarkGray),
+        );
+    }
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)),
+        area,
+    );
+}
+
+/// Public Rust items the changeset breaks, then the symbols it newly makes public, so API
+/// changes get a deliberate look.
+fn render_api_changes(breaks: &[ApiBreak], symbols: &[ApiSymbol], frame: &mut Frame, area: Rect) {
+    let title = if breaks.is_empty() {
+        format!(" New Public API: {} symbol(s) ", symbols.len())
+    } else {
+        format!(
+            " Public API: {} breaking, {} new ",
+            breaks.len(),
+            symbols.len()
+        )
+    };
+    let broken = breaks.iter().map(|b| {
+        Line::from(vec![
+            Span::styled(
+                "⚠ ",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(format!("{} ", b.kind), Style::default().fg(Color::Cyan)),
+            Span::styled(
+                b.name.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(format!(" {}", b.change), Style::default().fg(Color::Red)),
+            Span::styled(
+                format!("  {}:{}", b.path, b.line),
+                Style::default().fg(Color::DarkGray),
+            ),
         ])
-        .split(area);
+    });
+    let added = symbols.iter().map(|symbol| {
+        Line::from(vec![
+            Span::styled("+ ", Style::default().fg(Color::Green)),
+            Span::styled(
+                format!("{} ", symbol.kind),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::styled(
+                symbol.name.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("  {}:{}", symbol.path, symbol.line),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ])
+    });
+    let total = breaks.len() + symbols.len();
+    let mut lines: Vec<Line> = broken.chain(added).take(MAX_API_ROWS).collect();
+    if total > MAX_API_ROWS
+        && let Some(last) = lines.last_mut()
+    {
+        *last = Line::styled(
+            format!("… {} more", total - MAX_API_ROWS + 1),
+            Style::default().fg(Color::DarkGray),
+        );
+    }
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)),
+        area,
+    );
+}
 
-    // 1. Dashboard Info
-    let info_block = Block::default()
+/// Every check, with the highlighted one's details page a keypress away.
+fn render_checks_popup(checks: &[CheckStatus], cursor: usize, frame: &mut Frame, area: Rect) {
+    let items: Vec<ListItem> = checks
+        .iter()
+        .enumerate()
+        .map(|(i, check)| {
+            let style = if i == cursor {
+                Style::default().bg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+            ListItem::new(check_line(check)).style(style)
+        })
+        .collect();
+    let popup = centered_rect(area.width.saturating_sub(8), checks.len() as u16 + 2, area);
+    frame.render_widget(Clear, popup);
+    let mut state = ListState::default().with_selected(Some(cursor));
+    frame.render_stateful_widget(
+        List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" CI Checks [Enter] Open details [Esc] Close ")
+                .style(Style::default().fg(Color::Cyan)),
+        ),
+        popup,
+        &mut state,
+    );
+}
+
+/// Profiles from `config.toml`, the active one marked.
+fn render_profile_picker(app: &App, cursor: usize, frame: &mut Frame, area: Rect) {
+    let active = app.config.profile();
+    let names = app.profile_names();
+    let items: Vec<ListItem> = names
+        .iter()
+        .enumerate()
+        .map(|(i, &name)| {
+            let is_active = if i == 0 {
+                active.is_none()
+            } else {
+                active == Some(name)
+            };
+            let marker = if is_active { "● " } else { "  " };
+            let style = if i == cursor {
+                Style::default().bg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+            ListItem::new(format!("{marker}{name}")).style(style)
+        })
+        .collect();
+    let popup = centered_rect(48, names.len() as u16 + 2, area);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Profile [Enter] Switch [Esc] Close ")
+                .style(Style::default().fg(Color::Cyan)),
+        ),
+        popup,
+    );
+}
+
+/// People to request a review from or assign, those the PR already has in the role marked.
+fn render_people_picker(app: &App, picker: &PeoplePicker, frame: &mut Frame, area: Rect) {
+    let current = app
+        .pr_review()
+        .map_or(&[][..], |review| picker.role.current(review));
+    let matches = picker.matches();
+    let items: Vec<ListItem> = matches
+        .iter()
+        .map(|&name| {
+            let marker = if current.iter().any(|c| c == name) {
+                "● "
+            } else {
+                "  "
+            };
+            ListItem::new(format!("{marker}{name}"))
+        })
+        .collect();
+    let popup = centered_rect(48, area.height.saturating_sub(4).min(20), area);
+    frame.render_widget(Clear, popup);
+    let block = Block::default()
         .borders(Borders::ALL)
-        .title(" Repository Overview ");
+        .title(format!(
+            " {} [Tab] {} [Enter] Add [Esc] Close ",
+            picker.role.label(),
+            picker.role.next().label()
+        ))
+        .style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+    let [filter_area, list_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(inner);
+    frame.render_widget(
+        Paragraph::new(format!("> {}", picker.filter)).style(Style::default().fg(Color::Yellow)),
+        filter_area,
+    );
+    let mut state = ListState::default().with_selected(Some(picker.selected));
+    frame.render_stateful_widget(
+        List::new(items).highlight_style(Style::default().bg(Color::DarkGray)),
+        list_area,
+        &mut state,
+    );
+}
 
-    let rows = vec![
+/// The repository's labels, those on the PR marked, each in its own colour.
+fn render_label_picker(app: &App, picker: &LabelPicker, frame: &mut Frame, area: Rect) {
+    let current = app.pr_review().map_or(&[][..], |review| &review.labels[..]);
+    let items: Vec<ListItem> = picker
+        .matches()
+        .into_iter()
+        .map(|label| {
+            let marker = if current.iter().any(|c| c.name == label.name) {
+                "● "
+            } else {
+                "  "
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(marker),
+                Span::styled(format!(" {} ", label.name), label_style(&label.color)),
+            ]))
+        })
+        .collect();
+    let popup = centered_rect(48, area.height.saturating_sub(4).min(20), area);
+    frame.render_widget(Clear, popup);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Labels [Enter] Add / Remove [Esc] Close ")
+        .style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+    let [filter_area, list_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(inner);
+    frame.render_widget(
+        Paragraph::new(format!("> {}", picker.filter)).style(Style::default().fg(Color::Yellow)),
+        filter_area,
+    );
+    let mut state = ListState::default().with_selected(Some(picker.selected));
+    frame.render_stateful_widget(
+        List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+        list_area,
+        &mut state,
+    );
+}
+
+/// The start of the PR description, rendered from its Markdown.
+fn render_description(body: &str, frame: &mut Frame, area: Rect) {
+    let lines = markdown::render(body);
+    let title = if lines.len() > MAX_DESCRIPTION_ROWS {
+        " Description [D] Read all "
+    } else {
+        " Description "
+    };
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title(title)),
+        area,
+    );
+}
+
+/// The whole PR description, scrolled with j/k.
+fn render_description_popup(body: &str, scroll: u16, frame: &mut Frame, area: Rect) {
+    let popup = centered_rect(
+        area.width.saturating_sub(8),
+        area.height.saturating_sub(2),
+        area,
+    );
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(markdown::render(body))
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Description [j/k] Scroll [Esc] Close ")
+                    .border_style(Style::default().fg(Color::Cyan)),
+            ),
+        popup,
+    );
+}
+
+/// A config-defined panel: command output, or the failure in red.
+fn render_panel(panel: &Panel, frame: &mut Frame, area: Rect) {
+    let lines: Vec<Line> = match &panel.output {
+        Ok(lines) => lines.iter().map(|l| Line::raw(l.as_str())).collect(),
+        Err(e) => vec![Line::styled(e.as_str(), Style::default().fg(Color::Red))],
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {} ", panel.title));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Dashboard info on top, then the PR description, checks, new public API and any configured
+/// panels, then the heatmap.
+fn galaxy_layout(app: &App, area: Rect) -> Rc<[Rect]> {
+    let checks = app.pr_checks();
+    let checks_height = if checks.is_empty() {
+        0
+    } else {
+        checks.len().min(MAX_CHECK_ROWS) as u16 + 2
+    };
+    let api_rows = app.api_breaks.len() + app.api_changes.len();
+    let api_height = if api_rows == 0 {
+        0
+    } else {
+        api_rows.min(MAX_API_ROWS) as u16 + 2
+    };
+    let description_height = app.pr_description().map_or(0, |body| {
+        markdown::render(body).len().min(MAX_DESCRIPTION_ROWS) as u16 + 2
+    });
+    let dashboard_height = dashboard_rows(app).len() as u16 + 2;
+    let panel_heights = app.panels.iter().map(|p| {
+        let rows = p.output.as_ref().map_or(1, Vec::len);
+        Constraint::Length(rows.clamp(1, p.height.into()) as u16 + 2)
+    });
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(dashboard_height),
+                Constraint::Length(description_height),
+                Constraint::Length(checks_height),
+                Constraint::Length(api_height),
+            ]
+            .into_iter()
+            .chain(panel_heights)
+            .chain([Constraint::Min(0)]),
+        )
+        .split(area)
+}
+
+/// The Repository Overview rows; PR and verdict rows only show when there is something in them.
+fn dashboard_rows(app: &App) -> Vec<Row<'_>> {
+    let mut rows = vec![
         Row::new(vec![
             Cell::from("Repository").style(Style::default().fg(Color::Yellow)),
             Cell::from(app.dashboard_info.repo_name.as_str()),
@@ -108,8 +1025,85 @@ fn render_galaxy(app: &App, frame: &mut Frame, area: Rect) {
             Cell::from("Stats").style(Style::default().fg(Color::Yellow)),
             Cell::from(app.dashboard_info.stats.as_str()),
         ]),
+        Row::new(vec![
+            Cell::from("Breakdown").style(Style::default().fg(Color::Yellow)),
+            Cell::from(app.dashboard_info.breakdown.as_str()),
+        ]),
     ];
 
+    for warning in &app.dashboard_info.warnings {
+        rows.push(Row::new(vec![
+            Cell::from("Warning").style(Style::default().fg(Color::Yellow)),
+            Cell::from(format!("⚠ {warning}")).style(Style::default().fg(Color::Yellow)),
+        ]));
+    }
+    if let Some(summary) = app.api_break_summary() {
+        rows.push(Row::new(vec![
+            Cell::from("API").style(Style::default().fg(Color::Yellow)),
+            Cell::from(summary).style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+        ]));
+    }
+    if let Some(verdicts) = app.verdict_summary() {
+        rows.push(Row::new(vec![
+            Cell::from("Verdicts").style(Style::default().fg(Color::Yellow)),
+            Cell::from(verdicts),
+        ]));
+    }
+    if let Some(review) = app.pr_review()
+        && !review.labels.is_empty()
+    {
+        let badges: Vec<Span> = review
+            .labels
+            .iter()
+            .flat_map(|label| {
+                [
+                    Span::styled(format!(" {} ", label.name), label_style(&label.color)),
+                    Span::raw(" "),
+                ]
+            })
+            .collect();
+        rows.push(Row::new(vec![
+            Cell::from("Labels").style(Style::default().fg(Color::Yellow)),
+            Cell::from(Line::from(badges)),
+        ]));
+    }
+    if let Some(people) = app.pr_people() {
+        rows.push(Row::new(vec![
+            Cell::from("People").style(Style::default().fg(Color::Yellow)),
+            Cell::from(people),
+        ]));
+    }
+    rows
+}
+
+/// The optional sections between the dashboard and the heatmap, laid out by `galaxy_layout`.
+fn render_galaxy_sections(app: &App, frame: &mut Frame, chunks: &[Rect]) {
+    if let Some(body) = app.pr_description() {
+        render_description(body, frame, chunks[1]);
+    }
+    let checks = app.pr_checks();
+    if !checks.is_empty() {
+        render_checks(checks, frame, chunks[2]);
+    }
+    if !app.api_breaks.is_empty() || !app.api_changes.is_empty() {
+        render_api_changes(&app.api_breaks, &app.api_changes, frame, chunks[3]);
+    }
+    for (panel, &chunk) in app.panels.iter().zip(&chunks[4..]) {
+        render_panel(panel, frame, chunk);
+    }
+}
+
+fn render_galaxy(app: &App, frame: &mut Frame, area: Rect) {
+    let chunks = galaxy_layout(app, area);
+    render_galaxy_sections(app, frame, &chunks);
+    let heatmap_area = chunks[chunks.len() - 1];
+
+    // 1. Dashboard Info
+    let info_block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Repository Overview ");
+
+    let rows = dashboard_rows(app);
     let table = Table::new(rows, [Constraint::Length(15), Constraint::Min(0)])
         .block(info_block)
         .column_spacing(2);
@@ -165,43 +1159,281 @@ fn render_galaxy(app: &App, frame: &mut Frame, area: Rect) {
         )
         .highlight_style(Style::default().add_modifier(Modifier::BOLD));
 
-    frame.render_widget(list, chunks[1]);
+    frame.render_widget(list, heatmap_area);
 }
 
 fn render_structure(app: &App, frame: &mut Frame, area: Rect) {
-    // Use filtered indices to access structures
+    if app.is_symbol_search() {
+        render_symbol_hits(app, frame, area);
+        return;
+    }
+    let search_title =
+        (!app.search_query.is_empty()).then(|| format!(" Search Results: '{}' ", app.search_query));
+
+    if !app.has_sections() {
+        let title = search_title
+            .unwrap_or_else(|| " Structure Map (Space to Stage, / to Search) ".to_string());
+        let items = structure_items(
+            app,
+            &app.filtered_structure_indices,
+            Some(app.selected_index),
+        );
+        render_structure_list(items, title, true, app.selected_index, frame, area);
+        return;
+    }
+
+    // Lazygit style: Staged on top, Unstaged/Untracked below, Tab switches focus
+    let focused = app.structure_section;
+    let staged = app.section_indices(StructureSection::Staged);
+    let unstaged = app.section_indices(StructureSection::Unstaged);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length((staged.len() as u16 + 2).clamp(3, area.height / 2)),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+    for (section, indices, chunk, name) in [
+        (StructureSection::Staged, &staged, chunks[0], "Staged"),
+        (
+            StructureSection::Unstaged,
+            &unstaged,
+            chunks[1],
+            "Unstaged / Untracked",
+        ),
+    ] {
+        let is_focused = section == focused;
+        let selected = if is_focused {
+            app.selected_index
+        } else {
+            app.other_section_index
+        };
+        let title = match (&search_title, is_focused) {
+            (Some(search), true) => format!(" {name} ({}) -{search}", indices.len()),
+            _ => format!(" {name} ({}) ", indices.len()),
+        };
+        let items = structure_items(app, indices, is_focused.then_some(selected));
+        render_structure_list(items, title, is_focused, selected, frame, chunk);
+    }
+}
+
+/// Matches of a repo-wide `@` search in place of the Structure list.
+fn render_symbol_hits(app: &App, frame: &mut Frame, area: Rect) {
+    let files = app.symbol_index.lock().map_or(0, |index| index.files());
+    let title = if app.local_root().is_none() {
+        " Symbol search needs a local repository ".to_string()
+    } else if !app.indexed {
+        format!(" Indexing the repository… ({files} files so far) ")
+    } else {
+        format!(
+            " Symbols: '{}' ({} matches, {files} files indexed, ↑↓ select, Enter go) ",
+            &app.search_query[1..],
+            app.symbol_hits.len(),
+        )
+    };
     let items: Vec<ListItem> = app
-        .filtered_structure_indices
+        .symbol_hits
         .iter()
         .enumerate()
-        .map(|(i, &real_index)| {
-            let s = &app.structures[real_index]; // Map back to real structure
-            let prefix = if i == app.selected_index { "> " } else { "  " };
-            let style = if i == app.selected_index {
+        .map(|(i, hit)| {
+            let is_selected = i == app.symbol_hit;
+            let (icon, color) = symbol_icon(&hit.kind, app.config.nerd_fonts);
+            let line = Line::from(vec![
+                Span::styled(
+                    if is_selected { "> " } else { "  " },
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(format!("{icon} "), Style::default().fg(color)),
+                Span::raw(hit.name.clone()),
+                Span::styled(
+                    format!("  {}:{}", hit.path, hit.line),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]);
+            let style = if is_selected {
                 Style::default().bg(Color::DarkGray)
             } else {
                 Style::default()
             };
+            ListItem::new(line).style(style)
+        })
+        .collect();
+    render_structure_list(items, title, true, app.symbol_hit, frame, area);
+}
 
-            let (icon, color) = if s.is_file {
-                // File icons
-                if s.status.contains("New") {
-                    ("+", Color::Green)
-                } else if s.status.contains("Deleted") {
-                    ("-", Color::Red)
-                } else {
-                    ("M", Color::Yellow)
-                }
+fn render_structure_list(
+    items: Vec<ListItem>,
+    title: String,
+    focused: bool,
+    selected: usize,
+    frame: &mut Frame,
+    area: Rect,
+) {
+    let border = if focused {
+        Color::Yellow
+    } else {
+        Color::DarkGray
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border))
+                .title(title),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD));
+
+    // Stateful rendering keeps the selection scrolled into view
+    let mut state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// ` ✔ approve` after a file the reviewer has given a verdict on.
+fn verdict_badge(verdict: Verdict) -> Span<'static> {
+    let color = match verdict {
+        Verdict::Approve => Color::Green,
+        Verdict::NeedsWork => Color::Yellow,
+        Verdict::Blocked => Color::Red,
+    };
+    Span::styled(
+        format!(" {} {}", verdict.symbol(), verdict.label()),
+        Style::default().fg(color).add_modifier(Modifier::BOLD),
+    )
+}
+
+/// PR review badges of a file: unresolved threads, viewed state, CI annotations.
+fn review_badges(app: &App, path: &str) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let Some(review) = app.pr_review() else {
+        return spans;
+    };
+    if let Some(threads) = review.unresolved_threads.get(path) {
+        spans.push(Span::styled(
+            format!(" 💬{threads}"),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    if review.viewed.contains(path) {
+        spans.push(Span::styled(" ✔ viewed", Style::default().fg(Color::Green)));
+    }
+    let annotations = review.annotation_count(path);
+    if annotations > 0 {
+        spans.push(Span::styled(
+            format!(" ⚠ CI {annotations}"),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    spans
+}
+
+/// ` ⧉ +79 alike` on a folded cluster's first file, a bare ` ⧉` on its files when listed.
+fn cluster_badge(app: &App, path: &str) -> Option<Span<'static>> {
+    let cluster = app.cluster_of(path)?;
+    let style = Style::default().fg(Color::Magenta);
+    Some(
+        if app.expanded_clusters.contains(&cluster.key) || !app.search_query.is_empty() {
+            Span::styled(" ⧉", style)
+        } else {
+            Span::styled(
+                format!(" ⧉ +{} alike", cluster.paths.len() - 1),
+                style.add_modifier(Modifier::BOLD),
+            )
+        },
+    )
+}
+
+/// ` ⚠ API` after a file breaking public Rust items, and after each changed item with what
+/// changed; removed items only show on their file.
+fn api_badge(app: &App, s: &StructureItem) -> Option<Span<'static>> {
+    let style = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+    let mut breaks = app.api_breaks.iter().filter(|b| b.path == s.path);
+    if s.is_file {
+        return breaks.next().map(|_| Span::styled(" ⚠ API", style));
+    }
+    let line = s.line_no?;
+    breaks
+        .find(|b| !b.removed && b.line == line)
+        .map(|b| Span::styled(format!(" ⚠ API: {}", b.change), style))
+}
+
+/// Icon and colour of a Structure row: file status, or the kind of symbol.
+fn structure_icon(s: &StructureItem, nerd_fonts: bool) -> (&'static str, Color) {
+    if s.is_file {
+        // File icons; local statuses read like `Status(WT_NEW)`
+        if s.status.contains("NEW") {
+            ("+", Color::Green)
+        } else if s.status.contains("DELETED") {
+            ("-", Color::Red)
+        } else {
+            ("M", Color::Yellow)
+        }
+    } else {
+        symbol_icon(&s.status, nerd_fonts)
+    }
+}
+
+/// Icon of a symbol `kind` as the analyzer names it (`fn`, `struct`, `h2`, ...).
+fn symbol_icon(kind: &str, nerd_fonts: bool) -> (&'static str, Color) {
+    match kind {
+        "fn" if nerd_fonts => ("\u{f0295}", Color::Cyan),
+        "struct" if nerd_fonts => ("\u{ea91}", Color::Magenta),
+        "impl" if nerd_fonts => ("\u{eb61}", Color::Blue),
+        "class" if nerd_fonts => ("\u{eb5b}", Color::Yellow),
+        "interface" if nerd_fonts => ("\u{eb61}", Color::Magenta),
+        "const" if nerd_fonts => ("\u{eb5d}", Color::Green),
+        "type" if nerd_fonts => ("\u{ea92}", Color::Magenta),
+        "enum" if nerd_fonts => ("\u{ea95}", Color::Yellow),
+        "trait" if nerd_fonts => ("\u{eb61}", Color::LightMagenta),
+        "mod" if nerd_fonts => ("\u{ea8b}", Color::LightBlue),
+        "static" if nerd_fonts => ("\u{ea88}", Color::Green),
+        "macro" if nerd_fonts => ("\u{eb66}", Color::Red),
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" if nerd_fonts => ("\u{f48a}", Color::LightBlue),
+        "dir" if nerd_fonts => ("\u{f07b}", Color::Blue),
+        "fn" => ("ƒ", Color::Cyan),
+        "struct" => ("S", Color::Magenta),
+        "impl" => ("I", Color::Blue),
+        "class" => ("C", Color::Yellow),
+        "interface" => ("◇", Color::Magenta),
+        "const" => ("=", Color::Green),
+        "type" => ("T", Color::Magenta),
+        "enum" => ("E", Color::Yellow),
+        "trait" => ("Ŧ", Color::LightMagenta),
+        "mod" => ("§", Color::LightBlue),
+        "static" => ("$", Color::Green),
+        "macro" => ("!", Color::Red),
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => ("#", Color::LightBlue),
+        "dir" => ("▸", Color::Blue),
+        _ => ("•", Color::Gray),
+    }
+}
+
+/// List rows for `indices`, highlighting `selected` (a position within `indices`).
+fn structure_items<'a>(
+    app: &'a App,
+    indices: &[usize],
+    selected: Option<usize>,
+) -> Vec<ListItem<'a>> {
+    indices
+        .iter()
+        .enumerate()
+        .map(|(i, &real_index)| {
+            let s = &app.structures[real_index]; // Map back to real structure
+            let is_selected = selected == Some(i);
+            // The visual selection only shows in the focused list
+            let in_visual = selected.is_some()
+                && app
+                    .structure_selection()
+                    .is_some_and(|(start, end)| (start..=end).contains(&i));
+            let prefix = if is_selected { "> " } else { "  " };
+            let style = if is_selected || in_visual {
+                Style::default().bg(Color::DarkGray)
             } else {
-                // Symbol icons
-                match s.status.as_str() {
-                    "fn" => ("ƒ", Color::Cyan),
-                    "struct" => ("S", Color::Magenta),
-                    "impl" => ("I", Color::Blue),
-                    _ => ("•", Color::Gray),
-                }
+                Style::default()
             };
 
+            let (icon, color) = structure_icon(s, app.config.nerd_fonts);
+
             // Lazygit Style: Staged Indicator
             let staged_mark = if s.is_staged { "[x] " } else { "[ ] " };
             let staged_style = if s.is_staged {
@@ -210,9 +1442,9 @@ fn render_structure(app: &App, frame: &mut Frame, area: Rect) {
                 Style::default().fg(Color::DarkGray)
             };
 
-            ListItem::new(Line::from(vec![
+            let mut spans = vec![
                 Span::styled(prefix, Style::default().fg(Color::Yellow)),
-                if s.is_file {
+                if s.is_file && !app.has_sections() {
                     Span::styled(staged_mark, staged_style)
                 } else {
                     Span::raw("    ")
@@ -220,94 +1452,627 @@ fn render_structure(app: &App, frame: &mut Frame, area: Rect) {
                 Span::styled(format!("[{icon}] "), Style::default().fg(color)),
                 Span::styled(
                     s.text.clone(),
-                    Style::default().fg(if s.is_file { Color::White } else { Color::Gray }),
+                    if s.status == "dir" {
+                        Style::default()
+                            .fg(Color::Blue)
+                            .add_modifier(Modifier::BOLD)
+                    } else if s.is_file || s.touched {
+                        Style::default().fg(Color::White)
+                    } else {
+                        // Symbols the diff does not reach, merely in a changed file
+                        Style::default()
+                            .fg(Color::DarkGray)
+                            .add_modifier(Modifier::DIM)
+                    },
                 ),
-            ]))
-            .style(style)
+            ];
+
+            if s.is_file {
+                if app.opened.contains(&s.path) {
+                    spans.push(Span::styled(
+                        " · seen",
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                spans.extend(review_badges(app, &s.path));
+                spans.extend(cluster_badge(app, &s.path));
+                spans.extend(api_badge(app, s));
+                spans.extend(app.verdicts.get(&s.path).map(verdict_badge));
+            } else {
+                spans.extend(api_badge(app, s));
+                spans.extend(complexity_badge(s));
+                spans.extend(impact_badge(app, s));
+            }
+
+            // Permission changes, which have no lines of their own in the diff
+            if let Some(change) = s.mode_change {
+                spans.push(Span::styled(
+                    format!(" [{}]", mode_badge(change)),
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
+
+            // Badge for files whose symbols were not analyzed
+            if let Some(reason) = s.skip_reason {
+                spans.push(Span::styled(
+                    format!(" ({reason}, not analyzed)"),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+
+            ListItem::new(Line::from(spans)).style(style)
         })
-        .collect();
+        .collect()
+}
 
-    let title = if app.search_query.is_empty() {
-        " Structure Map (Space to Stage, / to Search) ".to_string()
-    } else {
-        format!(" Search Results: '{}' ", app.search_query)
+/// ` 42L b7 d3` (lines, branches, nesting depth) after a changed code symbol, in yellow or
+/// red once it gets busy.
+fn complexity_badge(s: &StructureItem) -> Option<Span<'static>> {
+    let complexity = s.complexity.filter(|_| s.touched)?;
+    let style = match complexity.severity() {
+        0 => Style::default().fg(Color::DarkGray),
+        1 => Style::default().fg(Color::Yellow),
+        _ => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
     };
+    Some(Span::styled(format!(" {complexity}"), style))
+}
 
-    let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title(title))
-        .highlight_style(Style::default().add_modifier(Modifier::BOLD));
-
-    frame.render_widget(list, area);
+/// ` · 3 refs` after a changed symbol other code refers to.
+fn impact_badge(app: &App, s: &StructureItem) -> Option<Span<'static>> {
+    let count = app.impact.get(&(s.path.clone(), s.line_no?))?.len();
+    (count > 0).then(|| {
+        Span::styled(
+            format!(" · {count} ref{}", if count == 1 { "" } else { "s" }),
+            Style::default().fg(Color::LightRed),
+        )
+    })
 }
 
-fn render_logic(app: &App, frame: &mut Frame, area: Rect) {
-    // Basic syntax highlighting setup (Load only once in real app)
-    let ps = SyntaxSet::load_defaults_newlines();
-    let ts = ThemeSet::load_defaults();
+/// Syntax definition for the selected file, by extension.
+/// Content lines around the cursor that can be on screen, for `rows` rows of Logic view.
+fn logic_window(app: &App, rows: usize) -> Range<usize> {
+    let len = app.logic_view_content.len();
+    let start = (0..app.logic_cursor)
+        .rev()
+        .filter(|&i| app.logic_line_visible(i))
+        .nth(rows)
+        .unwrap_or(0);
+    let end = (app.logic_cursor..len)
+        .filter(|&i| app.logic_line_visible(i))
+        .nth(rows)
+        .map_or(len, |i| i + 1);
+    start..end
+}
 
-    // Use filtered indices logic to get current selection
-    let syntax = if !app.filtered_structure_indices.is_empty()
-        && app.selected_index < app.filtered_structure_indices.len()
-    {
-        let real_index = app.filtered_structure_indices[app.selected_index];
-        let path = &app.structures[real_index].path;
-        let ext = std::path::Path::new(path)
-            .extension()
-            .and_then(|s| s.to_str())
-            .unwrap_or("rs");
-        ps.find_syntax_by_extension(ext)
-            .unwrap_or_else(|| ps.find_syntax_plain_text())
+/// Background, gutter sign and gitconfig style for one diff line.
+///
+/// glim's own backgrounds apply unless the matching `color.diff.*` slot sets one.
+fn diff_line_colors(
+    app: &App,
+    backgrounds: &DiffBackgrounds,
+    line: &str,
+) -> (Option<Color>, &'static str, Option<Style>) {
+    let theme = &app.diff_theme;
+    let (bg, prefix, git_style) = if line.trim_start().starts_with("@@") {
+        (backgrounds.context, " ", theme.frag)
+    } else if is_meta_line(line) {
+        (backgrounds.context, " ", theme.meta)
+    } else if line.starts_with('+') {
+        (backgrounds.added, "+", theme.added)
+    } else if line.starts_with('-') {
+        (backgrounds.removed, "-", theme.removed)
     } else {
-        ps.find_syntax_plain_text()
+        (backgrounds.context, " ", theme.context)
     };
+    let bg = bg.map(|c| match c {
+        Color::Rgb(r, g, b) => rgb(app, r, g, b),
+        other => other,
+    });
+    (git_style.and_then(|g| g.bg).or(bg), prefix, git_style)
+}
+
+/// Conflict, binary and LFS files show their own panel instead of the diff.
+fn render_logic_panel(app: &App, frame: &mut Frame, area: Rect) -> bool {
+    if let Some(conflict) = &app.conflict {
+        render_conflict(app, conflict, frame, area);
+    } else if app.binary.is_some() {
+        render_summary(app, " Binary File ", frame, area);
+    } else if app.lfs.is_some() {
+        render_summary(app, " Git LFS ", frame, area);
+    } else {
+        return false;
+    }
+    true
+}
+
+fn render_logic(app: &App, frame: &mut Frame, area: Rect) {
+    if render_logic_panel(app, frame, area) {
+        return;
+    }
 
-    let mut h = HighlightLines::new(syntax, &ts.themes["base16-ocean.dark"]);
+    let theme = highlight::theme(&app.config.syntax_theme);
+    let backgrounds = DiffBackgrounds::derive(theme, &app.config.diff_colors);
+    // Only what can be on screen is highlighted before drawing; the rest comes in the background
+    let window = logic_window(app, usize::from(area.height));
+    let styled = app.highlighter.lines(
+        &app.logic_view_content,
+        app.syntax(),
+        &app.config.syntax_theme,
+        window.clone(),
+    );
+    let annotations = app.line_annotations();
+    let threads = line_attachments(app);
+    let selection = app.logic_selection();
+    let color_mode = app.config.color_mode.effective();
 
     let lines: Vec<Line> = app
         .logic_view_content
         .iter()
-        .map(|s| {
-            // Determine Diff color background
-            let (bg_color, prefix) = if s.starts_with('+') {
-                (Some(Color::Rgb(20, 60, 20)), "+")
-            } else if s.starts_with('-') {
-                (Some(Color::Rgb(60, 20, 20)), "-")
+        .enumerate()
+        .flat_map(|(i, s)| {
+            let ranges = i
+                .checked_sub(window.start)
+                .and_then(|k| styled.get(k))
+                .and_then(Option::as_ref);
+            if !app.logic_line_visible(i) {
+                return vec![];
+            }
+            let (bg_color, prefix, git_style) = diff_line_colors(app, &backgrounds, s);
+
+            // Gutter: CI annotation marker, else how much of the hunk is staged
+            let gutter = if annotations.get(i).is_none_or(Vec::is_empty) {
+                hunk_stage_marker(app.hunk_stages.get(i).copied().flatten())
             } else {
-                (None, " ")
+                Span::styled("●", Style::default().fg(Color::Red))
             };
 
-            // Syntax Highlight the content (excluding prefix)
-            let content = if s.len() > 1 { &s[1..] } else { "" };
-            let ranges: Vec<(syntect::highlighting::Style, &str)> =
-                h.highlight_line(content, &ps).unwrap_or_default();
-
-            let mut spans = vec![Span::styled(prefix, Style::default().fg(Color::Gray))];
-
-            for (style, text) in ranges {
-                let fg = Color::Rgb(style.foreground.r, style.foreground.g, style.foreground.b);
-                spans.push(Span::styled(text.to_string(), Style::default().fg(fg)));
+            let mut spans = vec![];
+            if app.show_blame {
+                let blame = app.blame_lines.get(i).cloned().flatten();
+                spans.push(Span::styled(
+                    format!("{:<28}│", blame.unwrap_or_default()),
+                    Style::default().fg(Color::DarkGray),
+                ));
             }
+            spans.push(gutter);
+            let git_fg = git_style.and_then(|g| g.fg);
+            spans.push(Span::styled(
+                prefix,
+                Style::default().fg(git_fg.unwrap_or(Color::Gray)),
+            ));
+            spans.extend(underline(
+                highlighted_spans(app, s, ranges, git_fg),
+                &added_typos(app, s),
+            ));
 
             let mut line_style = Style::default();
+            if let Some(git_style) = git_style {
+                line_style = line_style.add_modifier(git_style.add_modifier);
+            }
             if let Some(bg) = bg_color {
                 line_style = line_style.bg(bg);
             }
+            line_style = line_style.add_modifier(color_mode.diff_emphasis(prefix));
+            if i == app.logic_cursor || selection.is_some_and(|(a, b)| (a..=b).contains(&i)) {
+                line_style = line_style.add_modifier(Modifier::REVERSED);
+            }
 
-            Line::from(spans).style(line_style)
+            // Existing review conversation sits right below the line it is anchored to
+            let mut rows = vec![Line::from(spans).style(line_style)];
+            rows.extend(threads.get(i).into_iter().flatten().cloned());
+            rows
         })
         .collect();
 
-    // Display context info in title
-    let title = format!(
-        " Code Diff (Context: {} lines) [+/- to expand] ",
+    let title = logic_title(app);
+
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let inner = block.inner(area);
+    app.logic_viewport.set((inner.width, inner.height));
+
+    let scroll = logic_scroll(app, &lines, &threads, inner);
+
+    let widths: Vec<usize> = lines.iter().map(Line::width).collect();
+    let paragraph = Paragraph::new(lines).block(block);
+    let paragraph = if app.wrap_lines {
+        paragraph
+            .wrap(Wrap { trim: false })
+            .scroll((scroll as u16, 0))
+    } else {
+        paragraph.scroll((scroll as u16, app.h_scroll as u16))
+    };
+
+    frame.render_widget(paragraph, area);
+
+    if !app.wrap_lines {
+        render_truncation_markers(
+            &widths[scroll.min(widths.len())..],
+            app.h_scroll,
+            inner,
+            frame,
+        );
+    }
+
+    if let Some(current) = annotations.get(app.logic_cursor)
+        && !current.is_empty()
+    {
+        render_annotation_popup(current, frame, area);
+    }
+}
+
+/// Gutter bar of a hunk: green when the index has all of it, yellow when part of it.
+fn hunk_stage_marker(stage: Option<HunkStage>) -> Span<'static> {
+    match stage {
+        Some(HunkStage::Staged) => Span::styled("┃", Style::default().fg(Color::Green)),
+        Some(HunkStage::Partial) => Span::styled("┃", Style::default().fg(Color::Yellow)),
+        Some(HunkStage::Unstaged) => Span::styled("│", Style::default().fg(Color::DarkGray)),
+        None => Span::raw(" "),
+    }
+}
+
+/// Rows scrolled off the top of the Logic view: the cursor stays roughly centred once it
+/// passes the middle, counting the screen rows of wrapped lines so long lines cannot push
+/// it off screen.
+fn logic_scroll(app: &App, lines: &[Line], threads: &[Vec<Line>], inner: Rect) -> usize {
+    let cursor_line: usize = (0..app.logic_cursor)
+        .filter(|&i| app.logic_line_visible(i))
+        .map(|i| 1 + threads.get(i).map_or(0, Vec::len))
+        .sum();
+    let cursor_row = if app.wrap_lines {
+        lines[..cursor_line.min(lines.len())]
+            .iter()
+            .map(|line| wrapped_rows(line, inner.width))
+            .sum::<usize>()
+            + app.logic_wrap_row()
+    } else {
+        cursor_line
+    };
+    cursor_row.saturating_sub(inner.height as usize / 2)
+}
+
+/// Indented, dimmed comment blocks (author, time, body) for the threads on one diff line.
+/// Threads whose suggested change the local clone has are marked as applied.
+fn review_thread_lines(
+    threads: &[&ReviewThread],
+    applied: &HashSet<(String, usize)>,
+) -> Vec<Line<'static>> {
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut lines = Vec::new();
+    for thread in threads {
+        for (n, comment) in thread.comments.iter().enumerate() {
+            let resolved = if thread.resolved && n == 0 {
+                " (resolved)"
+            } else {
+                ""
+            };
+            let mut header = vec![
+                Span::styled("      ┃ ", dim),
+                Span::styled(comment.author.clone(), dim.add_modifier(Modifier::BOLD)),
+                Span::styled(format!(" · {}{resolved}", comment.timestamp()), dim),
+            ];
+            if n == 0 && applied.contains(&(thread.path.clone(), thread.line)) {
+                header.push(Span::styled(
+                    " ✔ suggestion applied",
+                    Style::default().fg(Color::Green),
+                ));
+            }
+            lines.push(Line::from(header));
+            lines.extend(
+                comment
+                    .body
+                    .lines()
+                    .map(|text| Line::styled(format!("      ┃   {text}"), dim)),
+            );
+        }
+    }
+    lines
+}
+
+/// Rows shown under each line of the Logic view: the PR's review threads, then the
+/// reviewer's own notes.
+fn line_attachments(app: &App) -> Vec<Vec<Line<'static>>> {
+    app.line_threads()
+        .iter()
+        .zip(app.line_notes())
+        .map(|(threads, notes)| {
+            let mut rows = review_thread_lines(threads, &app.applied_suggestions);
+            rows.extend(note_lines(&notes));
+            rows
+        })
+        .collect()
+}
+
+/// The reviewer's own notes on one diff line, in the note colour so they stand apart from
+/// the PR's threads.
+fn note_lines(notes: &[&LineNote]) -> Vec<Line<'static>> {
+    let style = Style::default().fg(Color::Yellow);
+    notes
+        .iter()
+        .flat_map(|note| {
+            note.text.lines().enumerate().map(move |(n, text)| {
+                let mark = if n == 0 { "✎ " } else { "  " };
+                Line::styled(format!("      ┃ {mark}{text}"), style)
+            })
+        })
+        .collect()
+}
+
+/// Syntax-coloured spans for one line of highlighter output; plain until it is highlighted.
+fn highlighted_spans(
+    app: &App,
+    line: &str,
+    ranges: Option<&StyledLine>,
+    git_fg: Option<Color>,
+) -> Vec<Span<'static>> {
+    let Some(ranges) = ranges else {
+        let content = line.get(1..).unwrap_or_default().to_string();
+        return vec![Span::styled(
+            content,
+            Style::default().fg(git_fg.unwrap_or(Color::Gray)),
+        )];
+    };
+    ranges
+        .iter()
+        .map(|(style, text)| {
+            // A configured foreground wins over syntax colours, as in `git diff`
+            let fg = git_fg.unwrap_or_else(|| {
+                rgb(
+                    app,
+                    style.foreground.r,
+                    style.foreground.g,
+                    style.foreground.b,
+                )
+            });
+            Span::styled(text.clone(), Style::default().fg(fg))
+        })
+        .collect()
+}
+
+/// Byte ranges (after the diff prefix) of likely typos in an added comment or Markdown line.
+fn added_typos(app: &App, line: &str) -> Vec<Range<usize>> {
+    match (app.selected_item(), line.strip_prefix('+')) {
+        (Some(item), Some(text)) if app.config.spell_check && !line.starts_with("+++") => {
+            typos(&item.path, text)
+                .into_iter()
+                .map(|t| t.range)
+                .collect()
+        }
+        _ => vec![],
+    }
+}
+
+/// Splits `spans` so the byte `ranges` of their joined text come out underlined.
+fn underline(spans: Vec<Span<'static>>, ranges: &[Range<usize>]) -> Vec<Span<'static>> {
+    if ranges.is_empty() {
+        return spans;
+    }
+    let mut out = Vec::new();
+    let mut offset = 0;
+    for span in spans {
+        let end = offset + span.content.len();
+        let mut cuts = vec![0, span.content.len()];
+        cuts.extend(
+            ranges
+                .iter()
+                .flat_map(|r| [r.start, r.end])
+                .filter(|&b| b > offset && b < end)
+                .map(|b| b - offset),
+        );
+        cuts.sort_unstable();
+        cuts.dedup();
+        for cut in cuts.windows(2) {
+            let style = if ranges.iter().any(|r| r.contains(&(offset + cut[0]))) {
+                span.style.add_modifier(Modifier::UNDERLINED)
+            } else {
+                span.style
+            };
+            out.push(Span::styled(
+                span.content[cut[0]..cut[1]].to_string(),
+                style,
+            ));
+        }
+        offset = end;
+    }
+    out
+}
+
+/// Logic view title: context size, the diff scope for local repos, and the wrap state.
+fn logic_title(app: &App) -> String {
+    let wrap_note = if app.wrap_lines {
+        String::new()
+    } else {
+        format!("[nowrap +{}] ", app.h_scroll)
+    };
+    let scope_note = if app.has_sections() || app.merge_base.is_some() {
+        format!("[t: {}] ", app.diff_scope.label())
+    } else {
+        String::new()
+    };
+    let changes_note = if app.changes_only {
+        "[changes only] "
+    } else {
+        ""
+    };
+    let visual_note = app.logic_selection().map_or_else(String::new, |(a, b)| {
+        format!("[VISUAL {} lines] ", b - a + 1)
+    });
+    format!(
+        " Code Diff (Context: {} lines) [+/- to expand] {scope_note}{wrap_note}{changes_note}{visual_note}",
         app.context_lines
+    )
+}
+
+/// Marks rows cut off on either side while the Logic view scrolls horizontally.
+fn render_truncation_markers(widths: &[usize], h_scroll: usize, inner: Rect, frame: &mut Frame) {
+    if inner.width == 0 {
+        return;
+    }
+    let marker = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    for (row, width) in widths.iter().take(inner.height as usize).enumerate() {
+        let y = inner.y + row as u16;
+        if h_scroll > 0 && *width > 0 {
+            frame.buffer_mut().set_string(inner.x, y, "‹", marker);
+        }
+        if *width > h_scroll + inner.width as usize {
+            frame
+                .buffer_mut()
+                .set_string(inner.right() - 1, y, "›", marker);
+        }
+    }
+}
+
+/// A summary panel (binary or LFS file) in place of the diff; the first line is its headline.
+fn render_summary(app: &App, title: &str, frame: &mut Frame, area: Rect) {
+    let lines: Vec<Line> = app
+        .logic_view_content
+        .iter()
+        .enumerate()
+        .map(|(i, text)| {
+            let style = if i == 0 {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            Line::from(Span::styled(format!("  {text}"), style))
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)),
+        area,
     );
+}
 
-    let paragraph = Paragraph::new(lines)
-        .block(Block::default().borders(Borders::ALL).title(title))
-        .wrap(Wrap { trim: false });
+fn render_conflict(app: &App, conflict: &ConflictFile, frame: &mut Frame, area: Rect) {
+    let lines: Vec<Line> = conflict
+        .lines()
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let (gutter, style) = match line.kind {
+                LineKind::Common => (" ", Style::default().fg(Color::Gray)),
+                LineKind::Marker => (
+                    " ",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                LineKind::Ours => ("<", Style::default().bg(rgb(app, 20, 40, 70))),
+                LineKind::Base => ("|", Style::default().bg(rgb(app, 50, 50, 50))),
+                LineKind::Theirs => (">", Style::default().bg(rgb(app, 60, 20, 60))),
+                LineKind::Resolved => ("✔", Style::default().bg(rgb(app, 20, 60, 20))),
+            };
+            let style = if i == app.logic_cursor {
+                style.add_modifier(Modifier::REVERSED)
+            } else {
+                style
+            };
+            Line::from(vec![
+                Span::styled(gutter, Style::default().fg(Color::DarkGray)),
+                Span::raw(line.text),
+            ])
+            .style(style)
+        })
+        .collect();
 
-    frame.render_widget(paragraph, area);
+    let title = format!(
+        " Merge Conflict ({} unresolved) [o] Ours [t] Theirs [w] Write ",
+        conflict.unresolved()
+    );
+    let visible = area.height.saturating_sub(2) as usize;
+    let scroll = app.logic_cursor.saturating_sub(visible / 2);
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .scroll((scroll as u16, 0)),
+        area,
+    );
+}
+
+fn render_annotation_popup(annotations: &[&CheckAnnotation], frame: &mut Frame, area: Rect) {
+    let lines: Vec<Line> = annotations
+        .iter()
+        .flat_map(|a| {
+            let color = match a.level.as_str() {
+                "FAILURE" => Color::Red,
+                "WARNING" => Color::Yellow,
+                _ => Color::Cyan,
+            };
+            [
+                Line::from(vec![
+                    Span::styled(
+                        format!("[{}] ", a.level),
+                        Style::default().fg(color).add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!("{} {}", a.check_name, a.title),
+                        Style::default().fg(Color::White),
+                    ),
+                ]),
+                Line::from(Span::styled(
+                    a.message.clone(),
+                    Style::default().fg(Color::Gray),
+                )),
+            ]
+        })
+        .collect();
+
+    let height = (lines.len() as u16 + 2).min(area.height / 2);
+    let popup = Rect {
+        x: area.x + 2,
+        y: area.y + area.height.saturating_sub(height + 1),
+        width: area.width.saturating_sub(4),
+        height,
+    };
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" CI Annotations ")
+                    .style(Style::default().fg(Color::Red)),
+            )
+            .wrap(Wrap { trim: false }),
+        popup,
+    );
+}
+
+/// GitHub API quota left, right-aligned over the key hints; yellow once under a tenth.
+fn render_rate_limits(app: &App, frame: &mut Frame, area: Rect) {
+    let spans: Vec<Span> = app
+        .rate_limits()
+        .iter()
+        .map(|limit| {
+            let color = if limit.is_low() {
+                Color::Yellow
+            } else {
+                Color::DarkGray
+            };
+            Span::styled(
+                format!("  API {}", limit.summary()),
+                Style::default().fg(color),
+            )
+        })
+        .collect();
+    if spans.is_empty() {
+        return;
+    }
+    let width = spans.iter().map(Span::width).sum::<usize>();
+    let row = Block::default().borders(Borders::TOP).inner(area);
+    let row = Rect {
+        x: row.right().saturating_sub(width as u16).max(row.x),
+        width: (width as u16).min(row.width),
+        height: row.height.min(1),
+        ..row
+    };
+    frame.render_widget(Clear, row);
+    frame.render_widget(Paragraph::new(Line::from(spans)), row);
 }
 
 fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
@@ -317,14 +2082,31 @@ fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
             .style(Style::default().fg(Color::Yellow));
         let paragraph = Paragraph::new(format!("/{}", app.search_query)).block(block);
         frame.render_widget(paragraph, area);
+    } else if let Some(notice) = &app.notice {
+        let paragraph = Paragraph::new(notice.as_str())
+            .style(Style::default().fg(Color::Green))
+            .block(Block::default().borders(Borders::TOP));
+        frame.render_widget(paragraph, area);
     } else {
         let info_text = match app.zoom_level {
-            ZoomLevel::Galaxy => "Nav: [j/k] Select | [Enter] Zoom In | [q] Quit",
+            ZoomLevel::Galaxy if app.pr_picker.is_some() => {
+                "Nav: [j/k] Select | [Enter] Review PR | [r] Reload | [q] Quit"
+            }
+            ZoomLevel::Galaxy if app.review_queue.is_some() => {
+                "Nav: [j/k] Select | [Enter] Zoom In | [R] Submit Review | [Backspace] Queue | [q] Quit"
+            }
+            ZoomLevel::Galaxy => "Nav: [j/k] Select | [Enter] Zoom In | [B] Branches | [q] Quit",
+            ZoomLevel::Structure if app.structure_anchor.is_some() => {
+                "Visual: [j/k] Extend | [Space] Move | [a/u] Stage/Unstage | [d] Discard | [v/Esc] Cancel"
+            }
             ZoomLevel::Structure => {
-                "Nav: [j/k] Select | [Enter] Zoom In | [Space] Stage | [/] Search | [Back] Out"
+                "Nav: [j/k] Select | [v] Visual | [Enter] Zoom In | [Space] Stage | [a/u] All | [U] Undo | [Tab] Section | [d] Discard | [.] Actions | [/] Search | [Back] Out"
+            }
+            ZoomLevel::Logic if app.logic_anchor.is_some() => {
+                "Visual: [j/k] Extend | [y] Copy | [V/Esc] Cancel"
             }
             ZoomLevel::Logic => {
-                "Nav: [j/k] Scroll | [+/-] Context | [Backspace] Zoom Out | [q] Quit"
+                "Nav: [j/k] Scroll | [V] Select | [+/-] Context | [x] Changes Only | [d] Discard Hunk | [b] Blame | [z] Wrap | [h/l] Pan | [Backspace] Zoom Out | [q] Quit"
             }
         };
 
@@ -333,5 +2115,6 @@ fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
             .block(Block::default().borders(Borders::TOP));
 
         frame.render_widget(paragraph, area);
+        render_rate_limits(app, frame, area);
     }
 }
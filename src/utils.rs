@@ -1,6 +1,7 @@
 // Utility functions (Placeholder)
 use directories::ProjectDirs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 #[must_use]
 pub fn get_config_dir() -> PathBuf {
@@ -9,3 +10,183 @@ pub fn get_config_dir() -> PathBuf {
         |proj_dirs| proj_dirs.config_dir().to_path_buf(),
     )
 }
+
+/// Where generated artifacts (review exports, caches) are written.
+#[must_use]
+pub fn get_data_dir() -> PathBuf {
+    ProjectDirs::from("io", "glim", "glim").map_or_else(
+        || PathBuf::from(".").join(".glim"),
+        |proj_dirs| proj_dirs.data_dir().to_path_buf(),
+    )
+}
+
+/// FNV-1a, for file names that stay stable across builds (unlike `DefaultHasher`).
+#[must_use]
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// `name` as a file name: anything but letters, digits, `.`, `_` and `-` becomes `-`, e.g.
+/// `glim-#12` becomes `glim--12`.
+#[must_use]
+pub fn file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Builds a path from raw git path bytes without lossy conversion where the OS allows it.
+#[must_use]
+pub fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    #[cfg(unix)]
+    {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        PathBuf::from(OsStr::from_bytes(bytes))
+    }
+    #[cfg(not(unix))]
+    {
+        PathBuf::from(String::from_utf8_lossy(bytes).as_ref())
+    }
+}
+
+/// Opens `path` in `$VISUAL`/`$EDITOR` (falling back to `vi`), jumping to `line` when given.
+///
+/// # Errors
+/// Returns an error if the editor cannot be spawned.
+pub fn open_in_editor(path: &Path, line: Option<usize>) -> std::io::Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // Editors may be configured with arguments, e.g. "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+
+    let mut cmd = Command::new(program);
+    cmd.args(parts);
+    if let Some(line) = line {
+        cmd.arg(format!("+{line}"));
+    }
+    cmd.arg(path).status().map(|_| ())
+}
+
+/// Opens `url` with the platform's default handler (usually the browser).
+///
+/// # Errors
+/// Returns an error if the opener cannot be spawned.
+pub fn open_url(url: &str) -> std::io::Result<()> {
+    let mut cmd = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "start", ""]);
+        cmd
+    } else {
+        Command::new("xdg-open")
+    };
+    cmd.arg(url)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+/// Compact relative age for a Unix timestamp, e.g. `5m`, `3d`, `2mo`, `1y`.
+#[must_use]
+pub fn format_age(timestamp: i64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs().cast_signed());
+    let secs = (now - timestamp).max(0);
+    match secs {
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86_400 => format!("{}h", s / 3600),
+        s if s < 30 * 86_400 => format!("{}d", s / 86_400),
+        s if s < 365 * 86_400 => format!("{}mo", s / (30 * 86_400)),
+        s => format!("{}y", s / (365 * 86_400)),
+    }
+}
+
+/// Human-readable byte count, e.g. `512 B`, `3.4 KiB`, `1.2 MiB`.
+#[must_use]
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/// Matches a repository-relative path against a gitignore-style glob.
+///
+/// `*` and `?` stay within one path segment, `**` spans segments, a leading `.ext`
+/// is shorthand for `*.ext`, and patterns without `/` match the file name alone.
+#[must_use]
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim_start_matches("./");
+    if !pattern.contains(['/', '*', '?']) && pattern.starts_with('.') {
+        return Path::new(path)
+            .extension()
+            .is_some_and(|ext| ext.to_string_lossy() == pattern[1..]);
+    }
+    if pattern.contains('/') {
+        wildcard_match(pattern.as_bytes(), path.as_bytes())
+    } else {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        wildcard_match(pattern.as_bytes(), name.as_bytes())
+    }
+}
+
+fn wildcard_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+            (0..=text.len()).any(|i| wildcard_match(rest, &text[i..]))
+        }
+        [b'*', rest @ ..] => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != b'/')
+            .any(|i| wildcard_match(rest, &text[i..])),
+        [b'?', rest @ ..] => {
+            !text.is_empty() && text[0] != b'/' && wildcard_match(rest, &text[1..])
+        }
+        [c, rest @ ..] => text.first() == Some(c) && wildcard_match(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_size, glob_match};
+
+    #[test]
+    fn sizes_use_binary_units() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(3 * 1024 + 512), "3.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+    }
+
+    #[test]
+    fn globs_follow_gitignore_conventions() {
+        assert!(glob_match(".md", "docs/guide.md"));
+        assert!(glob_match("*.lock", "Cargo.lock"));
+        assert!(glob_match("generated/**", "generated/api/client.rs"));
+        assert!(glob_match("src/**/*.rs", "src/a/b/c.rs"));
+        assert!(glob_match("src/**/*.rs", "src/c.rs"));
+        assert!(!glob_match("src/*.rs", "src/a/c.rs"));
+        assert!(!glob_match(".md", "notes.mdx"));
+    }
+}
@@ -0,0 +1,192 @@
+// Per-file review verdicts, kept per repository or PR across runs
+use crate::utils::{fnv1a, get_data_dir};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// What the reviewer concluded about one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Verdict {
+    Approve,
+    NeedsWork,
+    Blocked,
+}
+
+impl Verdict {
+    pub const ALL: [Self; 3] = [Self::Approve, Self::NeedsWork, Self::Blocked];
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Approve => "approve",
+            Self::NeedsWork => "needs work",
+            Self::Blocked => "blocked",
+        }
+    }
+
+    #[must_use]
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Self::Approve => "✔",
+            Self::NeedsWork => "✎",
+            Self::Blocked => "✘",
+        }
+    }
+
+    /// The verdict `F` moves to from `current`; after blocked the file has none again.
+    #[must_use]
+    pub const fn cycle(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(Self::Approve),
+            Some(Self::Approve) => Some(Self::NeedsWork),
+            Some(Self::NeedsWork) => Some(Self::Blocked),
+            Some(Self::Blocked) => None,
+        }
+    }
+}
+
+/// Verdicts by file path for one repository or PR.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Verdicts {
+    // Repository workdir or PR URL, as for sessions
+    pub source: String,
+    pub files: BTreeMap<String, Verdict>,
+}
+
+impl Verdicts {
+    /// File the verdicts for `source` are kept in, under the data directory.
+    #[must_use]
+    pub fn path(source: &str) -> PathBuf {
+        get_data_dir()
+            .join("verdicts")
+            .join(format!("{:016x}.json", fnv1a(source.as_bytes())))
+    }
+
+    /// The verdicts given earlier on `source`, or none yet.
+    #[must_use]
+    pub fn load(source: &str) -> Self {
+        fs::read_to_string(Self::path(source))
+            .ok()
+            .and_then(|text| serde_json::from_str::<Self>(&text).ok())
+            .filter(|verdicts| verdicts.source == source)
+            .unwrap_or_else(|| Self {
+                source: source.to_string(),
+                files: BTreeMap::new(),
+            })
+    }
+
+    /// Writes atomically; an empty set removes the file.
+    ///
+    /// # Errors
+    /// Returns an error if the verdicts directory or file cannot be written.
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path(&self.source);
+        if self.files.is_empty() {
+            return match fs::remove_file(path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(tmp, path)
+    }
+
+    #[must_use]
+    pub fn get(&self, path: &str) -> Option<Verdict> {
+        self.files.get(path).copied()
+    }
+
+    pub fn set(&mut self, path: &str, verdict: Option<Verdict>) {
+        match verdict {
+            Some(verdict) => self.files.insert(path.to_string(), verdict),
+            None => self.files.remove(path),
+        };
+    }
+
+    /// Moves `path` to its next verdict and returns it.
+    pub fn cycle(&mut self, path: &str) -> Option<Verdict> {
+        let next = Verdict::cycle(self.get(path));
+        self.set(path, next);
+        next
+    }
+
+    /// [`rollup`] of the changed files in `paths`.
+    #[must_use]
+    pub fn summary(&self, paths: &[&str]) -> Option<String> {
+        rollup(paths.iter().map(|p| self.get(p)))
+    }
+
+    /// Markdown list of the files with a verdict, blocked first, for a PR comment.
+    #[must_use]
+    pub fn comment(&self, paths: &[&str]) -> Option<String> {
+        let summary = self.summary(paths)?;
+        let mut out = format!("**Per-file verdicts:** {summary}\n");
+        for verdict in Verdict::ALL.iter().rev() {
+            for path in paths.iter().filter(|p| self.get(p) == Some(*verdict)) {
+                let _ = write!(
+                    out,
+                    "\n- {} `{path}`: {}",
+                    verdict.symbol(),
+                    verdict.label()
+                );
+            }
+        }
+        Some(out)
+    }
+}
+
+/// Counts of each verdict, then of files without one, e.g. `3 approve, 1 blocked, 4 open`;
+/// `None` before the first verdict.
+pub fn rollup(verdicts: impl Iterator<Item = Option<Verdict>>) -> Option<String> {
+    let verdicts: Vec<Option<Verdict>> = verdicts.collect();
+    if verdicts.iter().all(Option::is_none) {
+        return None;
+    }
+    let mut parts: Vec<String> = Verdict::ALL
+        .iter()
+        .map(|&v| (v, verdicts.iter().filter(|&&g| g == Some(v)).count()))
+        .filter(|&(_, count)| count > 0)
+        .map(|(v, count)| format!("{count} {}", v.label()))
+        .collect();
+    let open = verdicts.iter().filter(|v| v.is_none()).count();
+    if open > 0 {
+        parts.push(format!("{open} open"));
+    }
+    Some(parts.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Verdict, Verdicts};
+
+    #[test]
+    fn cycles_and_rolls_up_verdicts() {
+        let mut verdicts = Verdicts::default();
+        let paths = ["src/a.rs", "src/b.rs", "src/c.rs"];
+        assert_eq!(verdicts.summary(&paths), None);
+        assert_eq!(verdicts.cycle("src/a.rs"), Some(Verdict::Approve));
+        verdicts.cycle("src/b.rs");
+        verdicts.cycle("src/b.rs");
+        assert_eq!(verdicts.cycle("src/b.rs"), Some(Verdict::Blocked));
+        assert_eq!(
+            verdicts.summary(&paths).as_deref(),
+            Some("1 approve, 1 blocked, 1 open")
+        );
+        assert_eq!(
+            verdicts.comment(&paths).as_deref(),
+            Some(
+                "**Per-file verdicts:** 1 approve, 1 blocked, 1 open\n\n\
+                 - ✘ `src/b.rs`: blocked\n- ✔ `src/a.rs`: approve"
+            )
+        );
+        assert_eq!(verdicts.cycle("src/b.rs"), None);
+        assert!(!verdicts.files.contains_key("src/b.rs"));
+    }
+}
@@ -0,0 +1,180 @@
+// Filesystem watching for auto-refresh: notify events, batched on a background thread until the
+// tree goes quiet
+use crate::event::AppEvent;
+use git2::Repository;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender, channel};
+use std::time::{Duration, Instant};
+
+// Editors write files in bursts (temp file, rename, chmod); wait for a quiet gap
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches a repository's working tree, minus what `.gitignore` leaves out, and hands over
+/// the changed paths in settled batches.
+#[derive(Debug)]
+pub struct RepoWatcher {
+    events: Receiver<AppEvent>,
+    // Set on drop, so the thread (and the platform watcher it owns) stops
+    stop: Arc<AtomicBool>,
+}
+
+impl RepoWatcher {
+    /// Starts watching `root` on a background thread; watches that fail to attach are reported
+    /// through [`Self::poll`] as [`AppEvent::WatchFailed`].
+    ///
+    /// # Errors
+    /// Returns an error if the thread cannot be started.
+    pub fn new(root: &Path) -> io::Result<Self> {
+        // Some backends report resolved paths (e.g. /private/tmp on macOS), so watch that form
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let (tx, events) = channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        std::thread::Builder::new()
+            .name("glim-watcher".to_string())
+            .spawn(move || {
+                if let Err(e) = watch(&root, &tx, &stopped) {
+                    let _ = tx.send(AppEvent::WatchFailed(e.to_string()));
+                }
+            })?;
+        Ok(Self { events, stop })
+    }
+
+    /// The next settled batch of changed paths, or a watch failure; never blocks.
+    pub fn poll(&mut self) -> Option<AppEvent> {
+        self.events.try_recv().ok()
+    }
+}
+
+impl Drop for RepoWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// The watcher thread: attaches the watches, then batches events until `stop` is set.
+fn watch(root: &Path, out: &Sender<AppEvent>, stop: &AtomicBool) -> anyhow::Result<()> {
+    let repo = Repository::open(root)?;
+    let git_dir = repo.path().to_path_buf();
+    let (tx, events) = channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watch_tree(&mut watcher, &repo, root, root)?;
+    // The index, HEAD and refs; the rest of `.git` churns on every command
+    watcher.watch(&git_dir, RecursiveMode::NonRecursive)?;
+    let refs = git_dir.join("refs");
+    if refs.is_dir() {
+        watcher.watch(&refs, RecursiveMode::Recursive)?;
+    }
+
+    let mut pending: HashSet<PathBuf> = HashSet::new();
+    let mut last_change = Instant::now();
+    while !stop.load(Ordering::Relaxed) {
+        match events.recv_timeout(DEBOUNCE) {
+            Ok(Ok(event)) => {
+                if event.kind.is_access() {
+                    continue;
+                }
+                for path in event.paths {
+                    let Some(relative) = relative_path(root, &git_dir, &path) else {
+                        continue;
+                    };
+                    if !is_relevant(&relative) || is_ignored(&repo, &relative) {
+                        continue;
+                    }
+                    // Directories created since the start need watches of their own
+                    if event.kind.is_create() && path.is_dir() {
+                        report(out, watch_tree(&mut watcher, &repo, root, &path));
+                    }
+                    pending.insert(relative);
+                    last_change = Instant::now();
+                }
+            }
+            Ok(Err(e)) => report(out, Err(e)),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        if !pending.is_empty() && last_change.elapsed() >= DEBOUNCE {
+            let mut paths: Vec<PathBuf> = pending.drain().collect();
+            paths.sort_unstable();
+            if out.send(AppEvent::FilesChanged(paths)).is_err() {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Watches `dir` and every directory below it that git does not ignore, one level at a time.
+fn watch_tree(
+    watcher: &mut RecommendedWatcher,
+    repo: &Repository,
+    root: &Path,
+    dir: &Path,
+) -> notify::Result<()> {
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    for entry in fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        if relative.starts_with(".git") || is_ignored(repo, relative) {
+            continue;
+        }
+        watch_tree(watcher, repo, root, &path)?;
+    }
+    Ok(())
+}
+
+/// `path` relative to the working tree, with the git directory spelt `.git` wherever it is.
+fn relative_path(root: &Path, git_dir: &Path, path: &Path) -> Option<PathBuf> {
+    path.strip_prefix(git_dir).map_or_else(
+        |_| path.strip_prefix(root).ok().map(Path::to_path_buf),
+        |inner| Some(Path::new(".git").join(inner)),
+    )
+}
+
+fn is_ignored(repo: &Repository, relative: &Path) -> bool {
+    !relative.starts_with(".git") && repo.is_path_ignored(relative).unwrap_or(false)
+}
+
+fn report(out: &Sender<AppEvent>, result: notify::Result<()>) {
+    if let Err(e) = result {
+        let _ = out.send(AppEvent::WatchFailed(e.to_string()));
+    }
+}
+
+/// Working-tree files, plus the parts of `.git` that change what glim shows.
+fn is_relevant(relative: &Path) -> bool {
+    let Ok(git) = relative.strip_prefix(".git") else {
+        return true;
+    };
+    // Lock files come and go around every git command; the final rename is what counts
+    if git.extension().is_some_and(|ext| ext == "lock") {
+        return false;
+    }
+    git == Path::new("index") || git == Path::new("HEAD") || git.starts_with("refs")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_relevant;
+    use std::path::Path;
+
+    #[test]
+    fn ignores_git_internals_except_index_and_refs() {
+        assert!(is_relevant(Path::new("src/main.rs")));
+        assert!(is_relevant(Path::new(".git/index")));
+        assert!(is_relevant(Path::new(".git/refs/heads/main")));
+        assert!(!is_relevant(Path::new(".git/index.lock")));
+        assert!(!is_relevant(Path::new(".git/objects/ab/cdef")));
+    }
+}